@@ -15,6 +15,104 @@ fn parse_msh_from_file(mshfile: &[u8]) -> mshio::MshFile<u64, i32, f64> {
     mshio::parse_msh_bytes(mshfile).unwrap()
 }
 
+/// Generates a MSH file of a structured hexahedral grid with `n^3` cells, one entity block per layer
+///
+/// The file is written in ASCII format if `binary` is false and in little endian binary format otherwise.
+fn generate_hex_grid_msh(n: usize, binary: bool) -> Vec<u8> {
+    let mut msh = Vec::new();
+
+    macro_rules! push {
+        ($($value:expr),*) => {
+            if binary {
+                $(msh.extend_from_slice(&$value.to_le_bytes());)*
+            } else {
+                let line = [$($value.to_string()),*].join(" ");
+                msh.extend_from_slice(line.as_bytes());
+                msh.push(b'\n');
+            }
+        };
+    }
+
+    let num_layers = n + 1;
+    let num_nodes = num_layers * num_layers * num_layers;
+    let node_tag =
+        |i: usize, j: usize, k: usize| (1 + i + num_layers * (j + num_layers * k)) as u64;
+    let h = 1.0 / n as f64;
+
+    if binary {
+        msh.extend_from_slice(b"$MeshFormat\n4.1 1 8\n");
+        push!(1i32);
+        msh.extend_from_slice(b"\n$EndMeshFormat\n");
+    } else {
+        msh.extend_from_slice(b"$MeshFormat\n4.1 0 8\n$EndMeshFormat\n");
+    }
+
+    msh.extend_from_slice(b"$Nodes\n");
+    push!(num_layers as u64, num_nodes as u64, 1u64, num_nodes as u64);
+    for k in 0..num_layers {
+        push!(3i32, (k + 1) as i32, 0i32, (num_layers * num_layers) as u64);
+        for j in 0..num_layers {
+            for i in 0..num_layers {
+                push!(node_tag(i, j, k));
+            }
+        }
+        for j in 0..num_layers {
+            for i in 0..num_layers {
+                push!(i as f64 * h, j as f64 * h, k as f64 * h);
+            }
+        }
+    }
+    if binary {
+        msh.push(b'\n');
+    }
+    msh.extend_from_slice(b"$EndNodes\n");
+
+    msh.extend_from_slice(b"$Elements\n");
+    push!(n as u64, (n * n * n) as u64, 1u64, (n * n * n) as u64);
+    for k in 0..n {
+        push!(3i32, (k + 1) as i32, 5i32, (n * n) as u64);
+        for j in 0..n {
+            for i in 0..n {
+                push!(
+                    (1 + i + n * (j + n * k)) as u64,
+                    node_tag(i, j, k),
+                    node_tag(i + 1, j, k),
+                    node_tag(i + 1, j + 1, k),
+                    node_tag(i, j + 1, k),
+                    node_tag(i, j, k + 1),
+                    node_tag(i + 1, j, k + 1),
+                    node_tag(i + 1, j + 1, k + 1),
+                    node_tag(i, j + 1, k + 1)
+                );
+            }
+        }
+    }
+    if binary {
+        msh.push(b'\n');
+    }
+    msh.extend_from_slice(b"$EndElements\n");
+
+    msh
+}
+
+fn ascii_vs_binary_benchmark(c: &mut Criterion) {
+    let ascii_msh = generate_hex_grid_msh(40, false);
+    let binary_msh = generate_hex_grid_msh(40, true);
+
+    let mut group = c.benchmark_group("hex grid 40^3");
+    group.sample_size(20);
+    group.bench_function("ascii", |b| {
+        b.iter(|| parse_msh_from_file(black_box(&ascii_msh)))
+    });
+    group.bench_function("binary", |b| {
+        b.iter(|| parse_msh_from_file(black_box(&binary_msh)))
+    });
+    group.bench_function("binary zero-copy", |b| {
+        b.iter(|| mshio::parse_msh_bytes_ref(black_box(&binary_msh)).unwrap())
+    });
+    group.finish();
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     let cylinder_msh = read_to_bytes("cylinder_3d.msh");
     let curved_bike_msh = read_to_bytes("bike_original.obj_curved.msh");
@@ -30,5 +128,5 @@ fn criterion_benchmark(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, criterion_benchmark);
+criterion_group!(benches, ascii_vs_binary_benchmark, criterion_benchmark);
 criterion_main!(benches);
@@ -199,6 +199,43 @@ impl<I> MshParserError<I> {
     }
 }
 
+impl MshParserError<&[u8]> {
+    /// Returns the byte offset of the first actual MSH format error relative to the start of the given input
+    ///
+    /// The given input has to be the slice that was passed to the parser, e.g. the whole content of a
+    /// (memory mapped) file. Returns `None` if the error does not point into this input.
+    pub fn offset_in(&self, input: &[u8]) -> Option<usize> {
+        let (error_input, _) = self.begin_msh_errors().next()?;
+        let input_start = input.as_ptr() as usize;
+        let error_start = error_input.as_ptr() as usize;
+        if error_start >= input_start && error_start <= input_start + input.len() {
+            Some(error_start - input_start)
+        } else {
+            None
+        }
+    }
+
+    /// Converts the error into an error that stores absolute byte offsets instead of references into the input
+    ///
+    /// The given input has to be the slice that was passed to the parser and `input_offset` its
+    /// position in the whole file.
+    pub(crate) fn into_offsets(self, input: &[u8], input_offset: u64) -> MshParserError<u64> {
+        let input_start = input.as_ptr() as usize;
+        MshParserError {
+            backtrace: self
+                .backtrace
+                .into_iter()
+                .map(|(error_input, kind)| {
+                    let position = (error_input.as_ptr() as usize)
+                        .saturating_sub(input_start)
+                        .min(input.len());
+                    (input_offset + position as u64, kind)
+                })
+                .collect(),
+        }
+    }
+}
+
 impl<I: Clone> MshParserError<I> {
     /// Returns a backtrace containing only the errors that are actual MSH format errors (i.e. without internal nom parser errors)
     pub fn filtered_backtrace(&self) -> Vec<(I, MshParserErrorKind)> {
@@ -246,6 +283,32 @@ impl<I: Debug + HexDisplay + ?Sized> Display for MshParserError<&I> {
     }
 }
 
+/// Display implementation for errors that store byte offsets into the file instead of references
+impl Display for MshParserError<u64> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Remove all internal nom errors
+        let backtrace = self.filtered_backtrace();
+        if backtrace.len() > 1 {
+            writeln!(f, "During parsing...")?;
+            for (_, ek) in backtrace[1..].iter().rev() {
+                if let Some(c) = ek.context() {
+                    writeln!(f, "\tin {},", c)?;
+                } else {
+                    writeln!(f, "\tin {},", ek)?;
+                }
+            }
+            write!(f, "an error occurred at byte offset {}: ", backtrace[0].0)?;
+            write!(f, "{}", backtrace[0].1)
+        } else if let Some((offset, ek)) = backtrace.first() {
+            write!(f, "An error occurred at byte offset {}: {}", offset, ek)
+        } else {
+            writeln!(f, "Unknown error occurred")
+        }
+    }
+}
+
+impl Error for MshParserError<u64> {}
+
 impl<I> ParseError<I> for MshParserError<I> {
     fn from_error_kind(input: I, kind: ErrorKind) -> Self {
         Self {
@@ -0,0 +1,121 @@
+use std::io;
+
+use thiserror::Error;
+
+use crate::flat_mesh::{FlatCell, FlatMesh, FlatMeshError};
+use crate::mshfile::{ElementFamily, ElementType};
+
+/// Writer for Abaqus input files (`.inp`)
+pub mod abaqus;
+/// Reader and writer for ASCII MEDIT files (`.mesh`)
+pub mod medit;
+/// Writer for Wavefront OBJ files (`.obj`)
+pub mod obj;
+/// Writer for ASCII and binary STL files (`.stl`)
+pub mod stl;
+/// Writer for legacy VTK files (`.vtk`)
+pub mod vtk;
+/// Writer for VTK XML unstructured grid files (`.vtu`)
+pub mod vtu;
+
+/// Encoding of the numeric data of an exported file
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum Encoding {
+    /// Human readable text
+    #[default]
+    Ascii,
+    /// Raw binary data (the byte order is defined by the respective file format)
+    Binary,
+}
+
+/// Selection of the surface entities whose elements are exported by the surface mesh writers
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub enum Selection {
+    /// All surface entities of the mesh
+    #[default]
+    All,
+    /// The surface entities with the given tags
+    Entities(Vec<i32>),
+    /// The surface entities belonging to at least one of the physical groups with the given tags
+    PhysicalGroups(Vec<i32>),
+}
+
+impl Selection {
+    fn contains(&self, cell: &FlatCell) -> bool {
+        match self {
+            Selection::All => true,
+            Selection::Entities(tags) => tags.contains(&cell.entity_tag),
+            Selection::PhysicalGroups(tags) => cell.physical_tags.iter().any(|t| tags.contains(t)),
+        }
+    }
+}
+
+/// Error type returned by the mesh writers
+#[derive(Debug, Error)]
+pub enum ExportError {
+    /// Writing to the output failed
+    #[error("Error while writing the mesh: {0}")]
+    Io(#[from] io::Error),
+    /// An element references a node tag that is not contained in the node section
+    #[error("Element {element_tag} references the node {node_tag} which does not exist")]
+    UnknownNode { element_tag: u64, node_tag: u64 },
+    /// The mesh contains elements that cannot be represented in the output format
+    #[error("Elements of type {0:?} are not supported by the output format")]
+    UnsupportedElementType(ElementType),
+    /// A tag, index or count does not fit into the value range of the output format
+    #[error("The value {0} is out of the range supported by the output format")]
+    ValueOutOfRange(String),
+    /// An entity belongs to several physical groups but the output format only supports one
+    #[error("Entity {entity_tag} of dimension {entity_dim} belongs to {num_physical_tags} groups")]
+    MultiplePhysicalTags {
+        entity_dim: i32,
+        entity_tag: i32,
+        num_physical_tags: usize,
+    },
+    /// A data field does not provide the declared number of components for a node or element
+    #[error("Data field '{name}' contains {len} values for tag {tag} instead of {num_components}")]
+    InvalidDataField {
+        name: String,
+        tag: u64,
+        len: usize,
+        num_components: usize,
+    },
+}
+
+impl FlatMesh {
+    /// Returns the corner nodes of all selected 2D elements, higher order elements are linearized
+    pub(crate) fn surface_polygons(
+        &self,
+        selection: &Selection,
+    ) -> Result<Vec<(&FlatCell, &[usize])>, ExportError> {
+        let mut polygons = Vec::new();
+        for cell in &self.cells {
+            if cell.entity_dim != 2 || !selection.contains(cell) {
+                continue;
+            }
+
+            let num_corners = match cell.element_type.family() {
+                ElementFamily::Triangle if cell.element_type.order() > 0 => 3,
+                ElementFamily::Quadrangle if cell.element_type.order() > 0 => 4,
+                _ => return Err(ExportError::UnsupportedElementType(cell.element_type)),
+            };
+            polygons.push((cell, &cell.nodes[0..num_corners]));
+        }
+        Ok(polygons)
+    }
+}
+
+impl From<FlatMeshError> for ExportError {
+    fn from(error: FlatMeshError) -> Self {
+        match error {
+            FlatMeshError::UnknownNode {
+                element_tag,
+                node_tag,
+            } => ExportError::UnknownNode {
+                element_tag,
+                node_tag,
+            },
+            FlatMeshError::ValueOutOfRange(value) => ExportError::ValueOutOfRange(value),
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, BufWriter, Write};
+use std::sync::OnceLock;
+
+use crate::export::ExportError;
+use crate::flat_mesh::FlatMesh;
+use crate::lagrange::{self, NUM_ELEMENT_TYPES};
+use crate::mshfile::{ElementType, MshFile, MshFloatT, MshIntT, MshUsizeT};
+
+/// Abaqus element types that are used for 2D elements
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum AbaqusPlanarElements {
+    /// Conventional shell elements (`S3`, `S4`, `STRI65`, `S8R`, `S9R5`)
+    #[default]
+    Shell,
+    /// Plane stress continuum elements (`CPS3`, `CPS4`, `CPS6`, `CPS8`)
+    PlaneStress,
+    /// Plane strain continuum elements (`CPE3`, `CPE4`, `CPE6`, `CPE8`)
+    PlaneStrain,
+}
+
+/// Options of the Abaqus writer
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AbaqusOptions {
+    /// Element types that are used for triangles and quadrangles
+    pub planar_elements: AbaqusPlanarElements,
+    /// Whether the elements of all dimensions are written
+    ///
+    /// By default, only the elements of the highest dimension of the mesh are written (e.g. no
+    /// boundary triangles of a tetrahedral mesh). Node sets are generated for the entities and
+    /// physical groups of all dimensions regardless.
+    pub all_dimensions: bool,
+}
+
+/// Writes the nodes and elements of a MSH file as an Abaqus input file (`.inp`)
+///
+/// See [`write_with_options`](fn.write_with_options.html).
+pub fn write<U, I, F, W>(msh: &MshFile<U, I, F>, writer: W) -> Result<(), ExportError>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+    W: Write,
+{
+    write_with_options(msh, &AbaqusOptions::default(), writer)
+}
+
+/// Writes the nodes and elements of a MSH file as an Abaqus input file (`.inp`)
+///
+/// All nodes are written in a single `*NODE` section, the elements are written in `*ELEMENT`
+/// sections with the Abaqus element type and node ordering returned by
+/// [`abaqus_element_type`](fn.abaqus_element_type.html) and
+/// [`abaqus_node_ordering`](fn.abaqus_node_ordering.html). Point elements are skipped, all other
+/// element types without an Abaqus equivalent result in an error. The node and element tags of the
+/// MSH file are used as node and element numbers.
+///
+/// For every entity and every physical group, an element set (`*ELSET`) with all of its written
+/// elements and a node set (`*NSET`) with all nodes of its elements are generated. The sets are
+/// named after the dimension and tag, e.g. `SURFACE_1` for the surface entity with tag 1 and
+/// `PHYSICAL_SURFACE_5` for the physical group of surfaces with tag 5 (physical names are not
+/// parsed). Empty sets are skipped.
+/// ```
+/// use mshio::export::abaqus;
+///
+/// # let msh_bytes = "$MeshFormat\n4.1 0 8\n$EndMeshFormat\n$Nodes\n1 3 1 3\n2 1 0 3\n1\n2\n3\n0 0 0\n1 0 0\n0 1 0\n$EndNodes\n$Elements\n1 1 1 1\n2 1 2 1\n1 1 2 3\n$EndElements\n".as_bytes();
+/// let msh = mshio::parse_msh_bytes(msh_bytes).unwrap();
+///
+/// let mut output = Vec::new();
+/// abaqus::write(&msh, &mut output).unwrap();
+///
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.contains("*ELEMENT, TYPE=S3\n1, 1, 2, 3\n"));
+/// assert!(output.contains("*ELSET, ELSET=SURFACE_1\n1\n"));
+/// ```
+pub fn write_with_options<U, I, F, W>(
+    msh: &MshFile<U, I, F>,
+    options: &AbaqusOptions,
+    writer: W,
+) -> Result<(), ExportError>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+    W: Write,
+{
+    let mesh = FlatMesh::new(msh)?;
+    let max_dim = mesh
+        .cells
+        .iter()
+        .map(|cell| cell.element_type.dimension())
+        .max()
+        .unwrap_or(0);
+    let is_written = |element_type: ElementType| {
+        element_type != ElementType::Pnt
+            && (options.all_dimensions || element_type.dimension() == max_dim)
+    };
+
+    // Element and node sets by name
+    let mut element_sets: BTreeMap<String, BTreeSet<u64>> = BTreeMap::new();
+    let mut node_sets: BTreeMap<String, BTreeSet<u64>> = BTreeMap::new();
+    for cell in &mesh.cells {
+        let entity_name = format!("{}_{}", dimension_name(cell.entity_dim), cell.entity_tag);
+        let mut names = vec![entity_name];
+        names.extend(
+            cell.physical_tags
+                .iter()
+                .map(|tag| format!("PHYSICAL_{}_{}", dimension_name(cell.entity_dim), tag)),
+        );
+
+        for name in names {
+            if is_written(cell.element_type) {
+                element_sets
+                    .entry(name.clone())
+                    .or_default()
+                    .insert(cell.element_tag);
+            }
+            node_sets
+                .entry(name)
+                .or_default()
+                .extend(cell.nodes.iter().map(|&i| mesh.node_tags[i]));
+        }
+    }
+
+    let mut writer = BufWriter::new(writer);
+    let w = &mut writer;
+    writeln!(w, "*HEADING")?;
+    writeln!(w, "Mesh exported by mshio")?;
+
+    writeln!(w, "*NODE")?;
+    for (tag, p) in mesh.node_tags.iter().zip(&mesh.points) {
+        writeln!(w, "{}, {:?}, {:?}, {:?}", tag, p[0], p[1], p[2])?;
+    }
+
+    let mut current_type = None;
+    for cell in mesh.cells.iter().filter(|c| is_written(c.element_type)) {
+        let unsupported = || ExportError::UnsupportedElementType(cell.element_type);
+        let type_name = abaqus_element_type(cell.element_type, options.planar_elements)
+            .ok_or_else(unsupported)?;
+        let ordering = abaqus_node_ordering(cell.element_type).ok_or_else(unsupported)?;
+
+        if current_type != Some(cell.element_type) {
+            writeln!(w, "*ELEMENT, TYPE={}", type_name)?;
+            current_type = Some(cell.element_type);
+        }
+        let entries: Vec<u64> = std::iter::once(cell.element_tag)
+            .chain(ordering.iter().map(|&i| mesh.node_tags[cell.nodes[i]]))
+            .collect();
+        write_entries(w, &entries, true)?;
+    }
+
+    for (name, elements) in &element_sets {
+        writeln!(w, "*ELSET, ELSET={}", name)?;
+        write_entries(w, &elements.iter().copied().collect::<Vec<_>>(), false)?;
+    }
+    for (name, nodes) in &node_sets {
+        writeln!(w, "*NSET, NSET={}", name)?;
+        write_entries(w, &nodes.iter().copied().collect::<Vec<_>>(), false)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Returns the name of the Abaqus element type that corresponds to the MSH element type
+///
+/// Lines are mapped to truss elements (`T3D2`, `T3D3`), triangles and quadrangles to the given kind
+/// of planar elements, tetrahedra to `C3D4` and `C3D10`, hexahedra to `C3D8`, `C3D20` and `C3D27`
+/// and prisms to `C3D6` and `C3D15`. Returns `None` for all other element types (e.g. pyramids and
+/// elements of order three and higher).
+/// ```
+/// use mshio::export::abaqus::{abaqus_element_type, AbaqusPlanarElements};
+/// use mshio::mshfile::ElementType;
+///
+/// let shell = AbaqusPlanarElements::Shell;
+/// assert_eq!(abaqus_element_type(ElementType::Tet10, shell), Some("C3D10"));
+/// assert_eq!(abaqus_element_type(ElementType::Qua8, shell), Some("S8R"));
+/// assert_eq!(abaqus_element_type(ElementType::Qua8, AbaqusPlanarElements::PlaneStrain), Some("CPE8"));
+/// assert_eq!(abaqus_element_type(ElementType::Pyr5, shell), None);
+/// ```
+pub fn abaqus_element_type(
+    element_type: ElementType,
+    planar_elements: AbaqusPlanarElements,
+) -> Option<&'static str> {
+    use AbaqusPlanarElements::*;
+    use ElementType::*;
+
+    let name = match (element_type, planar_elements) {
+        (Lin2, _) => "T3D2",
+        (Lin3, _) => "T3D3",
+        (Tri3, Shell) => "S3",
+        (Tri3, PlaneStress) => "CPS3",
+        (Tri3, PlaneStrain) => "CPE3",
+        (Tri6, Shell) => "STRI65",
+        (Tri6, PlaneStress) => "CPS6",
+        (Tri6, PlaneStrain) => "CPE6",
+        (Qua4, Shell) => "S4",
+        (Qua4, PlaneStress) => "CPS4",
+        (Qua4, PlaneStrain) => "CPE4",
+        (Qua8, Shell) => "S8R",
+        (Qua8, PlaneStress) => "CPS8",
+        (Qua8, PlaneStrain) => "CPE8",
+        (Qua9, Shell) => "S9R5",
+        (Tet4, _) => "C3D4",
+        (Tet10, _) => "C3D10",
+        (Hex8, _) => "C3D8",
+        (Hex20, _) => "C3D20",
+        (Hex27, _) => "C3D27",
+        (Pri6, _) => "C3D6",
+        (Pri15, _) => "C3D15",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Returns for each node of the Abaqus element the index of the corresponding node in Gmsh ordering
+///
+/// The node ordering is the same for all planar element kinds. Returns `None` for element types
+/// without an Abaqus equivalent, see [`abaqus_element_type`](fn.abaqus_element_type.html).
+/// ```
+/// use mshio::export::abaqus::abaqus_node_ordering;
+/// use mshio::mshfile::ElementType;
+///
+/// // The Abaqus element contains the Gmsh nodes in the order gmsh_nodes[ordering[i]]
+/// assert_eq!(abaqus_node_ordering(ElementType::Tet10).unwrap(), &[0, 1, 2, 3, 4, 5, 6, 7, 9, 8]);
+/// assert_eq!(abaqus_node_ordering(ElementType::Lin3).unwrap(), &[0, 2, 1]);
+/// ```
+pub fn abaqus_node_ordering(element_type: ElementType) -> Option<&'static [usize]> {
+    static CACHE: [OnceLock<Option<Vec<usize>>>; NUM_ELEMENT_TYPES] =
+        [const { OnceLock::new() }; NUM_ELEMENT_TYPES];
+
+    CACHE[element_type as usize]
+        .get_or_init(|| {
+            let nodes = abaqus_nodes(element_type)?;
+            let coordinates = lagrange::vertex_averages(element_type, nodes)?;
+            lagrange::node_permutation(element_type, &coordinates)
+        })
+        .as_deref()
+}
+
+/// Returns the nodes of the Abaqus elements, each given by the vertices that it is centered at
+#[rustfmt::skip]
+fn abaqus_nodes(element_type: ElementType) -> Option<&'static [&'static [usize]]> {
+    use ElementType::*;
+
+    let nodes: &'static [&'static [usize]] = match element_type {
+        Lin2 => &[&[0], &[1]],
+        // The middle node of 3-node line elements is the second node
+        Lin3 => &[&[0], &[0, 1], &[1]],
+        Tri3 => &[&[0], &[1], &[2]],
+        Tri6 => &[&[0], &[1], &[2], &[0, 1], &[1, 2], &[2, 0]],
+        Qua4 => &[&[0], &[1], &[2], &[3]],
+        Qua8 => &[
+            &[0], &[1], &[2], &[3],
+            &[0, 1], &[1, 2], &[2, 3], &[3, 0],
+        ],
+        Qua9 => &[
+            &[0], &[1], &[2], &[3],
+            &[0, 1], &[1, 2], &[2, 3], &[3, 0],
+            &[0, 1, 2, 3],
+        ],
+        Tet4 => &[&[0], &[1], &[2], &[3]],
+        Tet10 => &[
+            &[0], &[1], &[2], &[3],
+            &[0, 1], &[1, 2], &[2, 0], &[0, 3], &[1, 3], &[2, 3],
+        ],
+        Hex8 => &[&[0], &[1], &[2], &[3], &[4], &[5], &[6], &[7]],
+        Hex20 => &[
+            &[0], &[1], &[2], &[3], &[4], &[5], &[6], &[7],
+            &[0, 1], &[1, 2], &[2, 3], &[3, 0],
+            &[4, 5], &[5, 6], &[6, 7], &[7, 4],
+            &[0, 4], &[1, 5], &[2, 6], &[3, 7],
+        ],
+        Hex27 => &[
+            &[0], &[1], &[2], &[3], &[4], &[5], &[6], &[7],
+            &[0, 1], &[1, 2], &[2, 3], &[3, 0],
+            &[4, 5], &[5, 6], &[6, 7], &[7, 4],
+            &[0, 4], &[1, 5], &[2, 6], &[3, 7],
+            &[0, 1, 2, 3], &[4, 5, 6, 7], &[0, 1, 5, 4], &[1, 2, 6, 5], &[2, 3, 7, 6], &[3, 0, 4, 7],
+            &[0, 1, 2, 3, 4, 5, 6, 7],
+        ],
+        Pri6 => &[&[0], &[1], &[2], &[3], &[4], &[5]],
+        Pri15 => &[
+            &[0], &[1], &[2], &[3], &[4], &[5],
+            &[0, 1], &[1, 2], &[2, 0], &[3, 4], &[4, 5], &[5, 3],
+            &[0, 3], &[1, 4], &[2, 5],
+        ],
+        _ => return None,
+    };
+    Some(nodes)
+}
+
+/// Returns the name of the entity dimension used in set names
+fn dimension_name(dim: i32) -> &'static str {
+    match dim {
+        0 => "POINT",
+        1 => "CURVE",
+        2 => "SURFACE",
+        _ => "VOLUME",
+    }
+}
+
+/// Writes comma separated data lines with at most 16 entries per line
+///
+/// Continued lines (e.g. of elements with more than 15 nodes) end with a comma.
+fn write_entries<W: Write>(writer: &mut W, entries: &[u64], continued: bool) -> io::Result<()> {
+    let num_lines = entries.chunks(16).len();
+    for (i, line) in entries.chunks(16).enumerate() {
+        let line: Vec<_> = line.iter().map(|e| e.to_string()).collect();
+        let end = if continued && i + 1 < num_lines {
+            ","
+        } else {
+            ""
+        };
+        writeln!(writer, "{}{}", line.join(", "), end)?;
+    }
+    Ok(())
+}
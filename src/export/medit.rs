@@ -0,0 +1,526 @@
+use std::collections::HashMap;
+use std::io::{self, BufWriter, Read, Write};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::export::ExportError;
+use crate::flat_mesh::{entity_physical_tags, to_i32, FlatMesh};
+use crate::mshfile::{
+    Curve, Element, ElementBlock, ElementType, Elements, Entities, MshData, MshFile, MshFloatT,
+    MshHeader, MshIntT, MshUsizeT, Node, NodeBlock, Nodes, Surface, Volume,
+};
+
+/// Element sections of MEDIT files and the corresponding element types
+const SECTIONS: [(&str, ElementType); 5] = [
+    ("Edges", ElementType::Lin2),
+    ("Triangles", ElementType::Tri3),
+    ("Quadrilaterals", ElementType::Qua4),
+    ("Tetrahedra", ElementType::Tet4),
+    ("Hexahedra", ElementType::Hex8),
+];
+
+/// Sections that are skipped when reading and the number of values of each of their entries
+const IGNORED_SECTIONS: [(&str, usize); 10] = [
+    ("Corners", 1),
+    ("RequiredVertices", 1),
+    ("Ridges", 1),
+    ("RequiredEdges", 1),
+    ("RequiredTriangles", 1),
+    ("RequiredQuadrilaterals", 1),
+    ("Normals", 3),
+    ("NormalAtVertices", 2),
+    ("Tangents", 3),
+    ("TangentAtVertices", 2),
+];
+
+/// Source and target of the references of MEDIT vertices and elements
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum MeditReferences {
+    /// References correspond to the tags of the entities
+    #[default]
+    EntityTags,
+    /// References correspond to the tags of the physical groups of the entities
+    PhysicalTags,
+}
+
+/// Options of the MEDIT reader and writer
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MeditOptions {
+    /// How references are mapped from and to the MSH file
+    pub references: MeditReferences,
+}
+
+/// Error type returned by the MEDIT reader
+#[derive(Debug, Error)]
+pub enum MeditReadError {
+    /// Reading from the input failed
+    #[error("Error while reading the MEDIT file: {0}")]
+    Io(#[from] io::Error),
+    /// The input is not a valid MEDIT file
+    #[error("Invalid MEDIT file in line {line}: {message}")]
+    InvalidFile { line: usize, message: String },
+}
+
+/// Writes the nodes and elements of a MSH file as an ASCII MEDIT file (`.mesh`)
+///
+/// Uses the entity tags as references, see [`write_with_options`](fn.write_with_options.html).
+pub fn write<U, I, F, W>(msh: &MshFile<U, I, F>, writer: W) -> Result<(), ExportError>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+    W: Write,
+{
+    write_with_options(msh, &MeditOptions::default(), writer)
+}
+
+/// Writes the nodes and elements of a MSH file as an ASCII MEDIT file (`.mesh`)
+///
+/// All nodes are written as three dimensional vertices. `Lin2`, `Tri3`, `Qua4`, `Tet4` and `Hex8`
+/// elements are written as edges, triangles, quadrilaterals, tetrahedra and hexahedra (the node
+/// ordering of these elements is the same in both formats), point elements are skipped and all
+/// other element types result in an error.
+///
+/// The reference of every vertex and element is either the tag of the entity of its block or the
+/// physical tag of this entity (zero if the entity does not belong to a physical group). As there
+/// is only one reference per vertex or element, entities that belong to several physical groups
+/// result in an error in the latter case.
+/// ```
+/// use mshio::export::medit;
+///
+/// # let msh_bytes = "$MeshFormat\n4.1 0 8\n$EndMeshFormat\n$Nodes\n1 3 1 3\n2 1 0 3\n1\n2\n3\n0 0 0\n1 0 0\n0 1 0\n$EndNodes\n$Elements\n1 1 1 1\n2 1 2 1\n1 1 2 3\n$EndElements\n".as_bytes();
+/// let msh = mshio::parse_msh_bytes(msh_bytes).unwrap();
+///
+/// let mut output = Vec::new();
+/// medit::write(&msh, &mut output).unwrap();
+///
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.contains("Triangles\n1\n1 2 3 1\n"));
+/// ```
+pub fn write_with_options<U, I, F, W>(
+    msh: &MshFile<U, I, F>,
+    options: &MeditOptions,
+    writer: W,
+) -> Result<(), ExportError>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+    W: Write,
+{
+    let mesh = FlatMesh::new(msh)?;
+    let physical_tags = entity_physical_tags(msh)?;
+    let reference = |dim: i32, tag: i32| match options.references {
+        MeditReferences::EntityTags => Ok(tag),
+        MeditReferences::PhysicalTags => match physical_tags.get(&(dim, tag)) {
+            Some(tags) if tags.len() > 1 => Err(ExportError::MultiplePhysicalTags {
+                entity_dim: dim,
+                entity_tag: tag,
+                num_physical_tags: tags.len(),
+            }),
+            Some(tags) => Ok(tags.first().copied().unwrap_or(0)),
+            None => Ok(0),
+        },
+    };
+
+    if let Some(cell) = mesh.cells.iter().find(|cell| {
+        cell.element_type != ElementType::Pnt
+            && SECTIONS.iter().all(|(_, t)| *t != cell.element_type)
+    }) {
+        return Err(ExportError::UnsupportedElementType(cell.element_type));
+    }
+
+    let mut vertex_references = Vec::with_capacity(mesh.points.len());
+    if let Some(nodes) = &msh.data.nodes {
+        for block in &nodes.node_blocks {
+            let r = reference(to_i32(block.entity_dim)?, to_i32(block.entity_tag)?)?;
+            vertex_references.extend(std::iter::repeat_n(r, block.nodes.len()));
+        }
+    }
+
+    let cell_references = mesh
+        .cells
+        .iter()
+        .map(|cell| reference(cell.entity_dim, cell.entity_tag))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut writer = BufWriter::new(writer);
+    let w = &mut writer;
+    writeln!(w, "MeshVersionFormatted 2")?;
+    writeln!(w, "Dimension 3")?;
+    writeln!(w, "Vertices")?;
+    writeln!(w, "{}", mesh.points.len())?;
+    for (p, r) in mesh.points.iter().zip(&vertex_references) {
+        writeln!(w, "{:?} {:?} {:?} {}", p[0], p[1], p[2], r)?;
+    }
+
+    for (keyword, element_type) in SECTIONS.iter() {
+        let cells: Vec<_> = mesh
+            .cells
+            .iter()
+            .zip(&cell_references)
+            .filter(|(cell, _)| cell.element_type == *element_type)
+            .collect();
+        if cells.is_empty() {
+            continue;
+        }
+
+        writeln!(w, "{}", keyword)?;
+        writeln!(w, "{}", cells.len())?;
+        for (cell, r) in cells {
+            for node in &cell.nodes {
+                write!(w, "{} ", node + 1)?;
+            }
+            writeln!(w, "{}", r)?;
+        }
+    }
+    writeln!(w, "End")?;
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads an ASCII MEDIT file (`.mesh`) as a MSH file
+///
+/// Uses the references as entity tags, see [`read_with_options`](fn.read_with_options.html).
+pub fn read<R: Read>(reader: R) -> Result<MshFile<u64, i32, f64>, MeditReadError> {
+    read_with_options(reader, &MeditOptions::default())
+}
+
+/// Reads an ASCII MEDIT file (`.mesh`) as a MSH file
+///
+/// Supports two and three dimensional vertices as well as edges, triangles, quadrilaterals,
+/// tetrahedra and hexahedra. Corners, ridges, required entities, normals and tangents are skipped,
+/// other sections result in an error.
+///
+/// Every distinct reference of the elements of each dimension results in one entity. Positive
+/// references are used as entity tags, elements with other references (e.g. zero) are assigned to
+/// entities with new tags. If the references are mapped to physical tags, all entities with a
+/// positive reference additionally belong to the physical group with the reference as tag. The
+/// bounding boxes of the entities are computed from the nodes of their elements, the boundaries of
+/// the entities are left empty.
+///
+/// All nodes are stored in a single block on the first entity of the highest dimension, the
+/// references of the vertices are not preserved. Nodes and elements are numbered consecutively
+/// starting at one, where the elements are grouped by section and by reference.
+/// ```
+/// use mshio::export::medit;
+///
+/// let mesh = "MeshVersionFormatted 2\nDimension 2\nVertices\n3\n0 0 0\n1 0 0\n0 1 0\nTriangles\n1\n1 2 3 7\nEnd\n";
+/// let msh = medit::read(mesh.as_bytes()).unwrap();
+///
+/// assert_eq!(msh.total_node_count(), 3);
+/// let block = &msh.data.elements.unwrap().element_blocks[0];
+/// assert_eq!((block.entity_dim, block.entity_tag), (2, 7));
+/// assert_eq!(block.elements[0].nodes, vec![1, 2, 3]);
+/// ```
+pub fn read_with_options<R: Read>(
+    mut reader: R,
+    options: &MeditOptions,
+) -> Result<MshFile<u64, i32, f64>, MeditReadError> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    let mut tokens = Tokens::new(&input);
+
+    let mut dimension = 3;
+    let mut vertices = Vec::new();
+    // Nodes and reference of all elements per section
+    let mut sections = vec![Vec::new(); SECTIONS.len()];
+
+    while let Some((line, keyword)) = tokens.next() {
+        let matches = |name: &str| keyword.eq_ignore_ascii_case(name);
+
+        if matches("End") {
+            break;
+        } else if matches("MeshVersionFormatted") {
+            tokens.parse::<i32>("mesh version")?;
+        } else if matches("Dimension") {
+            dimension = tokens.parse::<usize>("dimension")?;
+            if dimension != 2 && dimension != 3 {
+                return Err(invalid(
+                    line,
+                    format!("Unsupported dimension {}", dimension),
+                ));
+            }
+        } else if matches("Vertices") {
+            let n = tokens.parse::<usize>("number of vertices")?;
+            for _ in 0..n {
+                let mut vertex = [0.0; 3];
+                for x in vertex.iter_mut().take(dimension) {
+                    *x = tokens.parse::<f64>("vertex coordinate")?;
+                }
+                tokens.parse::<i32>("vertex reference")?;
+                vertices.push(vertex);
+            }
+        } else if let Some(i) = SECTIONS.iter().position(|(name, _)| matches(name)) {
+            let num_nodes = SECTIONS[i].1.nodes().unwrap();
+            let n = tokens.parse::<usize>("number of elements")?;
+            for _ in 0..n {
+                let mut nodes = Vec::with_capacity(num_nodes);
+                for _ in 0..num_nodes {
+                    let (line, node) = tokens.parse_with_line::<u64>("element vertex")?;
+                    nodes.push((line, node));
+                }
+                let reference = tokens.parse::<i32>("element reference")?;
+                sections[i].push((nodes, reference));
+            }
+        } else if let Some((_, len)) = IGNORED_SECTIONS.iter().find(|(name, _)| matches(name)) {
+            let n = tokens.parse::<usize>("number of entries")?;
+            for _ in 0..n * len {
+                tokens.parse::<f64>("value")?;
+            }
+        } else {
+            return Err(invalid(line, format!("Unsupported keyword '{}'", keyword)));
+        }
+    }
+
+    // Check the vertex indices as vertices may be defined after the elements
+    let mut elements = Vec::with_capacity(sections.len());
+    for section in sections {
+        let mut section_elements = Vec::with_capacity(section.len());
+        for (nodes, reference) in section {
+            let nodes = nodes
+                .into_iter()
+                .map(|(line, node)| {
+                    if node >= 1 && node <= vertices.len() as u64 {
+                        Ok(node)
+                    } else {
+                        Err(invalid(line, format!("Invalid vertex index {}", node)))
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            section_elements.push((nodes, reference));
+        }
+        elements.push(section_elements);
+    }
+
+    Ok(build_msh(vertices, elements, options))
+}
+
+/// Assembles the MSH file from the vertices and the elements (with references) of all sections
+fn build_msh(
+    vertices: Vec<[f64; 3]>,
+    sections: Vec<Vec<(Vec<u64>, i32)>>,
+    options: &MeditOptions,
+) -> MshFile<u64, i32, f64> {
+    // Entity tags of the references per dimension (in order of their first appearance)
+    let mut entity_tags: [Vec<(i32, i32)>; 4] = Default::default();
+    for (i, section) in sections.iter().enumerate() {
+        let dim = SECTIONS[i].1.dimension();
+        for (_, reference) in section {
+            if entity_tags[dim].iter().all(|(r, _)| r != reference) {
+                entity_tags[dim].push((*reference, 0));
+            }
+        }
+    }
+    for tags in entity_tags.iter_mut() {
+        let mut next_tag = tags.iter().map(|&(r, _)| r).max().unwrap_or(0).max(0);
+        for (reference, tag) in tags.iter_mut() {
+            *tag = if *reference > 0 {
+                *reference
+            } else {
+                next_tag += 1;
+                next_tag
+            };
+        }
+    }
+
+    // Element blocks grouped by section and reference, and bounding boxes of the entities
+    let mut element_blocks = Vec::new();
+    let mut bounding_boxes: HashMap<(usize, i32), [f64; 6]> = HashMap::new();
+    let mut next_element_tag = 1;
+    for (i, section) in sections.iter().enumerate() {
+        let (_, element_type) = SECTIONS[i];
+        let dim = element_type.dimension();
+        for &(reference, entity_tag) in &entity_tags[dim] {
+            let mut elements = Vec::new();
+            for (nodes, _) in section.iter().filter(|(_, r)| *r == reference) {
+                let bounds = bounding_boxes.entry((dim, entity_tag)).or_insert([
+                    f64::INFINITY,
+                    f64::INFINITY,
+                    f64::INFINITY,
+                    f64::NEG_INFINITY,
+                    f64::NEG_INFINITY,
+                    f64::NEG_INFINITY,
+                ]);
+                for &node in nodes {
+                    let vertex = vertices[node as usize - 1];
+                    for k in 0..3 {
+                        bounds[k] = bounds[k].min(vertex[k]);
+                        bounds[k + 3] = bounds[k + 3].max(vertex[k]);
+                    }
+                }
+
+                elements.push(Element {
+                    element_tag: next_element_tag,
+                    nodes: nodes.clone(),
+                });
+                next_element_tag += 1;
+            }
+
+            if !elements.is_empty() {
+                element_blocks.push(ElementBlock {
+                    entity_dim: dim as i32,
+                    entity_tag,
+                    element_type,
+                    element_tags: None,
+                    elements,
+                });
+            }
+        }
+    }
+
+    let physical_tags = |reference: i32| match options.references {
+        MeditReferences::PhysicalTags if reference > 0 => vec![reference],
+        _ => Vec::new(),
+    };
+    let mut entities = Entities {
+        points: Vec::new(),
+        curves: Vec::new(),
+        surfaces: Vec::new(),
+        volumes: Vec::new(),
+    };
+    for (dim, tags) in entity_tags.iter().enumerate() {
+        for &(reference, tag) in tags {
+            let [min_x, min_y, min_z, max_x, max_y, max_z] = bounding_boxes[&(dim, tag)];
+            let physical_tags = physical_tags(reference);
+            match dim {
+                1 => entities.curves.push(Curve {
+                    tag,
+                    min_x,
+                    min_y,
+                    min_z,
+                    max_x,
+                    max_y,
+                    max_z,
+                    physical_tags,
+                    point_tags: Vec::new(),
+                }),
+                2 => entities.surfaces.push(Surface {
+                    tag,
+                    min_x,
+                    min_y,
+                    min_z,
+                    max_x,
+                    max_y,
+                    max_z,
+                    physical_tags,
+                    curve_tags: Vec::new(),
+                }),
+                3 => entities.volumes.push(Volume {
+                    tag,
+                    min_x,
+                    min_y,
+                    min_z,
+                    max_x,
+                    max_y,
+                    max_z,
+                    physical_tags,
+                    surface_tags: Vec::new(),
+                }),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    let nodes = if vertices.is_empty() {
+        None
+    } else {
+        let (entity_dim, entity_tag) = (1..=3)
+            .rev()
+            .find_map(|dim| entity_tags[dim].first().map(|&(_, tag)| (dim as i32, tag)))
+            .unwrap_or((0, 1));
+        Some(Nodes {
+            num_nodes: vertices.len() as u64,
+            min_node_tag: 1,
+            max_node_tag: vertices.len() as u64,
+            node_blocks: vec![NodeBlock {
+                entity_dim,
+                entity_tag,
+                parametric: false,
+                node_tags: None,
+                nodes: vertices.iter().map(|&[x, y, z]| Node { x, y, z }).collect(),
+                parametric_nodes: None,
+            }],
+        })
+    };
+
+    let elements = if element_blocks.is_empty() {
+        None
+    } else {
+        Some(Elements {
+            num_elements: next_element_tag - 1,
+            min_element_tag: 1,
+            max_element_tag: next_element_tag - 1,
+            element_blocks,
+        })
+    };
+
+    MshFile {
+        header: MshHeader {
+            version: 4.1,
+            file_type: 0,
+            size_t_size: 8,
+            int_size: 4,
+            float_size: 8,
+            endianness: None,
+        },
+        data: MshData {
+            physical_names: None,
+            entities: if elements.is_some() {
+                Some(entities)
+            } else {
+                None
+            },
+            nodes,
+            elements,
+        },
+    }
+}
+
+fn invalid(line: usize, message: String) -> MeditReadError {
+    MeditReadError::InvalidFile { line, message }
+}
+
+/// Whitespace separated tokens of a MEDIT file with their line numbers (comments are removed)
+struct Tokens<'a> {
+    tokens: std::vec::IntoIter<(usize, &'a str)>,
+    last_line: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(input: &'a str) -> Self {
+        let mut tokens = Vec::new();
+        for (i, line) in input.lines().enumerate() {
+            let line_without_comment = line.split('#').next().unwrap_or("");
+            tokens.extend(line_without_comment.split_whitespace().map(|t| (i + 1, t)));
+        }
+        let last_line = input.lines().count();
+        Tokens {
+            tokens: tokens.into_iter(),
+            last_line,
+        }
+    }
+
+    fn next(&mut self) -> Option<(usize, &'a str)> {
+        self.tokens.next()
+    }
+
+    fn parse<T: FromStr>(&mut self, what: &str) -> Result<T, MeditReadError> {
+        self.parse_with_line(what).map(|(_, value)| value)
+    }
+
+    fn parse_with_line<T: FromStr>(&mut self, what: &str) -> Result<(usize, T), MeditReadError> {
+        match self.next() {
+            Some((line, token)) => token
+                .parse()
+                .map(|value| (line, value))
+                .map_err(|_| invalid(line, format!("Expected {}, found '{}'", what, token))),
+            None => Err(invalid(
+                self.last_line,
+                format!("Unexpected end of file, expected {}", what),
+            )),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::io::{BufWriter, Write};
+
+use crate::export::{ExportError, Selection};
+use crate::flat_mesh::FlatMesh;
+use crate::mshfile::{MshFile, MshFloatT, MshIntT, MshUsizeT};
+
+/// Options of the OBJ writer
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ObjOptions {
+    /// Surface entities whose elements are exported
+    pub selection: Selection,
+}
+
+/// Writes the 2D elements of all surface entities of a MSH file as a Wavefront OBJ file
+///
+/// See [`write_with_options`](fn.write_with_options.html).
+pub fn write<U, I, F, W>(msh: &MshFile<U, I, F>, writer: W) -> Result<(), ExportError>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+    W: Write,
+{
+    write_with_options(msh, &ObjOptions::default(), writer)
+}
+
+/// Writes the 2D elements of the selected surface entities of a MSH file as a Wavefront OBJ file
+///
+/// Triangles and quadrangles are written as faces, higher order elements are linearized by only
+/// writing their corner nodes. Only the nodes that are referenced by the faces are written, in the
+/// order of the node section of the MSH file. The faces of every surface entity are written as a
+/// separate group named `surface_<tag>`. Other element types on surface entities (e.g. polygons)
+/// result in an error.
+/// ```
+/// use mshio::export::obj;
+///
+/// # let msh_bytes = "$MeshFormat\n4.1 0 8\n$EndMeshFormat\n$Nodes\n1 3 1 3\n2 1 0 3\n1\n2\n3\n0 0 0\n1 0 0\n0 1 0\n$EndNodes\n$Elements\n1 1 1 1\n2 1 2 1\n1 1 2 3\n$EndElements\n".as_bytes();
+/// let msh = mshio::parse_msh_bytes(msh_bytes).unwrap();
+///
+/// let mut output = Vec::new();
+/// obj::write(&msh, &mut output).unwrap();
+///
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.contains("v 1.0 0.0 0.0\n"));
+/// assert!(output.contains("g surface_1\nf 1 2 3\n"));
+/// ```
+pub fn write_with_options<U, I, F, W>(
+    msh: &MshFile<U, I, F>,
+    options: &ObjOptions,
+    writer: W,
+) -> Result<(), ExportError>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+    W: Write,
+{
+    let mesh = FlatMesh::new(msh)?;
+    let polygons = mesh.surface_polygons(&options.selection)?;
+    let mut writer = BufWriter::new(writer);
+    let w = &mut writer;
+
+    // OBJ vertex indices are one-based, unused nodes are skipped
+    let mut vertex_indices = vec![0; mesh.points.len()];
+    for (_, nodes) in &polygons {
+        for &node in nodes.iter() {
+            vertex_indices[node] = 1;
+        }
+    }
+    let mut num_vertices = 0;
+    for index in vertex_indices.iter_mut().filter(|i| **i != 0) {
+        num_vertices += 1;
+        *index = num_vertices;
+    }
+
+    writeln!(w, "# Surface mesh exported by mshio")?;
+    for (point, _) in mesh
+        .points
+        .iter()
+        .zip(&vertex_indices)
+        .filter(|(_, &i)| i != 0)
+    {
+        writeln!(w, "v {:?} {:?} {:?}", point[0], point[1], point[2])?;
+    }
+
+    let mut current_entity = None;
+    for (cell, nodes) in &polygons {
+        if current_entity != Some(cell.entity_tag) {
+            writeln!(w, "g surface_{}", cell.entity_tag)?;
+            current_entity = Some(cell.entity_tag);
+        }
+        write!(w, "f")?;
+        for &node in nodes.iter() {
+            write!(w, " {}", vertex_indices[node])?;
+        }
+        writeln!(w)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use std::convert::TryFrom;
+use std::io::{BufWriter, Write};
+
+use crate::export::{Encoding, ExportError, Selection};
+use crate::flat_mesh::FlatMesh;
+use crate::mshfile::{MshFile, MshFloatT, MshIntT, MshUsizeT};
+
+/// Options of the STL writer
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StlOptions {
+    /// Encoding of the file
+    pub encoding: Encoding,
+    /// Surface entities whose elements are exported
+    pub selection: Selection,
+}
+
+/// Writes the 2D elements of all surface entities of a MSH file as an ASCII STL file
+///
+/// See [`write_with_options`](fn.write_with_options.html).
+pub fn write<U, I, F, W>(msh: &MshFile<U, I, F>, writer: W) -> Result<(), ExportError>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+    W: Write,
+{
+    write_with_options(msh, &StlOptions::default(), writer)
+}
+
+/// Writes the 2D elements of the selected surface entities of a MSH file as an STL file
+///
+/// Higher order elements are linearized by only using their corner nodes, quadrangles are split
+/// into two triangles along the diagonal from their first to their third node. The facet normals
+/// are computed from the node coordinates using the right-hand rule on the node ordering of the
+/// elements (degenerate triangles get a zero normal). Other element types on surface entities
+/// (e.g. polygons) result in an error.
+///
+/// Binary files use the usual layout of an 80 byte header, the number of triangles and 50 bytes
+/// per triangle with single precision values in little endian byte order.
+/// ```
+/// use mshio::export::stl;
+///
+/// # let msh_bytes = "$MeshFormat\n4.1 0 8\n$EndMeshFormat\n$Nodes\n1 3 1 3\n2 1 0 3\n1\n2\n3\n0 0 0\n1 0 0\n0 1 0\n$EndNodes\n$Elements\n1 1 1 1\n2 1 2 1\n1 1 2 3\n$EndElements\n".as_bytes();
+/// let msh = mshio::parse_msh_bytes(msh_bytes).unwrap();
+///
+/// let mut output = Vec::new();
+/// stl::write(&msh, &mut output).unwrap();
+///
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.starts_with("solid mshio\n  facet normal 0e0 0e0 1e0\n"));
+/// ```
+pub fn write_with_options<U, I, F, W>(
+    msh: &MshFile<U, I, F>,
+    options: &StlOptions,
+    writer: W,
+) -> Result<(), ExportError>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+    W: Write,
+{
+    let mesh = FlatMesh::new(msh)?;
+    let mut triangles = Vec::new();
+    for (_, nodes) in mesh.surface_polygons(&options.selection)? {
+        triangles.push([nodes[0], nodes[1], nodes[2]]);
+        if nodes.len() == 4 {
+            triangles.push([nodes[0], nodes[2], nodes[3]]);
+        }
+    }
+
+    let mut writer = BufWriter::new(writer);
+    let w = &mut writer;
+    match options.encoding {
+        Encoding::Ascii => {
+            writeln!(w, "solid mshio")?;
+            for triangle in &triangles {
+                let vertices = triangle.map(|i| mesh.points[i]);
+                let n = normal(&vertices);
+                writeln!(w, "  facet normal {:e} {:e} {:e}", n[0], n[1], n[2])?;
+                writeln!(w, "    outer loop")?;
+                for v in &vertices {
+                    writeln!(w, "      vertex {:e} {:e} {:e}", v[0], v[1], v[2])?;
+                }
+                writeln!(w, "    endloop")?;
+                writeln!(w, "  endfacet")?;
+            }
+            writeln!(w, "endsolid mshio")?;
+        }
+        Encoding::Binary => {
+            let num_triangles = u32::try_from(triangles.len())
+                .map_err(|_| ExportError::ValueOutOfRange(triangles.len().to_string()))?;
+
+            let mut header = [b' '; 80];
+            let title = b"Binary STL exported by mshio";
+            header[0..title.len()].copy_from_slice(title);
+            w.write_all(&header)?;
+            w.write_all(&num_triangles.to_le_bytes())?;
+
+            for triangle in &triangles {
+                let vertices = triangle.map(|i| mesh.points[i]);
+                for v in std::iter::once(normal(&vertices)).chain(vertices) {
+                    for x in &v {
+                        w.write_all(&(*x as f32).to_le_bytes())?;
+                    }
+                }
+                // Attribute byte count
+                w.write_all(&0u16.to_le_bytes())?;
+            }
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Returns the unit normal of the triangle or zero if the triangle is degenerate
+fn normal(vertices: &[[f64; 3]; 3]) -> [f64; 3] {
+    let [a, b, c] = vertices;
+    let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+    let n = [
+        u[1] * v[2] - u[2] * v[1],
+        u[2] * v[0] - u[0] * v[2],
+        u[0] * v[1] - u[1] * v[0],
+    ];
+
+    let length = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+    if length > 0.0 {
+        [n[0] / length, n[1] / length, n[2] / length]
+    } else {
+        [0.0; 3]
+    }
+}
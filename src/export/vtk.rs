@@ -0,0 +1,431 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::{self, BufWriter, Write};
+
+use nom::number::Endianness;
+
+use crate::export::{Encoding, ExportError};
+use crate::flat_mesh::FlatMesh;
+use crate::mshfile::{MshFile, MshFloatT, MshIntT, MshUsizeT};
+
+/// Values per node or element that are exported as point or cell data
+///
+/// As the MSH parser does not parse post-processing sections, the data of e.g. `$NodeData` or
+/// `$ElementData` sections has to be provided this way.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataField {
+    /// Name of the data array
+    pub name: String,
+    /// Number of values per node or element
+    pub num_components: usize,
+    /// Values of the nodes or elements by tag
+    ///
+    /// Every entry has to contain `num_components` values. Nodes or elements without values are
+    /// written as NaN.
+    pub values: HashMap<u64, Vec<f64>>,
+}
+
+/// Options of the VTK and VTU writers
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VtkOptions {
+    /// Encoding of the data arrays
+    pub encoding: Encoding,
+    /// Additional point data that is written after the node tags
+    pub point_data: Vec<DataField>,
+    /// Additional cell data that is written after the element, entity and physical tags
+    pub cell_data: Vec<DataField>,
+}
+
+/// Writes the nodes and elements of a MSH file as a legacy VTK unstructured grid
+///
+/// Uses ASCII encoding, see [`write_with_options`](fn.write_with_options.html).
+pub fn write<U, I, F, W>(msh: &MshFile<U, I, F>, writer: W) -> Result<(), ExportError>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+    W: Write,
+{
+    write_with_options(msh, &VtkOptions::default(), writer)
+}
+
+/// Writes the nodes and elements of a MSH file as a legacy VTK unstructured grid
+///
+/// The file uses version 2.0 of the legacy format, binary data is written in big endian byte
+/// order. All elements are written as cells with the VTK cell type and node ordering returned
+/// by [`ElementType::vtk_cell_type`](../../mshfile/enum.ElementType.html#method.vtk_cell_type) and
+/// [`ElementType::vtk_node_ordering`](../../mshfile/enum.ElementType.html#method.vtk_node_ordering).
+/// The point data contains the node tags (`node_tag`), the cell data contains the element tags
+/// (`element_tag`), the dimension and tag of the elements' entities (`entity_dim`, `entity_tag`)
+/// and the physical tags of the entity (`physical_tag`), followed by the data fields of the
+/// options. The physical tags have as many components as the entity with the most physical groups,
+/// entities with fewer groups are padded with zeros. Whitespace in the names of the data fields is
+/// replaced by underscores.
+/// ```
+/// use mshio::export::vtk;
+///
+/// # let msh_bytes = "$MeshFormat\n4.1 0 8\n$EndMeshFormat\n$Nodes\n1 3 1 3\n2 1 0 3\n1\n2\n3\n0 0 0\n1 0 0\n0 1 0\n$EndNodes\n$Elements\n1 1 1 1\n2 1 2 1\n1 1 2 3\n$EndElements\n".as_bytes();
+/// let msh = mshio::parse_msh_bytes(msh_bytes).unwrap();
+///
+/// let mut output = Vec::new();
+/// vtk::write(&msh, &mut output).unwrap();
+///
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.starts_with("# vtk DataFile Version 2.0\n"));
+/// assert!(output.contains("CELLS 1 4\n3 0 1 2\n"));
+/// ```
+pub fn write_with_options<U, I, F, W>(
+    msh: &MshFile<U, I, F>,
+    options: &VtkOptions,
+    writer: W,
+) -> Result<(), ExportError>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+    W: Write,
+{
+    let grid = UnstructuredGrid::new(&FlatMesh::new(msh)?, options)?;
+    let mut writer = BufWriter::new(writer);
+    let w = &mut writer;
+
+    // Legacy files store cells with 32 bit integers
+    let to_i32 = |value: i64| {
+        i32::try_from(value).map_err(|_| ExportError::ValueOutOfRange(value.to_string()))
+    };
+    let mut cells = Vec::with_capacity(grid.num_cells + grid.connectivity.len());
+    let mut begin = 0;
+    for &end in &grid.offsets {
+        cells.push(to_i32(end - begin)?);
+        for &node in &grid.connectivity[begin as usize..end as usize] {
+            cells.push(to_i32(node)?);
+        }
+        begin = end;
+    }
+
+    let binary = options.encoding == Encoding::Binary;
+    writeln!(w, "# vtk DataFile Version 2.0")?;
+    writeln!(w, "Unstructured grid exported by mshio")?;
+    writeln!(w, "{}", if binary { "BINARY" } else { "ASCII" })?;
+    writeln!(w, "DATASET UNSTRUCTURED_GRID")?;
+
+    writeln!(w, "POINTS {} double", grid.num_points)?;
+    write_values(w, &Values::Float64(grid.points), 3, binary)?;
+    writeln!(w, "CELLS {} {}", grid.num_cells, cells.len())?;
+    write_cells(w, &cells, binary)?;
+    writeln!(w, "CELL_TYPES {}", grid.num_cells)?;
+    let types = grid.types.iter().map(|&t| t as i32).collect();
+    write_values(w, &Values::Int32(types), 1, binary)?;
+
+    writeln!(w, "CELL_DATA {}", grid.num_cells)?;
+    write_field(w, &grid.cell_data, binary)?;
+    writeln!(w, "POINT_DATA {}", grid.num_points)?;
+    write_field(w, &grid.point_data, binary)?;
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes the cell connectivity in the legacy format (number of nodes followed by the nodes)
+fn write_cells<W: Write>(writer: &mut W, cells: &[i32], binary: bool) -> io::Result<()> {
+    if binary {
+        return write_values(writer, &Values::Int32(cells.to_vec()), 1, true);
+    }
+
+    let mut i = 0;
+    while i < cells.len() {
+        let n = cells[i] as usize;
+        let line: Vec<_> = cells[i..=i + n].iter().map(|c| c.to_string()).collect();
+        writeln!(writer, "{}", line.join(" "))?;
+        i += n + 1;
+    }
+    Ok(())
+}
+
+/// Writes the data arrays as a legacy field
+fn write_field<W: Write>(writer: &mut W, arrays: &[DataArray], binary: bool) -> io::Result<()> {
+    writeln!(writer, "FIELD FieldData {}", arrays.len())?;
+    for array in arrays {
+        let name: String = array
+            .name
+            .chars()
+            .map(|c| if c.is_whitespace() { '_' } else { c })
+            .collect();
+        let type_name = match array.values {
+            Values::Float64(_) => "double",
+            Values::UInt64(_) => "vtktypeuint64",
+            Values::Int64(_) => "vtktypeint64",
+            Values::Int32(_) => "int",
+            Values::UInt8(_) => "unsigned_char",
+        };
+        writeln!(
+            writer,
+            "{} {} {} {}",
+            name,
+            array.num_components,
+            array.values.len() / array.num_components.max(1),
+            type_name
+        )?;
+        write_values(writer, &array.values, array.num_components, binary)?;
+    }
+    Ok(())
+}
+
+/// Writes the values either as ASCII with the given number of values per line or as big endian binary
+fn write_values<W: Write>(
+    writer: &mut W,
+    values: &Values,
+    per_line: usize,
+    binary: bool,
+) -> io::Result<()> {
+    if binary {
+        values.write_binary(writer, Endianness::Big)?;
+        writeln!(writer)
+    } else {
+        values.write_ascii(writer, per_line)
+    }
+}
+
+/// Typed values of a data array
+pub(crate) enum Values {
+    Float64(Vec<f64>),
+    UInt64(Vec<u64>),
+    Int64(Vec<i64>),
+    Int32(Vec<i32>),
+    UInt8(Vec<u8>),
+}
+
+/// Calls the expression with the vector of values of any variant
+macro_rules! with_values {
+    ($values:expr, $v:ident => $body:expr) => {
+        match $values {
+            Values::Float64($v) => $body,
+            Values::UInt64($v) => $body,
+            Values::Int64($v) => $body,
+            Values::Int32($v) => $body,
+            Values::UInt8($v) => $body,
+        }
+    };
+}
+
+impl Values {
+    /// Returns the number of values
+    pub(crate) fn len(&self) -> usize {
+        with_values!(self, v => v.len())
+    }
+
+    /// Returns the number of bytes of the values in binary encoding
+    pub(crate) fn byte_len(&self) -> usize {
+        with_values!(self, v => std::mem::size_of_val(&v[..]))
+    }
+
+    /// Writes the values as text, `per_line` values per line separated by spaces
+    pub(crate) fn write_ascii<W: Write>(&self, writer: &mut W, per_line: usize) -> io::Result<()> {
+        with_values!(self, v => {
+            for line in v.chunks(per_line.max(1)) {
+                for (i, value) in line.iter().enumerate() {
+                    if i > 0 {
+                        write!(writer, " ")?;
+                    }
+                    value.write_ascii(writer)?;
+                }
+                writeln!(writer)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Writes the values as binary data with the given byte order
+    pub(crate) fn write_binary<W: Write>(
+        &self,
+        writer: &mut W,
+        endianness: Endianness,
+    ) -> io::Result<()> {
+        with_values!(self, v => {
+            for value in v {
+                value.write_binary(writer, endianness)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Formatting of single values of data arrays
+trait VtkValue {
+    fn write_ascii<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+    fn write_binary<W: Write>(&self, writer: &mut W, endianness: Endianness) -> io::Result<()>;
+}
+
+macro_rules! impl_vtk_value {
+    ($($t:ty),*) => {
+        $(
+            impl VtkValue for $t {
+                fn write_ascii<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                    write!(writer, "{}", self)
+                }
+
+                fn write_binary<W: Write>(&self, writer: &mut W, endianness: Endianness) -> io::Result<()> {
+                    match endianness {
+                        Endianness::Big => writer.write_all(&self.to_be_bytes()),
+                        Endianness::Little => writer.write_all(&self.to_le_bytes()),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_vtk_value!(u64, i64, i32, u8);
+
+impl VtkValue for f64 {
+    fn write_ascii<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        // The debug representation is exact and uses exponents for very small and large values
+        if self.is_nan() {
+            write!(writer, "nan")
+        } else if self.is_infinite() {
+            write!(writer, "{}", if *self > 0.0 { "inf" } else { "-inf" })
+        } else {
+            write!(writer, "{:?}", self)
+        }
+    }
+
+    fn write_binary<W: Write>(&self, writer: &mut W, endianness: Endianness) -> io::Result<()> {
+        match endianness {
+            Endianness::Big => writer.write_all(&self.to_be_bytes()),
+            Endianness::Little => writer.write_all(&self.to_le_bytes()),
+        }
+    }
+}
+
+/// A named data array
+pub(crate) struct DataArray {
+    pub(crate) name: String,
+    pub(crate) num_components: usize,
+    pub(crate) values: Values,
+}
+
+/// Geometry, topology and data arrays of a VTK unstructured grid
+pub(crate) struct UnstructuredGrid {
+    pub(crate) num_points: usize,
+    pub(crate) num_cells: usize,
+    /// Coordinates of all points, three per point
+    pub(crate) points: Vec<f64>,
+    /// Point indices of all cells in VTK ordering
+    pub(crate) connectivity: Vec<i64>,
+    /// End of each cell in the connectivity array
+    pub(crate) offsets: Vec<i64>,
+    /// VTK cell type of each cell
+    pub(crate) types: Vec<u8>,
+    pub(crate) point_data: Vec<DataArray>,
+    pub(crate) cell_data: Vec<DataArray>,
+}
+
+impl UnstructuredGrid {
+    /// Converts the mesh to VTK cells and assembles the point and cell data
+    pub(crate) fn new(mesh: &FlatMesh, options: &VtkOptions) -> Result<Self, ExportError> {
+        let mut connectivity = Vec::new();
+        let mut offsets = Vec::with_capacity(mesh.cells.len());
+        let mut types = Vec::with_capacity(mesh.cells.len());
+        for cell in &mesh.cells {
+            let unsupported = || ExportError::UnsupportedElementType(cell.element_type);
+            let cell_type = cell.element_type.vtk_cell_type().ok_or_else(unsupported)?;
+            let ordering = cell
+                .element_type
+                .vtk_node_ordering()
+                .ok_or_else(unsupported)?;
+            connectivity.extend(ordering.iter().map(|&i| cell.nodes[i] as i64));
+            offsets.push(connectivity.len() as i64);
+            types.push(cell_type as u8);
+        }
+
+        let mut point_data = vec![DataArray {
+            name: "node_tag".to_string(),
+            num_components: 1,
+            values: Values::UInt64(mesh.node_tags.clone()),
+        }];
+        for field in &options.point_data {
+            point_data.push(field_array(field, &mesh.node_tags)?);
+        }
+
+        let element_tags: Vec<_> = mesh.cells.iter().map(|c| c.element_tag).collect();
+        // Entities may belong to several physical groups, missing tags are padded with zeros
+        let num_physical_tags = mesh
+            .cells
+            .iter()
+            .map(|c| c.physical_tags.len())
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let mut physical_tags = Vec::with_capacity(mesh.cells.len() * num_physical_tags);
+        for cell in &mesh.cells {
+            physical_tags.extend_from_slice(&cell.physical_tags);
+            let padding = num_physical_tags - cell.physical_tags.len();
+            physical_tags.extend(std::iter::repeat_n(0, padding));
+        }
+        let mut cell_data = vec![
+            DataArray {
+                name: "element_tag".to_string(),
+                num_components: 1,
+                values: Values::UInt64(element_tags.clone()),
+            },
+            DataArray {
+                name: "entity_dim".to_string(),
+                num_components: 1,
+                values: Values::Int32(mesh.cells.iter().map(|c| c.entity_dim).collect()),
+            },
+            DataArray {
+                name: "entity_tag".to_string(),
+                num_components: 1,
+                values: Values::Int32(mesh.cells.iter().map(|c| c.entity_tag).collect()),
+            },
+            DataArray {
+                name: "physical_tag".to_string(),
+                num_components: num_physical_tags,
+                values: Values::Int32(physical_tags),
+            },
+        ];
+        for field in &options.cell_data {
+            cell_data.push(field_array(field, &element_tags)?);
+        }
+
+        Ok(UnstructuredGrid {
+            num_points: mesh.points.len(),
+            num_cells: mesh.cells.len(),
+            points: mesh.points.iter().flatten().copied().collect(),
+            connectivity,
+            offsets,
+            types,
+            point_data,
+            cell_data,
+        })
+    }
+}
+
+/// Returns the values of the data field for the given tags in the given order
+fn field_array(field: &DataField, tags: &[u64]) -> Result<DataArray, ExportError> {
+    let n = field.num_components;
+    for (&tag, values) in &field.values {
+        if values.len() != n {
+            return Err(ExportError::InvalidDataField {
+                name: field.name.clone(),
+                tag,
+                len: values.len(),
+                num_components: n,
+            });
+        }
+    }
+
+    let mut values = Vec::with_capacity(tags.len() * n);
+    for tag in tags {
+        match field.values.get(tag) {
+            Some(v) => values.extend_from_slice(v),
+            None => values.extend(std::iter::repeat_n(f64::NAN, n)),
+        }
+    }
+
+    Ok(DataArray {
+        name: field.name.clone(),
+        num_components: n,
+        values: Values::Float64(values),
+    })
+}
@@ -0,0 +1,193 @@
+use std::io::{BufWriter, Write};
+
+use nom::number::Endianness;
+
+use crate::export::vtk::{DataArray, UnstructuredGrid, Values, VtkOptions};
+use crate::export::{Encoding, ExportError};
+use crate::flat_mesh::FlatMesh;
+use crate::mshfile::{MshFile, MshFloatT, MshIntT, MshUsizeT};
+
+/// Writes the nodes and elements of a MSH file as a VTK XML unstructured grid
+///
+/// Uses ASCII encoding, see [`write_with_options`](fn.write_with_options.html).
+pub fn write<U, I, F, W>(msh: &MshFile<U, I, F>, writer: W) -> Result<(), ExportError>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+    W: Write,
+{
+    write_with_options(msh, &VtkOptions::default(), writer)
+}
+
+/// Writes the nodes and elements of a MSH file as a VTK XML unstructured grid
+///
+/// With binary encoding, all data arrays are stored as raw little endian data in the appended
+/// data section of the file (with 64 bit block headers). The cells and the point and cell data
+/// are the same as for legacy files, see
+/// [`vtk::write_with_options`](../vtk/fn.write_with_options.html).
+/// ```
+/// use mshio::export::vtk::VtkOptions;
+/// use mshio::export::{vtu, Encoding};
+///
+/// # let msh_bytes = "$MeshFormat\n4.1 0 8\n$EndMeshFormat\n$Nodes\n1 3 1 3\n2 1 0 3\n1\n2\n3\n0 0 0\n1 0 0\n0 1 0\n$EndNodes\n$Elements\n1 1 1 1\n2 1 2 1\n1 1 2 3\n$EndElements\n".as_bytes();
+/// let msh = mshio::parse_msh_bytes(msh_bytes).unwrap();
+///
+/// let options = VtkOptions {
+///     encoding: Encoding::Binary,
+///     ..Default::default()
+/// };
+///
+/// let mut output = Vec::new();
+/// vtu::write_with_options(&msh, &options, &mut output).unwrap();
+/// assert!(output.starts_with(b"<?xml version=\"1.0\"?>\n<VTKFile type=\"UnstructuredGrid\""));
+/// ```
+pub fn write_with_options<U, I, F, W>(
+    msh: &MshFile<U, I, F>,
+    options: &VtkOptions,
+    writer: W,
+) -> Result<(), ExportError>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+    W: Write,
+{
+    let grid = UnstructuredGrid::new(&FlatMesh::new(msh)?, options)?;
+    let mut writer = BufWriter::new(writer);
+
+    let points = DataArray {
+        name: "Points".to_string(),
+        num_components: 3,
+        values: Values::Float64(grid.points),
+    };
+    let cells = [
+        DataArray {
+            name: "connectivity".to_string(),
+            num_components: 1,
+            values: Values::Int64(grid.connectivity),
+        },
+        DataArray {
+            name: "offsets".to_string(),
+            num_components: 1,
+            values: Values::Int64(grid.offsets),
+        },
+        DataArray {
+            name: "types".to_string(),
+            num_components: 1,
+            values: Values::UInt8(grid.types),
+        },
+    ];
+
+    let mut xml = XmlWriter {
+        writer: &mut writer,
+        binary: options.encoding == Encoding::Binary,
+        offset: 0,
+        appended: Vec::new(),
+    };
+
+    let w = &mut xml;
+    writeln!(w.writer, "<?xml version=\"1.0\"?>")?;
+    writeln!(
+        w.writer,
+        "<VTKFile type=\"UnstructuredGrid\" version=\"1.0\" byte_order=\"LittleEndian\" header_type=\"UInt64\">"
+    )?;
+    writeln!(w.writer, "  <UnstructuredGrid>")?;
+    writeln!(
+        w.writer,
+        "    <Piece NumberOfPoints=\"{}\" NumberOfCells=\"{}\">",
+        grid.num_points, grid.num_cells
+    )?;
+
+    writeln!(w.writer, "      <PointData>")?;
+    for array in &grid.point_data {
+        w.write_array(array)?;
+    }
+    writeln!(w.writer, "      </PointData>")?;
+    writeln!(w.writer, "      <CellData>")?;
+    for array in &grid.cell_data {
+        w.write_array(array)?;
+    }
+    writeln!(w.writer, "      </CellData>")?;
+    writeln!(w.writer, "      <Points>")?;
+    w.write_array(&points)?;
+    writeln!(w.writer, "      </Points>")?;
+    writeln!(w.writer, "      <Cells>")?;
+    for array in &cells {
+        w.write_array(array)?;
+    }
+    writeln!(w.writer, "      </Cells>")?;
+
+    writeln!(w.writer, "    </Piece>")?;
+    writeln!(w.writer, "  </UnstructuredGrid>")?;
+    if w.binary {
+        writeln!(w.writer, "  <AppendedData encoding=\"raw\">")?;
+        write!(w.writer, "_")?;
+        for array in &w.appended {
+            let values = &array.values;
+            w.writer
+                .write_all(&(values.byte_len() as u64).to_le_bytes())?;
+            values.write_binary(w.writer, Endianness::Little)?;
+        }
+        writeln!(w.writer)?;
+        writeln!(w.writer, "  </AppendedData>")?;
+    }
+    writeln!(w.writer, "</VTKFile>")?;
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes the data array elements and collects the arrays of the appended data section
+struct XmlWriter<'a, 'b, W: Write> {
+    writer: &'b mut W,
+    binary: bool,
+    /// Offset of the next array in the appended data section
+    offset: usize,
+    appended: Vec<&'a DataArray>,
+}
+
+impl<'a, 'b, W: Write> XmlWriter<'a, 'b, W> {
+    fn write_array(&mut self, array: &'a DataArray) -> Result<(), ExportError> {
+        let type_name = match array.values {
+            Values::Float64(_) => "Float64",
+            Values::UInt64(_) => "UInt64",
+            Values::Int64(_) => "Int64",
+            Values::Int32(_) => "Int32",
+            Values::UInt8(_) => "UInt8",
+        };
+        write!(
+            self.writer,
+            "        <DataArray type=\"{}\" Name=\"{}\" NumberOfComponents=\"{}\"",
+            type_name,
+            escape(&array.name),
+            array.num_components
+        )?;
+
+        if self.binary {
+            writeln!(
+                self.writer,
+                " format=\"appended\" offset=\"{}\"/>",
+                self.offset
+            )?;
+            self.offset += std::mem::size_of::<u64>() + array.values.byte_len();
+            self.appended.push(array);
+        } else {
+            writeln!(self.writer, " format=\"ascii\">")?;
+            array
+                .values
+                .write_ascii(self.writer, array.num_components)?;
+            writeln!(self.writer, "        </DataArray>")?;
+        }
+        Ok(())
+    }
+}
+
+/// Escapes the special characters of XML attribute values
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
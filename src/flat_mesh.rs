@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use crate::mshfile::{ElementType, MshFile, MshFloatT, MshIntT, MshUsizeT};
+
+/// Error type returned when collecting a [`FlatMesh`]
+#[derive(Debug, Error)]
+pub(crate) enum FlatMeshError {
+    /// An element references a node tag that is not contained in the node section
+    #[error("Element {element_tag} references the node {node_tag} which does not exist")]
+    UnknownNode { element_tag: u64, node_tag: u64 },
+    /// A tag or coordinate cannot be converted to the types of the flat representation
+    #[error("The value {0} is out of the supported range")]
+    ValueOutOfRange(String),
+}
+
+/// All nodes and elements of a mesh with element connectivity given by node indices
+///
+/// The nodes and elements are stored in the order of their blocks in the MSH file.
+pub(crate) struct FlatMesh {
+    /// Coordinates of all nodes
+    pub(crate) points: Vec<[f64; 3]>,
+    /// Tags of all nodes
+    pub(crate) node_tags: Vec<u64>,
+    /// All elements of the mesh
+    pub(crate) cells: Vec<FlatCell>,
+}
+
+/// A single element of a [`FlatMesh`]
+pub(crate) struct FlatCell {
+    pub(crate) element_type: ElementType,
+    pub(crate) element_tag: u64,
+    pub(crate) entity_dim: i32,
+    pub(crate) entity_tag: i32,
+    /// Tags of the physical groups of the element's entity
+    pub(crate) physical_tags: Vec<i32>,
+    /// Indices of the element's nodes into the points of the mesh in Gmsh ordering
+    pub(crate) nodes: Vec<usize>,
+}
+
+impl FlatMesh {
+    /// Collects the nodes and elements of the given MSH file
+    pub(crate) fn new<U, I, F>(msh: &MshFile<U, I, F>) -> Result<Self, FlatMeshError>
+    where
+        U: MshUsizeT,
+        I: MshIntT,
+        F: MshFloatT,
+    {
+        let mut points = Vec::new();
+        let mut node_tags = Vec::new();
+        let mut node_indices = HashMap::new();
+
+        if let Some(nodes) = &msh.data.nodes {
+            points.reserve(msh.total_node_count());
+            node_tags.reserve(msh.total_node_count());
+
+            for (block, block_tags) in nodes.node_blocks.iter().zip(nodes.block_node_tags()) {
+                for (tag, node) in block_tags.into_iter().zip(block.nodes.iter()) {
+                    node_tags.push(to_u64(tag)?);
+                    points.push([to_f64(node.x)?, to_f64(node.y)?, to_f64(node.z)?]);
+                }
+            }
+            node_indices.extend(node_tags.iter().enumerate().map(|(i, &tag)| (tag, i)));
+        }
+
+        let physical_tags = entity_physical_tags(msh)?;
+
+        let mut cells = Vec::with_capacity(msh.total_element_count());
+        if let Some(elements) = &msh.data.elements {
+            for block in &elements.element_blocks {
+                let entity_dim = to_i32(block.entity_dim)?;
+                let entity_tag = to_i32(block.entity_tag)?;
+                let block_physical_tags: Vec<i32> = physical_tags
+                    .get(&(entity_dim, entity_tag))
+                    .cloned()
+                    .unwrap_or_default();
+
+                for element in &block.elements {
+                    let element_tag = to_u64(element.element_tag)?;
+                    let nodes = element
+                        .nodes
+                        .iter()
+                        .map(|&tag| {
+                            let node_tag = to_u64(tag)?;
+                            node_indices
+                                .get(&node_tag)
+                                .copied()
+                                .ok_or(FlatMeshError::UnknownNode {
+                                    element_tag,
+                                    node_tag,
+                                })
+                        })
+                        .collect::<Result<_, _>>()?;
+
+                    cells.push(FlatCell {
+                        element_type: block.element_type,
+                        element_tag,
+                        entity_dim,
+                        entity_tag,
+                        physical_tags: block_physical_tags.clone(),
+                        nodes,
+                    });
+                }
+            }
+        }
+
+        Ok(FlatMesh {
+            points,
+            node_tags,
+            cells,
+        })
+    }
+}
+
+/// Returns the physical tags of all entities by their dimension and tag
+pub(crate) fn entity_physical_tags<U, I, F>(
+    msh: &MshFile<U, I, F>,
+) -> Result<HashMap<(i32, i32), Vec<i32>>, FlatMeshError>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+{
+    let mut physical_tags = HashMap::new();
+    if let Some(entities) = &msh.data.entities {
+        let mut insert = |dim: i32, tag: I, tags: &[I]| -> Result<(), FlatMeshError> {
+            let tags = tags.iter().map(|&t| to_i32(t)).collect::<Result<_, _>>()?;
+            physical_tags.insert((dim, to_i32(tag)?), tags);
+            Ok(())
+        };
+        for point in &entities.points {
+            insert(0, point.tag, &point.physical_tags)?;
+        }
+        for curve in &entities.curves {
+            insert(1, curve.tag, &curve.physical_tags)?;
+        }
+        for surface in &entities.surfaces {
+            insert(2, surface.tag, &surface.physical_tags)?;
+        }
+        for volume in &entities.volumes {
+            insert(3, volume.tag, &volume.physical_tags)?;
+        }
+    }
+    Ok(physical_tags)
+}
+
+fn to_u64<U: MshUsizeT>(value: U) -> Result<u64, FlatMeshError> {
+    value
+        .to_u64()
+        .ok_or_else(|| FlatMeshError::ValueOutOfRange(format!("{:?}", value)))
+}
+
+pub(crate) fn to_i32<I: MshIntT>(value: I) -> Result<i32, FlatMeshError> {
+    value
+        .to_i32()
+        .ok_or_else(|| FlatMeshError::ValueOutOfRange(format!("{:?}", value)))
+}
+
+fn to_f64<F: MshFloatT>(value: F) -> Result<f64, FlatMeshError> {
+    value
+        .to_f64()
+        .ok_or_else(|| FlatMeshError::ValueOutOfRange(format!("{:?}", value)))
+}
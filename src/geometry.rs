@@ -0,0 +1,79 @@
+use thiserror::Error;
+
+use crate::flat_mesh::FlatMeshError;
+
+/// Lengths, areas, volumes, centroids and normals of elements and their totals per entity
+pub mod measures;
+/// Quality metrics of elements (scaled Jacobian, aspect ratio, radius ratio and skewness)
+pub mod quality;
+
+/// Error type returned by the geometric computations on meshes
+#[derive(Debug, Error)]
+pub enum GeometryError {
+    /// An element references a node tag that is not contained in the node section
+    #[error("Element {element_tag} references the node {node_tag} which does not exist")]
+    UnknownNode { element_tag: u64, node_tag: u64 },
+    /// A tag or coordinate cannot be converted to the types used for the computations
+    #[error("The value {0} is out of the supported range")]
+    ValueOutOfRange(String),
+}
+
+impl From<FlatMeshError> for GeometryError {
+    fn from(error: FlatMeshError) -> Self {
+        match error {
+            FlatMeshError::UnknownNode {
+                element_tag,
+                node_tag,
+            } => GeometryError::UnknownNode {
+                element_tag,
+                node_tag,
+            },
+            FlatMeshError::ValueOutOfRange(value) => GeometryError::ValueOutOfRange(value),
+        }
+    }
+}
+
+pub(crate) fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub(crate) fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub(crate) fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+pub(crate) fn norm(a: [f64; 3]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+/// Returns the determinant of the matrix with the given columns
+pub(crate) fn det(a: [f64; 3], b: [f64; 3], c: [f64; 3]) -> f64 {
+    dot(a, cross(b, c))
+}
+
+/// Returns the unit normal of the polygon with the given vertices (Newell's method)
+///
+/// Returns `None` if the polygon is degenerate.
+pub(crate) fn polygon_normal(vertices: &[[f64; 3]]) -> Option<[f64; 3]> {
+    let mut normal = [0.0; 3];
+    for (i, &a) in vertices.iter().enumerate() {
+        let b = vertices[(i + 1) % vertices.len()];
+        let c = cross(a, b);
+        for k in 0..3 {
+            normal[k] += c[k];
+        }
+    }
+    let length = norm(normal);
+    if length > 0.0 {
+        Some(normal.map(|x| x / length))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,522 @@
+use std::collections::{BTreeMap, HashMap};
+use std::f64::consts::PI;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::flat_mesh::FlatMesh;
+use crate::geometry::{cross, det, norm, sub, GeometryError};
+use crate::mshfile::{ElementFamily, ElementType, MshFile, MshFloatT, MshIntT, MshUsizeT};
+
+/// Measure, centroid and normal of a single element
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ElementMeasure {
+    /// Tag of the element
+    pub element_tag: u64,
+    /// Type of the element
+    pub element_type: ElementType,
+    /// Dimension of the entity the element belongs to
+    pub entity_dim: i32,
+    /// Tag of the entity the element belongs to
+    pub entity_tag: i32,
+    /// Length, area or volume of the element, see [`measure`](fn.measure.html)
+    pub measure: f64,
+    /// Centroid of the element, see [`centroid`](fn.centroid.html)
+    pub centroid: [f64; 3],
+    /// Unit normal of surface elements, see [`normal`](fn.normal.html)
+    pub normal: Option<[f64; 3]>,
+}
+
+/// Integrals over an element that are required for its measure, centroid and normal
+struct Integrals {
+    /// Integral of one
+    measure: f64,
+    /// Integral of the position
+    moment: [f64; 3],
+    /// Integral of the (non-normalized) normal of surface elements
+    area_vector: [f64; 3],
+}
+
+/// Computes the measures, centroids and normals of all elements of the mesh
+///
+/// Elements without measure (e.g. polyhedra and the single node elements of order 0) are skipped.
+/// The elements are returned in the order of their blocks in the MSH file.
+///
+/// ```
+/// use mshio::geometry::measures::element_measures;
+/// # let msh_bytes = "\
+/// # $MeshFormat\n4.1 0 8\n$EndMeshFormat
+/// # $Nodes\n1 3 1 3\n2 1 0 3\n1\n2\n3\n0 0 0\n2 0 0\n0 2 0\n$EndNodes
+/// # $Elements\n1 1 1 1\n2 1 2 1\n1 1 2 3\n$EndElements\n";
+///
+/// // A mesh consisting of a single triangle in the xy-plane
+/// let msh = mshio::parse_msh_bytes(msh_bytes.as_bytes()).unwrap();
+/// let measures = element_measures(&msh).unwrap();
+///
+/// assert_eq!(measures[0].measure, 2.0);
+/// assert_eq!(measures[0].normal, Some([0.0, 0.0, 1.0]));
+/// ```
+pub fn element_measures<U, I, F>(
+    msh: &MshFile<U, I, F>,
+) -> Result<Vec<ElementMeasure>, GeometryError>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+{
+    let mesh = FlatMesh::new(msh)?;
+    let mut measures = Vec::with_capacity(mesh.cells.len());
+    for cell in &mesh.cells {
+        let nodes: Vec<[f64; 3]> = cell.nodes.iter().map(|&i| mesh.points[i]).collect();
+        if let Some(integrals) = integrals(cell.element_type, &nodes) {
+            measures.push(ElementMeasure {
+                element_tag: cell.element_tag,
+                element_type: cell.element_type,
+                entity_dim: cell.entity_dim,
+                entity_tag: cell.entity_tag,
+                measure: integrals.measure,
+                centroid: integrals.centroid(&nodes),
+                normal: integrals.normal(cell.element_type),
+            });
+        }
+    }
+    Ok(measures)
+}
+
+/// Returns the total measure of the elements of each entity by `(dim, tag)` of the entities
+///
+/// Only elements whose dimension matches the dimension of their entity are summed up, i.e. the
+/// totals are lengths of curves, areas of surfaces and volumes of volumes. Entities without such
+/// elements are not contained in the returned map.
+pub fn entity_measures<U, I, F>(
+    msh: &MshFile<U, I, F>,
+) -> Result<BTreeMap<(i32, i32), f64>, GeometryError>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+{
+    let mut totals = BTreeMap::new();
+    for measure in element_measures(msh)? {
+        if measure.element_type.dimension() as i32 == measure.entity_dim {
+            *totals
+                .entry((measure.entity_dim, measure.entity_tag))
+                .or_insert(0.0) += measure.measure;
+        }
+    }
+    Ok(totals)
+}
+
+/// Returns the total measure of the elements of each physical group by `(dim, tag)` of the groups
+///
+/// The physical groups of an element are the physical groups of its entity. As for
+/// [`entity_measures`](fn.entity_measures.html), only elements whose dimension matches the
+/// dimension of their entity are summed up.
+///
+/// ```
+/// use mshio::geometry::measures::physical_group_measures;
+/// # let msh_bytes = "\
+/// # $MeshFormat\n4.1 0 8\n$EndMeshFormat
+/// # $PhysicalNames\n1\n2 7 \"wall\"\n$EndPhysicalNames
+/// # $Entities\n0 0 1 0\n1 0 0 0 1 1 0 1 7 0\n$EndEntities
+/// # $Nodes\n1 4 1 4\n2 1 0 4\n1\n2\n3\n4\n0 0 0\n1 0 0\n1 1 0\n0 1 0\n$EndNodes
+/// # $Elements\n1 1 1 1\n2 1 3 1\n1 1 2 3 4\n$EndElements\n";
+///
+/// // The area of the physical surface "wall" consisting of the unit square
+/// let msh = mshio::parse_msh_bytes(msh_bytes.as_bytes()).unwrap();
+/// let wall = msh.physical_group_by_name("wall").unwrap();
+///
+/// let areas = physical_group_measures(&msh).unwrap();
+/// assert!((areas[&(wall.dim, wall.tag)] - 1.0).abs() < 1e-14);
+/// ```
+pub fn physical_group_measures<U, I, F>(
+    msh: &MshFile<U, I, F>,
+) -> Result<BTreeMap<(i32, i32), f64>, GeometryError>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+{
+    let mesh = FlatMesh::new(msh)?;
+    let mut totals = BTreeMap::new();
+    for cell in &mesh.cells {
+        if cell.element_type.dimension() as i32 != cell.entity_dim || cell.physical_tags.is_empty()
+        {
+            continue;
+        }
+        let nodes: Vec<[f64; 3]> = cell.nodes.iter().map(|&i| mesh.points[i]).collect();
+        if let Some(integrals) = integrals(cell.element_type, &nodes) {
+            for &tag in &cell.physical_tags {
+                *totals.entry((cell.entity_dim, tag)).or_insert(0.0) += integrals.measure;
+            }
+        }
+    }
+    Ok(totals)
+}
+
+/// Computes the length, area or volume of an element from the coordinates of all of its nodes
+///
+/// Linear lines, triangles and tetrahedra are evaluated exactly. All other elements are integrated
+/// numerically using Gauss quadrature rules (collapsed for triangles, tetrahedra, prisms and
+/// pyramids) whose number of points grows with the order of the element. The volumes of linear
+/// elements are exact. Points have a measure of zero.
+///
+/// Returns `None` for element types without reference nodes (e.g. polyhedra), for the single node
+/// elements of order 0 and if the number of nodes does not match the element type.
+/// ```
+/// use mshio::geometry::measures::measure;
+/// use mshio::mshfile::ElementType;
+///
+/// let nodes = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+/// assert_eq!(measure(ElementType::Tet4, &nodes), Some(1.0 / 6.0));
+///
+/// // A quarter of the unit circle with a quadratic arc
+/// let s = 0.5f64.sqrt();
+/// let nodes = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [s, s, 0.0]];
+/// let length = measure(ElementType::Lin3, &nodes).unwrap();
+/// assert!((length - std::f64::consts::FRAC_PI_2).abs() < 1e-2);
+/// ```
+pub fn measure(element_type: ElementType, nodes: &[[f64; 3]]) -> Option<f64> {
+    integrals(element_type, nodes).map(|integrals| integrals.measure)
+}
+
+/// Computes the centroid (center of mass) of an element from the coordinates of all of its nodes
+///
+/// The centroid is computed with the same integration as [`measure`](fn.measure.html). The
+/// centroid of degenerate elements (with a measure of zero) is the average of their nodes.
+/// Returns `None` in the same cases as `measure`.
+/// ```
+/// use mshio::geometry::measures::centroid;
+/// use mshio::mshfile::ElementType;
+///
+/// let nodes = [[0.0, 0.0, 0.0], [3.0, 0.0, 0.0], [0.0, 3.0, 0.0]];
+/// assert_eq!(centroid(ElementType::Tri3, &nodes), Some([1.0, 1.0, 0.0]));
+/// ```
+pub fn centroid(element_type: ElementType, nodes: &[[f64; 3]]) -> Option<[f64; 3]> {
+    integrals(element_type, nodes).map(|integrals| integrals.centroid(nodes))
+}
+
+/// Computes the unit normal of a surface element from the coordinates of all of its nodes
+///
+/// The normal is oriented by the node ordering of the element (right-hand rule), as Gmsh does for
+/// the elements of surfaces, i.e. it does not necessarily point out of an adjacent volume (see
+/// [`outward_normals`](fn.outward_normals.html)). For curved elements, the normal is the
+/// normalized integral of the normals over the element, i.e. the area weighted average normal.
+/// Returns `None` for all elements that are not surface elements, for degenerate elements and in
+/// the same cases as [`measure`](fn.measure.html).
+/// ```
+/// use mshio::geometry::measures::normal;
+/// use mshio::mshfile::ElementType;
+///
+/// let nodes = [[0.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 1.0, 1.0], [0.0, 0.0, 1.0]];
+/// assert_eq!(normal(ElementType::Qua4, &nodes), Some([1.0, 0.0, 0.0]));
+/// ```
+pub fn normal(element_type: ElementType, nodes: &[[f64; 3]]) -> Option<[f64; 3]> {
+    integrals(element_type, nodes)?.normal(element_type)
+}
+
+/// Computes the outward unit normals of the surface elements on the boundary of the volume elements
+///
+/// A surface element is on the boundary if it has the same vertices as a face of exactly one
+/// volume element (see [`Elements::extract_boundary`](../../mshfile/struct.Elements.html#method.extract_boundary)).
+/// Its normal (see [`normal`](fn.normal.html)) is flipped if its node ordering is opposite to the
+/// outward orientation of the face, so the returned normal points away from the volume element
+/// independently of the node ordering. Surface elements inside the volume mesh (e.g. on
+/// interfaces between volumes) or without an adjacent volume element have no outward direction and
+/// are not contained in the returned map, which is indexed by element tag.
+///
+/// ```
+/// use mshio::geometry::measures::outward_normals;
+/// # let msh_bytes = "\
+/// # $MeshFormat\n4.1 0 8\n$EndMeshFormat
+/// # $Nodes\n1 4 1 4\n3 1 0 4\n1\n2\n3\n4\n0 0 0\n1 0 0\n0 1 0\n0 0 1\n$EndNodes
+/// # $Elements\n2 2 1 2\n3 1 4 1\n1 1 2 3 4\n2 1 2 1\n2 1 2 3\n$EndElements\n";
+///
+/// // A tetrahedron above the xy-plane and a triangle on its bottom face whose node ordering gives
+/// // a normal pointing into the tetrahedron
+/// let msh = mshio::parse_msh_bytes(msh_bytes.as_bytes()).unwrap();
+/// let normals = outward_normals(&msh).unwrap();
+///
+/// assert_eq!(normals[&2], [0.0, 0.0, -1.0]);
+/// ```
+pub fn outward_normals<U, I, F>(
+    msh: &MshFile<U, I, F>,
+) -> Result<BTreeMap<u64, [f64; 3]>, GeometryError>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+{
+    let mesh = FlatMesh::new(msh)?;
+    let elements = match &msh.data.elements {
+        Some(elements) => elements,
+        None => return Ok(BTreeMap::new()),
+    };
+
+    // Outward oriented boundary faces of the volume elements by the tag of their surface element
+    let boundary = elements.extract_boundary(3, I::zero());
+    let mut outward_faces = HashMap::new();
+    let boundary_elements = boundary
+        .element_blocks
+        .iter()
+        .flat_map(|block| block.elements.iter());
+    for (element, face) in boundary_elements.zip(&boundary.faces) {
+        if let Some((_, tag)) = face.covered_by {
+            outward_faces.insert(tag, &element.nodes);
+        }
+    }
+
+    // The cells of the flat mesh are in the order of the elements
+    let mut normals = BTreeMap::new();
+    let mut cells = mesh.cells.iter();
+    for block in &elements.element_blocks {
+        let num_vertices = block.element_type.vertex_count().unwrap_or(0);
+        for (element, cell) in block.elements.iter().zip(&mut cells) {
+            let face = match outward_faces.get(&element.element_tag) {
+                Some(face) => face,
+                None => continue,
+            };
+            let nodes: Vec<[f64; 3]> = cell.nodes.iter().map(|&i| mesh.points[i]).collect();
+            if let Some(normal) = normal(cell.element_type, &nodes) {
+                let vertices = &element.nodes[..num_vertices];
+                let outward = if same_orientation(vertices, &face[..num_vertices]) {
+                    normal
+                } else {
+                    normal.map(|x| -x)
+                };
+                normals.insert(cell.element_tag, outward);
+            }
+        }
+    }
+    Ok(normals)
+}
+
+/// Returns whether two faces with the same vertices traverse them in the same cyclic order
+fn same_orientation<U: PartialEq>(a: &[U], b: &[U]) -> bool {
+    match (a, b.iter().position(|v| Some(v) == a.first())) {
+        ([_, second, ..], Some(p)) => b[(p + 1) % b.len()] == *second,
+        _ => false,
+    }
+}
+
+impl Integrals {
+    fn centroid(&self, nodes: &[[f64; 3]]) -> [f64; 3] {
+        if self.measure > 0.0 {
+            self.moment.map(|x| x / self.measure)
+        } else {
+            average(nodes)
+        }
+    }
+
+    fn normal(&self, element_type: ElementType) -> Option<[f64; 3]> {
+        let length = norm(self.area_vector);
+        if element_type.dimension() == 2 && length > 0.0 {
+            Some(self.area_vector.map(|x| x / length))
+        } else {
+            None
+        }
+    }
+}
+
+/// Integrates over an element of the given type, see [`measure`] for the supported types
+fn integrals(element_type: ElementType, nodes: &[[f64; 3]]) -> Option<Integrals> {
+    let family = element_type.family();
+    if element_type.reference_nodes().is_none()
+        || element_type.nodes() != Ok(nodes.len())
+        || (element_type.order() == 0 && family != ElementFamily::Point)
+    {
+        return None;
+    }
+
+    // Exact formulas for points and linear simplices
+    let simplex_measure = match family {
+        _ if element_type.order() > 1 => None,
+        ElementFamily::Point => Some(0.0),
+        ElementFamily::Line => Some(norm(sub(nodes[1], nodes[0]))),
+        ElementFamily::Triangle => {
+            Some(norm(cross(sub(nodes[1], nodes[0]), sub(nodes[2], nodes[0]))) / 2.0)
+        }
+        ElementFamily::Tetrahedron => Some(
+            det(
+                sub(nodes[1], nodes[0]),
+                sub(nodes[2], nodes[0]),
+                sub(nodes[3], nodes[0]),
+            )
+            .abs()
+                / 6.0,
+        ),
+        _ => None,
+    };
+    if let Some(measure) = simplex_measure {
+        let area_vector = if family == ElementFamily::Triangle {
+            cross(sub(nodes[1], nodes[0]), sub(nodes[2], nodes[0])).map(|x| x / 2.0)
+        } else {
+            [0.0; 3]
+        };
+        return Some(Integrals {
+            measure,
+            moment: average(nodes).map(|x| x * measure),
+            area_vector,
+        });
+    }
+
+    let mut integrals = Integrals {
+        measure: 0.0,
+        moment: [0.0; 3],
+        area_vector: [0.0; 3],
+    };
+    for (xi, weight) in quadrature(family, element_type.order() + 3) {
+        let shape_functions = element_type.shape_functions(xi)?;
+        let gradients = element_type.shape_gradients(xi)?;
+
+        let mut x = [0.0; 3];
+        let mut columns = [[0.0; 3]; 3];
+        for ((&n, gradient), node) in shape_functions.iter().zip(&gradients).zip(nodes) {
+            for k in 0..3 {
+                x[k] += n * node[k];
+                for (column, &g) in columns.iter_mut().zip(gradient) {
+                    column[k] += g * node[k];
+                }
+            }
+        }
+
+        let [a, b, c] = columns;
+        let density = match family.dimension() {
+            1 => norm(a),
+            2 => {
+                let normal = cross(a, b);
+                for (sum, n) in integrals.area_vector.iter_mut().zip(normal) {
+                    *sum += weight * n;
+                }
+                norm(normal)
+            }
+            _ => det(a, b, c).abs(),
+        };
+        integrals.measure += weight * density;
+        for (sum, x) in integrals.moment.iter_mut().zip(x) {
+            *sum += weight * density * x;
+        }
+    }
+    Some(integrals)
+}
+
+/// Returns the quadrature points and weights on the reference domain of the element family
+///
+/// The rules are tensor products of Gauss-Legendre rules with `n` points per direction. Triangles,
+/// tetrahedra and pyramids are collapsed from squares and cubes (Duffy transformation).
+fn quadrature(family: ElementFamily, n: usize) -> Vec<([f64; 3], f64)> {
+    let gauss = gauss_legendre(n);
+    let unit: Vec<(f64, f64)> = gauss
+        .iter()
+        .map(|&(x, w)| ((x + 1.0) / 2.0, w / 2.0))
+        .collect();
+
+    let mut points = Vec::new();
+    match family {
+        ElementFamily::Line => {
+            points.extend(gauss.iter().map(|&(x, w)| ([x, 0.0, 0.0], w)));
+        }
+        ElementFamily::Quadrangle => {
+            for &(x, wx) in &gauss {
+                for &(y, wy) in &gauss {
+                    points.push(([x, y, 0.0], wx * wy));
+                }
+            }
+        }
+        ElementFamily::Hexahedron => {
+            for &(x, wx) in &gauss {
+                for &(y, wy) in &gauss {
+                    for &(z, wz) in &gauss {
+                        points.push(([x, y, z], wx * wy * wz));
+                    }
+                }
+            }
+        }
+        ElementFamily::Triangle | ElementFamily::Prism => {
+            let heights: &[(f64, f64)] = if family == ElementFamily::Prism {
+                &gauss
+            } else {
+                &[(0.0, 1.0)]
+            };
+            for &(u, wu) in &unit {
+                for &(v, wv) in &unit {
+                    for &(z, wz) in heights {
+                        points.push(([u, v * (1.0 - u), z], wu * wv * wz * (1.0 - u)));
+                    }
+                }
+            }
+        }
+        ElementFamily::Tetrahedron => {
+            for &(u, wu) in &unit {
+                for &(v, wv) in &unit {
+                    for &(w, ww) in &unit {
+                        let scale = (1.0 - u) * (1.0 - v);
+                        points.push((
+                            [u, v * (1.0 - u), w * scale],
+                            wu * wv * ww * (1.0 - u) * scale,
+                        ));
+                    }
+                }
+            }
+        }
+        ElementFamily::Pyramid => {
+            for &(x, wx) in &gauss {
+                for &(y, wy) in &gauss {
+                    for &(z, wz) in &unit {
+                        let scale = 1.0 - z;
+                        points.push(([x * scale, y * scale, z], wx * wy * wz * scale * scale));
+                    }
+                }
+            }
+        }
+        ElementFamily::Point
+        | ElementFamily::Polygon
+        | ElementFamily::Polyhedron
+        | ElementFamily::Trihedron => {}
+    }
+    points
+}
+
+/// Returns the points and weights of the Gauss-Legendre rule with `n` points on `[-1, 1]`
+fn gauss_legendre(n: usize) -> Vec<(f64, f64)> {
+    (0..n)
+        .map(|i| {
+            // Newton iteration starting from an approximation of the i-th root
+            let mut x = (PI * (i as f64 + 0.75) / (n as f64 + 0.5)).cos();
+            for _ in 0..100 {
+                let (p, dp) = legendre(n, x);
+                let dx = p / dp;
+                x -= dx;
+                if dx.abs() < 1e-16 {
+                    break;
+                }
+            }
+            let (_, dp) = legendre(n, x);
+            (x, 2.0 / ((1.0 - x * x) * dp * dp))
+        })
+        .collect()
+}
+
+/// Evaluates the Legendre polynomial `P_n` (`n > 0`) and its derivative at `x`
+fn legendre(n: usize, x: f64) -> (f64, f64) {
+    let (mut previous, mut current) = (1.0, x);
+    for k in 2..=n {
+        let kf = k as f64;
+        let next = ((2.0 * kf - 1.0) * x * current - (kf - 1.0) * previous) / kf;
+        previous = current;
+        current = next;
+    }
+    let derivative = n as f64 * (x * current - previous) / (x * x - 1.0);
+    (current, derivative)
+}
+
+fn average(nodes: &[[f64; 3]]) -> [f64; 3] {
+    let mut sum = [0.0; 3];
+    for node in nodes {
+        for k in 0..3 {
+            sum[k] += node[k];
+        }
+    }
+    sum.map(|x| x / nodes.len() as f64)
+}
@@ -0,0 +1,668 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::flat_mesh::FlatMesh;
+use crate::geometry::{cross, det, dot, norm, polygon_normal, sub, GeometryError};
+use crate::mshfile::{ElementFamily, ElementType, MshFile, MshFloatT, MshIntT, MshUsizeT};
+
+/// Adjacent vertices of each corner of the volume element families
+///
+/// The edges from a corner to its adjacent vertices form a right-handed system for valid elements.
+const TETRAHEDRON_CORNERS: [(usize, [usize; 3]); 4] = [
+    (0, [1, 2, 3]),
+    (1, [2, 0, 3]),
+    (2, [0, 1, 3]),
+    (3, [0, 2, 1]),
+];
+const HEXAHEDRON_CORNERS: [(usize, [usize; 3]); 8] = [
+    (0, [1, 3, 4]),
+    (1, [2, 0, 5]),
+    (2, [3, 1, 6]),
+    (3, [0, 2, 7]),
+    (4, [7, 5, 0]),
+    (5, [4, 6, 1]),
+    (6, [5, 7, 2]),
+    (7, [6, 4, 3]),
+];
+const PRISM_CORNERS: [(usize, [usize; 3]); 6] = [
+    (0, [1, 2, 3]),
+    (1, [2, 0, 4]),
+    (2, [0, 1, 5]),
+    (3, [5, 4, 0]),
+    (4, [3, 5, 1]),
+    (5, [4, 3, 2]),
+];
+/// The apex of pyramids is not a regular corner and is skipped
+const PYRAMID_CORNERS: [(usize, [usize; 3]); 4] = [
+    (0, [1, 3, 4]),
+    (1, [2, 0, 4]),
+    (2, [3, 1, 4]),
+    (3, [0, 2, 4]),
+];
+
+/// Quality metrics of a single element
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ElementQuality {
+    /// Tag of the element
+    pub element_tag: u64,
+    /// Type of the element
+    pub element_type: ElementType,
+    /// Dimension of the entity the element belongs to
+    pub entity_dim: i32,
+    /// Tag of the entity the element belongs to
+    pub entity_tag: i32,
+    /// Minimum scaled Jacobian, see [`scaled_jacobian`](fn.scaled_jacobian.html)
+    pub scaled_jacobian: Option<f64>,
+    /// Aspect ratio, see [`aspect_ratio`](fn.aspect_ratio.html)
+    pub aspect_ratio: Option<f64>,
+    /// Radius ratio, see [`radius_ratio`](fn.radius_ratio.html)
+    pub radius_ratio: Option<f64>,
+    /// Equiangle skewness, see [`skewness`](fn.skewness.html)
+    pub skewness: Option<f64>,
+}
+
+/// The quality metrics that can be summarized by [`summarize`](fn.summarize.html)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum QualityMetric {
+    ScaledJacobian,
+    AspectRatio,
+    RadiusRatio,
+    Skewness,
+}
+
+impl QualityMetric {
+    /// Returns the value of this metric of the given element
+    pub fn value(&self, quality: &ElementQuality) -> Option<f64> {
+        match self {
+            QualityMetric::ScaledJacobian => quality.scaled_jacobian,
+            QualityMetric::AspectRatio => quality.aspect_ratio,
+            QualityMetric::RadiusRatio => quality.radius_ratio,
+            QualityMetric::Skewness => quality.skewness,
+        }
+    }
+
+    /// Returns whether larger values of this metric indicate better shaped elements
+    ///
+    /// This is only the case for the scaled Jacobian, all other metrics grow as elements degrade.
+    pub fn larger_is_better(&self) -> bool {
+        *self == QualityMetric::ScaledJacobian
+    }
+}
+
+/// Histogram with bins of equal width
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Histogram {
+    /// Boundaries of the bins, bin `i` ranges from `bin_edges[i]` to `bin_edges[i + 1]`
+    pub bin_edges: Vec<f64>,
+    /// Number of values in each bin
+    pub counts: Vec<usize>,
+}
+
+/// Summary of a quality metric over all elements it is defined for
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QualitySummary {
+    /// The summarized metric
+    pub metric: QualityMetric,
+    /// Number of elements the metric is defined for
+    pub count: usize,
+    /// Minimum value of the metric
+    pub min: f64,
+    /// Maximum value of the metric
+    pub max: f64,
+    /// Mean value of the metric
+    pub mean: f64,
+    /// Distribution of the values
+    pub histogram: Histogram,
+    /// Tags and values of the worst elements, starting with the worst one
+    pub worst: Vec<(u64, f64)>,
+}
+
+/// Computes the quality metrics of all surface and volume elements of the mesh
+///
+/// Elements of all Lagrange types of the triangle, quadrangle, tetrahedron, hexahedron, prism and
+/// pyramid families are evaluated, all other elements (e.g. points, lines and polygons) are
+/// skipped. The elements are returned in the order of their blocks in the MSH file.
+///
+/// The scaled Jacobian of surface elements is measured against the dominant normal of their
+/// entity, i.e. the sum of the area vectors of all of its surface elements (see
+/// [`oriented_scaled_jacobian`](fn.oriented_scaled_jacobian.html)), so elements oriented against
+/// the majority of their entity are reported as inverted. Closed surfaces have no dominant normal
+/// and their elements are measured against their own normal.
+///
+/// ```
+/// use mshio::geometry::quality::element_qualities;
+/// # let msh_bytes = "\
+/// # $MeshFormat\n4.1 0 8\n$EndMeshFormat
+/// # $Nodes\n1 3 1 3\n2 1 0 3\n1\n2\n3\n0 0 0\n1 0 0\n0 1 0\n$EndNodes
+/// # $Elements\n1 1 1 1\n2 1 2 1\n1 1 2 3\n$EndElements\n";
+///
+/// // A mesh consisting of a single right isosceles triangle
+/// let msh = mshio::parse_msh_bytes(msh_bytes.as_bytes()).unwrap();
+/// let qualities = element_qualities(&msh).unwrap();
+///
+/// let scaled_jacobian = qualities[0].scaled_jacobian.unwrap();
+/// assert!((scaled_jacobian - 2f64.sqrt() / 3f64.sqrt()).abs() < 1e-14);
+/// ```
+pub fn element_qualities<U, I, F>(
+    msh: &MshFile<U, I, F>,
+) -> Result<Vec<ElementQuality>, GeometryError>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+{
+    let mesh = FlatMesh::new(msh)?;
+
+    // Sum of the area vectors of the surface elements of each entity
+    let mut entity_areas: HashMap<(i32, i32), ([f64; 3], f64)> = HashMap::new();
+    for cell in &mesh.cells {
+        let nodes: Vec<[f64; 3]> = cell.nodes.iter().map(|&i| mesh.points[i]).collect();
+        if let Some((ElementFamily::Triangle | ElementFamily::Quadrangle, vertices)) =
+            element_vertices(cell.element_type, &nodes)
+        {
+            let area = area_vector(vertices);
+            let (sum, total) = entity_areas
+                .entry((cell.entity_dim, cell.entity_tag))
+                .or_insert(([0.0; 3], 0.0));
+            for k in 0..3 {
+                sum[k] += area[k];
+            }
+            *total += norm(area);
+        }
+    }
+    // Closed surfaces have no dominant normal
+    let dominant_normals: HashMap<(i32, i32), [f64; 3]> = entity_areas
+        .into_iter()
+        .filter(|(_, (sum, total))| norm(*sum) > 1e-10 * total)
+        .map(|(entity, (sum, _))| (entity, sum))
+        .collect();
+
+    let mut qualities = Vec::new();
+    for cell in &mesh.cells {
+        let nodes: Vec<[f64; 3]> = cell.nodes.iter().map(|&i| mesh.points[i]).collect();
+        let reference = dominant_normals
+            .get(&(cell.entity_dim, cell.entity_tag))
+            .copied();
+        let scaled_jacobian = scaled_jacobian_against(cell.element_type, &nodes, reference);
+        if scaled_jacobian.is_none() {
+            continue;
+        }
+
+        qualities.push(ElementQuality {
+            element_tag: cell.element_tag,
+            element_type: cell.element_type,
+            entity_dim: cell.entity_dim,
+            entity_tag: cell.entity_tag,
+            scaled_jacobian,
+            aspect_ratio: aspect_ratio(cell.element_type, &nodes),
+            radius_ratio: radius_ratio(cell.element_type, &nodes),
+            skewness: skewness(cell.element_type, &nodes),
+        });
+    }
+    Ok(qualities)
+}
+
+/// Summarizes a quality metric over all elements it is defined for
+///
+/// The histogram uses `num_bins` bins (at least one) of equal width between the smallest and the
+/// largest finite value, infinite values are counted in the outermost bins. The `num_worst`
+/// worst elements are listed starting with the worst one, elements with equal values are kept in
+/// their original order. Returns `None` if the metric is not defined for any of the elements.
+///
+/// ```
+/// use mshio::geometry::quality::{element_qualities, summarize, QualityMetric};
+/// # let msh_bytes = "\
+/// # $MeshFormat\n4.1 0 8\n$EndMeshFormat
+/// # $Nodes\n1 4 1 4\n2 1 0 4\n1\n2\n3\n4\n0 0 0\n1 0 0\n0 1 0\n3 1 0\n$EndNodes
+/// # $Elements\n1 2 1 2\n2 1 2 2\n1 1 2 3\n2 2 4 3\n$EndElements\n";
+///
+/// // A right isosceles triangle (element 1) and a stretched triangle (element 2)
+/// let msh = mshio::parse_msh_bytes(msh_bytes.as_bytes()).unwrap();
+/// let qualities = element_qualities(&msh).unwrap();
+///
+/// let summary = summarize(&qualities, QualityMetric::AspectRatio, 4, 1).unwrap();
+/// assert_eq!(summary.count, 2);
+/// assert_eq!(summary.histogram.counts, vec![1, 0, 0, 1]);
+/// assert_eq!(summary.worst[0].0, 2);
+/// ```
+pub fn summarize(
+    qualities: &[ElementQuality],
+    metric: QualityMetric,
+    num_bins: usize,
+    num_worst: usize,
+) -> Option<QualitySummary> {
+    let values: Vec<(u64, f64)> = qualities
+        .iter()
+        .filter_map(|q| metric.value(q).map(|value| (q.element_tag, value)))
+        .collect();
+    if values.is_empty() {
+        return None;
+    }
+
+    let min = values.iter().map(|v| v.1).fold(f64::INFINITY, f64::min);
+    let max = values.iter().map(|v| v.1).fold(f64::NEG_INFINITY, f64::max);
+    let mean = values.iter().map(|v| v.1).sum::<f64>() / values.len() as f64;
+
+    // The bins cover the range of the finite values
+    let finite = || values.iter().map(|v| v.1).filter(|v| v.is_finite());
+    let lower = finite().fold(f64::INFINITY, f64::min);
+    let upper = finite().fold(f64::NEG_INFINITY, f64::max);
+    let (lower, upper) = if lower <= upper {
+        (lower, upper)
+    } else {
+        (min, max)
+    };
+
+    let num_bins = num_bins.max(1);
+    let width = (upper - lower) / num_bins as f64;
+    let bin_edges = (0..=num_bins)
+        .map(|i| {
+            if i == num_bins {
+                upper
+            } else {
+                lower + i as f64 * width
+            }
+        })
+        .collect();
+    let mut counts = vec![0; num_bins];
+    for &(_, value) in &values {
+        let bin = if value >= upper {
+            num_bins - 1
+        } else if value <= lower {
+            0
+        } else {
+            (((value - lower) / width) as usize).min(num_bins - 1)
+        };
+        counts[bin] += 1;
+    }
+
+    let mut worst = values.clone();
+    if metric.larger_is_better() {
+        worst.sort_by(|a, b| a.1.total_cmp(&b.1));
+    } else {
+        worst.sort_by(|a, b| b.1.total_cmp(&a.1));
+    }
+    worst.truncate(num_worst);
+
+    Some(QualitySummary {
+        metric,
+        count: values.len(),
+        min,
+        max,
+        mean,
+        histogram: Histogram { bin_edges, counts },
+        worst,
+    })
+}
+
+/// Computes the minimum scaled Jacobian of an element from the coordinates of all of its nodes
+///
+/// For linear elements, this is the minimum over all corners of the determinant of the edges at
+/// the corner divided by the product of their lengths, scaled such that the ideal element has the
+/// value one, and clamped to `[-1, 1]`. Pyramids skip the apex. Surface elements use the unit normal of their vertex polygon
+/// instead of a third edge, i.e. they can only be detected as inverted if the orientation changes
+/// within the element (e.g. non-convex quadrangles). Use
+/// [`oriented_scaled_jacobian`](fn.oriented_scaled_jacobian.html) to detect inverted surface
+/// elements against a reference orientation.
+///
+/// Higher order elements additionally sample the scaled Jacobian of their geometric mapping at
+/// all of their reference nodes (except for pyramids, whose rational mapping is singular at the
+/// apex) and return the minimum of all samples and the corners of their vertices.
+///
+/// Returns `None` for unsupported element types (see [`element_qualities`](fn.element_qualities.html))
+/// or if the number of nodes does not match the element type. Elements with edges of zero
+/// length have a scaled Jacobian of zero.
+/// ```
+/// use mshio::geometry::quality::scaled_jacobian;
+/// use mshio::mshfile::ElementType;
+///
+/// let cube = [
+///     [0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0],
+///     [0.0, 0.0, 1.0], [1.0, 0.0, 1.0], [1.0, 1.0, 1.0], [0.0, 1.0, 1.0],
+/// ];
+/// assert_eq!(scaled_jacobian(ElementType::Hex8, &cube), Some(1.0));
+///
+/// // Swapping the bottom and top face inverts the element
+/// let inverted = [&cube[4..], &cube[..4]].concat();
+/// assert_eq!(scaled_jacobian(ElementType::Hex8, &inverted), Some(-1.0));
+/// ```
+pub fn scaled_jacobian(element_type: ElementType, nodes: &[[f64; 3]]) -> Option<f64> {
+    scaled_jacobian_against(element_type, nodes, None)
+}
+
+/// Computes the minimum scaled Jacobian of an element relative to a reference orientation
+///
+/// Same as [`scaled_jacobian`](fn.scaled_jacobian.html), except that surface elements are
+/// measured against the side of their vertex polygon that faces the `reference` normal (e.g. the
+/// normal of their entity or `+z` for planar meshes). Surface elements whose nodes are ordered
+/// clockwise when viewed from the reference direction are inverted and have a negative scaled
+/// Jacobian. The reference does not need to be normalized and is ignored for volume elements.
+/// ```
+/// use mshio::geometry::quality::oriented_scaled_jacobian;
+/// use mshio::mshfile::ElementType;
+///
+/// let h = 3f64.sqrt() / 2.0;
+/// let triangle = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.5, h, 0.0]];
+/// let up = [0.0, 0.0, 1.0];
+/// let value = oriented_scaled_jacobian(ElementType::Tri3, &triangle, up).unwrap();
+/// assert!((value - 1.0).abs() < 1e-14);
+///
+/// // Reversing the node order flips the triangle
+/// let flipped = [triangle[0], triangle[2], triangle[1]];
+/// let value = oriented_scaled_jacobian(ElementType::Tri3, &flipped, up).unwrap();
+/// assert!((value + 1.0).abs() < 1e-14);
+/// ```
+pub fn oriented_scaled_jacobian(
+    element_type: ElementType,
+    nodes: &[[f64; 3]],
+    reference: [f64; 3],
+) -> Option<f64> {
+    scaled_jacobian_against(element_type, nodes, Some(reference))
+}
+
+/// Computes the scaled Jacobian with surface elements oriented towards the optional reference
+fn scaled_jacobian_against(
+    element_type: ElementType,
+    nodes: &[[f64; 3]],
+    reference: Option<[f64; 3]>,
+) -> Option<f64> {
+    let (family, vertices) = element_vertices(element_type, nodes)?;
+    let factor = scaling_factor(family);
+
+    let normal = match (polygon_normal(vertices), reference) {
+        (Some(normal), Some(reference)) if dot(normal, reference) < 0.0 => Some(normal.map(|x| -x)),
+        (normal, _) => normal,
+    };
+    let mut min = if family.dimension() == 2 {
+        let normal = match normal {
+            Some(normal) => normal,
+            None => return Some(0.0),
+        };
+        let n = vertices.len();
+        (0..n)
+            .map(|i| {
+                let a = sub(vertices[(i + 1) % n], vertices[i]);
+                let b = sub(vertices[(i + n - 1) % n], vertices[i]);
+                scaled_determinant(dot(cross(a, b), normal), &[a, b])
+            })
+            .fold(f64::INFINITY, f64::min)
+    } else {
+        volume_corners(family)
+            .iter()
+            .map(|&(corner, [i, j, k])| {
+                let a = sub(vertices[i], vertices[corner]);
+                let b = sub(vertices[j], vertices[corner]);
+                let c = sub(vertices[k], vertices[corner]);
+                scaled_determinant(det(a, b, c), &[a, b, c])
+            })
+            .fold(f64::INFINITY, f64::min)
+    };
+
+    if element_type.order() > 1 && family != ElementFamily::Pyramid {
+        for &xi in element_type.reference_nodes()? {
+            let gradients = element_type.shape_gradients(xi)?;
+            let mut columns = [[0.0; 3]; 3];
+            for (gradient, x) in gradients.iter().zip(nodes) {
+                for (column, &g) in columns.iter_mut().zip(gradient) {
+                    for k in 0..3 {
+                        column[k] += g * x[k];
+                    }
+                }
+            }
+
+            let [a, b, c] = columns;
+            let sample = match (family.dimension(), normal) {
+                (2, Some(normal)) => scaled_determinant(dot(cross(a, b), normal), &[a, b]),
+                (2, None) => 0.0,
+                _ => scaled_determinant(det(a, b, c), &[a, b, c]),
+            };
+            min = min.min(sample);
+        }
+    }
+
+    Some((factor * min).clamp(-1.0, 1.0))
+}
+
+/// Computes the aspect ratio of an element from the coordinates of its nodes
+///
+/// The aspect ratio of triangles and tetrahedra is the ratio of the longest edge to the inradius,
+/// normalized such that the equilateral element has the value one. For all other families, it is
+/// the ratio of the longest to the shortest edge. Higher order elements are evaluated using their
+/// vertices only. Degenerate elements have an infinite aspect ratio.
+///
+/// Returns `None` for unsupported element types (see [`element_qualities`](fn.element_qualities.html))
+/// or if the number of nodes does not match the element type.
+/// ```
+/// use mshio::geometry::quality::aspect_ratio;
+/// use mshio::mshfile::ElementType;
+///
+/// let rectangle = [[0.0, 0.0, 0.0], [4.0, 0.0, 0.0], [4.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+/// assert_eq!(aspect_ratio(ElementType::Qua4, &rectangle), Some(4.0));
+/// ```
+pub fn aspect_ratio(element_type: ElementType, nodes: &[[f64; 3]]) -> Option<f64> {
+    let (family, vertices) = element_vertices(element_type, nodes)?;
+    let edges = edge_lengths(element_type, vertices)?;
+    let longest = edges.iter().copied().fold(0.0, f64::max);
+
+    let ratio = match family {
+        ElementFamily::Triangle => {
+            let inradius = triangle_inradius(vertices);
+            longest / (2.0 * 3f64.sqrt() * inradius)
+        }
+        ElementFamily::Tetrahedron => {
+            let inradius = tetrahedron_inradius(vertices);
+            longest / (2.0 * 6f64.sqrt() * inradius)
+        }
+        _ => longest / edges.iter().copied().fold(f64::INFINITY, f64::min),
+    };
+    Some(if ratio.is_nan() { f64::INFINITY } else { ratio })
+}
+
+/// Computes the radius ratio of a triangle or tetrahedron from the coordinates of its nodes
+///
+/// The radius ratio is the ratio of the circumradius to the inradius, normalized such that the
+/// equilateral element has the value one. Higher order elements are evaluated using their
+/// vertices only. Degenerate elements have an infinite radius ratio.
+///
+/// Returns `None` for all other element families, for unsupported element types and if the
+/// number of nodes does not match the element type.
+/// ```
+/// use mshio::geometry::quality::radius_ratio;
+/// use mshio::mshfile::ElementType;
+///
+/// let equilateral = [[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [1.0, 3f64.sqrt(), 0.0]];
+/// let ratio = radius_ratio(ElementType::Tri3, &equilateral).unwrap();
+/// assert!((ratio - 1.0).abs() < 1e-14);
+/// ```
+pub fn radius_ratio(element_type: ElementType, nodes: &[[f64; 3]]) -> Option<f64> {
+    let (family, vertices) = element_vertices(element_type, nodes)?;
+
+    let ratio = match family {
+        ElementFamily::Triangle => {
+            let [a, b, c] =
+                [(0, 1), (1, 2), (2, 0)].map(|(i, j)| norm(sub(vertices[j], vertices[i])));
+            let area = triangle_area(vertices);
+            let circumradius = a * b * c / (4.0 * area);
+            circumradius / (2.0 * triangle_inradius(vertices))
+        }
+        ElementFamily::Tetrahedron => {
+            let [a, b, c] = [1, 2, 3].map(|i| sub(vertices[i], vertices[0]));
+            let center = [
+                (dot(a, a), cross(b, c)),
+                (dot(b, b), cross(c, a)),
+                (dot(c, c), cross(a, b)),
+            ]
+            .iter()
+            .fold([0.0; 3], |sum, &(s, v)| {
+                [sum[0] + s * v[0], sum[1] + s * v[1], sum[2] + s * v[2]]
+            });
+            let circumradius = norm(center) / (2.0 * det(a, b, c).abs());
+            circumradius / (3.0 * tetrahedron_inradius(vertices))
+        }
+        _ => return None,
+    };
+    Some(if ratio.is_finite() {
+        ratio
+    } else {
+        f64::INFINITY
+    })
+}
+
+/// Computes the equiangle skewness of an element from the coordinates of its nodes
+///
+/// The skewness of a face is the maximum relative deviation of its angles from the angles of the
+/// equilateral triangle or square, i.e. `max((θmax - θe) / (180° - θe), (θe - θmin) / θe)`. The
+/// skewness of an element is the maximum skewness of its faces (surface elements are a single
+/// face). Higher order elements are evaluated using their vertices only. The skewness is zero for
+/// ideal elements and one for degenerate elements.
+///
+/// Returns `None` for unsupported element types (see [`element_qualities`](fn.element_qualities.html))
+/// or if the number of nodes does not match the element type.
+/// ```
+/// use mshio::geometry::quality::skewness;
+/// use mshio::mshfile::ElementType;
+///
+/// let right_triangle = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+/// let skew = skewness(ElementType::Tri3, &right_triangle).unwrap();
+/// assert!((skew - 0.25).abs() < 1e-14);
+/// ```
+pub fn skewness(element_type: ElementType, nodes: &[[f64; 3]]) -> Option<f64> {
+    let (family, vertices) = element_vertices(element_type, nodes)?;
+
+    let mut faces: Vec<Vec<usize>> = Vec::new();
+    if family.dimension() == 2 {
+        faces.push((0..vertices.len()).collect());
+    } else {
+        for (face, face_type) in element_type.faces()?.iter().zip(element_type.face_types()?) {
+            faces.push(face[..face_type.vertex_count()?].to_vec());
+        }
+    }
+
+    let mut max_skewness: f64 = 0.0;
+    for face in faces {
+        let n = face.len();
+        let ideal_angle = PI * (n - 2) as f64 / n as f64;
+        let mut min_angle = PI;
+        let mut max_angle: f64 = 0.0;
+        for i in 0..n {
+            let a = sub(vertices[face[(i + 1) % n]], vertices[face[i]]);
+            let b = sub(vertices[face[(i + n - 1) % n]], vertices[face[i]]);
+            let lengths = norm(a) * norm(b);
+            if lengths == 0.0 {
+                return Some(1.0);
+            }
+            let angle = (dot(a, b) / lengths).clamp(-1.0, 1.0).acos();
+            min_angle = min_angle.min(angle);
+            max_angle = max_angle.max(angle);
+        }
+        max_skewness = max_skewness
+            .max((max_angle - ideal_angle) / (PI - ideal_angle))
+            .max((ideal_angle - min_angle) / ideal_angle);
+    }
+    Some(max_skewness)
+}
+
+/// Returns the family and the vertex coordinates of an element of a supported type
+fn element_vertices(
+    element_type: ElementType,
+    nodes: &[[f64; 3]],
+) -> Option<(ElementFamily, &[[f64; 3]])> {
+    let family = element_type.family();
+    let supported = matches!(
+        family,
+        ElementFamily::Triangle
+            | ElementFamily::Quadrangle
+            | ElementFamily::Tetrahedron
+            | ElementFamily::Hexahedron
+            | ElementFamily::Prism
+            | ElementFamily::Pyramid
+    );
+    let num_vertices = element_type.vertex_count()?;
+    if !supported || element_type.nodes() != Ok(nodes.len()) {
+        return None;
+    }
+    Some((family, &nodes[..num_vertices]))
+}
+
+/// Returns the factor that normalizes the scaled Jacobian of the ideal element of the family to one
+fn scaling_factor(family: ElementFamily) -> f64 {
+    match family {
+        ElementFamily::Triangle | ElementFamily::Prism => 2.0 / 3f64.sqrt(),
+        ElementFamily::Tetrahedron | ElementFamily::Pyramid => 2f64.sqrt(),
+        _ => 1.0,
+    }
+}
+
+fn volume_corners(family: ElementFamily) -> &'static [(usize, [usize; 3])] {
+    match family {
+        ElementFamily::Tetrahedron => &TETRAHEDRON_CORNERS,
+        ElementFamily::Hexahedron => &HEXAHEDRON_CORNERS,
+        ElementFamily::Prism => &PRISM_CORNERS,
+        ElementFamily::Pyramid => &PYRAMID_CORNERS,
+        _ => &[],
+    }
+}
+
+/// Divides the determinant by the product of the lengths of the vectors spanning it
+fn scaled_determinant(determinant: f64, vectors: &[[f64; 3]]) -> f64 {
+    let lengths: f64 = vectors.iter().map(|&v| norm(v)).product();
+    if lengths > 0.0 {
+        determinant / lengths
+    } else {
+        0.0
+    }
+}
+
+/// Returns the lengths of the straight edges between the vertices of the element
+fn edge_lengths(element_type: ElementType, vertices: &[[f64; 3]]) -> Option<Vec<f64>> {
+    Some(
+        element_type
+            .edges()?
+            .iter()
+            .map(|edge| norm(sub(vertices[edge[1]], vertices[edge[0]])))
+            .collect(),
+    )
+}
+
+/// Returns the area vector of a polygon, i.e. its normal scaled by its area
+fn area_vector(vertices: &[[f64; 3]]) -> [f64; 3] {
+    let n = vertices.len();
+    (1..n.saturating_sub(1))
+        .map(|i| {
+            cross(
+                sub(vertices[i], vertices[0]),
+                sub(vertices[i + 1], vertices[0]),
+            )
+        })
+        .fold([0.0; 3], |sum, c| [0, 1, 2].map(|k| sum[k] + c[k] / 2.0))
+}
+
+fn triangle_area(vertices: &[[f64; 3]]) -> f64 {
+    let a = sub(vertices[1], vertices[0]);
+    let b = sub(vertices[2], vertices[0]);
+    norm(cross(a, b)) / 2.0
+}
+
+fn triangle_inradius(vertices: &[[f64; 3]]) -> f64 {
+    let perimeter: f64 = (0..3)
+        .map(|i| norm(sub(vertices[(i + 1) % 3], vertices[i])))
+        .sum();
+    2.0 * triangle_area(vertices) / perimeter
+}
+
+fn tetrahedron_inradius(vertices: &[[f64; 3]]) -> f64 {
+    let [a, b, c] = [1, 2, 3].map(|i| sub(vertices[i], vertices[0]));
+    let volume = det(a, b, c).abs() / 6.0;
+    let surface: f64 = [[0, 1, 2], [0, 1, 3], [0, 2, 3], [1, 2, 3]]
+        .iter()
+        .map(|&[i, j, k]| triangle_area(&[vertices[i], vertices[j], vertices[k]]))
+        .sum();
+    3.0 * volume / surface
+}
@@ -0,0 +1,456 @@
+//! Lagrange reference elements following the conventions of Gmsh
+//!
+//! The node ordering is generated in the same way as in Gmsh's `pointsGenerators.cpp`: the
+//! vertices come first, followed by the nodes on the edges, then the nodes in the interior of the
+//! faces and finally the interior nodes of the element. The face and interior nodes are ordered
+//! recursively like the nodes of a lower order element of the corresponding shape.
+//!
+//! All nodes are first generated as integer lattice points (Gmsh's "monomials") and then mapped
+//! to the reference domain of the element family.
+
+use std::sync::OnceLock;
+
+use crate::mshfile::{ElementFamily, ElementType};
+
+mod shape_functions;
+mod topology;
+
+pub(crate) use shape_functions::Basis;
+pub(crate) use topology::{find_element_type, Topology};
+
+/// Integer lattice coordinates of a node, the coordinates of the vertices are either 0 or the order
+pub(crate) type Monomial = [i32; 3];
+
+/// Vertex pairs of the triangle edges
+pub(crate) static TRIANGLE_EDGES: [[usize; 2]; 3] = [[0, 1], [1, 2], [2, 0]];
+/// Vertex pairs of the quadrangle edges
+pub(crate) static QUADRANGLE_EDGES: [[usize; 2]; 4] = [[0, 1], [1, 2], [2, 3], [3, 0]];
+/// Vertex pairs of the tetrahedron edges (`MTetrahedron::edges_tetra`)
+pub(crate) static TETRAHEDRON_EDGES: [[usize; 2]; 6] =
+    [[0, 1], [1, 2], [2, 0], [3, 0], [3, 2], [3, 1]];
+/// Vertices of the tetrahedron faces (`MTetrahedron::faces_tetra`)
+pub(crate) static TETRAHEDRON_FACES: [&[usize]; 4] =
+    [&[0, 2, 1], &[0, 1, 3], &[0, 3, 2], &[3, 1, 2]];
+/// Vertex pairs of the hexahedron edges (`MHexahedron::edges_hexa`)
+pub(crate) static HEXAHEDRON_EDGES: [[usize; 2]; 12] = [
+    [0, 1],
+    [0, 3],
+    [0, 4],
+    [1, 2],
+    [1, 5],
+    [2, 3],
+    [2, 6],
+    [3, 7],
+    [4, 5],
+    [4, 7],
+    [5, 6],
+    [6, 7],
+];
+/// Vertices of the hexahedron faces (`MHexahedron::faces_hexa`)
+pub(crate) static HEXAHEDRON_FACES: [&[usize]; 6] = [
+    &[0, 3, 2, 1],
+    &[0, 1, 5, 4],
+    &[0, 4, 7, 3],
+    &[1, 2, 6, 5],
+    &[2, 3, 7, 6],
+    &[4, 5, 6, 7],
+];
+/// Vertex pairs of the prism edges (`MPrism::edges_prism`)
+pub(crate) static PRISM_EDGES: [[usize; 2]; 9] = [
+    [0, 1],
+    [0, 2],
+    [0, 3],
+    [1, 2],
+    [1, 4],
+    [2, 5],
+    [3, 4],
+    [3, 5],
+    [4, 5],
+];
+/// Vertices of the prism faces (`MPrism::faces_prism`)
+pub(crate) static PRISM_FACES: [&[usize]; 5] = [
+    &[0, 2, 1],
+    &[3, 4, 5],
+    &[0, 1, 4, 3],
+    &[0, 3, 5, 2],
+    &[1, 2, 5, 4],
+];
+/// Vertex pairs of the pyramid edges (`MPyramid::edges_pyramid`)
+pub(crate) static PYRAMID_EDGES: [[usize; 2]; 8] = [
+    [0, 1],
+    [0, 3],
+    [0, 4],
+    [1, 2],
+    [1, 4],
+    [2, 3],
+    [2, 4],
+    [3, 4],
+];
+/// Vertices of the pyramid faces (`MPyramid::faces_pyramid`)
+pub(crate) static PYRAMID_FACES: [&[usize]; 5] = [
+    &[0, 1, 4],
+    &[3, 0, 4],
+    &[1, 2, 4],
+    &[2, 3, 4],
+    &[0, 3, 2, 1],
+];
+
+/// Number of element types, i.e. the largest MSH element type number plus one
+pub(crate) const NUM_ELEMENT_TYPES: usize = ElementType::Trih4 as usize + 1;
+
+/// Reference nodes and shape function basis of a Lagrange element type
+pub(crate) struct ReferenceElement {
+    /// Coordinates of the reference nodes in Gmsh ordering
+    pub(crate) nodes: Vec<[f64; 3]>,
+    /// The nodal basis used to evaluate the shape functions
+    pub(crate) basis: Basis,
+    /// Local edges and faces
+    pub(crate) topology: Topology,
+}
+
+/// Returns the reference element of the given element type, it is generated on first use
+pub(crate) fn reference_element(element_type: ElementType) -> Option<&'static ReferenceElement> {
+    static CACHE: [OnceLock<Option<ReferenceElement>>; NUM_ELEMENT_TYPES] =
+        [const { OnceLock::new() }; NUM_ELEMENT_TYPES];
+
+    CACHE[element_type as usize]
+        .get_or_init(|| generate_reference_element(element_type))
+        .as_ref()
+}
+
+/// Distance below which two reference coordinates are considered equal
+const TOLERANCE: f64 = 1e-10;
+
+/// Returns the reference coordinates of nodes that are each centered at the given vertices
+///
+/// Used to describe the nodes of elements in other file formats, e.g. `&[0, 1]` is the midpoint of
+/// the edge from the first to the second vertex.
+pub(crate) fn vertex_averages(
+    element_type: ElementType,
+    nodes: &[&[usize]],
+) -> Option<Vec<[f64; 3]>> {
+    let reference_nodes = element_type.reference_nodes()?;
+    nodes
+        .iter()
+        .map(|vertices| {
+            let n = vertices.len() as f64;
+            let mut node = [0.0; 3];
+            for &v in vertices.iter() {
+                let vertex = reference_nodes.get(v)?;
+                for (x, vx) in node.iter_mut().zip(vertex) {
+                    *x += vx / n;
+                }
+            }
+            Some(node)
+        })
+        .collect()
+}
+
+/// Returns for each of the given reference coordinates the index of the matching Gmsh node
+///
+/// Returns `None` unless every node of the element is matched exactly once.
+pub(crate) fn node_permutation(
+    element_type: ElementType,
+    coordinates: &[[f64; 3]],
+) -> Option<Vec<usize>> {
+    let reference_nodes = element_type.reference_nodes()?;
+    if coordinates.len() != reference_nodes.len() {
+        return None;
+    }
+
+    let mut is_used = vec![false; reference_nodes.len()];
+    coordinates
+        .iter()
+        .map(|coordinate| {
+            let i = reference_nodes
+                .iter()
+                .position(|node| (0..3).all(|d| (node[d] - coordinate[d]).abs() < TOLERANCE))?;
+            // Every Gmsh node has to be used exactly once
+            if std::mem::replace(&mut is_used[i], true) {
+                None
+            } else {
+                Some(i)
+            }
+        })
+        .collect()
+}
+
+/// Generates the reference element of the given element type if it is a Lagrange element
+fn generate_reference_element(element_type: ElementType) -> Option<ReferenceElement> {
+    let order = element_type.order() as i32;
+    let serendipity = element_type.is_serendipity();
+    if !element_type.is_complete() && !serendipity {
+        return None;
+    }
+
+    let family = element_type.family();
+    if order == 0 {
+        return centroid(family).map(|c| ReferenceElement {
+            nodes: vec![c],
+            basis: Basis::Constant,
+            topology: Topology::default(),
+        });
+    }
+
+    let monomials = match family {
+        ElementFamily::Point => vec![[0, 0, 0]],
+        ElementFamily::Line => line(order),
+        ElementFamily::Triangle => triangle(order, serendipity),
+        ElementFamily::Quadrangle => quadrangle(order, serendipity),
+        ElementFamily::Tetrahedron => tetrahedron(order, serendipity),
+        ElementFamily::Hexahedron => hexahedron(order, serendipity),
+        ElementFamily::Prism => prism(order, serendipity),
+        ElementFamily::Pyramid => pyramid(order, serendipity),
+        ElementFamily::Polygon | ElementFamily::Polyhedron | ElementFamily::Trihedron => {
+            return None
+        }
+    };
+
+    let o = order as f64;
+    let nodes: Vec<_> = monomials
+        .iter()
+        .map(|&[i, j, k]| {
+            let [u, v, w] = [i as f64 / o, j as f64 / o, k as f64 / o];
+            match family {
+                ElementFamily::Point => [0.0, 0.0, 0.0],
+                ElementFamily::Line => [2.0 * u - 1.0, 0.0, 0.0],
+                ElementFamily::Triangle | ElementFamily::Tetrahedron => [u, v, w],
+                ElementFamily::Quadrangle => [2.0 * u - 1.0, 2.0 * v - 1.0, 0.0],
+                ElementFamily::Hexahedron => [2.0 * u - 1.0, 2.0 * v - 1.0, 2.0 * w - 1.0],
+                ElementFamily::Prism => [u, v, 2.0 * w - 1.0],
+                // The pyramid lattice has the apex at k = 0 and the base at k = order
+                ElementFamily::Pyramid => [2.0 * u - w, 2.0 * v - w, 1.0 - w],
+                ElementFamily::Polygon | ElementFamily::Polyhedron | ElementFamily::Trihedron => {
+                    unreachable!()
+                }
+            }
+        })
+        .collect();
+
+    let basis = Basis::new(family, order as usize, serendipity, monomials, &nodes)?;
+    let topology = Topology::new(element_type, &nodes)?;
+    Some(ReferenceElement {
+        nodes,
+        basis,
+        topology,
+    })
+}
+
+/// Returns the centroid of the reference domain, used as the single node of order 0 elements
+fn centroid(family: ElementFamily) -> Option<[f64; 3]> {
+    match family {
+        ElementFamily::Point
+        | ElementFamily::Line
+        | ElementFamily::Quadrangle
+        | ElementFamily::Hexahedron => Some([0.0, 0.0, 0.0]),
+        ElementFamily::Triangle => Some([1.0 / 3.0, 1.0 / 3.0, 0.0]),
+        ElementFamily::Tetrahedron => Some([0.25, 0.25, 0.25]),
+        ElementFamily::Prism => Some([1.0 / 3.0, 1.0 / 3.0, 0.0]),
+        ElementFamily::Pyramid => Some([0.0, 0.0, 0.25]),
+        ElementFamily::Polygon | ElementFamily::Polyhedron | ElementFamily::Trihedron => None,
+    }
+}
+
+/// Returns the given monomials shifted by the offset
+fn shifted(monomials: Vec<Monomial>, offset: Monomial) -> Vec<Monomial> {
+    monomials
+        .into_iter()
+        .map(|m| [m[0] + offset[0], m[1] + offset[1], m[2] + offset[2]])
+        .collect()
+}
+
+/// Appends the interior nodes of all edges, ordered from the first to the second edge vertex
+fn push_edge_nodes(monomials: &mut Vec<Monomial>, edges: &[[usize; 2]], order: i32) {
+    for &[i0, i1] in edges {
+        let (m0, m1) = (monomials[i0], monomials[i1]);
+        let du = [0, 1, 2].map(|d| (m1[d] - m0[d]) / order);
+        for i in 1..order {
+            monomials.push([0, 1, 2].map(|d| m0[d] + i * du[d]));
+        }
+    }
+}
+
+/// Appends the given face nodes (2D monomials) mapped onto the face spanned by the vertices
+///
+/// The first local axis points from the first to the second face vertex, the second local axis
+/// from the first to the last face vertex.
+fn push_face_nodes(
+    monomials: &mut Vec<Monomial>,
+    face: &[usize],
+    face_nodes: &[Monomial],
+    order: i32,
+) {
+    let m0 = monomials[face[0]];
+    let m1 = monomials[face[1]];
+    let m2 = monomials[face[face.len() - 1]];
+    let du = [0, 1, 2].map(|d| (m1[d] - m0[d]) / order);
+    let dv = [0, 1, 2].map(|d| (m2[d] - m0[d]) / order);
+    for n in face_nodes {
+        monomials.push([0, 1, 2].map(|d| m0[d] + n[0] * du[d] + n[1] * dv[d]));
+    }
+}
+
+/// Node lattice of the line element, the vertices are at 0 and `order`
+fn line(order: i32) -> Vec<Monomial> {
+    if order == 0 {
+        return vec![[0, 0, 0]];
+    }
+
+    let mut monomials = vec![[0, 0, 0], [order, 0, 0]];
+    monomials.extend((1..order).map(|i| [i, 0, 0]));
+    monomials
+}
+
+/// Node lattice of the triangle element
+fn triangle(order: i32, serendipity: bool) -> Vec<Monomial> {
+    if order == 0 {
+        return vec![[0, 0, 0]];
+    }
+
+    let mut monomials = vec![[0, 0, 0], [order, 0, 0], [0, order, 0]];
+    push_edge_nodes(&mut monomials, &TRIANGLE_EDGES, order);
+    if !serendipity && order > 2 {
+        monomials.extend(shifted(triangle(order - 3, false), [1, 1, 0]));
+    }
+    monomials
+}
+
+/// Node lattice of the quadrangle element
+fn quadrangle(order: i32, serendipity: bool) -> Vec<Monomial> {
+    if order == 0 {
+        return vec![[0, 0, 0]];
+    }
+
+    let mut monomials = vec![[0, 0, 0], [order, 0, 0], [order, order, 0], [0, order, 0]];
+    push_edge_nodes(&mut monomials, &QUADRANGLE_EDGES, order);
+    if !serendipity && order > 1 {
+        monomials.extend(shifted(quadrangle(order - 2, false), [1, 1, 0]));
+    }
+    monomials
+}
+
+/// Node lattice of the tetrahedron element
+fn tetrahedron(order: i32, serendipity: bool) -> Vec<Monomial> {
+    if order == 0 {
+        return vec![[0, 0, 0]];
+    }
+
+    let mut monomials = vec![[0, 0, 0], [order, 0, 0], [0, order, 0], [0, 0, order]];
+    push_edge_nodes(&mut monomials, &TETRAHEDRON_EDGES, order);
+    if !serendipity && order > 2 {
+        let face_nodes = shifted(triangle(order - 3, false), [1, 1, 0]);
+        for face in TETRAHEDRON_FACES.iter() {
+            push_face_nodes(&mut monomials, face, &face_nodes, order);
+        }
+        if order > 3 {
+            monomials.extend(shifted(tetrahedron(order - 4, false), [1, 1, 1]));
+        }
+    }
+    monomials
+}
+
+/// Node lattice of the hexahedron element
+fn hexahedron(order: i32, serendipity: bool) -> Vec<Monomial> {
+    if order == 0 {
+        return vec![[0, 0, 0]];
+    }
+
+    let o = order;
+    let mut monomials = vec![
+        [0, 0, 0],
+        [o, 0, 0],
+        [o, o, 0],
+        [0, o, 0],
+        [0, 0, o],
+        [o, 0, o],
+        [o, o, o],
+        [0, o, o],
+    ];
+    push_edge_nodes(&mut monomials, &HEXAHEDRON_EDGES, order);
+    if !serendipity && order > 1 {
+        let face_nodes = shifted(quadrangle(order - 2, false), [1, 1, 0]);
+        for face in HEXAHEDRON_FACES.iter() {
+            push_face_nodes(&mut monomials, face, &face_nodes, order);
+        }
+        monomials.extend(shifted(hexahedron(order - 2, false), [1, 1, 1]));
+    }
+    monomials
+}
+
+/// Node lattice of the prism element
+fn prism(order: i32, serendipity: bool) -> Vec<Monomial> {
+    if order == 0 {
+        return vec![[0, 0, 0]];
+    }
+
+    let o = order;
+    let mut monomials = vec![
+        [0, 0, 0],
+        [o, 0, 0],
+        [0, o, 0],
+        [0, 0, o],
+        [o, 0, o],
+        [0, o, o],
+    ];
+    push_edge_nodes(&mut monomials, &PRISM_EDGES, order);
+    if !serendipity && order > 1 {
+        let quadrangle_nodes = shifted(quadrangle(order - 2, false), [1, 1, 0]);
+        let triangle_nodes = if order > 2 {
+            shifted(triangle(order - 3, false), [1, 1, 0])
+        } else {
+            Vec::new()
+        };
+        for face in PRISM_FACES.iter() {
+            let face_nodes = if face.len() == 4 {
+                &quadrangle_nodes
+            } else {
+                &triangle_nodes
+            };
+            push_face_nodes(&mut monomials, face, face_nodes, order);
+        }
+
+        if order > 2 {
+            let lines = line(order - 2);
+            for t in triangle(order - 3, false) {
+                for l in lines.iter() {
+                    monomials.push([t[0] + 1, t[1] + 1, l[0] + 1]);
+                }
+            }
+        }
+    }
+    monomials
+}
+
+/// Node lattice of the pyramid element
+///
+/// The layer `k` of the lattice contains the nodes `0 <= i, j <= k`, i.e. the apex is at `k = 0`.
+fn pyramid(order: i32, serendipity: bool) -> Vec<Monomial> {
+    if order == 0 {
+        return vec![[0, 0, 0]];
+    }
+
+    let o = order;
+    let mut monomials = vec![[0, 0, o], [o, 0, o], [o, o, o], [0, o, o], [0, 0, 0]];
+    push_edge_nodes(&mut monomials, &PYRAMID_EDGES, order);
+    if !serendipity && order > 1 {
+        let quadrangle_nodes = shifted(quadrangle(order - 2, false), [1, 1, 0]);
+        let triangle_nodes = if order > 2 {
+            shifted(triangle(order - 3, false), [1, 1, 0])
+        } else {
+            Vec::new()
+        };
+        for face in PYRAMID_FACES.iter() {
+            let face_nodes = if face.len() == 4 {
+                &quadrangle_nodes
+            } else {
+                &triangle_nodes
+            };
+            push_face_nodes(&mut monomials, face, face_nodes, order);
+        }
+
+        if order > 2 {
+            monomials.extend(shifted(pyramid(order - 3, false), [1, 1, 2]));
+        }
+    }
+    monomials
+}
@@ -0,0 +1,597 @@
+//! Evaluation of the Lagrange shape functions and their gradients on the reference elements
+//!
+//! Complete line, quadrangle and hexahedron elements use tensor products of 1D Lagrange
+//! polynomials, complete triangles and tetrahedra use Silvester's product formula in barycentric
+//! coordinates and complete prisms combine both. For pyramids and serendipity elements, the nodal
+//! basis is obtained by inverting the generalized Vandermonde matrix of a modal basis that spans
+//! the function space of the element:
+//!  - complete pyramids use the rational basis of Bergot, Cohen and Duruflé (as Gmsh does),
+//!  - serendipity quadrangles and hexahedra use the same monomials as Gmsh,
+//!  - all other serendipity elements use the vertex functions of the linear element enriched by
+//!    edge modes.
+
+use crate::lagrange::{Monomial, PRISM_EDGES, PYRAMID_EDGES, TETRAHEDRON_EDGES, TRIANGLE_EDGES};
+use crate::mshfile::ElementFamily;
+
+/// Values and gradients of a set of functions at one point
+pub(crate) type Evaluation = (Vec<f64>, Vec<[f64; 3]>);
+
+/// Values and derivatives of a set of univariate polynomials at one point
+type Evaluation1d = (Vec<f64>, Vec<f64>);
+
+/// Distance to the pyramid apex below which the rational pyramid functions are evaluated with this
+/// distance instead, i.e. they are replaced by their limit along the pyramid axis
+const PYRAMID_APEX_TOLERANCE: f64 = 1e-12;
+
+/// Nodal basis of a Lagrange element
+pub(crate) enum Basis {
+    /// A single constant shape function (points and elements of order 0)
+    Constant,
+    /// Tensor product of 1D Lagrange polynomials, the lattice indices select the 1D polynomials
+    Tensor {
+        dim: usize,
+        order: usize,
+        indices: Vec<Monomial>,
+    },
+    /// Silvester's formula, the lattice indices are the barycentric indices without the first one
+    Simplex {
+        dim: usize,
+        order: usize,
+        indices: Vec<Monomial>,
+    },
+    /// Product of Silvester's formula for the triangle and 1D Lagrange polynomials
+    Prism {
+        order: usize,
+        indices: Vec<Monomial>,
+    },
+    /// Linear combination of the functions of a modal basis
+    Modal {
+        modal: ModalBasis,
+        order: usize,
+        /// LU factorization of the transposed Vandermonde matrix, i.e. the shape function values
+        /// are the solution of `V^T N = f` where `f` are the values of the modal functions
+        factorization: LuFactorization,
+    },
+}
+
+/// Modal bases used for element types without an explicit formula for their nodal basis
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum ModalBasis {
+    /// Rational basis of complete pyramids
+    Pyramid,
+    /// Gmsh's serendipity monomials of quadrangles
+    QuadrangleSerendipity,
+    /// Gmsh's serendipity monomials of hexahedra
+    HexahedronSerendipity,
+    /// Vertex functions of the linear element and edge modes
+    EdgeModes(ElementFamily),
+}
+
+impl Basis {
+    /// Constructs the nodal basis for the given element nodes and their lattice indices
+    ///
+    /// Returns `None` if the element family is not supported or the Vandermonde matrix of the
+    /// modal basis is singular.
+    pub(crate) fn new(
+        family: ElementFamily,
+        order: usize,
+        serendipity: bool,
+        indices: Vec<Monomial>,
+        nodes: &[[f64; 3]],
+    ) -> Option<Basis> {
+        let modal = match family {
+            ElementFamily::Point => return Some(Basis::Constant),
+            ElementFamily::Line => {
+                return Some(Basis::Tensor {
+                    dim: 1,
+                    order,
+                    indices,
+                })
+            }
+            ElementFamily::Quadrangle | ElementFamily::Hexahedron if !serendipity => {
+                return Some(Basis::Tensor {
+                    dim: family.dimension(),
+                    order,
+                    indices,
+                })
+            }
+            ElementFamily::Triangle | ElementFamily::Tetrahedron if !serendipity => {
+                return Some(Basis::Simplex {
+                    dim: family.dimension(),
+                    order,
+                    indices,
+                })
+            }
+            ElementFamily::Prism if !serendipity => return Some(Basis::Prism { order, indices }),
+            ElementFamily::Pyramid if !serendipity => ModalBasis::Pyramid,
+            ElementFamily::Quadrangle => ModalBasis::QuadrangleSerendipity,
+            ElementFamily::Hexahedron => ModalBasis::HexahedronSerendipity,
+            ElementFamily::Triangle
+            | ElementFamily::Tetrahedron
+            | ElementFamily::Prism
+            | ElementFamily::Pyramid => ModalBasis::EdgeModes(family),
+            ElementFamily::Polygon | ElementFamily::Polyhedron | ElementFamily::Trihedron => {
+                return None
+            }
+        };
+
+        // Transposed generalized Vandermonde matrix, column `i` contains the modal functions
+        // evaluated at node `i`
+        let n = nodes.len();
+        let mut vandermonde_t = vec![0.0; n * n];
+        for (i, node) in nodes.iter().enumerate() {
+            let (values, _) = modal.evaluate(order, *node);
+            if values.len() != n {
+                return None;
+            }
+            for (j, value) in values.into_iter().enumerate() {
+                vandermonde_t[j * n + i] = value;
+            }
+        }
+
+        let factorization = LuFactorization::new(vandermonde_t, n)?;
+        Some(Basis::Modal {
+            modal,
+            order,
+            factorization,
+        })
+    }
+
+    /// Evaluates all shape functions and their gradients at the given reference coordinates
+    ///
+    /// The gradients may be omitted (i.e. left empty) if they are not requested.
+    pub(crate) fn evaluate(&self, xi: [f64; 3], with_gradients: bool) -> Evaluation {
+        match self {
+            Basis::Constant => (vec![1.0], vec![[0.0; 3]]),
+            Basis::Tensor {
+                dim,
+                order,
+                indices,
+            } => {
+                let polynomials: Vec<_> = xi[..*dim]
+                    .iter()
+                    .map(|&x| lagrange_polynomials(*order, x))
+                    .collect();
+
+                indices
+                    .iter()
+                    .map(|m| {
+                        let factor = |d: usize, derivative: bool| {
+                            let (values, derivatives) = &polynomials[d];
+                            let i = m[d] as usize;
+                            if derivative {
+                                derivatives[i]
+                            } else {
+                                values[i]
+                            }
+                        };
+
+                        let value: f64 = (0..*dim).map(|d| factor(d, false)).product();
+                        let mut gradient = [0.0; 3];
+                        for (e, g) in gradient.iter_mut().enumerate().take(*dim) {
+                            *g = (0..*dim).map(|d| factor(d, d == e)).product();
+                        }
+                        (value, gradient)
+                    })
+                    .unzip()
+            }
+            Basis::Simplex {
+                dim,
+                order,
+                indices,
+            } => {
+                let factors = silvester_factors(*dim, *order, xi);
+                indices
+                    .iter()
+                    .map(|m| silvester_function(&factors, *order, m))
+                    .unzip()
+            }
+            Basis::Prism { order, indices } => {
+                let factors = silvester_factors(2, *order, xi);
+                let (values_z, derivatives_z) = lagrange_polynomials(*order, xi[2]);
+                indices
+                    .iter()
+                    .map(|m| {
+                        let (value, gradient) = silvester_function(&factors, *order, m);
+                        let k = m[2] as usize;
+                        (
+                            value * values_z[k],
+                            [
+                                gradient[0] * values_z[k],
+                                gradient[1] * values_z[k],
+                                value * derivatives_z[k],
+                            ],
+                        )
+                    })
+                    .unzip()
+            }
+            Basis::Modal {
+                modal,
+                order,
+                factorization,
+            } => {
+                let (mut values, modal_gradients) = modal.evaluate(*order, xi);
+                factorization.solve(&mut values);
+                if !with_gradients {
+                    return (values, Vec::new());
+                }
+
+                let mut gradients = vec![[0.0; 3]; values.len()];
+                for d in 0..3 {
+                    let mut component: Vec<_> = modal_gradients.iter().map(|g| g[d]).collect();
+                    factorization.solve(&mut component);
+                    for (gradient, c) in gradients.iter_mut().zip(component) {
+                        gradient[d] = c;
+                    }
+                }
+                (values, gradients)
+            }
+        }
+    }
+}
+
+impl ModalBasis {
+    /// Evaluates all modal functions and their gradients at the given reference coordinates
+    fn evaluate(self, order: usize, xi: [f64; 3]) -> Evaluation {
+        match self {
+            ModalBasis::Pyramid => pyramid_modes(order, xi),
+            ModalBasis::QuadrangleSerendipity => {
+                let mut exponents = vec![[0, 0, 0], [1, 0, 0], [0, 1, 0], [1, 1, 0]];
+                for p in 2..=order {
+                    exponents.extend_from_slice(&[[p, 0, 0], [0, p, 0], [p, 1, 0], [1, p, 0]]);
+                }
+                legendre_products(2, order, &exponents, xi)
+            }
+            ModalBasis::HexahedronSerendipity => {
+                let mut exponents = vec![
+                    [0, 0, 0],
+                    [1, 0, 0],
+                    [0, 1, 0],
+                    [0, 0, 1],
+                    [1, 1, 0],
+                    [1, 0, 1],
+                    [0, 1, 1],
+                    [1, 1, 1],
+                ];
+                for p in 2..=order {
+                    exponents.extend_from_slice(&[
+                        [p, 0, 0],
+                        [0, p, 0],
+                        [0, 0, p],
+                        [p, 1, 0],
+                        [p, 0, 1],
+                        [1, p, 0],
+                        [0, p, 1],
+                        [1, 0, p],
+                        [0, 1, p],
+                        [p, 1, 1],
+                        [1, p, 1],
+                        [1, 1, p],
+                    ]);
+                }
+                legendre_products(3, order, &exponents, xi)
+            }
+            ModalBasis::EdgeModes(family) => edge_modes(family, order, xi),
+        }
+    }
+}
+
+/// LU factorization with partial pivoting of a dense row major `n x n` matrix
+pub(crate) struct LuFactorization {
+    n: usize,
+    /// Unit lower triangular factor (without its diagonal) and upper triangular factor
+    lu: Vec<f64>,
+    /// Row permutation, row `i` of the factorized matrix is row `permutation[i]` of the input
+    permutation: Vec<usize>,
+}
+
+impl LuFactorization {
+    /// Factorizes the matrix, returns `None` if it is (numerically) singular
+    fn new(mut lu: Vec<f64>, n: usize) -> Option<Self> {
+        let mut permutation: Vec<_> = (0..n).collect();
+        for col in 0..n {
+            let pivot_row = (col..n).max_by(|&a, &b| {
+                lu[a * n + col]
+                    .abs()
+                    .partial_cmp(&lu[b * n + col].abs())
+                    .unwrap()
+            })?;
+            let pivot = lu[pivot_row * n + col];
+            if pivot.is_nan() || pivot.abs() <= 1e-12 {
+                return None;
+            }
+            if pivot_row != col {
+                for j in 0..n {
+                    lu.swap(col * n + j, pivot_row * n + j);
+                }
+                permutation.swap(col, pivot_row);
+            }
+
+            let (upper, lower) = lu.split_at_mut((col + 1) * n);
+            let pivot = &upper[col * n..];
+            for row in lower.chunks_exact_mut(n) {
+                let factor = row[col] / pivot[col];
+                row[col] = factor;
+                if factor != 0.0 {
+                    for (a, b) in row[col + 1..].iter_mut().zip(&pivot[col + 1..n]) {
+                        *a -= factor * b;
+                    }
+                }
+            }
+        }
+
+        Some(LuFactorization { n, lu, permutation })
+    }
+
+    /// Solves the linear system with the given right hand side in place
+    fn solve(&self, rhs: &mut [f64]) {
+        let n = self.n;
+        let mut x: Vec<_> = self.permutation.iter().map(|&i| rhs[i]).collect();
+        for i in 0..n {
+            let row = &self.lu[i * n..i * n + i];
+            x[i] -= row.iter().zip(&x[..i]).map(|(a, b)| a * b).sum::<f64>();
+        }
+        for i in (0..n).rev() {
+            let row = &self.lu[i * n..(i + 1) * n];
+            let sum: f64 = row[i + 1..]
+                .iter()
+                .zip(&x[i + 1..])
+                .map(|(a, b)| a * b)
+                .sum();
+            x[i] = (x[i] - sum) / row[i];
+        }
+        rhs.copy_from_slice(&x);
+    }
+}
+
+/// Evaluates the 1D Lagrange polynomials of the equidistant nodes `-1 + 2i/order` at `x`
+fn lagrange_polynomials(order: usize, x: f64) -> Evaluation1d {
+    let t = |i: usize| -1.0 + 2.0 * i as f64 / order as f64;
+
+    // Product of the factors (x - t_m) / (t_i - t_m) for all m except i and the skipped index
+    let partial_product = |i: usize, skip: Option<usize>| -> f64 {
+        (0..=order)
+            .filter(|&m| m != i && Some(m) != skip)
+            .map(|m| (x - t(m)) / (t(i) - t(m)))
+            .product()
+    };
+
+    (0..=order)
+        .map(|i| {
+            let value = partial_product(i, None);
+            let derivative: f64 = (0..=order)
+                .filter(|&l| l != i)
+                .map(|l| partial_product(i, Some(l)) / (t(i) - t(l)))
+                .sum();
+            (value, derivative)
+        })
+        .unzip()
+}
+
+/// Evaluates the factors `s_a(l) = prod_{m < a} (order * l - m) / (m + 1)` of Silvester's formula
+/// for `a = 0..=order` and all barycentric coordinates `l` of the point
+fn silvester_factors(dim: usize, order: usize, xi: [f64; 3]) -> Vec<Evaluation1d> {
+    let p = order as f64;
+    let first = 1.0 - xi[..dim].iter().sum::<f64>();
+    std::iter::once(first)
+        .chain(xi[..dim].iter().copied())
+        .map(|l| {
+            let mut values = vec![1.0];
+            let mut derivatives = vec![0.0];
+            for a in 1..=order {
+                let m = (a - 1) as f64;
+                let factor = (p * l - m) / a as f64;
+                derivatives.push(derivatives[a - 1] * factor + values[a - 1] * p / a as f64);
+                values.push(values[a - 1] * factor);
+            }
+            (values, derivatives)
+        })
+        .collect()
+}
+
+/// Evaluates Silvester's formula for the simplex node with the given lattice index
+fn silvester_function(factors: &[Evaluation1d], order: usize, m: &Monomial) -> (f64, [f64; 3]) {
+    let dim = factors.len() - 1;
+    let mut a = [0; 4];
+    for d in 0..dim {
+        a[d + 1] = m[d] as usize;
+    }
+    a[0] = order - a[1..].iter().sum::<usize>();
+
+    let factor = |q: usize, derivative: bool| {
+        let (values, derivatives) = &factors[q];
+        if derivative {
+            derivatives[a[q]]
+        } else {
+            values[a[q]]
+        }
+    };
+
+    // Derivatives with respect to the barycentric coordinates
+    let barycentric_derivative =
+        |q: usize| -> f64 { (0..=dim).map(|r| factor(r, r == q)).product() };
+
+    let value = (0..=dim).map(|q| factor(q, false)).product();
+    let d0 = barycentric_derivative(0);
+    let mut gradient = [0.0; 3];
+    for (e, g) in gradient.iter_mut().enumerate().take(dim) {
+        *g = barycentric_derivative(e + 1) - d0;
+    }
+    (value, gradient)
+}
+
+/// Evaluates the Legendre polynomials `P_0..=P_n` and their derivatives at `t`
+fn legendre(n: usize, t: f64) -> Evaluation1d {
+    let mut values = vec![1.0, t];
+    let mut derivatives = vec![0.0, 1.0];
+    for k in 1..n {
+        let kf = k as f64;
+        values.push(((2.0 * kf + 1.0) * t * values[k] - kf * values[k - 1]) / (kf + 1.0));
+        derivatives.push(derivatives[k - 1] + (2.0 * kf + 1.0) * values[k]);
+    }
+    values.truncate(n + 1);
+    derivatives.truncate(n + 1);
+    (values, derivatives)
+}
+
+/// Evaluates the scaled Legendre polynomials `s^k P_k(x / s)` for `k = 0..=n` and their
+/// derivatives with respect to `x` and `s`
+fn scaled_legendre(n: usize, x: f64, s: f64) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let mut values = vec![1.0, x];
+    let mut dx = vec![0.0, 1.0];
+    let mut ds = vec![0.0, 0.0];
+    for k in 1..n {
+        let kf = k as f64;
+        let a = (2.0 * kf + 1.0) / (kf + 1.0);
+        let b = kf / (kf + 1.0);
+        values.push(a * x * values[k] - b * s * s * values[k - 1]);
+        dx.push(a * (values[k] + x * dx[k]) - b * s * s * dx[k - 1]);
+        ds.push(a * x * ds[k] - b * (2.0 * s * values[k - 1] + s * s * ds[k - 1]));
+    }
+    values.truncate(n + 1);
+    dx.truncate(n + 1);
+    ds.truncate(n + 1);
+    (values, dx, ds)
+}
+
+/// Evaluates products of Legendre polynomials with the given degrees per coordinate direction
+fn legendre_products(
+    dim: usize,
+    order: usize,
+    exponents: &[[usize; 3]],
+    xi: [f64; 3],
+) -> Evaluation {
+    let polynomials: Vec<_> = xi[..dim].iter().map(|&x| legendre(order, x)).collect();
+    exponents
+        .iter()
+        .map(|e| {
+            let factor = |d: usize, derivative: bool| {
+                let (values, derivatives) = &polynomials[d];
+                if derivative {
+                    derivatives[e[d]]
+                } else {
+                    values[e[d]]
+                }
+            };
+
+            let value: f64 = (0..dim).map(|d| factor(d, false)).product();
+            let mut gradient = [0.0; 3];
+            for (k, g) in gradient.iter_mut().enumerate().take(dim) {
+                *g = (0..dim).map(|d| factor(d, d == k)).product();
+            }
+            (value, gradient)
+        })
+        .unzip()
+}
+
+/// Returns `1 - z` at the given point, moved away from zero at the pyramid apex
+fn pyramid_scale(z: f64) -> f64 {
+    let s = 1.0 - z;
+    if s.abs() < PYRAMID_APEX_TOLERANCE {
+        PYRAMID_APEX_TOLERANCE
+    } else {
+        s
+    }
+}
+
+/// Evaluates the functions `P_a(x/s) P_b(y/s) s^max(a,b) P_k(2z - 1)` with `s = 1 - z` for all
+/// `a, b <= order` and `k <= order - max(a, b)`
+fn pyramid_modes(order: usize, [x, y, z]: [f64; 3]) -> Evaluation {
+    let s = pyramid_scale(z);
+    let (qx, qx_dx, qx_ds) = scaled_legendre(order, x, s);
+    let (qy, qy_dy, qy_ds) = scaled_legendre(order, y, s);
+    let (r, r_dt) = legendre(order, 2.0 * z - 1.0);
+
+    let mut values = Vec::new();
+    let mut gradients = Vec::new();
+    for a in 0..=order {
+        for b in 0..=order {
+            let c = a.min(b) as i32;
+            let scale = s.powi(-c);
+            let f = qx[a] * qy[b] * scale;
+            let f_dx = qx_dx[a] * qy[b] * scale;
+            let f_dy = qx[a] * qy_dy[b] * scale;
+            let f_ds = (qx_ds[a] * qy[b] + qx[a] * qy_ds[b]) * scale - c as f64 * f / s;
+
+            for k in 0..=(order - a.max(b)) {
+                values.push(f * r[k]);
+                gradients.push([f_dx * r[k], f_dy * r[k], -f_ds * r[k] + 2.0 * f * r_dt[k]]);
+            }
+        }
+    }
+    (values, gradients)
+}
+
+/// Evaluates the shape functions of the linear element of the given family
+fn vertex_functions(family: ElementFamily, [x, y, z]: [f64; 3]) -> Vec<(f64, [f64; 3])> {
+    let triangle = [
+        (1.0 - x - y, [-1.0, -1.0, 0.0]),
+        (x, [1.0, 0.0, 0.0]),
+        (y, [0.0, 1.0, 0.0]),
+    ];
+
+    match family {
+        ElementFamily::Triangle => triangle.to_vec(),
+        ElementFamily::Tetrahedron => vec![
+            (1.0 - x - y - z, [-1.0, -1.0, -1.0]),
+            (x, [1.0, 0.0, 0.0]),
+            (y, [0.0, 1.0, 0.0]),
+            (z, [0.0, 0.0, 1.0]),
+        ],
+        ElementFamily::Prism => [(0.5 * (1.0 - z), -0.5), (0.5 * (1.0 + z), 0.5)]
+            .iter()
+            .flat_map(|&(h, h_dz)| {
+                triangle
+                    .iter()
+                    .map(move |&(l, l_d)| (l * h, [l_d[0] * h, l_d[1] * h, l * h_dz]))
+            })
+            .collect(),
+        ElementFamily::Pyramid => {
+            // (s + sx x)(s + sy y) / (4s) for the base vertices with signs sx, sy
+            let s = pyramid_scale(z);
+            let mut functions: Vec<_> = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+                .iter()
+                .map(|&(sx, sy)| {
+                    let value = 0.25 * (s + sx * x + sy * y + sx * sy * x * y / s);
+                    let gradient = [
+                        0.25 * (sx + sx * sy * y / s),
+                        0.25 * (sy + sx * sy * x / s),
+                        0.25 * (-1.0 + sx * sy * x * y / (s * s)),
+                    ];
+                    (value, gradient)
+                })
+                .collect();
+            functions.push((z, [0.0, 0.0, 1.0]));
+            functions
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Evaluates the vertex functions and the edge modes `f_a f_b P_{k-2}(f_a - f_b)`, `k = 2..=order`
+/// of all edges `(a, b)`
+fn edge_modes(family: ElementFamily, order: usize, xi: [f64; 3]) -> Evaluation {
+    let edges: &[[usize; 2]] = match family {
+        ElementFamily::Triangle => &TRIANGLE_EDGES,
+        ElementFamily::Tetrahedron => &TETRAHEDRON_EDGES,
+        ElementFamily::Prism => &PRISM_EDGES,
+        ElementFamily::Pyramid => &PYRAMID_EDGES,
+        _ => unreachable!(),
+    };
+
+    let vertices = vertex_functions(family, xi);
+    let (mut values, mut gradients): (Vec<_>, Vec<_>) = vertices.iter().copied().unzip();
+    for &[a, b] in edges {
+        let (fa, ga) = vertices[a];
+        let (fb, gb) = vertices[b];
+        let (l, l_dt) = legendre(order.saturating_sub(2), fa - fb);
+        for k in 2..=order {
+            let (l, l_dt) = (l[k - 2], l_dt[k - 2]);
+            values.push(fa * fb * l);
+            gradients.push(
+                [0, 1, 2].map(|d| (ga[d] * fb + fa * gb[d]) * l + fa * fb * l_dt * (ga[d] - gb[d])),
+            );
+        }
+    }
+    (values, gradients)
+}
@@ -0,0 +1,193 @@
+//! Local edges and faces of the reference elements
+//!
+//! The vertices of the edges and faces follow the definitions of Gmsh's `MElement` subclasses,
+//! i.e. the faces of volume elements are oriented such that their normals point outwards. The
+//! higher order nodes of an edge or face are found by mapping the reference nodes of the
+//! corresponding line, triangle or quadrangle element onto the edge or face, so the node list of
+//! an edge or face forms a valid element of the respective type in Gmsh ordering.
+
+use num_traits::FromPrimitive;
+
+use crate::lagrange::{
+    reference_element, HEXAHEDRON_EDGES, HEXAHEDRON_FACES, PRISM_EDGES, PRISM_FACES, PYRAMID_EDGES,
+    PYRAMID_FACES, QUADRANGLE_EDGES, TETRAHEDRON_EDGES, TETRAHEDRON_FACES, TRIANGLE_EDGES,
+};
+use crate::mshfile::{ElementFamily, ElementType};
+
+/// Distance below which two reference coordinates are considered equal
+const TOLERANCE: f64 = 1e-10;
+
+/// Local edges and faces of an element type
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Topology {
+    /// Local node indices of each edge
+    pub(crate) edges: Vec<Vec<usize>>,
+    /// Local node indices of each face
+    pub(crate) faces: Vec<Vec<usize>>,
+    /// Element type of each face
+    pub(crate) face_types: Vec<ElementType>,
+}
+
+impl Topology {
+    /// Determines the edges and faces of an element from the coordinates of its reference nodes
+    ///
+    /// Returns `None` if the nodes of an edge or face cannot be found in the reference nodes.
+    pub(crate) fn new(element_type: ElementType, nodes: &[[f64; 3]]) -> Option<Self> {
+        let family = element_type.family();
+        let order = element_type.order();
+        let all_nodes = || vec![(0..nodes.len()).collect()];
+
+        // Lines are their own edge and surface elements are their own face
+        let (edge_vertices, face_vertices): (&[[usize; 2]], &[&[usize]]) = match family {
+            ElementFamily::Point => (&[], &[]),
+            ElementFamily::Line => {
+                return Some(Topology {
+                    edges: all_nodes(),
+                    ..Default::default()
+                })
+            }
+            ElementFamily::Triangle => (&TRIANGLE_EDGES, &[]),
+            ElementFamily::Quadrangle => (&QUADRANGLE_EDGES, &[]),
+            ElementFamily::Tetrahedron => (&TETRAHEDRON_EDGES, &TETRAHEDRON_FACES),
+            ElementFamily::Hexahedron => (&HEXAHEDRON_EDGES, &HEXAHEDRON_FACES),
+            ElementFamily::Prism => (&PRISM_EDGES, &PRISM_FACES),
+            ElementFamily::Pyramid => (&PYRAMID_EDGES, &PYRAMID_FACES),
+            ElementFamily::Polygon | ElementFamily::Polyhedron | ElementFamily::Trihedron => {
+                return None
+            }
+        };
+
+        let find_node = |point: [f64; 3]| {
+            nodes
+                .iter()
+                .position(|node| distance(*node, point) < TOLERANCE)
+        };
+
+        // All edges contain the nodes of a complete line element
+        let mut edges = Vec::with_capacity(edge_vertices.len());
+        if !edge_vertices.is_empty() {
+            let line_type = find_element_type(ElementFamily::Line, order, order + 1)?;
+            let line_nodes = &reference_element(line_type)?.nodes;
+            for &[a, b] in edge_vertices {
+                let edge = line_nodes
+                    .iter()
+                    .map(|xi| {
+                        let t = 0.5 * (xi[0] + 1.0);
+                        find_node([0, 1, 2].map(|d| nodes[a][d] + t * (nodes[b][d] - nodes[a][d])))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                edges.push(edge);
+            }
+        }
+
+        let mut faces = Vec::with_capacity(face_vertices.len());
+        let mut face_types = Vec::with_capacity(face_vertices.len());
+        for face in face_vertices {
+            let vertices: Vec<_> = face.iter().map(|&v| nodes[v]).collect();
+
+            // The face type is determined by the number of element nodes in the plane of the face
+            let normal = cross(
+                sub(vertices[1], vertices[0]),
+                sub(vertices[vertices.len() - 1], vertices[0]),
+            );
+            let num_face_nodes = nodes
+                .iter()
+                .filter(|node| dot(normal, sub(**node, vertices[0])).abs() < TOLERANCE)
+                .count();
+
+            let is_triangle = face.len() == 3;
+            let face_family = if is_triangle {
+                ElementFamily::Triangle
+            } else {
+                ElementFamily::Quadrangle
+            };
+            let face_type = find_element_type(face_family, order, num_face_nodes)?;
+            let face_nodes = reference_element(face_type)?
+                .nodes
+                .iter()
+                .map(|xi| {
+                    find_node(if is_triangle {
+                        map_to_triangle(&vertices, *xi)
+                    } else {
+                        map_to_quadrangle(&vertices, *xi)
+                    })
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            faces.push(face_nodes);
+            face_types.push(face_type);
+        }
+
+        if family.dimension() == 2 {
+            faces = all_nodes();
+            face_types = vec![element_type];
+        }
+
+        Some(Topology {
+            edges,
+            faces,
+            face_types,
+        })
+    }
+}
+
+/// Returns the Lagrange element type of the given family and order with the given number of nodes
+pub(crate) fn find_element_type(
+    family: ElementFamily,
+    order: usize,
+    num_nodes: usize,
+) -> Option<ElementType> {
+    (1..=u8::MAX).filter_map(ElementType::from_u8).find(|t| {
+        t.family() == family
+            && t.order() == order
+            && (t.is_complete() || t.is_serendipity())
+            && t.nodes() == Ok(num_nodes)
+    })
+}
+
+/// Maps reference coordinates of the unit triangle onto the triangle with the given vertices
+fn map_to_triangle(vertices: &[[f64; 3]], xi: [f64; 3]) -> [f64; 3] {
+    [0, 1, 2].map(|d| {
+        vertices[0][d]
+            + xi[0] * (vertices[1][d] - vertices[0][d])
+            + xi[1] * (vertices[2][d] - vertices[0][d])
+    })
+}
+
+/// Maps reference coordinates of the `[-1, 1]^2` quadrangle onto the quadrangle with the given vertices
+fn map_to_quadrangle(vertices: &[[f64; 3]], xi: [f64; 3]) -> [f64; 3] {
+    let (u, v) = (xi[0], xi[1]);
+    let weights = [
+        0.25 * (1.0 - u) * (1.0 - v),
+        0.25 * (1.0 + u) * (1.0 - v),
+        0.25 * (1.0 + u) * (1.0 + v),
+        0.25 * (1.0 - u) * (1.0 + v),
+    ];
+    [0, 1, 2].map(|d| {
+        vertices
+            .iter()
+            .zip(&weights)
+            .map(|(vertex, w)| w * vertex[d])
+            .sum()
+    })
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    dot(sub(a, b), sub(a, b)).sqrt()
+}
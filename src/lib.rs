@@ -28,8 +28,8 @@
 //! an arbitray number of [`ElementBlock`](mshfile/struct.ElementBlock.html) instances, where each
 //! `ElementBlock` only contains elements of the same type and dimension.
 //!
-//! Currently, only the following sections of MSH files are actually parsed: `Entities`, `Nodes`,
-//! `Elements`. All other sections are silently ignored, if they follow the pattern of being
+//! Currently, only the following sections of MSH files are actually parsed: `PhysicalNames`,
+//! `Entities`, `Nodes`, `Elements`. All other sections are silently ignored, if they follow the pattern of being
 //! delimited by `$SectionName` and `$EndSectionName` (in accordance to the MSH format specification).
 //!
 //! Note that the actual values are not checked for consistency beyond what is defined in the MSH format specification.
@@ -60,24 +60,72 @@ use nom::IResult;
 /// Error handling components of the parser
 #[allow(unused)]
 pub mod error;
+/// Export of parsed MSH files to other mesh file formats
+///
+/// Each supported format has its own module with `write` functions that take a
+/// [`MshFile`](../mshfile/struct.MshFile.html) and an output implementing `std::io::Write`.
+/// MEDIT files can also be read back as a `MshFile`, see [`medit::read`](medit/fn.read.html).
+pub mod export;
+/// All nodes and elements of a mesh with connectivity given by node indices
+///
+/// This is the common input of the mesh writers in `export` and the computations in `geometry`.
+mod flat_mesh;
+/// Geometric computations on the elements of parsed MSH files
+///
+/// The element quality metrics are provided by the [`quality`](quality/index.html) module, e.g.
+/// [`quality::element_qualities`](quality/fn.element_qualities.html) evaluates all elements of a
+/// [`MshFile`](../mshfile/struct.MshFile.html). Lengths, areas, volumes, centroids and normals are
+/// provided by the [`measures`](measures/index.html) module.
+pub mod geometry;
+/// Lagrange reference elements (node ordering follows Gmsh)
+mod lagrange;
 /// Contains all types that are used to represent the structure of parsed MSH files
 ///
 /// The central type is [`MshFile`](struct.MshFile.html) which contains the whole structure of the
 /// parsed mesh.
 pub mod mshfile;
+/// Borrowed representation of binary MSH files that decodes node and element data lazily
+///
+/// The central type is [`MshFileRef`](struct.MshFileRef.html) which is returned by
+/// [`parse_msh_bytes_ref`](../fn.parse_msh_bytes_ref.html).
+pub mod mshfile_ref;
+/// Memory mapped loading of MSH files (requires the `mmap` feature)
+#[cfg(feature = "mmap")]
+pub mod mmap;
 /// Parser utility functions used by this MSH parser (may be private in the future)
 pub mod parsers;
+/// Visitor based parsing of MSH files from `Read` sources without loading them into memory
+///
+/// The entry point is [`parse_msh_stream`](fn.parse_msh_stream.html) which passes the contents of
+/// a MSH file to a [`MshVisitor`](trait.MshVisitor.html).
+pub mod streaming;
+/// Mapping of MSH element types to VTK cell types including the permutation of the element nodes
+///
+/// The VTK cell type of an element type is returned by
+/// [`ElementType::vtk_cell_type`](../mshfile/enum.ElementType.html#method.vtk_cell_type), the
+/// node permutation by [`ElementType::vtk_node_ordering`](../mshfile/enum.ElementType.html#method.vtk_node_ordering).
+pub mod vtk;
 
 /// Error type returned by the MSH parser if parsing fails without panic
 pub use error::MshParserError;
 /// Re-exports all types that are used to represent the structure of an MSH file
 pub use mshfile::*;
+/// Re-exports the borrowed representation of an MSH file
+pub use mshfile_ref::MshFileRef;
+/// Re-exports the visitor based streaming parser
+pub use streaming::{parse_msh_stream, MshVisitor};
+/// Re-exports the memory mapped file loading
+#[cfg(feature = "mmap")]
+pub use mmap::{open_mmap, parse_mmap, MshMmap};
 
-use crate::error::{make_error, MapMshError, MshParserErrorKind};
+use crate::error::{make_error, MapMshError, MshParserErrorKind, ValueType};
+use crate::mshfile_ref::{BinaryLayout, MshDataRef};
 use error::{always_error, context};
+use parsers::num_parser_traits::{ParsesFloat, ParsesInParallel, ParsesInt, ParsesSizeT};
 use parsers::{br, take_sp};
 use parsers::{
-    parse_element_section, parse_entity_section, parse_header_section, parse_node_section,
+    parse_element_section, parse_element_section_ref, parse_entity_section, parse_header_section,
+    parse_node_section, parse_node_section_ref, parse_physical_names_section,
 };
 
 // TODO: Error instead of panic on num_parser construction if size of the data type is not supported
@@ -89,7 +137,6 @@ use parsers::{
 //  (e.g. a single section parser, then per section type one header and one content parser)
 // TODO: Unify entity parsing (currently, point parsers and the curve/surface/volume parsers are separate)
 
-// TODO: Implement parser for physical groups
 // TODO: Log in the MeshData struct which unknown sections were ignored
 // TODO: Add more .context() calls/more specialized errors
 // TODO: Replace remaining unimplemented!/expect calls with errors
@@ -122,18 +169,160 @@ pub fn parse_msh_bytes<'a>(
     input.try_into()
 }
 
+/// Try to parse a [`MshFileRef`](mshfile_ref/struct.MshFileRef.html) from a slice of bytes without copying node and element data
+///
+/// The input has to be the content of a binary encoded MSH file of file format version 4.1.
+/// Node coordinates and element connectivity are not decoded during parsing. Instead, the returned
+/// structure contains views into the input that decode the values when they are accessed.
+/// This allows to parse e.g. memory mapped files while only reading the blocks that are actually used.
+///
+/// ```
+/// # let msh_bytes = "$MeshFormat\n4.1 0 8\n$EndMeshFormat\n".as_bytes();
+/// // Parsing ASCII files is not supported by the zero-copy parser
+/// assert!(mshio::parse_msh_bytes_ref(msh_bytes).is_err());
+/// ```
+pub fn parse_msh_bytes_ref<'a>(
+    input: &'a [u8],
+) -> Result<MshFileRef<'a>, MshParserError<&'a [u8]>> {
+    match private_parse_msh_bytes_ref(input) {
+        Ok((_, file)) => Ok(file),
+        Err(e) => Err(e.into()),
+    }
+}
+
 fn private_parse_msh_bytes<'a>(
     input: &'a [u8],
 ) -> IResult<&'a [u8], MshFile<u64, i32, f64>, MshParserError<&'a [u8]>> {
-    let (input, (header, parsers)) = context(
+    let (input, (header, parsers)) = parse_msh_header(input)?;
+
+    let (input, (physical_names, entities, nodes, elements)) = parse_msh_sections(
+        input,
+        |i| context("entity section", parse_entity_section(&parsers))(i),
+        |i| context("node section", parse_node_section(&parsers))(i),
+        |i| context("element section", parse_element_section(&parsers))(i),
+    )?;
+
+    Ok((
+        input,
+        MshFile {
+            header,
+            data: MshData {
+                physical_names,
+                entities,
+                nodes,
+                elements,
+            },
+        },
+    ))
+}
+
+fn private_parse_msh_bytes_ref<'a>(
+    input: &'a [u8],
+) -> IResult<&'a [u8], MshFileRef<'a>, MshParserError<&'a [u8]>> {
+    let header_input = input;
+    let (input, (header, parsers)) = parse_msh_header(input)?;
+
+    let endianness = match header.endianness {
+        Some(endianness) => endianness,
+        None => {
+            return Err(
+                make_error(header_input, MshParserErrorKind::Unimplemented).with_context(
+                    header_input,
+                    "Zero-copy parsing is only supported for binary MSH files",
+                ),
+            )
+        }
+    };
+
+    // Only size_t values that fit into u64 can be decoded by the views
+    if ![1, 2, 4, 8].contains(&header.size_t_size) {
+        return Err(make_error(
+            header_input,
+            MshParserErrorKind::UnsupportedTypeSize(ValueType::UnsignedInt, header.size_t_size),
+        ));
+    }
+
+    let layout = BinaryLayout {
+        size_t_size: header.size_t_size,
+        endianness,
+    };
+
+    let (input, (physical_names, entities, nodes, elements)) = parse_msh_sections(
+        input,
+        |i| context("entity section", parse_entity_section(&parsers))(i),
+        |i| context("node section", parse_node_section_ref(&parsers, layout))(i),
+        |i| {
+            context(
+                "element section",
+                parse_element_section_ref(&parsers, layout),
+            )(i)
+        },
+    )?;
+
+    Ok((
+        input,
+        MshFileRef {
+            header,
+            data: MshDataRef {
+                physical_names,
+                entities,
+                nodes,
+                elements,
+            },
+        },
+    ))
+}
+
+/// Parses the `$MeshFormat` section at the beginning of a MSH file
+#[allow(clippy::type_complexity)]
+fn parse_msh_header(
+    input: &[u8],
+) -> IResult<
+    &[u8],
+    (
+        MshHeader,
+        impl ParsesSizeT<u64> + ParsesInt<i32> + ParsesFloat<f64> + ParsesInParallel,
+    ),
+    MshParserError<&[u8]>,
+> {
+    context(
         "MSH file header section",
         parsers::parse_delimited_block(
             terminated(tag("$MeshFormat"), br),
             terminated(tag("$EndMeshFormat"), br),
             context("MSH format header content", parse_header_section),
         ),
-    )(input)?;
+    )(input)
+}
+
+/// Parser result containing the optional physical name, entity, node and element sections of a MSH file
+#[allow(clippy::type_complexity)]
+type MshSectionsResult<'a, E, N, L> = IResult<
+    &'a [u8],
+    (
+        Option<Vec<PhysicalName<i32>>>,
+        Option<E>,
+        Option<N>,
+        Option<L>,
+    ),
+    MshParserError<&'a [u8]>,
+>;
 
+/// Parses all sections following the file header using the supplied section content parsers
+///
+/// Unknown sections are skipped. At the moment, at most one section of each known type is allowed.
+/// The `$PhysicalNames` section is always ASCII encoded and therefore parsed the same way for all files.
+fn parse_msh_sections<'a, E, N, L, PE, PN, PL>(
+    input: &'a [u8],
+    entity_section_parser: PE,
+    node_section_parser: PN,
+    element_section_parser: PL,
+) -> MshSectionsResult<'a, E, N, L>
+where
+    PE: Fn(&'a [u8]) -> IResult<&'a [u8], E, MshParserError<&'a [u8]>>,
+    PN: Fn(&'a [u8]) -> IResult<&'a [u8], N, MshParserError<&'a [u8]>>,
+    PL: Fn(&'a [u8]) -> IResult<&'a [u8], L, MshParserError<&'a [u8]>>,
+{
     // Closure to detect a line with a section start tag
     let section_detected = |start_tag, input| {
         peek::<_, _, (), _>(delimited(take_sp, tag(start_tag), br))(input).is_ok()
@@ -150,6 +339,7 @@ fn private_parse_msh_bytes<'a>(
         }};
     }
 
+    let mut physical_name_sections = Vec::new();
     let mut entity_sections = Vec::new();
     let mut node_sections = Vec::new();
     let mut element_sections = Vec::new();
@@ -158,38 +348,38 @@ fn private_parse_msh_bytes<'a>(
 
     // Loop over all sections of the mesh file
     while !parsers::eof::<_, ()>(input).is_ok() {
-        // Check for entity section
-        if section_detected("$Entities", input) {
-            let (input_, entities) = parse_section!(
-                "$Entities",
-                "$EndEntities",
-                |i| context("entity section", parse_entity_section(&parsers))(i),
+        // Check for physical names section
+        if section_detected("$PhysicalNames", input) {
+            let (input_, physical_names) = parse_section!(
+                "$PhysicalNames",
+                "$EndPhysicalNames",
+                context("physical names section", parse_physical_names_section),
                 input
             )?;
 
+            physical_name_sections.push(physical_names);
+            input = input_;
+        }
+        // Check for entity section
+        else if section_detected("$Entities", input) {
+            let (input_, entities) =
+                parse_section!("$Entities", "$EndEntities", &entity_section_parser, input)?;
+
             entity_sections.push(entities);
             input = input_;
         }
         // Check for node section
         else if section_detected("$Nodes", input) {
-            let (input_, nodes) = parse_section!(
-                "$Nodes",
-                "$EndNodes",
-                |i| context("node section", parse_node_section(&parsers))(i),
-                input
-            )?;
+            let (input_, nodes) =
+                parse_section!("$Nodes", "$EndNodes", &node_section_parser, input)?;
 
             node_sections.push(nodes);
             input = input_;
         }
         // Check for elements section
         else if section_detected("$Elements", input) {
-            let (input_, elements) = parse_section!(
-                "$Elements",
-                "$EndElements",
-                |i| context("element section", parse_element_section(&parsers))(i),
-                input
-            )?;
+            let (input_, elements) =
+                parse_section!("$Elements", "$EndElements", &element_section_parser, input)?;
 
             element_sections.push(elements);
             input = input_;
@@ -217,6 +407,13 @@ fn private_parse_msh_bytes<'a>(
 
     // TODO: Replace the unimplemented! calls with errors
 
+    let physical_names = match physical_name_sections.len() {
+        1 => Some(physical_name_sections.remove(0)),
+        0 => None,
+        _ => return Err(make_error(input, MshParserErrorKind::Unimplemented)
+            .with_context(input, "Multiple physical name sections found in the MSH file, this cannot be handled at the moment.")),
+    };
+
     let entities = match entity_sections.len() {
         1 => Some(entity_sections.remove(0)),
         0 => None,
@@ -240,15 +437,5 @@ fn private_parse_msh_bytes<'a>(
             .with_context(input, "Multiple element sections found in the MSH file, this cannot be handled at the moment.")),
     };
 
-    Ok((
-        input,
-        MshFile {
-            header,
-            data: MshData {
-                entities,
-                nodes,
-                elements,
-            },
-        },
-    ))
+    Ok((input, (physical_names, entities, nodes, elements)))
 }
@@ -0,0 +1,91 @@
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::error::MshParserError;
+use crate::mshfile_ref::MshFileRef;
+
+/// A memory mapped MSH file
+///
+/// The mesh data is parsed from the mapped memory using [`parse`](#method.parse) which returns a
+/// [`MshFileRef`](../mshfile_ref/struct.MshFileRef.html) that borrows from the map. Only the parts
+/// of the file that are actually accessed are read from disk by the operating system.
+pub struct MshMmap {
+    mmap: Mmap,
+}
+
+impl MshMmap {
+    /// Returns the whole content of the mapped file
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.mmap
+    }
+
+    /// Parses the mapped file without copying node and element data
+    ///
+    /// This only supports binary MSH files, see [`parse_msh_bytes_ref`](../fn.parse_msh_bytes_ref.html).
+    /// ASCII files can still be parsed by passing [`as_bytes`](#method.as_bytes) to `parse_msh_bytes`.
+    /// Use [`MshParserError::offset_in`](../error/struct.MshParserError.html#method.offset_in) with
+    /// the mapped bytes to obtain the file offset of a parser error.
+    pub fn parse(&self) -> Result<MshFileRef<'_>, MshParserError<&[u8]>> {
+        crate::parse_msh_bytes_ref(self.as_bytes())
+    }
+}
+
+/// Memory maps the MSH file at the given path for parsing
+///
+/// ```no_run
+/// use std::error::Error;
+///
+/// fn main() -> Result<(), Box<dyn Error>> {
+///     let mapped = mshio::open_mmap("mesh.msh")?;
+///     let msh = mapped
+///         .parse()
+///         .map_err(|e| format!("Error at offset {:?}:\n{}", e.offset_in(mapped.as_bytes()), e))?;
+///     println!("Mesh has {} nodes", msh.total_node_count());
+///
+///     Ok(())
+/// }
+/// ```
+///
+/// Note that the contents of the map (and therefore of all views returned by the parser) are
+/// undefined if the file is modified by another process while it is mapped. To map and parse a
+/// file in one step, see [`parse_mmap`](fn.parse_mmap.html).
+pub fn open_mmap<P: AsRef<Path>>(path: P) -> io::Result<MshMmap> {
+    let file = File::open(path)?;
+    // Safety: the map is read-only, modifications of the file by other processes are documented above
+    let mmap = unsafe { Mmap::map(&file)? };
+    Ok(MshMmap { mmap })
+}
+
+/// Memory maps and parses the MSH file at the given path and passes the map and the parsed mesh to `f`
+///
+/// The parsed mesh borrows from the map, so both cannot be returned together in one value (it would
+/// be self-referential). Instead, they are passed to the closure whose result is returned. The map
+/// is provided along with the parser result, e.g. to compute error offsets in the mapped file. Only
+/// errors of opening and mapping the file are reported as `io::Error`, the parser result is left to
+/// the closure. See [`MshMmap::parse`](struct.MshMmap.html#method.parse) for the supported files.
+///
+/// ```no_run
+/// use std::error::Error;
+///
+/// fn main() -> Result<(), Box<dyn Error>> {
+///     let num_nodes = mshio::parse_mmap("mesh.msh", |mapped, msh| match msh {
+///         Ok(msh) => Ok(msh.total_node_count()),
+///         Err(e) => Err(format!("Error at offset {:?}:\n{}", e.offset_in(mapped.as_bytes()), e)),
+///     })??;
+///     println!("Mesh has {} nodes", num_nodes);
+///
+///     Ok(())
+/// }
+/// ```
+pub fn parse_mmap<P, R, Fun>(path: P, f: Fun) -> io::Result<R>
+where
+    P: AsRef<Path>,
+    Fun: for<'a> FnOnce(&'a MshMmap, Result<MshFileRef<'a>, MshParserError<&'a [u8]>>) -> R,
+{
+    let mapped = open_mmap(path)?;
+    let msh = mapped.parse();
+    Ok(f(&mapped, msh))
+}
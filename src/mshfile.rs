@@ -8,6 +8,28 @@ use nom::number::Endianness;
 use num::Integer;
 use num_derive::FromPrimitive;
 use num_traits::{Float, FromPrimitive, NumCast, Signed, ToPrimitive, Unsigned};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::lagrange;
+use crate::vtk::{self, VtkCellType};
+
+mod adjacency;
+mod boundary;
+mod bounding_box;
+mod duplicate_nodes;
+mod entity_graph;
+mod physical_group;
+mod renumber;
+mod stats;
+
+pub use adjacency::{Csr, ElementFace, FaceAdjacency, NodeElements};
+pub use boundary::{Boundary, BoundaryFace};
+pub use bounding_box::{BoundingBox, BoundingBoxMismatch};
+pub use entity_graph::{EntityGraph, OrientedTag};
+pub use physical_group::PhysicalGroup;
+pub use renumber::{Renumbering, TagOrdering};
+pub use stats::{ElementSizeStats, EntityStats, MeshStats, PhysicalGroupStats, TagRange};
 
 /// Super-trait for all purposes in the MSH parser that require `size_t` like types
 pub trait MshUsizeT:
@@ -65,6 +87,7 @@ impl<T: Float + ToPrimitive + Copy + Clone + Debug + str::FromStr> MshFloatT for
 /// Models MSH files after revision 4.1 described at
 /// [gmsh.info](http://gmsh.info/doc/texinfo/gmsh.html#MSH-file-format)
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MshFile<U, I, F>
 where
     U: MshUsizeT,
@@ -151,6 +174,7 @@ where
 
 /// The header of a MSH file (irrelevant for most users)
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MshHeader {
     /// File format version of the parsed MSH file
     pub version: f64,
@@ -163,17 +187,57 @@ pub struct MshHeader {
     /// Size in bytes of the float data type in this MSH file
     pub float_size: usize,
     /// The detected endianness of this MSh file if it is binary
+    #[cfg_attr(feature = "serde", serde(with = "serde_endianness"))]
     pub endianness: Option<Endianness>,
 }
 
+/// Serialization of the endianness of binary files, as `nom`'s type does not implement serde traits
+#[cfg(feature = "serde")]
+mod serde_endianness {
+    use nom::number::Endianness;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    enum EndiannessDef {
+        Big,
+        Little,
+    }
+
+    pub fn serialize<S: Serializer>(
+        endianness: &Option<Endianness>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        endianness
+            .map(|e| match e {
+                Endianness::Big => EndiannessDef::Big,
+                Endianness::Little => EndiannessDef::Little,
+            })
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Endianness>, D::Error> {
+        Ok(
+            Option::<EndiannessDef>::deserialize(deserializer)?.map(|e| match e {
+                EndiannessDef::Big => Endianness::Big,
+                EndiannessDef::Little => Endianness::Little,
+            }),
+        )
+    }
+}
+
 /// Contains all parsed sections of the MSH file
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MshData<U, I, F>
 where
     U: MshUsizeT,
     I: MshIntT,
     F: MshFloatT,
 {
+    /// Names of the physical groups of this mesh (if it contains a physical names section)
+    pub physical_names: Option<Vec<PhysicalName<I>>>,
     /// Geometric entities of this mesh such as points, curves, etc. (if it contains entities)
     pub entities: Option<Entities<I, F>>,
     /// Node data of this mesh (if it contains nodes)
@@ -182,8 +246,24 @@ where
     pub elements: Option<Elements<U, I>>,
 }
 
+/// The name of a physical group as defined in the `$PhysicalNames` section
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PhysicalName<I>
+where
+    I: MshIntT,
+{
+    /// Dimension of the physical group
+    pub dim: I,
+    /// Tag of the physical group
+    pub tag: I,
+    /// Name of the physical group (without the enclosing double quotes)
+    pub name: String,
+}
+
 /// Boundary representations of geometrical entities of the MSH file
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Entities<I, F>
 where
     I: MshIntT,
@@ -197,6 +277,7 @@ where
 
 /// A geometrical point entity
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Point<I, F>
 where
     I: MshIntT,
@@ -218,6 +299,7 @@ where
 
 /// A geometrical curve entity and its boundary
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Curve<I, F>
 where
     I: MshIntT,
@@ -247,6 +329,7 @@ where
 
 /// A geometrical surface entity and its boundary
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Surface<I, F>
 where
     I: MshIntT,
@@ -276,6 +359,7 @@ where
 
 /// A geometrical volume entity and its boundary
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Volume<I, F>
 where
     I: MshIntT,
@@ -305,6 +389,7 @@ where
 
 /// All node data of a mesh
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Nodes<U, I, F>
 where
     U: MshUsizeT,
@@ -321,8 +406,106 @@ where
     pub node_blocks: Vec<NodeBlock<U, I, F>>,
 }
 
+impl<U, I, F> Nodes<U, I, F>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+{
+    /// Returns the tags of the nodes of each node block in the order of the nodes
+    pub(crate) fn block_node_tags(&self) -> Vec<Vec<U>> {
+        let mut offset = 0;
+        self.node_blocks
+            .iter()
+            .map(|block| {
+                let block_tags = match &block.node_tags {
+                    // Sparse node tags are stored per block
+                    Some(tags) => {
+                        let mut block_tags = vec![U::zero(); block.nodes.len()];
+                        for (&tag, &i) in tags {
+                            block_tags[i] = tag;
+                        }
+                        block_tags
+                    }
+                    // Otherwise, the nodes are numbered consecutively across all blocks
+                    None => (offset..offset + block.nodes.len())
+                        .map(|i| self.min_node_tag + U::from_usize(i).unwrap())
+                        .collect(),
+                };
+                offset += block.nodes.len();
+                block_tags
+            })
+            .collect()
+    }
+
+    /// Assembles nodes from blocks given by an original block, the node tags, the nodes and their
+    /// parametric coordinates
+    ///
+    /// The entity of each block is copied from its original block, a block is parametric if its
+    /// parametric coordinates are given and empty blocks are dropped. Consecutive tags in block
+    /// order are stored without the tag maps, like the parser does.
+    pub(crate) fn from_tagged_blocks(blocks: Vec<TaggedNodeBlock<U, I, F>>) -> Self {
+        let blocks: Vec<_> = blocks
+            .into_iter()
+            .filter(|(_, _, nodes, _)| !nodes.is_empty())
+            .collect();
+
+        let all_tags = blocks.iter().flat_map(|(_, tags, _, _)| tags.iter().copied());
+        let num_nodes: usize = blocks.iter().map(|(_, tags, _, _)| tags.len()).sum();
+        let (min_node_tag, max_node_tag) =
+            min_max(all_tags.clone()).unwrap_or((U::zero(), U::zero()));
+        let consecutive = all_tags
+            .enumerate()
+            .all(|(i, tag)| tag == min_node_tag + U::from_usize(i).unwrap());
+
+        Nodes {
+            num_nodes: U::from_usize(num_nodes).unwrap(),
+            min_node_tag,
+            max_node_tag,
+            node_blocks: blocks
+                .into_iter()
+                .map(|(block, tags, nodes, parametric_nodes)| NodeBlock {
+                    entity_dim: block.entity_dim,
+                    entity_tag: block.entity_tag,
+                    parametric: parametric_nodes.is_some(),
+                    node_tags: if consecutive {
+                        None
+                    } else {
+                        Some(
+                            tags.into_iter()
+                                .enumerate()
+                                .map(|(i, tag)| (tag, i))
+                                .collect::<HashMap<_, _>>(),
+                        )
+                    },
+                    nodes,
+                    parametric_nodes,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A node block given by its original block, the tags of its nodes, its nodes and optionally their
+/// parametric coordinates
+pub(crate) type TaggedNodeBlock<'a, U, I, F> = (
+    &'a NodeBlock<U, I, F>,
+    Vec<U>,
+    Vec<Node<F>>,
+    Option<Vec<Node<F>>>,
+);
+
+/// Returns the smallest and largest value of the iterator
+pub(crate) fn min_max<U: MshUsizeT>(values: impl Iterator<Item = U>) -> Option<(U, U)> {
+    values.fold(None, |bounds, value| match bounds {
+        Some((min, max)) => Some((std::cmp::min(min, value), std::cmp::max(max, value))),
+        None => Some((value, value)),
+    })
+}
+
 /// A block of nodes
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NodeBlock<U, I, F>
 where
     U: MshUsizeT,
@@ -356,6 +539,7 @@ where
 /// Note that only the components corresponding to the number of dimensions of the node's block
 /// may contain meaningful values.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Node<F>
 where
     F: MshFloatT,
@@ -370,6 +554,7 @@ where
 
 /// All element data of a mesh
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Elements<U, I>
 where
     U: MshUsizeT,
@@ -387,6 +572,7 @@ where
 
 /// A block of elements
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ElementBlock<U, I>
 where
     U: MshUsizeT,
@@ -410,6 +596,7 @@ where
 
 /// Data of one mesh element
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Element<U>
 where
     U: Unsigned + Integer,
@@ -435,6 +622,7 @@ where
 /// let elems: HashMap<_,_> = vec![(ElementType::Tet4, 2),(ElementType::Tri3, 10)].into_iter().collect();
 /// ```
 #[derive(Copy, Clone, Debug, PartialEq, Eq, FromPrimitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ElementType {
     Lin2 = 1,
     Tri3 = 2,
@@ -587,9 +775,10 @@ pub enum ElementType {
 
 impl ElementType {
     /// Returns the number of nodes per element of the given type
-    /// 
-    /// Not that for some Gmsh supported element types there is no fixed number of nodes per elements.
-    /// This crate currently does not support parsing of such elements.
+    ///
+    /// Some element types have no fixed number of nodes per element in Gmsh, i.e. polygons,
+    /// polyhedra and the border elements `TriB` and `PolygB`. This crate currently does not
+    /// support parsing of such elements and returns an error for them.
     pub fn nodes(&self) -> Result<usize, ()> {
         Ok(match self {
             ElementType::Lin2 => 2,
@@ -604,7 +793,7 @@ impl ElementType {
             ElementType::Qua9 => 9,
             ElementType::Tet10 => 10,
             ElementType::Hex27 => 27,
-            ElementType::Pri18 => 28,
+            ElementType::Pri18 => 18,
             ElementType::Pyr14 => 14,
             ElementType::Pnt => 1,
             ElementType::Qua8 => 8,
@@ -658,10 +847,10 @@ impl ElementType {
             ElementType::Lin9 => 9,
             ElementType::Lin10 => 10,
             ElementType::Lin11 => 11,
-            ElementType::LinB => return Err(()),
+            ElementType::LinB => 3,
             ElementType::TriB => return Err(()),
             ElementType::PolygB => return Err(()),
-            ElementType::LinC => return Err(()),
+            ElementType::LinC => 3,
             ElementType::Tet84 => 84,
             ElementType::Tet120 => 120,
             ElementType::Tet165 => 165,
@@ -721,14 +910,437 @@ impl ElementType {
             ElementType::Pyr61 => 61,
             ElementType::Pyr69 => 69,
             ElementType::Pyr1 => 1,
-            ElementType::PntSub => return Err(()),
-            ElementType::LinSub => return Err(()),
-            ElementType::TriSub => return Err(()),
-            ElementType::TetSub => return Err(()),
+            ElementType::PntSub => 1,
+            ElementType::LinSub => 2,
+            ElementType::TriSub => 3,
+            ElementType::TetSub => 4,
             ElementType::Tet16 => 16,
-            ElementType::TriMini => return Err(()),
-            ElementType::TetMini => return Err(()),
-            ElementType::Trih4 => return Err(()),
+            ElementType::TriMini => 4,
+            ElementType::TetMini => 5,
+            ElementType::Trih4 => 4,
         })
     }
+
+    /// Returns the family (i.e. the reference shape) of elements of the given type
+    ///
+    /// ```
+    /// use mshio::mshfile::{ElementFamily, ElementType};
+    ///
+    /// assert_eq!(ElementType::Tet10.family(), ElementFamily::Tetrahedron);
+    /// assert_eq!(ElementType::Qua8.family(), ElementFamily::Quadrangle);
+    /// ```
+    pub fn family(&self) -> ElementFamily {
+        self.properties().0
+    }
+
+    /// Returns the topological dimension (0 to 3) of elements of the given type
+    pub fn dimension(&self) -> usize {
+        self.family().dimension()
+    }
+
+    /// Returns the polynomial order of elements of the given type
+    ///
+    /// Element types with a single node (e.g. `Tri1`) and points have order 0. Element types that
+    /// are not Lagrange elements (e.g. polygons, border and Xfem elements) are reported with order 1.
+    pub fn order(&self) -> usize {
+        self.properties().1
+    }
+
+    /// Returns whether the element is a complete Lagrange element, i.e. it has all nodes of the
+    /// full polynomial space of its order (this includes all linear elements)
+    pub fn is_complete(&self) -> bool {
+        self.properties().2 == Completeness::Complete
+    }
+
+    /// Returns whether the element is an incomplete (serendipity) higher order Lagrange element,
+    /// i.e. it lacks face or volume interior nodes (e.g. `Qua8` or `Hex20`)
+    pub fn is_serendipity(&self) -> bool {
+        self.properties().2 == Completeness::Serendipity
+    }
+
+    /// Returns the number of vertices (i.e. corner nodes) of elements of the given type
+    ///
+    /// The vertices are always the first nodes of an element. Returns `None` for all element types
+    /// without reference nodes and for the single node elements of order 0 (e.g. `Tri1`).
+    /// ```
+    /// use mshio::mshfile::ElementType;
+    ///
+    /// assert_eq!(ElementType::Tet10.vertex_count(), Some(4));
+    /// assert_eq!(ElementType::Pnt.vertex_count(), Some(1));
+    /// assert_eq!(ElementType::Tri1.vertex_count(), None);
+    /// ```
+    pub fn vertex_count(&self) -> Option<usize> {
+        if self.reference_nodes().is_none()
+            || (self.order() == 0 && self.family() != ElementFamily::Point)
+        {
+            return None;
+        }
+        match self.family() {
+            ElementFamily::Point => Some(1),
+            ElementFamily::Line => Some(2),
+            ElementFamily::Triangle => Some(3),
+            ElementFamily::Quadrangle | ElementFamily::Tetrahedron => Some(4),
+            ElementFamily::Pyramid => Some(5),
+            ElementFamily::Prism => Some(6),
+            ElementFamily::Hexahedron => Some(8),
+            ElementFamily::Polygon | ElementFamily::Polyhedron | ElementFamily::Trihedron => None,
+        }
+    }
+
+    /// Returns the name that Gmsh uses for the given element type (e.g. "Tetrahedron 10")
+    ///
+    /// ```
+    /// use mshio::mshfile::ElementType;
+    ///
+    /// assert_eq!(ElementType::Tet10.name(), "Tetrahedron 10");
+    /// assert_eq!(ElementType::Qua16i.name(), "Quadrilateral 16I");
+    /// ```
+    pub fn name(&self) -> &'static str {
+        self.properties().3
+    }
+
+    /// Returns the coordinates of the nodes of the reference element in Gmsh's node ordering
+    ///
+    /// Reference nodes are available for all complete and serendipity Lagrange elements (including
+    /// points and the single node elements of order 0), otherwise `None` is returned. The reference
+    /// domains are the same as in Gmsh:
+    ///  - lines: `[-1, 1]`,
+    ///  - triangles and tetrahedra: the unit simplex with vertices at the origin and the unit vectors,
+    ///  - quadrangles and hexahedra: `[-1, 1]^2` and `[-1, 1]^3`,
+    ///  - prisms: the unit triangle extruded over `[-1, 1]` in z-direction,
+    ///  - pyramids: the base `[-1, 1]^2` at `z = 0` with the apex at `(0, 0, 1)`.
+    ///
+    /// The nodes are generated (and cached) on first use.
+    /// ```
+    /// use mshio::mshfile::ElementType;
+    ///
+    /// let nodes = ElementType::Tri6.reference_nodes().unwrap();
+    /// assert_eq!(nodes.len(), 6);
+    /// assert_eq!(nodes[1], [1.0, 0.0, 0.0]);
+    /// assert_eq!(nodes[3], [0.5, 0.0, 0.0]);
+    /// assert!(ElementType::Polyg.reference_nodes().is_none());
+    /// ```
+    pub fn reference_nodes(&self) -> Option<&'static [[f64; 3]]> {
+        lagrange::reference_element(*self).map(|element| element.nodes.as_slice())
+    }
+
+    /// Evaluates all shape functions of the element type at the given reference coordinates
+    ///
+    /// The shape functions are the nodal (Lagrange) basis functions associated to the
+    /// [`reference_nodes`](#method.reference_nodes), i.e. the shape function of node `i` is one at
+    /// node `i` and zero at all other nodes. Reference coordinates that are not used by elements of
+    /// lower dimension (e.g. `xi[2]` of triangles) are ignored. Returns `None` for all element
+    /// types without reference nodes.
+    ///
+    /// Complete pyramids use the same rational basis as Gmsh. The shape functions of serendipity
+    /// elements span the same spaces as in Gmsh for quadrangles and hexahedra, for all other
+    /// families the spaces of the linear elements enriched by edge modes are used.
+    ///
+    /// Combined with the node coordinates of an element, the shape functions map reference
+    /// coordinates to physical coordinates:
+    /// ```
+    /// use mshio::mshfile::{ElementType, Node};
+    ///
+    /// let nodes = [
+    ///     Node { x: 1.0, y: 1.0, z: 0.0 },
+    ///     Node { x: 3.0, y: 1.0, z: 0.0 },
+    ///     Node { x: 1.0, y: 2.0, z: 0.0 },
+    /// ];
+    ///
+    /// let shape_functions = ElementType::Tri3.shape_functions([0.5, 0.5, 0.0]).unwrap();
+    /// let x: f64 = shape_functions.iter().zip(&nodes).map(|(n, node)| n * node.x).sum();
+    /// let y: f64 = shape_functions.iter().zip(&nodes).map(|(n, node)| n * node.y).sum();
+    /// assert_eq!([x, y], [2.0, 1.5]);
+    /// ```
+    pub fn shape_functions(&self, xi: [f64; 3]) -> Option<Vec<f64>> {
+        lagrange::reference_element(*self).map(|element| element.basis.evaluate(xi, false).0)
+    }
+
+    /// Evaluates the gradients of all shape functions with respect to the reference coordinates
+    ///
+    /// See [`shape_functions`](#method.shape_functions) for details. Gradient components that
+    /// correspond to reference coordinates unused by the element type are zero.
+    /// ```
+    /// use mshio::mshfile::ElementType;
+    ///
+    /// let gradients = ElementType::Qua4.shape_gradients([0.0, 0.0, 0.0]).unwrap();
+    /// assert_eq!(gradients[0], [-0.25, -0.25, 0.0]);
+    /// ```
+    pub fn shape_gradients(&self, xi: [f64; 3]) -> Option<Vec<[f64; 3]>> {
+        lagrange::reference_element(*self).map(|element| element.basis.evaluate(xi, true).1)
+    }
+
+    /// Returns the local node indices of all edges of the element type
+    ///
+    /// The edges are defined as in Gmsh and each edge contains the nodes of a Lagrange line element
+    /// of the same order in Gmsh ordering, i.e. the two vertices of the edge followed by its
+    /// interior nodes. Line elements have a single edge, points and elements of order 0 have no
+    /// edges. Returns `None` for all element types without reference nodes.
+    /// ```
+    /// use mshio::mshfile::ElementType;
+    ///
+    /// let edges = ElementType::Tri6.edges().unwrap();
+    /// assert_eq!(edges[0], vec![0, 1, 3]);
+    /// assert_eq!(edges[1], vec![1, 2, 4]);
+    /// assert_eq!(edges[2], vec![2, 0, 5]);
+    /// ```
+    pub fn edges(&self) -> Option<&'static [Vec<usize>]> {
+        lagrange::reference_element(*self).map(|element| element.topology.edges.as_slice())
+    }
+
+    /// Returns the local node indices of all faces of the element type
+    ///
+    /// The faces are defined as in Gmsh, i.e. the faces of volume elements are oriented such that
+    /// their normals point outwards and surface elements have a single face containing all of
+    /// their nodes. The nodes of each face form an element of the corresponding type returned by
+    /// [`face_types`](#method.face_types) in Gmsh ordering. Points, lines and elements of order 0
+    /// have no faces. Returns `None` for all element types without reference nodes.
+    /// ```
+    /// use mshio::mshfile::ElementType;
+    ///
+    /// let faces = ElementType::Tet10.faces().unwrap();
+    /// assert_eq!(faces[0], vec![0, 2, 1, 6, 5, 4]);
+    /// assert_eq!(ElementType::Tet10.face_types().unwrap()[0], ElementType::Tri6);
+    /// ```
+    pub fn faces(&self) -> Option<&'static [Vec<usize>]> {
+        lagrange::reference_element(*self).map(|element| element.topology.faces.as_slice())
+    }
+
+    /// Returns the element types of the faces returned by [`faces`](#method.faces)
+    ///
+    /// ```
+    /// use mshio::mshfile::ElementType;
+    ///
+    /// let face_types = ElementType::Pri15.face_types().unwrap();
+    /// assert_eq!(face_types[0], ElementType::Tri6);
+    /// assert_eq!(face_types[2], ElementType::Qua8);
+    /// ```
+    pub fn face_types(&self) -> Option<&'static [ElementType]> {
+        lagrange::reference_element(*self).map(|element| element.topology.face_types.as_slice())
+    }
+
+    /// Returns the VTK cell type that elements of the given type are represented by
+    ///
+    /// Linear and quadratic elements are mapped to the corresponding fixed VTK cell types (e.g.
+    /// `Tet10` to `VTK_QUADRATIC_TETRA` or `Hex27` to `VTK_TRIQUADRATIC_HEXAHEDRON`), complete
+    /// elements of higher order to VTK's arbitrary order Lagrange cells and elements of order 0 to
+    /// VTK vertices. Returns `None` for element types without corresponding VTK cell, i.e. pyramids
+    /// with 14 or more nodes, serendipity elements of order 3 and higher (e.g. `Tri9`) and element
+    /// types that are neither complete nor serendipity (e.g. polygons or `TriMini`).
+    /// ```
+    /// use mshio::mshfile::ElementType;
+    /// use mshio::vtk::VtkCellType;
+    ///
+    /// assert_eq!(ElementType::Tet10.vtk_cell_type(), Some(VtkCellType::QuadraticTetra));
+    /// assert_eq!(ElementType::Hex64.vtk_cell_type(), Some(VtkCellType::LagrangeHexahedron));
+    /// assert_eq!(ElementType::Pyr14.vtk_cell_type(), None);
+    /// ```
+    pub fn vtk_cell_type(&self) -> Option<VtkCellType> {
+        vtk::cell_type(*self)
+    }
+
+    /// Returns the permutation from Gmsh's to VTK's node ordering of the element type
+    ///
+    /// The `i`-th node of the VTK cell is the node with index `ordering[i]` of the element in Gmsh
+    /// ordering. Returns `None` if [`vtk_cell_type`](#method.vtk_cell_type) returns `None`.
+    /// ```
+    /// use mshio::mshfile::ElementType;
+    ///
+    /// let ordering = ElementType::Tet10.vtk_node_ordering().unwrap();
+    /// assert_eq!(ordering, &[0, 1, 2, 3, 4, 5, 6, 7, 9, 8]);
+    ///
+    /// let element_nodes = [10, 11, 12, 13, 14, 15, 16, 17, 18, 19];
+    /// let vtk_nodes: Vec<_> = ordering.iter().map(|&i| element_nodes[i]).collect();
+    /// assert_eq!(vtk_nodes, vec![10, 11, 12, 13, 14, 15, 16, 17, 19, 18]);
+    /// ```
+    pub fn vtk_node_ordering(&self) -> Option<&'static [usize]> {
+        vtk::node_ordering(*self)
+    }
+
+    /// Returns family, order, completeness and Gmsh name of the element type
+    fn properties(&self) -> (ElementFamily, usize, Completeness, &'static str) {
+        use Completeness::*;
+        use ElementFamily::*;
+
+        match self {
+            ElementType::Lin2 => (Line, 1, Complete, "Line 2"),
+            ElementType::Tri3 => (Triangle, 1, Complete, "Triangle 3"),
+            ElementType::Qua4 => (Quadrangle, 1, Complete, "Quadrilateral 4"),
+            ElementType::Tet4 => (Tetrahedron, 1, Complete, "Tetrahedron 4"),
+            ElementType::Hex8 => (Hexahedron, 1, Complete, "Hexahedron 8"),
+            ElementType::Pri6 => (Prism, 1, Complete, "Prism 6"),
+            ElementType::Pyr5 => (Pyramid, 1, Complete, "Pyramid 5"),
+            ElementType::Lin3 => (Line, 2, Complete, "Line 3"),
+            ElementType::Tri6 => (Triangle, 2, Complete, "Triangle 6"),
+            ElementType::Qua9 => (Quadrangle, 2, Complete, "Quadrilateral 9"),
+            ElementType::Tet10 => (Tetrahedron, 2, Complete, "Tetrahedron 10"),
+            ElementType::Hex27 => (Hexahedron, 2, Complete, "Hexahedron 27"),
+            ElementType::Pri18 => (Prism, 2, Complete, "Prism 18"),
+            ElementType::Pyr14 => (Pyramid, 2, Complete, "Pyramid 14"),
+            ElementType::Pnt => (Point, 0, Complete, "Point"),
+            ElementType::Qua8 => (Quadrangle, 2, Serendipity, "Quadrilateral 8"),
+            ElementType::Hex20 => (Hexahedron, 2, Serendipity, "Hexahedron 20"),
+            ElementType::Pri15 => (Prism, 2, Serendipity, "Prism 15"),
+            ElementType::Pyr13 => (Pyramid, 2, Serendipity, "Pyramid 13"),
+            ElementType::Tri9 => (Triangle, 3, Serendipity, "Triangle 9"),
+            ElementType::Tri10 => (Triangle, 3, Complete, "Triangle 10"),
+            ElementType::Tri12 => (Triangle, 4, Serendipity, "Triangle 12"),
+            ElementType::Tri15 => (Triangle, 4, Complete, "Triangle 15"),
+            ElementType::Tri15i => (Triangle, 5, Serendipity, "Triangle 15I"),
+            ElementType::Tri21 => (Triangle, 5, Complete, "Triangle 21"),
+            ElementType::Lin4 => (Line, 3, Complete, "Line 4"),
+            ElementType::Lin5 => (Line, 4, Complete, "Line 5"),
+            ElementType::Lin6 => (Line, 5, Complete, "Line 6"),
+            ElementType::Tet20 => (Tetrahedron, 3, Complete, "Tetrahedron 20"),
+            ElementType::Tet35 => (Tetrahedron, 4, Complete, "Tetrahedron 35"),
+            ElementType::Tet56 => (Tetrahedron, 5, Complete, "Tetrahedron 56"),
+            ElementType::Tet22 => (Tetrahedron, 4, Serendipity, "Tetrahedron 22"),
+            ElementType::Tet28 => (Tetrahedron, 5, Serendipity, "Tetrahedron 28"),
+            ElementType::Polyg => (Polygon, 1, Other, "Polygon"),
+            ElementType::Polyh => (Polyhedron, 1, Other, "Polyhedron"),
+            ElementType::Qua16 => (Quadrangle, 3, Complete, "Quadrilateral 16"),
+            ElementType::Qua25 => (Quadrangle, 4, Complete, "Quadrilateral 25"),
+            ElementType::Qua36 => (Quadrangle, 5, Complete, "Quadrilateral 36"),
+            ElementType::Qua12 => (Quadrangle, 3, Serendipity, "Quadrilateral 12"),
+            ElementType::Qua16i => (Quadrangle, 4, Serendipity, "Quadrilateral 16I"),
+            ElementType::Qua20 => (Quadrangle, 5, Serendipity, "Quadrilateral 20"),
+            ElementType::Tri28 => (Triangle, 6, Complete, "Triangle 28"),
+            ElementType::Tri36 => (Triangle, 7, Complete, "Triangle 36"),
+            ElementType::Tri45 => (Triangle, 8, Complete, "Triangle 45"),
+            ElementType::Tri55 => (Triangle, 9, Complete, "Triangle 55"),
+            ElementType::Tri66 => (Triangle, 10, Complete, "Triangle 66"),
+            ElementType::Qua49 => (Quadrangle, 6, Complete, "Quadrilateral 49"),
+            ElementType::Qua64 => (Quadrangle, 7, Complete, "Quadrilateral 64"),
+            ElementType::Qua81 => (Quadrangle, 8, Complete, "Quadrilateral 81"),
+            ElementType::Qua100 => (Quadrangle, 9, Complete, "Quadrilateral 100"),
+            ElementType::Qua121 => (Quadrangle, 10, Complete, "Quadrilateral 121"),
+            ElementType::Tri18 => (Triangle, 6, Serendipity, "Triangle 18"),
+            ElementType::Tri21i => (Triangle, 7, Serendipity, "Triangle 21I"),
+            ElementType::Tri24 => (Triangle, 8, Serendipity, "Triangle 24"),
+            ElementType::Tri27 => (Triangle, 9, Serendipity, "Triangle 27"),
+            ElementType::Tri30 => (Triangle, 10, Serendipity, "Triangle 30"),
+            ElementType::Qua24 => (Quadrangle, 6, Serendipity, "Quadrilateral 24"),
+            ElementType::Qua28 => (Quadrangle, 7, Serendipity, "Quadrilateral 28"),
+            ElementType::Qua32 => (Quadrangle, 8, Serendipity, "Quadrilateral 32"),
+            ElementType::Qua36i => (Quadrangle, 9, Serendipity, "Quadrilateral 36I"),
+            ElementType::Qua40 => (Quadrangle, 10, Serendipity, "Quadrilateral 40"),
+            ElementType::Lin7 => (Line, 6, Complete, "Line 7"),
+            ElementType::Lin8 => (Line, 7, Complete, "Line 8"),
+            ElementType::Lin9 => (Line, 8, Complete, "Line 9"),
+            ElementType::Lin10 => (Line, 9, Complete, "Line 10"),
+            ElementType::Lin11 => (Line, 10, Complete, "Line 11"),
+            ElementType::LinB => (Line, 1, Other, "Line Border"),
+            ElementType::TriB => (Triangle, 1, Other, "Triangle Border"),
+            ElementType::PolygB => (Polygon, 1, Other, "Polygon Border"),
+            ElementType::LinC => (Line, 1, Other, "Line Child"),
+            ElementType::Tet84 => (Tetrahedron, 6, Complete, "Tetrahedron 84"),
+            ElementType::Tet120 => (Tetrahedron, 7, Complete, "Tetrahedron 120"),
+            ElementType::Tet165 => (Tetrahedron, 8, Complete, "Tetrahedron 165"),
+            ElementType::Tet220 => (Tetrahedron, 9, Complete, "Tetrahedron 220"),
+            ElementType::Tet286 => (Tetrahedron, 10, Complete, "Tetrahedron 286"),
+            ElementType::Tet34 => (Tetrahedron, 6, Serendipity, "Tetrahedron 34"),
+            ElementType::Tet40 => (Tetrahedron, 7, Serendipity, "Tetrahedron 40"),
+            ElementType::Tet46 => (Tetrahedron, 8, Serendipity, "Tetrahedron 46"),
+            ElementType::Tet52 => (Tetrahedron, 9, Serendipity, "Tetrahedron 52"),
+            ElementType::Tet58 => (Tetrahedron, 10, Serendipity, "Tetrahedron 58"),
+            ElementType::Lin1 => (Line, 0, Complete, "Line 1"),
+            ElementType::Tri1 => (Triangle, 0, Complete, "Triangle 1"),
+            ElementType::Qua1 => (Quadrangle, 0, Complete, "Quadrilateral 1"),
+            ElementType::Tet1 => (Tetrahedron, 0, Complete, "Tetrahedron 1"),
+            ElementType::Hex1 => (Hexahedron, 0, Complete, "Hexahedron 1"),
+            ElementType::Pri1 => (Prism, 0, Complete, "Prism 1"),
+            ElementType::Pri40 => (Prism, 3, Complete, "Prism 40"),
+            ElementType::Pri75 => (Prism, 4, Complete, "Prism 75"),
+            ElementType::Hex64 => (Hexahedron, 3, Complete, "Hexahedron 64"),
+            ElementType::Hex125 => (Hexahedron, 4, Complete, "Hexahedron 125"),
+            ElementType::Hex216 => (Hexahedron, 5, Complete, "Hexahedron 216"),
+            ElementType::Hex343 => (Hexahedron, 6, Complete, "Hexahedron 343"),
+            ElementType::Hex512 => (Hexahedron, 7, Complete, "Hexahedron 512"),
+            ElementType::Hex729 => (Hexahedron, 8, Complete, "Hexahedron 729"),
+            ElementType::Hex1000 => (Hexahedron, 9, Complete, "Hexahedron 1000"),
+            ElementType::Hex32 => (Hexahedron, 3, Serendipity, "Hexahedron 32"),
+            ElementType::Hex44 => (Hexahedron, 4, Serendipity, "Hexahedron 44"),
+            ElementType::Hex56 => (Hexahedron, 5, Serendipity, "Hexahedron 56"),
+            ElementType::Hex68 => (Hexahedron, 6, Serendipity, "Hexahedron 68"),
+            ElementType::Hex80 => (Hexahedron, 7, Serendipity, "Hexahedron 80"),
+            ElementType::Hex92 => (Hexahedron, 8, Serendipity, "Hexahedron 92"),
+            ElementType::Hex104 => (Hexahedron, 9, Serendipity, "Hexahedron 104"),
+            ElementType::Pri126 => (Prism, 5, Complete, "Prism 126"),
+            ElementType::Pri196 => (Prism, 6, Complete, "Prism 196"),
+            ElementType::Pri288 => (Prism, 7, Complete, "Prism 288"),
+            ElementType::Pri405 => (Prism, 8, Complete, "Prism 405"),
+            ElementType::Pri550 => (Prism, 9, Complete, "Prism 550"),
+            ElementType::Pri24 => (Prism, 3, Serendipity, "Prism 24"),
+            ElementType::Pri33 => (Prism, 4, Serendipity, "Prism 33"),
+            ElementType::Pri42 => (Prism, 5, Serendipity, "Prism 42"),
+            ElementType::Pri51 => (Prism, 6, Serendipity, "Prism 51"),
+            ElementType::Pri60 => (Prism, 7, Serendipity, "Prism 60"),
+            ElementType::Pri69 => (Prism, 8, Serendipity, "Prism 69"),
+            ElementType::Pri78 => (Prism, 9, Serendipity, "Prism 78"),
+            ElementType::Pyr30 => (Pyramid, 3, Complete, "Pyramid 30"),
+            ElementType::Pyr55 => (Pyramid, 4, Complete, "Pyramid 55"),
+            ElementType::Pyr91 => (Pyramid, 5, Complete, "Pyramid 91"),
+            ElementType::Pyr140 => (Pyramid, 6, Complete, "Pyramid 140"),
+            ElementType::Pyr204 => (Pyramid, 7, Complete, "Pyramid 204"),
+            ElementType::Pyr285 => (Pyramid, 8, Complete, "Pyramid 285"),
+            ElementType::Pyr385 => (Pyramid, 9, Complete, "Pyramid 385"),
+            ElementType::Pyr21 => (Pyramid, 3, Serendipity, "Pyramid 21"),
+            ElementType::Pyr29 => (Pyramid, 4, Serendipity, "Pyramid 29"),
+            ElementType::Pyr37 => (Pyramid, 5, Serendipity, "Pyramid 37"),
+            ElementType::Pyr45 => (Pyramid, 6, Serendipity, "Pyramid 45"),
+            ElementType::Pyr53 => (Pyramid, 7, Serendipity, "Pyramid 53"),
+            ElementType::Pyr61 => (Pyramid, 8, Serendipity, "Pyramid 61"),
+            ElementType::Pyr69 => (Pyramid, 9, Serendipity, "Pyramid 69"),
+            ElementType::Pyr1 => (Pyramid, 0, Complete, "Pyramid 1"),
+            ElementType::PntSub => (Point, 0, Other, "Point Xfem"),
+            ElementType::LinSub => (Line, 1, Other, "Line Xfem"),
+            ElementType::TriSub => (Triangle, 1, Other, "Triangle Xfem"),
+            ElementType::TetSub => (Tetrahedron, 1, Other, "Tetrahedron Xfem"),
+            ElementType::Tet16 => (Tetrahedron, 3, Serendipity, "Tetrahedron 16"),
+            ElementType::TriMini => (Triangle, 1, Other, "Triangle Mini"),
+            ElementType::TetMini => (Tetrahedron, 1, Other, "Tetrahedron Mini"),
+            ElementType::Trih4 => (Trihedron, 1, Other, "Trihedron 4"),
+        }
+    }
+}
+
+/// Whether an element type contains all nodes of its polynomial space
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Completeness {
+    Complete,
+    Serendipity,
+    Other,
+}
+
+/// Families of element shapes, corresponding to Gmsh's `TYPE_*` constants
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ElementFamily {
+    Point,
+    Line,
+    Triangle,
+    Quadrangle,
+    Tetrahedron,
+    Hexahedron,
+    Prism,
+    Pyramid,
+    Polygon,
+    Polyhedron,
+    Trihedron,
+}
+
+impl ElementFamily {
+    /// Returns the topological dimension of elements of this family
+    pub fn dimension(&self) -> usize {
+        match self {
+            ElementFamily::Point => 0,
+            ElementFamily::Line => 1,
+            ElementFamily::Triangle | ElementFamily::Quadrangle | ElementFamily::Polygon => 2,
+            ElementFamily::Tetrahedron
+            | ElementFamily::Hexahedron
+            | ElementFamily::Prism
+            | ElementFamily::Pyramid
+            | ElementFamily::Polyhedron
+            | ElementFamily::Trihedron => 3,
+        }
+    }
 }
@@ -0,0 +1,305 @@
+//! Node to element incidence and face neighbors of the elements
+
+use crate::mshfile::{ElementType, Elements, MshIntT, MshUsizeT};
+
+/// The end points of line elements, which are the faces of lines
+static LINE_FACES: [&[usize]; 2] = [&[0], &[1]];
+
+/// Rows of varying length stored in compressed sparse row (CSR) format
+///
+/// The values of row `i` are `values[offsets[i]..offsets[i + 1]]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Csr<T> {
+    /// Start of each row in `values` followed by the total number of values
+    pub offsets: Vec<usize>,
+    /// The values of all rows
+    pub values: Vec<T>,
+}
+
+impl<T> Csr<T> {
+    /// Returns an empty CSR structure without rows
+    fn new() -> Self {
+        Csr {
+            offsets: vec![0],
+            values: Vec::new(),
+        }
+    }
+
+    /// Appends a row containing the given values
+    fn push_row<It: IntoIterator<Item = T>>(&mut self, row: It) {
+        self.values.extend(row);
+        self.offsets.push(self.values.len());
+    }
+
+    /// Returns the number of rows
+    pub fn num_rows(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Returns the values of the given row
+    ///
+    /// Panics if the row does not exist.
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.values[self.offsets[row]..self.offsets[row + 1]]
+    }
+
+    /// Returns an iterator over the values of all rows
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.offsets
+            .windows(2)
+            .map(move |range| &self.values[range[0]..range[1]])
+    }
+}
+
+/// The elements that reference each node, see [`Elements::node_elements`](struct.Elements.html#method.node_elements)
+///
+/// Elements are identified by their index in the order of all element blocks, see
+/// [`Elements::element_location`](struct.Elements.html#method.element_location).
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeElements<U>
+where
+    U: MshUsizeT,
+{
+    /// Tags of all nodes that are referenced by at least one element in ascending order
+    pub node_tags: Vec<U>,
+    /// Indices of the elements referencing each of the nodes in `node_tags` in ascending order
+    pub elements: Csr<usize>,
+}
+
+impl<U> NodeElements<U>
+where
+    U: MshUsizeT,
+{
+    /// Returns the indices of all elements that reference the node with the given tag
+    ///
+    /// Returns `None` if the node is not referenced by any element.
+    pub fn elements_of(&self, node_tag: U) -> Option<&[usize]> {
+        self.node_tags
+            .binary_search(&node_tag)
+            .ok()
+            .map(|row| self.elements.row(row))
+    }
+}
+
+/// A local face of an element, given by the index of the element and the index of the face
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ElementFace {
+    /// Index of the element in the order of all element blocks
+    pub element: usize,
+    /// Index of the face in the local faces of the element
+    pub face: usize,
+}
+
+/// Neighbors of the elements across their faces, see [`Elements::face_adjacency`](struct.Elements.html#method.face_adjacency)
+///
+/// The faces of an element are the entities of one dimension lower on its boundary:
+///  - the faces of volume elements are given by [`ElementType::faces`](enum.ElementType.html#method.faces),
+///  - the faces of surface elements are their edges given by [`ElementType::edges`](enum.ElementType.html#method.edges),
+///  - the faces of lines are their two end points.
+///
+/// Two faces are matched if they have the same vertices, so elements of different types (e.g.
+/// tetrahedra and prisms) and orders are neighbors if they share a face. Only elements of the
+/// same dimension are neighbors of each other, e.g. triangles on the boundary of a tetrahedral
+/// mesh do not affect the adjacency of the tetrahedra.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FaceAdjacency {
+    /// The neighbor across each local face of each element (one row per element)
+    ///
+    /// The neighbor is given by the element index and the index of the shared face in the local
+    /// faces of the neighbor. Boundary faces and non-manifold faces do not have a neighbor.
+    pub neighbors: Csr<Option<ElementFace>>,
+    /// All faces that are not shared with another element in ascending order
+    pub boundary_faces: Vec<ElementFace>,
+    /// Groups of more than two faces with the same vertices (e.g. an edge shared by three triangles)
+    pub non_manifold_faces: Vec<Vec<ElementFace>>,
+}
+
+impl FaceAdjacency {
+    /// Returns the neighbor across the given face, `None` for boundary and non-manifold faces
+    ///
+    /// Panics if the element does not exist or has fewer faces.
+    pub fn neighbor(&self, face: ElementFace) -> Option<ElementFace> {
+        self.neighbors.row(face.element)[face.face]
+    }
+
+    /// Returns the indices of all elements that share a face with the given element
+    ///
+    /// Panics if the element does not exist.
+    pub fn element_neighbors(&self, element: usize) -> impl Iterator<Item = usize> + '_ {
+        self.neighbors
+            .row(element)
+            .iter()
+            .filter_map(|neighbor| neighbor.map(|n| n.element))
+    }
+}
+
+/// Returns the local node indices of the faces of the element type and their number of vertices
+///
+/// See [`FaceAdjacency`] for the definition of the faces. Element types without reference nodes
+/// and points do not have any faces.
+pub(crate) fn local_faces(element_type: ElementType) -> Vec<(&'static [usize], usize)> {
+    match element_type.dimension() {
+        1 if element_type.order() > 0 => LINE_FACES.iter().map(|&face| (face, 1)).collect(),
+        2 => element_type
+            .edges()
+            .map(|edges| edges.iter().map(|edge| (edge.as_slice(), 2)).collect())
+            .unwrap_or_default(),
+        3 => element_type
+            .faces()
+            .zip(element_type.face_types())
+            .map(|(faces, face_types)| {
+                faces
+                    .iter()
+                    .zip(face_types)
+                    .filter_map(|(face, face_type)| {
+                        Some((face.as_slice(), face_type.vertex_count()?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+impl<U, I> Elements<U, I>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+{
+    /// Returns the block index and the index within the block of the element with the given index
+    ///
+    /// Elements are indexed consecutively in the order of the element blocks, starting at zero.
+    /// Returns `None` if the index is out of range.
+    pub fn element_location(&self, index: usize) -> Option<(usize, usize)> {
+        let mut first = 0;
+        for (block_index, block) in self.element_blocks.iter().enumerate() {
+            if index < first + block.elements.len() {
+                return Some((block_index, index - first));
+            }
+            first += block.elements.len();
+        }
+        None
+    }
+
+    /// Returns the indices of the elements that reference each node
+    ///
+    /// Elements are indexed consecutively in the order of the element blocks, see
+    /// [`element_location`](#method.element_location).
+    ///
+    /// ```
+    /// # let msh_bytes = "\
+    /// # $MeshFormat\n4.1 0 8\n$EndMeshFormat
+    /// # $Nodes\n1 4 1 4\n2 1 0 4\n1\n2\n3\n4\n0 0 0\n1 0 0\n1 1 0\n0 1 0\n$EndNodes
+    /// # $Elements\n1 2 1 2\n2 1 2 2\n1 1 2 3\n2 1 3 4\n$EndElements\n";
+    /// // A unit square consisting of the triangles (1, 2, 3) and (1, 3, 4)
+    /// let msh = mshio::parse_msh_bytes(msh_bytes.as_bytes()).unwrap();
+    /// let incidence = msh.data.elements.as_ref().unwrap().node_elements();
+    ///
+    /// assert_eq!(incidence.node_tags, vec![1, 2, 3, 4]);
+    /// assert_eq!(incidence.elements_of(3), Some(&[0, 1][..]));
+    /// assert_eq!(incidence.elements_of(4), Some(&[1][..]));
+    /// assert_eq!(incidence.elements_of(5), None);
+    /// ```
+    pub fn node_elements(&self) -> NodeElements<U> {
+        let mut pairs: Vec<(U, usize)> = self
+            .element_blocks
+            .iter()
+            .flat_map(|block| block.elements.iter())
+            .enumerate()
+            .flat_map(|(index, element)| element.nodes.iter().map(move |&tag| (tag, index)))
+            .collect();
+        pairs.sort_unstable();
+        pairs.dedup();
+
+        let mut node_tags = Vec::new();
+        let mut elements = Csr::new();
+        for group in pairs.chunk_by(|a, b| a.0 == b.0) {
+            node_tags.push(group[0].0);
+            elements.push_row(group.iter().map(|&(_, index)| index));
+        }
+        NodeElements {
+            node_tags,
+            elements,
+        }
+    }
+
+    /// Returns the neighbors of all elements across their faces and the faces on the boundary
+    ///
+    /// See [`FaceAdjacency`](struct.FaceAdjacency.html) for the definition of faces and neighbors.
+    /// Elements are indexed consecutively in the order of the element blocks, see
+    /// [`element_location`](#method.element_location). Elements whose number of nodes does not
+    /// match their type and element types without reference nodes (e.g. polygons) do not have
+    /// any faces.
+    ///
+    /// ```
+    /// use mshio::mshfile::ElementFace;
+    /// # let msh_bytes = "\
+    /// # $MeshFormat\n4.1 0 8\n$EndMeshFormat
+    /// # $Nodes\n1 4 1 4\n2 1 0 4\n1\n2\n3\n4\n0 0 0\n1 0 0\n1 1 0\n0 1 0\n$EndNodes
+    /// # $Elements\n1 2 1 2\n2 1 2 2\n1 1 2 3\n2 1 3 4\n$EndElements\n";
+    /// // A unit square consisting of the triangles (1, 2, 3) and (1, 3, 4)
+    /// let msh = mshio::parse_msh_bytes(msh_bytes.as_bytes()).unwrap();
+    /// let adjacency = msh.data.elements.as_ref().unwrap().face_adjacency();
+    ///
+    /// // The diagonal is the third edge (3, 1) of the first and the first edge (1, 3) of the second triangle
+    /// let diagonal = ElementFace { element: 0, face: 2 };
+    /// assert_eq!(adjacency.neighbor(diagonal), Some(ElementFace { element: 1, face: 0 }));
+    /// assert_eq!(adjacency.boundary_faces.len(), 4);
+    /// ```
+    pub fn face_adjacency(&self) -> FaceAdjacency {
+        // The sorted vertex tags of all faces with the dimension of their element
+        let mut faces: Vec<(usize, Vec<U>, ElementFace)> = Vec::new();
+        let mut neighbors = Csr::new();
+        let mut index = 0;
+        for block in &self.element_blocks {
+            let local_faces = local_faces(block.element_type);
+            let dim = block.element_type.dimension();
+            for element in &block.elements {
+                let num_faces = if block.element_type.nodes() == Ok(element.nodes.len()) {
+                    local_faces.len()
+                } else {
+                    0
+                };
+                for (face, &(nodes, num_vertices)) in local_faces[..num_faces].iter().enumerate() {
+                    let mut vertices: Vec<U> = nodes[..num_vertices]
+                        .iter()
+                        .map(|&i| element.nodes[i])
+                        .collect();
+                    vertices.sort_unstable();
+                    faces.push((
+                        dim,
+                        vertices,
+                        ElementFace {
+                            element: index,
+                            face,
+                        },
+                    ));
+                }
+                neighbors.push_row(std::iter::repeat_n(None, num_faces));
+                index += 1;
+            }
+        }
+        faces.sort_unstable();
+
+        let mut boundary_faces = Vec::new();
+        let mut non_manifold_faces = Vec::new();
+        for group in faces.chunk_by(|a, b| (a.0, &a.1) == (b.0, &b.1)) {
+            match group {
+                [(_, _, face)] => boundary_faces.push(*face),
+                [(_, _, a), (_, _, b)] => {
+                    neighbors.values[neighbors.offsets[a.element] + a.face] = Some(*b);
+                    neighbors.values[neighbors.offsets[b.element] + b.face] = Some(*a);
+                }
+                _ => non_manifold_faces.push(group.iter().map(|(_, _, face)| *face).collect()),
+            }
+        }
+        boundary_faces.sort_unstable();
+        non_manifold_faces.sort_unstable();
+
+        FaceAdjacency {
+            neighbors,
+            boundary_faces,
+            non_manifold_faces,
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! Extraction of the boundary of a mesh as elements of one dimension lower
+
+use std::collections::HashMap;
+
+use crate::lagrange::find_element_type;
+use crate::mshfile::adjacency::local_faces;
+use crate::mshfile::{
+    Element, ElementBlock, ElementFamily, ElementType, Elements, MshIntT, MshUsizeT,
+};
+
+/// Boundary elements returned by [`Elements::extract_boundary`](struct.Elements.html#method.extract_boundary)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Boundary<U, I>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+{
+    /// The boundary elements with one block per element type
+    pub element_blocks: Vec<ElementBlock<U, I>>,
+    /// The origin of each boundary element in the order of the element blocks
+    pub faces: Vec<BoundaryFace<U, I>>,
+}
+
+/// A boundary element and the face of the mesh element it was extracted from
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BoundaryFace<U, I>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+{
+    /// Tag of the boundary element
+    pub boundary_element_tag: U,
+    /// Tag of the element whose face is on the boundary
+    pub element_tag: U,
+    /// Index of the face in the local faces of the element
+    pub face: usize,
+    /// Entity tag and element tag of an existing element of the mesh with the same vertices
+    pub covered_by: Option<(I, U)>,
+}
+
+impl<U, I> Boundary<U, I>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+{
+    /// Returns the boundary faces that are not covered by existing elements of the mesh
+    pub fn uncovered_faces(&self) -> impl Iterator<Item = &BoundaryFace<U, I>> {
+        self.faces.iter().filter(|face| face.covered_by.is_none())
+    }
+
+    /// Appends the boundary element blocks to the given elements and updates their element counts
+    pub fn append_to(self, elements: &mut Elements<U, I>) {
+        for face in &self.faces {
+            let tag = face.boundary_element_tag;
+            if elements.num_elements.is_zero() {
+                elements.min_element_tag = tag;
+                elements.max_element_tag = tag;
+            } else {
+                elements.min_element_tag = elements.min_element_tag.min(tag);
+                elements.max_element_tag = elements.max_element_tag.max(tag);
+            }
+            elements.num_elements = elements.num_elements + U::one();
+        }
+        elements.element_blocks.extend(self.element_blocks);
+    }
+}
+
+/// Returns the element type of the given local face of an element
+fn face_type(element_type: ElementType, face: usize, num_nodes: usize) -> Option<ElementType> {
+    match element_type.dimension() {
+        3 => element_type.face_types()?.get(face).copied(),
+        2 => find_element_type(ElementFamily::Line, element_type.order(), num_nodes),
+        1 => Some(ElementType::Pnt),
+        _ => None,
+    }
+}
+
+impl<U, I> Elements<U, I>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+{
+    /// Extracts the boundary of all elements of the given dimension as elements of one dimension lower
+    ///
+    /// The boundary consists of the faces that are not shared with another element of the same
+    /// dimension (see [`face_adjacency`](#method.face_adjacency), non-manifold faces are not part
+    /// of the boundary). Each boundary face becomes an element of the corresponding type that
+    /// contains all nodes of the face, e.g. quadratic tetrahedra result in quadratic triangles.
+    /// The boundary elements are oriented consistently with the outward normals: faces of volume
+    /// elements are oriented such that their normals point outwards and edges of surface elements
+    /// follow the orientation of the surface elements, i.e. the surface is on the left-hand side
+    /// with respect to the surface normal.
+    ///
+    /// The boundary elements are grouped into one block per element type that is assigned to the
+    /// entity with dimension `dim - 1` and the given tag. Their tags are consecutive and start
+    /// after the largest element tag. For each boundary element, an existing element of dimension
+    /// `dim - 1` with the same vertices is reported, if one exists, so boundary faces that are
+    /// already covered by entities of the mesh can be identified.
+    ///
+    /// ```
+    /// # let msh_bytes = "\
+    /// # $MeshFormat\n4.1 0 8\n$EndMeshFormat
+    /// # $Nodes\n1 4 1 4\n2 1 0 4\n1\n2\n3\n4\n0 0 0\n1 0 0\n1 1 0\n0 1 0\n$EndNodes
+    /// # $Elements\n2 3 1 3\n2 1 2 2\n1 1 2 3\n2 1 3 4\n1 1 1 1\n3 1 2\n$EndElements\n";
+    /// // A unit square consisting of the triangles (1, 2, 3) and (1, 3, 4) and the line (1, 2)
+    /// let mut msh = mshio::parse_msh_bytes(msh_bytes.as_bytes()).unwrap();
+    /// let elements = msh.data.elements.as_mut().unwrap();
+    ///
+    /// let boundary = elements.extract_boundary(2, 10);
+    /// let lines = &boundary.element_blocks[0].elements;
+    /// assert_eq!(lines.len(), 4);
+    /// assert_eq!(lines[0].nodes, vec![1, 2]);
+    /// assert_eq!(lines[0].element_tag, 4);
+    /// assert_eq!(boundary.faces[0].covered_by, Some((1, 3)));
+    /// assert_eq!(boundary.uncovered_faces().count(), 3);
+    ///
+    /// boundary.append_to(elements);
+    /// assert_eq!((elements.num_elements, elements.max_element_tag), (7, 7));
+    /// ```
+    pub fn extract_boundary(&self, dim: usize, entity_tag: I) -> Boundary<U, I> {
+        // Existing elements of the boundary dimension by their sorted vertex tags
+        let mut existing: HashMap<Vec<U>, (I, U)> = HashMap::new();
+        for block in &self.element_blocks {
+            let num_vertices = match block.element_type.vertex_count() {
+                Some(n) if block.element_type.dimension() + 1 == dim => n,
+                _ => continue,
+            };
+            for element in &block.elements {
+                if let Some(vertices) = element.nodes.get(..num_vertices) {
+                    let mut vertices = vertices.to_vec();
+                    vertices.sort_unstable();
+                    existing
+                        .entry(vertices)
+                        .or_insert((block.entity_tag, element.element_tag));
+                }
+            }
+        }
+
+        let mut element_blocks: Vec<ElementBlock<U, I>> = Vec::new();
+        let mut block_faces: Vec<Vec<BoundaryFace<U, I>>> = Vec::new();
+
+        let adjacency = self.face_adjacency();
+        let mut boundary_faces = adjacency.boundary_faces.iter().peekable();
+        let mut index = 0;
+        for block in &self.element_blocks {
+            let faces = local_faces(block.element_type);
+            for element in &block.elements {
+                while let Some(face) = boundary_faces.next_if(|face| face.element == index) {
+                    if block.element_type.dimension() != dim {
+                        continue;
+                    }
+                    let (local_nodes, num_vertices) = faces[face.face];
+                    let face_type =
+                        match face_type(block.element_type, face.face, local_nodes.len()) {
+                            Some(face_type) => face_type,
+                            None => continue,
+                        };
+
+                    let nodes: Vec<U> = local_nodes.iter().map(|&i| element.nodes[i]).collect();
+                    let mut vertices = nodes[..num_vertices].to_vec();
+                    vertices.sort_unstable();
+
+                    let block_index = match element_blocks
+                        .iter()
+                        .position(|b| b.element_type == face_type)
+                    {
+                        Some(i) => i,
+                        None => {
+                            element_blocks.push(ElementBlock {
+                                entity_dim: I::from_usize(dim - 1).unwrap(),
+                                entity_tag,
+                                element_type: face_type,
+                                element_tags: None,
+                                elements: Vec::new(),
+                            });
+                            block_faces.push(Vec::new());
+                            element_blocks.len() - 1
+                        }
+                    };
+                    element_blocks[block_index].elements.push(Element {
+                        element_tag: U::zero(),
+                        nodes,
+                    });
+                    block_faces[block_index].push(BoundaryFace {
+                        boundary_element_tag: U::zero(),
+                        element_tag: element.element_tag,
+                        face: face.face,
+                        covered_by: existing.get(&vertices).copied(),
+                    });
+                }
+                index += 1;
+            }
+        }
+
+        // Number the boundary elements consecutively after the existing elements
+        let mut tag = if self.num_elements.is_zero() {
+            U::one()
+        } else {
+            self.max_element_tag + U::one()
+        };
+        for (block, faces) in element_blocks.iter_mut().zip(block_faces.iter_mut()) {
+            for (element, face) in block.elements.iter_mut().zip(faces.iter_mut()) {
+                element.element_tag = tag;
+                face.boundary_element_tag = tag;
+                tag = tag + U::one();
+            }
+        }
+
+        Boundary {
+            element_blocks,
+            faces: block_faces.into_iter().flatten().collect(),
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! Bounding boxes of the geometrical entities computed from the mesh nodes
+
+use std::collections::{BTreeMap, HashMap};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::mshfile::{MshFile, MshFloatT, MshIntT, MshUsizeT, Node};
+
+/// Axis aligned bounding box
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BoundingBox<F>
+where
+    F: MshFloatT,
+{
+    /// Lower x-, y- and z-coordinate bounds
+    pub min: [F; 3],
+    /// Upper x-, y- and z-coordinate bounds
+    pub max: [F; 3],
+}
+
+impl<F> BoundingBox<F>
+where
+    F: MshFloatT,
+{
+    /// Returns the bounding box of a single node
+    fn from_node(node: &Node<F>) -> Self {
+        let coords = [node.x, node.y, node.z];
+        BoundingBox {
+            min: coords,
+            max: coords,
+        }
+    }
+
+    /// Enlarges the bounding box such that it contains the given node
+    fn extend(&mut self, node: &Node<F>) {
+        for (i, &x) in [node.x, node.y, node.z].iter().enumerate() {
+            self.min[i] = self.min[i].min(x);
+            self.max[i] = self.max[i].max(x);
+        }
+    }
+
+    /// Returns whether this bounding box contains the other box when enlarged by the tolerance
+    pub fn contains(&self, other: &BoundingBox<F>, tolerance: F) -> bool {
+        (0..3).all(|i| {
+            self.min[i] - tolerance <= other.min[i] && other.max[i] <= self.max[i] + tolerance
+        })
+    }
+}
+
+/// A curve, surface or volume whose declared bounding box does not contain all of its nodes
+///
+/// Returned by [`MshFile::verify_entity_bounding_boxes`](struct.MshFile.html#method.verify_entity_bounding_boxes).
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundingBoxMismatch<I, F>
+where
+    I: MshIntT,
+    F: MshFloatT,
+{
+    /// Dimension of the entity
+    pub dim: I,
+    /// Tag of the entity
+    pub tag: I,
+    /// The bounding box stored in the entity section
+    pub declared: BoundingBox<F>,
+    /// The bounding box of the nodes of the entity
+    pub computed: BoundingBox<F>,
+}
+
+/// Evaluates the body for the dimension and each of the curves, surfaces and volumes
+///
+/// The entities are iterated using the given iterator method (i.e. `iter` or `iter_mut`).
+macro_rules! for_each_entity_box {
+    ($entities:expr, $iter:ident, |$dim:ident, $entity:ident| $body:block) => {
+        for $entity in $entities.curves.$iter() {
+            let $dim = 1;
+            $body
+        }
+        for $entity in $entities.surfaces.$iter() {
+            let $dim = 2;
+            $body
+        }
+        for $entity in $entities.volumes.$iter() {
+            let $dim = 3;
+            $body
+        }
+    };
+}
+
+impl<U, I, F> MshFile<U, I, F>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+{
+    /// Computes the bounding boxes of all entities from their nodes
+    ///
+    /// The nodes of an entity are the nodes of its node blocks and all nodes referenced by the
+    /// elements of its element blocks (e.g. the nodes on the boundary of a surface that are stored
+    /// in the node blocks of the bounding curves). Entities without nodes are not contained in the
+    /// returned map, which is indexed by `(dim, tag)` of the entities. Element nodes that are
+    /// missing in the node section are ignored.
+    pub fn compute_entity_bounding_boxes(&self) -> BTreeMap<(I, I), BoundingBox<F>> {
+        let mut boxes: BTreeMap<(I, I), BoundingBox<F>> = BTreeMap::new();
+        let mut add_node = |dim: I, tag: I, node: &Node<F>| {
+            boxes
+                .entry((dim, tag))
+                .and_modify(|b| b.extend(node))
+                .or_insert_with(|| BoundingBox::from_node(node));
+        };
+
+        let mut nodes_by_tag = HashMap::new();
+        if let Some(nodes) = &self.data.nodes {
+            for (block, block_tags) in nodes.node_blocks.iter().zip(nodes.block_node_tags()) {
+                for (tag, node) in block_tags.into_iter().zip(block.nodes.iter()) {
+                    add_node(block.entity_dim, block.entity_tag, node);
+                    nodes_by_tag.insert(tag, node);
+                }
+            }
+        }
+
+        if let Some(elements) = &self.data.elements {
+            for block in &elements.element_blocks {
+                let node_tags = block.elements.iter().flat_map(|e| e.nodes.iter());
+                for node in node_tags.filter_map(|tag| nodes_by_tag.get(tag)) {
+                    add_node(block.entity_dim, block.entity_tag, node);
+                }
+            }
+        }
+
+        boxes
+    }
+
+    /// Returns all curves, surfaces and volumes whose declared bounding box does not contain their nodes
+    ///
+    /// The declared bounding boxes are enlarged by the given absolute tolerance before checking
+    /// whether they contain the bounding boxes computed by
+    /// [`compute_entity_bounding_boxes`](#method.compute_entity_bounding_boxes).
+    /// Point entities do not have a bounding box and are not checked.
+    ///
+    /// ```
+    /// # let msh_bytes = "\
+    /// # $MeshFormat\n4.1 0 8\n$EndMeshFormat
+    /// # $Entities\n0 0 1 0\n1 0 0 0 1 0.5 0 0 0\n$EndEntities
+    /// # $Nodes\n1 3 1 3\n2 1 0 3\n1\n2\n3\n0 0 0\n1 0 0\n0 1 0\n$EndNodes\n";
+    /// let mut msh = mshio::parse_msh_bytes(msh_bytes.as_bytes()).unwrap();
+    ///
+    /// // The declared bounding box of the surface is too small
+    /// let mismatches = msh.verify_entity_bounding_boxes(1e-8);
+    /// assert_eq!(mismatches.len(), 1);
+    /// assert_eq!(mismatches[0].computed.max, [1.0, 1.0, 0.0]);
+    ///
+    /// assert_eq!(msh.update_entity_bounding_boxes(), 1);
+    /// assert!(msh.verify_entity_bounding_boxes(0.0).is_empty());
+    /// ```
+    pub fn verify_entity_bounding_boxes(&self, tolerance: F) -> Vec<BoundingBoxMismatch<I, F>> {
+        let mut mismatches = Vec::new();
+        let entities = match &self.data.entities {
+            Some(entities) => entities,
+            None => return mismatches,
+        };
+
+        let computed_boxes = self.compute_entity_bounding_boxes();
+        for_each_entity_box!(entities, iter, |dim, entity| {
+            let dim = I::from_i32(dim).unwrap();
+            if let Some(computed) = computed_boxes.get(&(dim, entity.tag)) {
+                let declared = BoundingBox {
+                    min: [entity.min_x, entity.min_y, entity.min_z],
+                    max: [entity.max_x, entity.max_y, entity.max_z],
+                };
+                if !declared.contains(computed, tolerance) {
+                    mismatches.push(BoundingBoxMismatch {
+                        dim,
+                        tag: entity.tag,
+                        declared,
+                        computed: *computed,
+                    });
+                }
+            }
+        });
+
+        mismatches
+    }
+
+    /// Overwrites the bounding boxes of all curves, surfaces and volumes with the boxes of their nodes
+    ///
+    /// The bounding boxes are computed by [`compute_entity_bounding_boxes`](#method.compute_entity_bounding_boxes).
+    /// Entities without nodes keep their bounding box. Returns the number of updated entities.
+    pub fn update_entity_bounding_boxes(&mut self) -> usize {
+        let computed_boxes = self.compute_entity_bounding_boxes();
+        let entities = match &mut self.data.entities {
+            Some(entities) => entities,
+            None => return 0,
+        };
+
+        let mut updated = 0;
+        for_each_entity_box!(entities, iter_mut, |dim, entity| {
+            let dim = I::from_i32(dim).unwrap();
+            if let Some(computed) = computed_boxes.get(&(dim, entity.tag)) {
+                let [min_x, min_y, min_z] = computed.min;
+                let [max_x, max_y, max_z] = computed.max;
+                entity.min_x = min_x;
+                entity.min_y = min_y;
+                entity.min_z = min_z;
+                entity.max_x = max_x;
+                entity.max_y = max_y;
+                entity.max_z = max_z;
+                updated += 1;
+            }
+        });
+
+        updated
+    }
+}
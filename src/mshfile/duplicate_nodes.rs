@@ -0,0 +1,136 @@
+//! Detection and merging of coincident nodes
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::mshfile::{MshFile, MshFloatT, MshIntT, MshUsizeT, Nodes};
+
+/// Nodes in the cells of a spatial hash grid given by their position in the order of all nodes,
+/// their tag and their coordinates
+type Grid<U> = HashMap<[i64; 3], Vec<(usize, U, [f64; 3])>>;
+
+/// Returns the Euclidean distance of two points
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    let d = [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+    (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt()
+}
+
+/// Returns the cells of a spatial hash grid that neighbor the given cell (including the cell)
+fn neighbor_cells(cell: [i64; 3]) -> impl Iterator<Item = [i64; 3]> {
+    (0..27).map(move |i| {
+        let offset = [i % 3 - 1, i / 3 % 3 - 1, i / 9 - 1];
+        [0, 1, 2].map(|d| cell[d].saturating_add(offset[d]))
+    })
+}
+
+impl<U, I, F> MshFile<U, I, F>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+{
+    /// Merges coincident nodes whose distance is at most the given tolerance
+    ///
+    /// Nodes are visited in the order of the node blocks. A node is merged into the first
+    /// preceding node that was kept and whose Euclidean distance is at most `tolerance`, so nodes
+    /// in blocks of lower dimensional entities take precedence if the blocks are ordered by
+    /// dimension like in files written by Gmsh. Coincident nodes are found across all node blocks
+    /// using a spatial hash grid with cells of the size of the tolerance.
+    ///
+    /// The merged nodes are removed from their node blocks (blocks without remaining nodes are
+    /// dropped), the number of nodes and the node tag range are updated and the element
+    /// connectivity is rewritten to reference the remaining nodes. Elements may become degenerate
+    /// if several of their nodes are merged. Returns the tags of the removed nodes mapped to the
+    /// tags of the nodes they were merged into. Nodes that appear more than once with the same
+    /// tag and coordinates are removed as well, but are not contained in the returned map.
+    ///
+    /// ```
+    /// # let msh_bytes = "\
+    /// # $MeshFormat\n4.1 0 8\n$EndMeshFormat
+    /// # $Nodes\n2 6 1 6\n2 1 0 3\n1\n2\n3\n0 0 0\n1 0 0\n0 1 0\n2 2 0 3\n4\n5\n6\n1 0 0\n0 1 0\n1 1 0\n$EndNodes
+    /// # $Elements\n2 2 1 2\n2 1 2 1\n1 1 2 3\n2 2 2 1\n2 4 6 5\n$EndElements\n";
+    /// // Two triangles of different surfaces with separate nodes on their common edge
+    /// let mut msh = mshio::parse_msh_bytes(msh_bytes.as_bytes()).unwrap();
+    ///
+    /// let mapping = msh.merge_duplicate_nodes(1e-10);
+    /// assert_eq!(mapping.into_iter().collect::<Vec<_>>(), vec![(4, 2), (5, 3)]);
+    ///
+    /// let nodes = msh.data.nodes.as_ref().unwrap();
+    /// assert_eq!((nodes.num_nodes, nodes.min_node_tag, nodes.max_node_tag), (4, 1, 6));
+    /// let elements = msh.data.elements.as_ref().unwrap();
+    /// assert_eq!(elements.element_blocks[1].elements[0].nodes, vec![2, 6, 3]);
+    /// ```
+    pub fn merge_duplicate_nodes(&mut self, tolerance: F) -> BTreeMap<U, U> {
+        let mut mapping = BTreeMap::new();
+        let nodes = match &mut self.data.nodes {
+            Some(nodes) => nodes,
+            None => return mapping,
+        };
+
+        let tolerance = tolerance.to_f64().unwrap_or(0.0).max(0.0);
+        let cell_size = if tolerance > 0.0 { tolerance } else { 1.0 };
+        let cell_of = |point: [f64; 3]| point.map(|x| (x / cell_size).floor() as i64);
+
+        // The kept nodes by their cell
+        let mut grid: Grid<U> = HashMap::new();
+        let mut num_removed = 0;
+        let mut index = 0;
+        let mut node_blocks = Vec::new();
+        for (block, block_tags) in nodes.node_blocks.iter().zip(nodes.block_node_tags()) {
+            let mut kept_tags = Vec::new();
+            let mut kept_nodes = Vec::new();
+            let mut kept_indices = Vec::new();
+            for (i, (tag, node)) in block_tags.into_iter().zip(block.nodes.iter()).enumerate() {
+                let point = [node.x, node.y, node.z].map(|x| x.to_f64().unwrap_or(f64::NAN));
+                let cell = cell_of(point);
+                let target = neighbor_cells(cell)
+                    .filter_map(|neighbor| grid.get(&neighbor))
+                    .flatten()
+                    .filter(|(_, _, other)| distance(point, *other) <= tolerance)
+                    .min_by_key(|(i, _, _)| *i);
+
+                match target {
+                    Some(&(_, target_tag, _)) => {
+                        if target_tag != tag {
+                            mapping.insert(tag, target_tag);
+                        }
+                        num_removed += 1;
+                    }
+                    None => {
+                        grid.entry(cell).or_default().push((index, tag, point));
+                        kept_tags.push(tag);
+                        kept_nodes.push(node.clone());
+                        kept_indices.push(i);
+                    }
+                }
+                index += 1;
+            }
+            let kept_parametric_nodes = block.parametric_nodes.as_ref().map(|parametric_nodes| {
+                kept_indices
+                    .iter()
+                    .filter_map(|&i| parametric_nodes.get(i).cloned())
+                    .collect()
+            });
+            node_blocks.push((block, kept_tags, kept_nodes, kept_parametric_nodes));
+        }
+
+        if num_removed == 0 {
+            return mapping;
+        }
+        *nodes = Nodes::from_tagged_blocks(node_blocks);
+
+        if let Some(elements) = &mut self.data.elements {
+            let element_nodes = elements
+                .element_blocks
+                .iter_mut()
+                .flat_map(|block| block.elements.iter_mut())
+                .flat_map(|element| element.nodes.iter_mut());
+            for node in element_nodes {
+                if let Some(&target) = mapping.get(node) {
+                    *node = target;
+                }
+            }
+        }
+
+        mapping
+    }
+}
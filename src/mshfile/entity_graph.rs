@@ -0,0 +1,274 @@
+//! Topology queries on the boundary representation of the geometrical entities
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::mshfile::{Entities, MshFile, MshFloatT, MshIntT, MshUsizeT};
+
+/// Tag of an entity together with its orientation relative to a neighboring entity
+///
+/// In the MSH file, bounding entities with opposite orientation are referenced with negative tags.
+/// The tag stored here is always positive.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OrientedTag<I>
+where
+    I: MshIntT,
+{
+    /// The entity tag (always positive)
+    pub tag: I,
+    /// Whether the entity is referenced with opposite orientation
+    pub reversed: bool,
+}
+
+impl<I: MshIntT> OrientedTag<I> {
+    /// Splits a signed tag of a bounding entity into its absolute value and orientation
+    fn from_signed(tag: I) -> Self {
+        OrientedTag {
+            tag: tag.abs(),
+            reversed: tag.is_negative(),
+        }
+    }
+}
+
+/// Graph of the geometrical entities of a MSH file connected by their boundary relations
+///
+/// The graph is built from the bounding entity tags of the curves, surfaces and volumes (i.e.
+/// [`Curve::point_tags`](struct.Curve.html#structfield.point_tags),
+/// [`Surface::curve_tags`](struct.Surface.html#structfield.curve_tags) and
+/// [`Volume::surface_tags`](struct.Volume.html#structfield.surface_tags)).
+/// Entities are identified by their dimension and tag. All queries about an entity that is not
+/// part of the graph return `None`.
+///
+/// ```
+/// # let msh_bytes = "\
+/// # $MeshFormat\n4.1 0 8\n$EndMeshFormat
+/// # $Entities\n2 1 0 0
+/// # 1 0 0 0 0\n2 1 0 0 0
+/// # 1 0 0 0 1 0 0 0 2 1 -2
+/// # $EndEntities\n";
+/// let msh = mshio::parse_msh_bytes(msh_bytes.as_bytes()).unwrap();
+/// let graph = msh.entity_graph();
+///
+/// // The end point of a curve is referenced with opposite orientation
+/// let boundary = graph.boundary(1, 1).unwrap();
+/// assert_eq!((boundary[1].tag, boundary[1].reversed), (2, true));
+///
+/// assert_eq!(graph.upward_adjacency(0, 2).unwrap()[0].tag, 1);
+/// assert_eq!(graph.closure(1, 1).unwrap(), vec![(0, 1), (0, 2), (1, 1)]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct EntityGraph<I>
+where
+    I: MshIntT,
+{
+    /// Bounding entities of dimension `dim - 1` of all entities per dimension `dim`
+    boundaries: [BTreeMap<I, Vec<OrientedTag<I>>>; 4],
+    /// Entities of dimension `dim + 1` bounded by each entity per dimension `dim`
+    upward: [BTreeMap<I, Vec<OrientedTag<I>>>; 4],
+}
+
+impl<I> Default for EntityGraph<I>
+where
+    I: MshIntT,
+{
+    /// Returns a graph without any entities
+    fn default() -> Self {
+        EntityGraph {
+            boundaries: Default::default(),
+            upward: Default::default(),
+        }
+    }
+}
+
+impl<I> EntityGraph<I>
+where
+    I: MshIntT,
+{
+    /// Builds the graph of the given entities
+    pub fn new<F: MshFloatT>(entities: &Entities<I, F>) -> Self {
+        let mut graph = EntityGraph::default();
+
+        let boundaries = entities
+            .points
+            .iter()
+            .map(|p| (0, p.tag, &[][..]))
+            .chain(
+                entities
+                    .curves
+                    .iter()
+                    .map(|c| (1, c.tag, &c.point_tags[..])),
+            )
+            .chain(
+                entities
+                    .surfaces
+                    .iter()
+                    .map(|s| (2, s.tag, &s.curve_tags[..])),
+            )
+            .chain(
+                entities
+                    .volumes
+                    .iter()
+                    .map(|v| (3, v.tag, &v.surface_tags[..])),
+            );
+
+        for (dim, tag, boundary) in boundaries {
+            graph.boundaries[dim].insert(
+                tag,
+                boundary
+                    .iter()
+                    .copied()
+                    .map(OrientedTag::from_signed)
+                    .collect(),
+            );
+            graph.upward[dim].entry(tag).or_default();
+        }
+
+        for dim in 1..4 {
+            for (&tag, boundary) in &graph.boundaries[dim] {
+                for bounding in boundary {
+                    // References to missing entities are not part of the upward adjacency
+                    if let Some(upward) = graph.upward[dim - 1].get_mut(&bounding.tag) {
+                        upward.push(OrientedTag {
+                            tag,
+                            reversed: bounding.reversed,
+                        });
+                    }
+                }
+            }
+        }
+
+        graph
+    }
+
+    /// Returns whether the graph contains the entity with the given dimension and tag
+    pub fn contains(&self, dim: I, tag: I) -> bool {
+        dim_index(dim).is_some_and(|d| self.boundaries[d].contains_key(&tag))
+    }
+
+    /// Returns the tags of all entities of the given dimension in ascending order
+    pub fn entity_tags(&self, dim: I) -> Vec<I> {
+        dim_index(dim).map_or_else(Vec::new, |d| self.boundaries[d].keys().copied().collect())
+    }
+
+    /// Returns the bounding entities of dimension `dim - 1` of an entity with their orientation
+    ///
+    /// The boundary of points is always empty.
+    pub fn boundary(&self, dim: I, tag: I) -> Option<&[OrientedTag<I>]> {
+        self.boundaries[dim_index(dim)?]
+            .get(&tag)
+            .map(|b| b.as_slice())
+    }
+
+    /// Returns the entities of dimension `dim + 1` that are bounded by an entity
+    ///
+    /// The orientation is the one with which the entity appears in the boundary of the returned
+    /// entities, e.g. the volumes bounded by a surface and whether the surface is reversed with
+    /// respect to each volume.
+    pub fn upward_adjacency(&self, dim: I, tag: I) -> Option<&[OrientedTag<I>]> {
+        self.upward[dim_index(dim)?].get(&tag).map(|u| u.as_slice())
+    }
+
+    /// Returns the closure of an entity, i.e. the entity itself and all entities of its boundary
+    /// down to the points, as `(dim, tag)` pairs sorted by dimension and tag
+    pub fn closure(&self, dim: I, tag: I) -> Option<Vec<(I, I)>> {
+        let dim_index = dim_index(dim)?;
+        if !self.boundaries[dim_index].contains_key(&tag) {
+            return None;
+        }
+
+        let mut closure = BTreeSet::new();
+        let mut stack = vec![(dim_index, tag)];
+        while let Some((d, t)) = stack.pop() {
+            if !closure.insert((d, t)) || d == 0 {
+                continue;
+            }
+            if let Some(boundary) = self.boundaries[d].get(&t) {
+                stack.extend(boundary.iter().map(|b| (d - 1, b.tag)));
+            }
+        }
+
+        Some(
+            closure
+                .into_iter()
+                .map(|(d, t)| (I::from_usize(d).unwrap(), t))
+                .collect(),
+        )
+    }
+
+    /// Returns the connected components of all entities of the given dimension
+    ///
+    /// Two curves, surfaces or volumes are connected if they share a bounding entity. Two points
+    /// are connected if they bound the same curve. Each component is sorted by tag and the
+    /// components are sorted by their smallest tag.
+    pub fn connected_components(&self, dim: I) -> Vec<Vec<I>> {
+        let d = match dim_index(dim) {
+            Some(d) => d,
+            None => return Vec::new(),
+        };
+
+        // Entities of the given dimension connected through each shared neighbor
+        let neighbors = |tag: &I| -> Vec<I> {
+            let shared: Vec<I> = if d > 0 {
+                self.boundaries[d][tag].iter().map(|b| b.tag).collect()
+            } else {
+                self.upward[d][tag].iter().map(|u| u.tag).collect()
+            };
+            shared
+                .iter()
+                .flat_map(|s| {
+                    let adjacent = if d > 0 {
+                        self.upward[d - 1].get(s)
+                    } else {
+                        self.boundaries[d + 1].get(s)
+                    };
+                    adjacent.into_iter().flatten().map(|a| a.tag)
+                })
+                .collect()
+        };
+
+        let mut visited = BTreeSet::new();
+        let mut components = Vec::new();
+        for &start in self.boundaries[d].keys() {
+            if !visited.insert(start) {
+                continue;
+            }
+
+            let mut component = vec![start];
+            let mut stack = vec![start];
+            while let Some(tag) = stack.pop() {
+                for neighbor in neighbors(&tag) {
+                    if self.boundaries[d].contains_key(&neighbor) && visited.insert(neighbor) {
+                        component.push(neighbor);
+                        stack.push(neighbor);
+                    }
+                }
+            }
+
+            component.sort_unstable();
+            components.push(component);
+        }
+
+        components
+    }
+}
+
+impl<U, I, F> MshFile<U, I, F>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+{
+    /// Builds the [`EntityGraph`](struct.EntityGraph.html) of the entities of the MSH file
+    ///
+    /// The graph is empty if the MSH file does not contain an entity section.
+    pub fn entity_graph(&self) -> EntityGraph<I> {
+        match &self.data.entities {
+            Some(entities) => EntityGraph::new(entities),
+            None => EntityGraph::default(),
+        }
+    }
+}
+
+/// Converts an entity dimension to an index into the per dimension maps
+fn dim_index<I: MshIntT>(dim: I) -> Option<usize> {
+    dim.to_usize().filter(|&d| d < 4)
+}
@@ -0,0 +1,354 @@
+//! Lookup of physical groups and extraction of their elements as standalone meshes
+
+use std::collections::{BTreeSet, HashSet};
+
+use crate::mshfile::{
+    min_max, ElementBlock, Elements, Entities, MshData, MshFile, MshFloatT, MshIntT, MshUsizeT,
+    NodeBlock, Nodes, PhysicalName, TaggedNodeBlock,
+};
+
+/// The entities and element blocks of a MSH file that belong to a physical group
+///
+/// Returned by [`MshFile::physical_group`](struct.MshFile.html#method.physical_group) and
+/// [`MshFile::physical_group_by_name`](struct.MshFile.html#method.physical_group_by_name).
+#[derive(Clone, Debug, PartialEq)]
+pub struct PhysicalGroup<'a, U, I, F>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+{
+    /// Dimension of the physical group
+    pub dim: I,
+    /// Tag of the physical group
+    pub tag: I,
+    /// Name of the physical group (if it is defined in the `$PhysicalNames` section)
+    pub name: Option<&'a str>,
+    /// Tags of all entities of dimension `dim` that belong to the physical group
+    pub entity_tags: Vec<I>,
+    /// All element blocks that are associated to one of the entities of the physical group
+    pub element_blocks: Vec<&'a ElementBlock<U, I>>,
+    /// The MSH file containing the physical group
+    msh: &'a MshFile<U, I, F>,
+}
+
+impl<U, I, F> MshFile<U, I, F>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+{
+    /// Returns the physical group with the given dimension and tag
+    ///
+    /// Returns `None` if neither an entity of the MSH file belongs to the physical group nor the
+    /// `$PhysicalNames` section contains a name for it.
+    ///
+    /// ```
+    /// # let msh_bytes = "\
+    /// # $MeshFormat\n4.1 0 8\n$EndMeshFormat
+    /// # $PhysicalNames\n1\n2 5 \"Domain\"\n$EndPhysicalNames
+    /// # $Entities\n0 0 1 0\n1 0 0 0 1 1 0 1 5 0\n$EndEntities
+    /// # $Nodes\n1 3 1 3\n2 1 0 3\n1\n2\n3\n0 0 0\n1 0 0\n0 1 0\n$EndNodes
+    /// # $Elements\n1 1 1 1\n2 1 2 1\n1 1 2 3\n$EndElements\n";
+    /// let msh = mshio::parse_msh_bytes(msh_bytes.as_bytes()).unwrap();
+    ///
+    /// let group = msh.physical_group(2, 5).unwrap();
+    /// assert_eq!(group.name, Some("Domain"));
+    /// assert_eq!(group.entity_tags, vec![1]);
+    /// assert_eq!(group.element_blocks.len(), 1);
+    ///
+    /// assert!(msh.physical_group(1, 5).is_none());
+    /// ```
+    pub fn physical_group(&self, dim: I, tag: I) -> Option<PhysicalGroup<'_, U, I, F>> {
+        let name = self.data.physical_names.as_ref().and_then(|names| {
+            names
+                .iter()
+                .find(|n| n.dim == dim && n.tag == tag)
+                .map(|n| n.name.as_str())
+        });
+
+        let entity_tags = match &self.data.entities {
+            Some(entities) => entities_with_physical_tag(entities, dim, tag),
+            None => Vec::new(),
+        };
+
+        if name.is_none() && entity_tags.is_empty() {
+            return None;
+        }
+
+        let element_blocks = match &self.data.elements {
+            Some(elements) => elements
+                .element_blocks
+                .iter()
+                .filter(|b| b.entity_dim == dim && entity_tags.contains(&b.entity_tag))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        Some(PhysicalGroup {
+            dim,
+            tag,
+            name,
+            entity_tags,
+            element_blocks,
+            msh: self,
+        })
+    }
+
+    /// Returns the physical group with the given name as defined in the `$PhysicalNames` section
+    ///
+    /// If several physical groups share the same name, the first one listed in the section is returned.
+    ///
+    /// ```
+    /// # let msh_bytes = "\
+    /// # $MeshFormat\n4.1 0 8\n$EndMeshFormat
+    /// # $PhysicalNames\n1\n2 5 \"Domain\"\n$EndPhysicalNames
+    /// # $Entities\n0 0 1 0\n1 0 0 0 1 1 0 1 5 0\n$EndEntities
+    /// # $Nodes\n1 3 1 3\n2 1 0 3\n1\n2\n3\n0 0 0\n1 0 0\n0 1 0\n$EndNodes
+    /// # $Elements\n1 1 1 1\n2 1 2 1\n1 1 2 3\n$EndElements\n";
+    /// let msh = mshio::parse_msh_bytes(msh_bytes.as_bytes()).unwrap();
+    ///
+    /// let group = msh.physical_group_by_name("Domain").unwrap();
+    /// assert_eq!((group.dim, group.tag), (2, 5));
+    /// assert!(msh.physical_group_by_name("Boundary").is_none());
+    /// ```
+    pub fn physical_group_by_name(&self, name: &str) -> Option<PhysicalGroup<'_, U, I, F>> {
+        let physical_name: &PhysicalName<I> = self
+            .data
+            .physical_names
+            .as_ref()?
+            .iter()
+            .find(|n| n.name == name)?;
+        self.physical_group(physical_name.dim, physical_name.tag)
+    }
+}
+
+impl<'a, U, I, F> PhysicalGroup<'a, U, I, F>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+{
+    /// Returns the total number of elements in the physical group
+    pub fn element_count(&self) -> usize {
+        self.element_blocks.iter().map(|b| b.elements.len()).sum()
+    }
+
+    /// Creates a standalone MSH file that only contains the elements of the physical group
+    ///
+    /// The new file contains the entities of the physical group together with their closure (i.e.
+    /// all entities of their boundaries down to the points, see
+    /// [`EntityGraph::closure`](struct.EntityGraph.html#method.closure)), so the boundary tags of
+    /// all entities refer to entities of the new file. It contains the element blocks of the
+    /// physical group and only the nodes that are referenced by its elements. Node blocks of
+    /// entities outside of the closure are dropped and their referenced nodes are moved to the
+    /// node block of the first entity of the physical group. Node and element tags are preserved,
+    /// so the node blocks are stored with sparse node tags if the remaining node tags are not
+    /// consecutive.
+    ///
+    /// ```
+    /// # let msh_bytes = "\
+    /// # $MeshFormat\n4.1 0 8\n$EndMeshFormat
+    /// # $PhysicalNames\n1\n1 7 \"Edge\"\n$EndPhysicalNames
+    /// # $Entities\n0 1 1 0\n3 0 0 0 1 1 0 1 7 0\n1 0 0 0 1 1 0 0 0\n$EndEntities
+    /// # $Nodes\n1 3 1 3\n2 1 0 3\n1\n2\n3\n0 0 0\n1 0 0\n0 1 0\n$EndNodes
+    /// # $Elements\n2 2 1 2\n1 3 1 1\n1 2 3\n2 1 2 1\n2 1 2 3\n$EndElements\n";
+    /// let msh = mshio::parse_msh_bytes(msh_bytes.as_bytes()).unwrap();
+    ///
+    /// let edge = msh.physical_group_by_name("Edge").unwrap().extract_submesh();
+    /// assert_eq!(edge.total_element_count(), 1);
+    /// assert_eq!(edge.total_node_count(), 2);
+    /// assert_eq!(edge.curve_count(), 1);
+    /// assert_eq!(edge.surface_count(), 0);
+    /// ```
+    pub fn extract_submesh(&self) -> MshFile<U, I, F> {
+        let data = &self.msh.data;
+
+        let physical_names = data.physical_names.as_ref().map(|names| {
+            names
+                .iter()
+                .filter(|n| n.dim == self.dim && n.tag == self.tag)
+                .cloned()
+                .collect()
+        });
+
+        // The entities of the physical group and all entities of their boundaries
+        let graph = self.msh.entity_graph();
+        let closure: BTreeSet<(I, I)> = self
+            .entity_tags
+            .iter()
+            .filter_map(|&tag| graph.closure(self.dim, tag))
+            .flatten()
+            .collect();
+
+        let entities = data.entities.as_ref().map(|entities| {
+            let keep = |dim: usize, tag: &I| {
+                let dim = I::from_usize(dim).unwrap();
+                closure.contains(&(dim, *tag))
+            };
+            Entities {
+                points: entities
+                    .points
+                    .iter()
+                    .filter(|e| keep(0, &e.tag))
+                    .cloned()
+                    .collect(),
+                curves: entities
+                    .curves
+                    .iter()
+                    .filter(|e| keep(1, &e.tag))
+                    .cloned()
+                    .collect(),
+                surfaces: entities
+                    .surfaces
+                    .iter()
+                    .filter(|e| keep(2, &e.tag))
+                    .cloned()
+                    .collect(),
+                volumes: entities
+                    .volumes
+                    .iter()
+                    .filter(|e| keep(3, &e.tag))
+                    .cloned()
+                    .collect(),
+            }
+        });
+
+        let element_blocks: Vec<ElementBlock<U, I>> =
+            self.element_blocks.iter().map(|&b| b.clone()).collect();
+        let referenced_nodes: HashSet<U> = element_blocks
+            .iter()
+            .flat_map(|b| b.elements.iter())
+            .flat_map(|e| e.nodes.iter().copied())
+            .collect();
+
+        let target = self.entity_tags.first().map(|&tag| (self.dim, tag));
+        let nodes = data
+            .nodes
+            .as_ref()
+            .map(|nodes| extract_nodes(nodes, &referenced_nodes, &closure, target));
+
+        let elements = if element_blocks.is_empty() {
+            None
+        } else {
+            let tags = element_blocks
+                .iter()
+                .flat_map(|b| b.elements.iter())
+                .map(|e| e.element_tag);
+            let (min_element_tag, max_element_tag) = min_max(tags).unwrap();
+            Some(Elements {
+                num_elements: U::from_usize(self.element_count()).unwrap(),
+                min_element_tag,
+                max_element_tag,
+                element_blocks,
+            })
+        };
+
+        MshFile {
+            header: self.msh.header.clone(),
+            data: MshData {
+                physical_names,
+                entities,
+                nodes,
+                elements,
+            },
+        }
+    }
+}
+
+/// Returns the tags of all entities of the given dimension that belong to the physical group
+fn entities_with_physical_tag<I: MshIntT, F: MshFloatT>(
+    entities: &Entities<I, F>,
+    dim: I,
+    tag: I,
+) -> Vec<I> {
+    macro_rules! tags_of {
+        ($entities:expr) => {
+            $entities
+                .iter()
+                .filter(|e| e.physical_tags.contains(&tag))
+                .map(|e| e.tag)
+                .collect()
+        };
+    }
+
+    match dim.to_i32() {
+        Some(0) => tags_of!(entities.points),
+        Some(1) => tags_of!(entities.curves),
+        Some(2) => tags_of!(entities.surfaces),
+        Some(3) => tags_of!(entities.volumes),
+        _ => Vec::new(),
+    }
+}
+
+/// Copies all referenced nodes while preserving their tags, empty node blocks are dropped
+///
+/// Nodes of blocks whose entity is not kept are moved to the block of the `target` entity, which
+/// is created if it does not exist.
+fn extract_nodes<U, I, F>(
+    nodes: &Nodes<U, I, F>,
+    referenced_nodes: &HashSet<U>,
+    kept_entities: &BTreeSet<(I, I)>,
+    target: Option<(I, I)>,
+) -> Nodes<U, I, F>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+{
+    let target_block = target.map(|(entity_dim, entity_tag)| NodeBlock {
+        entity_dim,
+        entity_tag,
+        parametric: false,
+        node_tags: None,
+        nodes: Vec::new(),
+        parametric_nodes: None,
+    });
+
+    let mut node_blocks: Vec<TaggedNodeBlock<U, I, F>> = Vec::new();
+    let mut moved_tags = Vec::new();
+    let mut moved_nodes = Vec::new();
+    for (block, block_tags) in nodes.node_blocks.iter().zip(nodes.block_node_tags()) {
+        let (kept_indices, kept_tags): (Vec<usize>, Vec<U>) = block_tags
+            .into_iter()
+            .enumerate()
+            .filter(|(_, tag)| referenced_nodes.contains(tag))
+            .unzip();
+        let kept_nodes: Vec<_> = kept_indices
+            .iter()
+            .map(|&i| block.nodes[i].clone())
+            .collect();
+        if kept_entities.contains(&(block.entity_dim, block.entity_tag)) {
+            let kept_parametric_nodes = block.parametric_nodes.as_ref().map(|parametric_nodes| {
+                kept_indices
+                    .iter()
+                    .filter_map(|&i| parametric_nodes.get(i).cloned())
+                    .collect()
+            });
+            node_blocks.push((block, kept_tags, kept_nodes, kept_parametric_nodes));
+        } else {
+            moved_tags.extend(kept_tags);
+            moved_nodes.extend(kept_nodes);
+        }
+    }
+
+    if let Some(target_block) = &target_block {
+        let target = (target_block.entity_dim, target_block.entity_tag);
+        match node_blocks
+            .iter_mut()
+            .find(|(block, _, _, _)| (block.entity_dim, block.entity_tag) == target)
+        {
+            Some((_, tags, nodes, parametric_nodes)) => {
+                // The parametric coordinates of the moved nodes refer to their original entities
+                if !moved_nodes.is_empty() {
+                    *parametric_nodes = None;
+                }
+                tags.extend(moved_tags);
+                nodes.extend(moved_nodes);
+            }
+            None => node_blocks.push((target_block, moved_tags, moved_nodes, None)),
+        }
+    }
+
+    Nodes::from_tagged_blocks(node_blocks)
+}
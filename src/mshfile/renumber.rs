@@ -0,0 +1,382 @@
+//! Renumbering of node and element tags
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use crate::mshfile::{Element, MshFile, MshFloatT, MshIntT, MshUsizeT, Node};
+
+/// Number of bits per coordinate of the grid used to compute Hilbert curve indices
+const HILBERT_BITS: u32 = 21;
+
+/// Order in which new tags are assigned by [`MshFile::renumber`](struct.MshFile.html#method.renumber)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TagOrdering {
+    /// Keep the order of the nodes and elements in their blocks
+    Compact,
+    /// Order the nodes by the reverse Cuthill–McKee algorithm to reduce the bandwidth of the node
+    /// graph and the elements by their earliest node in this order
+    ReverseCuthillMcKee,
+    /// Order the nodes and the element centroids along a Hilbert space-filling curve
+    Hilbert,
+}
+
+/// Old tags mapped to new tags, returned by [`MshFile::renumber`](struct.MshFile.html#method.renumber)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Renumbering<U>
+where
+    U: MshUsizeT,
+{
+    /// New tag of each node by its old tag
+    pub node_tags: BTreeMap<U, U>,
+    /// New tag of each element by its old tag
+    pub element_tags: BTreeMap<U, U>,
+}
+
+impl<U, I, F> MshFile<U, I, F>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+{
+    /// Assigns dense and contiguous node and element tags starting at 1
+    ///
+    /// All nodes of the mesh are numbered from 1 to the number of nodes in the given ordering,
+    /// i.e. by their reverse Cuthill–McKee rank in the node graph of the whole mesh or by their
+    /// position on the Hilbert curve through the bounding box of all nodes. The elements are
+    /// numbered in the same way by their earliest node or the position of their centroid on the
+    /// curve. Nodes and elements with equal keys (and all of them for
+    /// [`TagOrdering::Compact`](enum.TagOrdering.html#variant.Compact)) keep the order of their
+    /// blocks.
+    ///
+    /// Nodes and elements are not moved between blocks, but are sorted by their new tags within
+    /// each block. Blocks whose new tags are consecutive in the order of the blocks are stored
+    /// without tag maps, all other blocks keep a map of their new tags. In particular, compact
+    /// renumbering never requires tag maps.
+    ///
+    /// The element connectivity and the tag ranges are updated. Element nodes that are missing in
+    /// the node section keep their tag. Sections that are not parsed by this crate (e.g. periodic
+    /// links or post-processing data) have to be updated using the returned mapping of old to new
+    /// tags.
+    ///
+    /// ```
+    /// use mshio::mshfile::TagOrdering;
+    /// # let msh_bytes = "\
+    /// # $MeshFormat\n4.1 0 8\n$EndMeshFormat
+    /// # $Nodes\n1 4 10 40\n2 1 0 4\n10\n20\n30\n40\n0 0 0\n1 0 0\n1 1 0\n0 1 0\n$EndNodes
+    /// # $Elements\n1 2 5 7\n2 1 2 2\n5 10 20 30\n7 10 30 40\n$EndElements\n";
+    /// // A unit square with the sparse node tags 10, 20, 30, 40 and element tags 5 and 7
+    /// let mut msh = mshio::parse_msh_bytes(msh_bytes.as_bytes()).unwrap();
+    ///
+    /// let renumbering = msh.renumber(TagOrdering::Compact);
+    /// assert_eq!(renumbering.node_tags[&30], 3);
+    /// assert_eq!(renumbering.element_tags[&7], 2);
+    ///
+    /// let nodes = msh.data.nodes.as_ref().unwrap();
+    /// assert_eq!((nodes.min_node_tag, nodes.max_node_tag), (1, 4));
+    /// assert!(nodes.node_blocks[0].node_tags.is_none());
+    /// let elements = msh.data.elements.as_ref().unwrap();
+    /// assert_eq!(elements.element_blocks[0].elements[1].nodes, vec![1, 3, 4]);
+    /// ```
+    pub fn renumber(&mut self, ordering: TagOrdering) -> Renumbering<U> {
+        let mut renumbering = Renumbering {
+            node_tags: BTreeMap::new(),
+            element_tags: BTreeMap::new(),
+        };
+
+        // The tags and coordinates of all nodes in the order of the node blocks
+        let (node_tags, points): (Vec<U>, Vec<[f64; 3]>) = match &self.data.nodes {
+            Some(nodes) => nodes
+                .node_blocks
+                .iter()
+                .zip(nodes.block_node_tags())
+                .flat_map(|(block, block_tags)| block_tags.into_iter().zip(block.nodes.iter()))
+                .map(|(tag, node)| (tag, to_point(node)))
+                .unzip(),
+            None => (Vec::new(), Vec::new()),
+        };
+        let node_index: HashMap<U, usize> = node_tags
+            .iter()
+            .enumerate()
+            .map(|(i, &tag)| (tag, i))
+            .collect();
+
+        // The sort key of each node and a function computing the sort key of an element
+        let curve = HilbertCurve::new(&points);
+        let node_keys: Vec<u64> = match ordering {
+            TagOrdering::Compact => vec![0; node_tags.len()],
+            TagOrdering::ReverseCuthillMcKee => {
+                let graph = self.node_graph(&node_index);
+                let mut ranks = vec![0; node_tags.len()];
+                for (rank, node) in reverse_cuthill_mckee(&graph).into_iter().enumerate() {
+                    ranks[node] = rank as u64;
+                }
+                ranks
+            }
+            TagOrdering::Hilbert => points.iter().map(|&point| curve.index(point)).collect(),
+        };
+        let element_key = |element: &Element<U>| -> u64 {
+            let nodes = || element.nodes.iter().filter_map(|tag| node_index.get(tag));
+            match ordering {
+                TagOrdering::Compact => 0,
+                TagOrdering::ReverseCuthillMcKee => {
+                    nodes().map(|&i| node_keys[i]).min().unwrap_or(u64::MAX)
+                }
+                TagOrdering::Hilbert => {
+                    let count = nodes().count();
+                    if count == 0 {
+                        return u64::MAX;
+                    }
+                    let mut centroid = [0.0; 3];
+                    for &i in nodes() {
+                        for (c, x) in centroid.iter_mut().zip(points[i].iter()) {
+                            *c += x / count as f64;
+                        }
+                    }
+                    curve.index(centroid)
+                }
+            }
+        };
+
+        // New node tags by rank of the keys, ties keep the order of the blocks
+        let mut node_order: Vec<usize> = (0..node_tags.len()).collect();
+        node_order.sort_by_key(|&i| node_keys[i]);
+        let mut new_node_tags = vec![U::zero(); node_tags.len()];
+        for (rank, &i) in node_order.iter().enumerate() {
+            new_node_tags[i] = U::from_usize(rank + 1).unwrap();
+            renumbering.node_tags.insert(node_tags[i], new_node_tags[i]);
+        }
+
+        if let Some(nodes) = &mut self.data.nodes {
+            let mut offset = 0;
+            for block in &mut nodes.node_blocks {
+                let mut order: Vec<usize> = (offset..offset + block.nodes.len()).collect();
+                order.sort_by_key(|&i| new_node_tags[i]);
+                let reorder = |nodes: &Vec<Node<F>>| -> Vec<Node<F>> {
+                    order.iter().map(|&i| nodes[i - offset].clone()).collect()
+                };
+                block.parametric_nodes = block.parametric_nodes.as_ref().map(reorder);
+                block.nodes = reorder(&block.nodes);
+                let block_tags: Vec<U> = order.iter().map(|&i| new_node_tags[i]).collect();
+                block.node_tags = tag_map(&block_tags, offset);
+                offset += order.len();
+            }
+            if !node_tags.is_empty() {
+                nodes.min_node_tag = U::one();
+                nodes.max_node_tag = U::from_usize(node_tags.len()).unwrap();
+            }
+        }
+
+        if let Some(elements) = &mut self.data.elements {
+            // New element tags by rank of the keys, ties keep the order of the blocks
+            let element_keys: Vec<Vec<u64>> = elements
+                .element_blocks
+                .iter()
+                .map(|block| block.elements.iter().map(&element_key).collect())
+                .collect();
+            let mut element_order: Vec<(usize, usize)> = element_keys
+                .iter()
+                .enumerate()
+                .flat_map(|(b, keys)| (0..keys.len()).map(move |i| (b, i)))
+                .collect();
+            element_order.sort_by_key(|&(b, i)| element_keys[b][i]);
+            let mut new_element_tags: Vec<Vec<U>> = element_keys
+                .iter()
+                .map(|keys| vec![U::zero(); keys.len()])
+                .collect();
+            for (rank, &(b, i)) in element_order.iter().enumerate() {
+                new_element_tags[b][i] = U::from_usize(rank + 1).unwrap();
+            }
+
+            let mut offset = 0;
+            for (block, new_tags) in elements.element_blocks.iter_mut().zip(new_element_tags) {
+                let mut block_elements: Vec<(U, Element<U>)> = new_tags
+                    .into_iter()
+                    .zip(std::mem::take(&mut block.elements))
+                    .collect();
+                block_elements.sort_by_key(|(tag, _)| *tag);
+                for (new_tag, element) in &mut block_elements {
+                    renumbering
+                        .element_tags
+                        .insert(element.element_tag, *new_tag);
+                    element.element_tag = *new_tag;
+                    for node in &mut element.nodes {
+                        if let Some(&tag) = renumbering.node_tags.get(node) {
+                            *node = tag;
+                        }
+                    }
+                }
+                let block_tags: Vec<U> = block_elements.iter().map(|(tag, _)| *tag).collect();
+                block.element_tags = tag_map(&block_tags, offset);
+                block.elements = block_elements.into_iter().map(|(_, e)| e).collect();
+                offset += block_tags.len();
+            }
+            if !element_order.is_empty() {
+                elements.min_element_tag = U::one();
+                elements.max_element_tag = U::from_usize(element_order.len()).unwrap();
+            }
+        }
+
+        renumbering
+    }
+
+    /// Returns the indices of the nodes that share an element with each node
+    fn node_graph(&self, node_index: &HashMap<U, usize>) -> Vec<Vec<usize>> {
+        let mut graph = vec![Vec::new(); node_index.len()];
+        if let Some(elements) = &self.data.elements {
+            let elements = elements
+                .element_blocks
+                .iter()
+                .flat_map(|block| block.elements.iter());
+            for element in elements {
+                let nodes: Vec<usize> = element
+                    .nodes
+                    .iter()
+                    .filter_map(|tag| node_index.get(tag).copied())
+                    .collect();
+                for &a in &nodes {
+                    graph[a].extend(nodes.iter().copied().filter(|&b| b != a));
+                }
+            }
+        }
+        for neighbors in &mut graph {
+            neighbors.sort_unstable();
+            neighbors.dedup();
+        }
+        graph
+    }
+}
+
+/// Returns the map of the tags of a block to their index, or `None` if the tags are consecutive
+/// and start after the given number of tags of the preceding blocks
+fn tag_map<U: MshUsizeT>(tags: &[U], offset: usize) -> Option<HashMap<U, usize>> {
+    let consecutive = tags
+        .iter()
+        .enumerate()
+        .all(|(i, &tag)| tag == U::from_usize(offset + i + 1).unwrap());
+    if consecutive {
+        None
+    } else {
+        Some(tags.iter().enumerate().map(|(i, &tag)| (tag, i)).collect())
+    }
+}
+
+/// Returns the coordinates of a node as `f64`
+fn to_point<F: MshFloatT>(node: &Node<F>) -> [f64; 3] {
+    [node.x, node.y, node.z].map(|x| x.to_f64().unwrap_or(0.0))
+}
+
+/// Returns the reverse Cuthill–McKee ordering of the nodes of the graph
+///
+/// Each connected component is traversed in breadth-first order starting at its node with the
+/// smallest degree, visiting the neighbors of each node by increasing degree.
+fn reverse_cuthill_mckee(graph: &[Vec<usize>]) -> Vec<usize> {
+    let degree = |node: usize| graph[node].len();
+    let mut start_nodes: Vec<usize> = (0..graph.len()).collect();
+    start_nodes.sort_by_key(|&node| degree(node));
+
+    let mut visited = vec![false; graph.len()];
+    let mut order = Vec::with_capacity(graph.len());
+    let mut queue = VecDeque::new();
+    for start in start_nodes {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        queue.push_back(start);
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            let mut neighbors: Vec<usize> = graph[node]
+                .iter()
+                .copied()
+                .filter(|&n| !visited[n])
+                .collect();
+            neighbors.sort_by_key(|&n| degree(n));
+            for neighbor in neighbors {
+                visited[neighbor] = true;
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    order.reverse();
+    order
+}
+
+/// Maps points in a bounding box to their index on a three dimensional Hilbert curve
+struct HilbertCurve {
+    min: [f64; 3],
+    scale: [f64; 3],
+}
+
+impl HilbertCurve {
+    /// Returns the Hilbert curve through the bounding box of the given points
+    fn new(points: &[[f64; 3]]) -> Self {
+        let mut min = [f64::INFINITY; 3];
+        let mut max = [f64::NEG_INFINITY; 3];
+        for point in points {
+            for d in 0..3 {
+                min[d] = min[d].min(point[d]);
+                max[d] = max[d].max(point[d]);
+            }
+        }
+        let cells = ((1u64 << HILBERT_BITS) - 1) as f64;
+        let scale = [0, 1, 2].map(|d| {
+            let extent = max[d] - min[d];
+            if extent > 0.0 && extent.is_finite() {
+                cells / extent
+            } else {
+                0.0
+            }
+        });
+        let min = min.map(|x| if x.is_finite() { x } else { 0.0 });
+        HilbertCurve { min, scale }
+    }
+
+    /// Returns the index of the grid cell containing the point along the curve
+    ///
+    /// Uses the algorithm of J. Skilling, "Programming the Hilbert curve", AIP Conference
+    /// Proceedings 707 (2004).
+    fn index(&self, point: [f64; 3]) -> u64 {
+        let max_cell = (1u64 << HILBERT_BITS) - 1;
+        let mut x = [0, 1, 2]
+            .map(|d| (((point[d] - self.min[d]) * self.scale[d]).max(0.0) as u64).min(max_cell));
+
+        // Inverse undo
+        let mut q = 1u64 << (HILBERT_BITS - 1);
+        while q > 1 {
+            let p = q - 1;
+            for i in 0..3 {
+                if x[i] & q != 0 {
+                    x[0] ^= p;
+                } else {
+                    let t = (x[0] ^ x[i]) & p;
+                    x[0] ^= t;
+                    x[i] ^= t;
+                }
+            }
+            q >>= 1;
+        }
+
+        // Gray encode
+        x[1] ^= x[0];
+        x[2] ^= x[1];
+        let mut t = 0;
+        let mut q = 1u64 << (HILBERT_BITS - 1);
+        while q > 1 {
+            if x[2] & q != 0 {
+                t ^= q - 1;
+            }
+            q >>= 1;
+        }
+        for xi in &mut x {
+            *xi ^= t;
+        }
+
+        // Interleave the bits of the transposed index
+        let mut index = 0;
+        for bit in (0..HILBERT_BITS).rev() {
+            for xi in &x {
+                index = (index << 1) | ((xi >> bit) & 1);
+            }
+        }
+        index
+    }
+}
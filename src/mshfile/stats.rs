@@ -0,0 +1,415 @@
+//! Summary statistics of a mesh for reports and comparisons between meshes
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::mshfile::{
+    BoundingBox, ElementType, MshFile, MshFloatT, MshIntT, MshUsizeT, PhysicalGroup,
+};
+
+/// Statistics of a mesh returned by [`MshFile::stats`](struct.MshFile.html#method.stats)
+///
+/// All tags are converted to `i64`/`u64` and all coordinates to `f64`, independent of the value
+/// types of the `MshFile`. The `Display` implementation prints a human readable report.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MeshStats {
+    /// Total number of nodes
+    pub num_nodes: usize,
+    /// Total number of elements
+    pub num_elements: usize,
+    /// Range of the node tags (if the mesh contains nodes)
+    pub node_tags: Option<TagRange>,
+    /// Range of the element tags (if the mesh contains elements)
+    pub element_tags: Option<TagRange>,
+    /// Bounding box of all nodes (if the mesh contains nodes)
+    pub bounding_box: Option<BoundingBox<f64>>,
+    /// Number of elements per element type, ordered by the Gmsh element type number
+    pub element_types: Vec<(ElementType, usize)>,
+    /// Statistics of the element sizes (if the mesh contains elements with a size)
+    pub element_size: Option<ElementSizeStats>,
+    /// Number of nodes with exactly the same coordinates as a node that precedes them
+    pub duplicate_nodes: usize,
+    /// Number of nodes that are not referenced by any element
+    pub orphan_nodes: usize,
+    /// Statistics of all entities, ordered by dimension and tag
+    pub entities: Vec<EntityStats>,
+    /// Statistics of all physical groups, ordered by dimension and tag
+    pub physical_groups: Vec<PhysicalGroupStats>,
+}
+
+/// Smallest and largest tag of a set of tags
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TagRange {
+    /// The smallest tag
+    pub min: u64,
+    /// The largest tag
+    pub max: u64,
+    /// Whether the tags do not cover the full range from `min` to `max`
+    pub sparse: bool,
+}
+
+/// Statistics of the sizes of all elements with a size
+///
+/// The size of an element is its diameter, i.e. the largest distance between two of its vertices.
+/// Points and elements without vertices (e.g. polygons) are not taken into account.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ElementSizeStats {
+    /// The smallest element size
+    pub min: f64,
+    /// The largest element size
+    pub max: f64,
+    /// The arithmetic mean of the element sizes
+    pub mean: f64,
+}
+
+/// Node and element counts of an entity
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EntityStats {
+    /// Dimension of the entity
+    pub dim: i64,
+    /// Tag of the entity
+    pub tag: i64,
+    /// Number of distinct nodes in the node blocks of the entity or referenced by its elements
+    pub num_nodes: usize,
+    /// Number of elements in the element blocks of the entity
+    pub num_elements: usize,
+}
+
+/// Entity, node and element counts of a physical group
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PhysicalGroupStats {
+    /// Dimension of the physical group
+    pub dim: i64,
+    /// Tag of the physical group
+    pub tag: i64,
+    /// Name of the physical group (if it is defined in the `$PhysicalNames` section)
+    pub name: Option<String>,
+    /// Number of entities that belong to the physical group
+    pub num_entities: usize,
+    /// Number of distinct nodes of all entities of the physical group
+    pub num_nodes: usize,
+    /// Number of elements of all entities of the physical group
+    pub num_elements: usize,
+}
+
+impl<U, I, F> MshFile<U, I, F>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+    F: MshFloatT,
+{
+    /// Computes statistics of the whole mesh, its entities and its physical groups
+    ///
+    /// ```
+    /// # let msh_bytes = "\
+    /// # $MeshFormat\n4.1 0 8\n$EndMeshFormat
+    /// # $Entities\n0 0 1 0\n1 0 0 0 1 1 0 1 5 0\n$EndEntities
+    /// # $Nodes\n1 4 1 4\n2 1 0 4\n1\n2\n3\n4\n0 0 0\n3 0 0\n0 4 0\n9 9 9\n$EndNodes
+    /// # $Elements\n1 1 1 1\n2 1 2 1\n1 1 2 3\n$EndElements\n";
+    /// let msh = mshio::parse_msh_bytes(msh_bytes.as_bytes()).unwrap();
+    ///
+    /// let stats = msh.stats();
+    /// assert_eq!(stats.element_size.unwrap().max, 5.0);
+    /// assert_eq!(stats.orphan_nodes, 1);
+    /// assert_eq!(stats.physical_groups[0].num_elements, 1);
+    /// println!("{}", stats);
+    /// ```
+    pub fn stats(&self) -> MeshStats {
+        let to_i64 = |value: I| value.to_i64().unwrap();
+
+        // Nodes of each entity, including the nodes referenced by its elements
+        let mut entity_nodes: BTreeMap<(i64, i64), HashSet<u64>> = BTreeMap::new();
+        let mut entity_elements: BTreeMap<(i64, i64), usize> = BTreeMap::new();
+        if let Some(entities) = &self.data.entities {
+            let keys = (entities.points.iter().map(|e| (0, e.tag)))
+                .chain(entities.curves.iter().map(|e| (1, e.tag)))
+                .chain(entities.surfaces.iter().map(|e| (2, e.tag)))
+                .chain(entities.volumes.iter().map(|e| (3, e.tag)));
+            for (dim, tag) in keys {
+                entity_nodes.insert((dim, to_i64(tag)), HashSet::new());
+            }
+        }
+
+        let mut coordinates = Vec::new();
+        let mut node_indices = HashMap::new();
+        if let Some(nodes) = &self.data.nodes {
+            for (block, block_tags) in nodes.node_blocks.iter().zip(nodes.block_node_tags()) {
+                let key = (to_i64(block.entity_dim), to_i64(block.entity_tag));
+                let block_nodes = entity_nodes.entry(key).or_default();
+                for (tag, node) in block_tags.into_iter().zip(block.nodes.iter()) {
+                    let tag = tag.to_u64().unwrap();
+                    block_nodes.insert(tag);
+                    node_indices.insert(tag, coordinates.len());
+                    coordinates.push([
+                        node.x.to_f64().unwrap(),
+                        node.y.to_f64().unwrap(),
+                        node.z.to_f64().unwrap(),
+                    ]);
+                }
+            }
+        }
+
+        let mut element_types = HashMap::new();
+        let mut element_tags = Vec::new();
+        let mut element_sizes = Vec::new();
+        let mut referenced_nodes = HashSet::new();
+        if let Some(elements) = &self.data.elements {
+            for block in &elements.element_blocks {
+                let key = (to_i64(block.entity_dim), to_i64(block.entity_tag));
+                *entity_elements.entry(key).or_default() += block.elements.len();
+                *element_types.entry(block.element_type).or_insert(0) += block.elements.len();
+
+                let block_nodes = entity_nodes.entry(key).or_default();
+                for element in &block.elements {
+                    element_tags.push(element.element_tag.to_u64().unwrap());
+                    let nodes: Vec<u64> =
+                        element.nodes.iter().map(|n| n.to_u64().unwrap()).collect();
+                    if let Some(size) =
+                        element_size(block.element_type, &nodes, &node_indices, &coordinates)
+                    {
+                        element_sizes.push(size);
+                    }
+                    block_nodes.extend(nodes.iter().copied());
+                    referenced_nodes.extend(nodes);
+                }
+            }
+        }
+
+        let node_tags: Vec<u64> = node_indices.keys().copied().collect();
+        let orphan_nodes = node_tags
+            .iter()
+            .filter(|tag| !referenced_nodes.contains(tag))
+            .count();
+
+        // Coordinates are compared bitwise after adding zero to treat -0.0 and 0.0 the same
+        let mut unique_coordinates = HashSet::new();
+        let duplicate_nodes = coordinates
+            .iter()
+            .filter(|x| !unique_coordinates.insert(x.map(|x| (x + 0.0).to_bits())))
+            .count();
+
+        let bounding_box = coordinates.split_first().map(|(first, rest)| {
+            rest.iter().fold(
+                BoundingBox {
+                    min: *first,
+                    max: *first,
+                },
+                |mut bounds, x| {
+                    for (k, &x) in x.iter().enumerate() {
+                        bounds.min[k] = bounds.min[k].min(x);
+                        bounds.max[k] = bounds.max[k].max(x);
+                    }
+                    bounds
+                },
+            )
+        });
+
+        let element_size = if element_sizes.is_empty() {
+            None
+        } else {
+            Some(ElementSizeStats {
+                min: element_sizes.iter().copied().fold(f64::INFINITY, f64::min),
+                max: element_sizes
+                    .iter()
+                    .copied()
+                    .fold(f64::NEG_INFINITY, f64::max),
+                mean: element_sizes.iter().sum::<f64>() / element_sizes.len() as f64,
+            })
+        };
+
+        let mut element_types: Vec<(ElementType, usize)> = element_types.into_iter().collect();
+        element_types.sort_by_key(|&(element_type, _)| element_type as i32);
+
+        let physical_groups = self
+            .physical_group_keys()
+            .into_iter()
+            .filter_map(|(dim, tag)| self.physical_group(dim, tag))
+            .map(|group: PhysicalGroup<U, I, F>| {
+                let dim = to_i64(group.dim);
+                let mut nodes = HashSet::new();
+                for &tag in &group.entity_tags {
+                    if let Some(n) = entity_nodes.get(&(dim, to_i64(tag))) {
+                        nodes.extend(n.iter().copied());
+                    }
+                }
+                PhysicalGroupStats {
+                    dim,
+                    tag: to_i64(group.tag),
+                    name: group.name.map(String::from),
+                    num_entities: group.entity_tags.len(),
+                    num_nodes: nodes.len(),
+                    num_elements: group.element_count(),
+                }
+            })
+            .collect();
+
+        MeshStats {
+            num_nodes: coordinates.len(),
+            num_elements: element_tags.len(),
+            node_tags: tag_range(&node_tags),
+            element_tags: tag_range(&element_tags),
+            bounding_box,
+            element_types,
+            element_size,
+            duplicate_nodes,
+            orphan_nodes,
+            entities: entity_nodes
+                .iter()
+                .map(|(&(dim, tag), nodes)| EntityStats {
+                    dim,
+                    tag,
+                    num_nodes: nodes.len(),
+                    num_elements: entity_elements.get(&(dim, tag)).copied().unwrap_or(0),
+                })
+                .collect(),
+            physical_groups,
+        }
+    }
+
+    /// Returns the dimensions and tags of all physical groups of the entities and physical names
+    fn physical_group_keys(&self) -> BTreeSet<(I, I)> {
+        let mut keys = BTreeSet::new();
+        if let Some(entities) = &self.data.entities {
+            macro_rules! insert_keys {
+                ($entities:expr, $dim:expr) => {
+                    for entity in &$entities {
+                        for &tag in &entity.physical_tags {
+                            keys.insert((I::from_i32($dim).unwrap(), tag));
+                        }
+                    }
+                };
+            }
+            insert_keys!(entities.points, 0);
+            insert_keys!(entities.curves, 1);
+            insert_keys!(entities.surfaces, 2);
+            insert_keys!(entities.volumes, 3);
+        }
+        if let Some(names) = &self.data.physical_names {
+            keys.extend(names.iter().map(|n| (n.dim, n.tag)));
+        }
+        keys
+    }
+}
+
+/// Returns the largest distance between two vertices of the element
+fn element_size(
+    element_type: ElementType,
+    nodes: &[u64],
+    node_indices: &HashMap<u64, usize>,
+    coordinates: &[[f64; 3]],
+) -> Option<f64> {
+    let num_vertices = element_type.vertex_count().filter(|&n| n > 1)?;
+    let vertices = nodes
+        .get(..num_vertices)?
+        .iter()
+        .map(|tag| node_indices.get(tag).map(|&i| coordinates[i]))
+        .collect::<Option<Vec<_>>>()?;
+
+    let mut size: f64 = 0.0;
+    for (i, a) in vertices.iter().enumerate() {
+        for b in &vertices[i + 1..] {
+            let distance = (0..3).map(|k| (a[k] - b[k]).powi(2)).sum::<f64>().sqrt();
+            size = size.max(distance);
+        }
+    }
+    Some(size)
+}
+
+/// Returns the range of the given tags
+fn tag_range(tags: &[u64]) -> Option<TagRange> {
+    let min = *tags.iter().min()?;
+    let max = *tags.iter().max()?;
+    Some(TagRange {
+        min,
+        max,
+        sparse: max - min + 1 != tags.len() as u64,
+    })
+}
+
+/// Returns the name of entities of the given dimension
+fn entity_name(dim: i64) -> &'static str {
+    match dim {
+        0 => "point",
+        1 => "curve",
+        2 => "surface",
+        3 => "volume",
+        _ => "entity",
+    }
+}
+
+impl fmt::Display for TagRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sparsity = if self.sparse { "sparse" } else { "consecutive" };
+        write!(f, "tags {} to {}, {}", self.min, self.max, sparsity)
+    }
+}
+
+impl fmt::Display for MeshStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Nodes: {}", self.num_nodes)?;
+        match &self.node_tags {
+            Some(range) => writeln!(f, " ({})", range)?,
+            None => writeln!(f)?,
+        }
+        write!(f, "Elements: {}", self.num_elements)?;
+        match &self.element_tags {
+            Some(range) => writeln!(f, " ({})", range)?,
+            None => writeln!(f)?,
+        }
+        if let Some(bounds) = &self.bounding_box {
+            writeln!(f, "Bounding box: {:?} to {:?}", bounds.min, bounds.max)?;
+        }
+        if let Some(size) = &self.element_size {
+            writeln!(
+                f,
+                "Element size: min {}, max {}, mean {}",
+                size.min, size.max, size.mean
+            )?;
+        }
+        writeln!(f, "Duplicate nodes: {}", self.duplicate_nodes)?;
+        writeln!(f, "Orphan nodes: {}", self.orphan_nodes)?;
+
+        if !self.element_types.is_empty() {
+            writeln!(f, "Element types:")?;
+            for (element_type, count) in &self.element_types {
+                writeln!(f, "  {:?}: {}", element_type, count)?;
+            }
+        }
+        if !self.entities.is_empty() {
+            writeln!(f, "Entities:")?;
+            for entity in &self.entities {
+                writeln!(
+                    f,
+                    "  {} {}: {} nodes, {} elements",
+                    entity_name(entity.dim),
+                    entity.tag,
+                    entity.num_nodes,
+                    entity.num_elements
+                )?;
+            }
+        }
+        if !self.physical_groups.is_empty() {
+            writeln!(f, "Physical groups:")?;
+            for group in &self.physical_groups {
+                write!(f, "  {} {}", entity_name(group.dim), group.tag)?;
+                if let Some(name) = &group.name {
+                    write!(f, " \"{}\"", name)?;
+                }
+                writeln!(
+                    f,
+                    ": {} entities, {} nodes, {} elements",
+                    group.num_entities, group.num_nodes, group.num_elements
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,372 @@
+use std::collections::HashMap;
+use std::convert::TryInto;
+
+use nom::number::Endianness;
+
+use crate::mshfile::{
+    Element, ElementBlock, ElementType, Elements, Entities, MshData, MshFile, MshHeader, Node,
+    NodeBlock, Nodes, PhysicalName,
+};
+
+/// A MSH file parsed without copying the node and element data out of the input buffer
+///
+/// Only binary MSH files can be parsed into this representation. The node coordinates and element
+/// connectivity of each block are kept as views into the original input and are only decoded
+/// when accessed. Use [`parse_msh_bytes_ref`](../fn.parse_msh_bytes_ref.html) to obtain an instance.
+///
+/// Note that the entity section is small in general and therefore still parsed eagerly.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MshFileRef<'a> {
+    /// Data extracted from the file format header
+    pub header: MshHeader,
+    /// Actual mesh data of the MSH file
+    pub data: MshDataRef<'a>,
+}
+
+impl<'a> MshFileRef<'a> {
+    /// Returns the total number of nodes in the MSH file
+    pub fn total_node_count(&self) -> usize {
+        if let Some(nodes) = &self.data.nodes {
+            nodes.num_nodes as usize
+        } else {
+            0
+        }
+    }
+
+    /// Returns the total number of elements in the MSH file
+    pub fn total_element_count(&self) -> usize {
+        if let Some(elements) = &self.data.elements {
+            elements.num_elements as usize
+        } else {
+            0
+        }
+    }
+
+    /// Decodes all node and element data and returns an owned [`MshFile`](../mshfile/struct.MshFile.html)
+    ///
+    /// The result is identical to parsing the same input using `parse_msh_bytes`.
+    pub fn to_msh_file(&self) -> MshFile<u64, i32, f64> {
+        MshFile {
+            header: self.header.clone(),
+            data: MshData {
+                physical_names: self.data.physical_names.clone(),
+                entities: self.data.entities.clone(),
+                nodes: self.data.nodes.as_ref().map(|n| n.to_nodes()),
+                elements: self.data.elements.as_ref().map(|e| e.to_elements()),
+            },
+        }
+    }
+}
+
+/// Contains all parsed sections of the MSH file, node and element sections are borrowed
+#[derive(Clone, Debug, PartialEq)]
+pub struct MshDataRef<'a> {
+    /// Names of the physical groups of this mesh (if it contains a physical names section)
+    pub physical_names: Option<Vec<PhysicalName<i32>>>,
+    /// Geometric entities of this mesh such as points, curves, etc. (if it contains entities)
+    pub entities: Option<Entities<i32, f64>>,
+    /// Views into the node data of this mesh (if it contains nodes)
+    pub nodes: Option<NodesRef<'a>>,
+    /// Views into the element data of this mesh (if it contains elements)
+    pub elements: Option<ElementsRef<'a>>,
+}
+
+/// Describes how the raw values referenced by the block views are encoded
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct BinaryLayout {
+    /// Size in bytes of the size_t data type
+    pub(crate) size_t_size: usize,
+    /// Byte order of all values
+    pub(crate) endianness: Endianness,
+}
+
+/// Size in bytes of a single floating point value, the only float size supported by the views
+pub(crate) const FLOAT_SIZE: usize = 8;
+
+impl BinaryLayout {
+    /// Decodes the size_t value at the beginning of the given slice
+    #[inline]
+    fn read_size_t(&self, bytes: &[u8]) -> u64 {
+        macro_rules! read_as {
+            ($int_type:ty, $from_bytes:ident) => {
+                <$int_type>::$from_bytes(bytes[..self.size_t_size].try_into().unwrap()) as u64
+            };
+        }
+
+        match (self.endianness, self.size_t_size) {
+            (Endianness::Little, 1) => read_as!(u8, from_le_bytes),
+            (Endianness::Little, 2) => read_as!(u16, from_le_bytes),
+            (Endianness::Little, 4) => read_as!(u32, from_le_bytes),
+            (Endianness::Little, 8) => read_as!(u64, from_le_bytes),
+            (Endianness::Big, 1) => read_as!(u8, from_be_bytes),
+            (Endianness::Big, 2) => read_as!(u16, from_be_bytes),
+            (Endianness::Big, 4) => read_as!(u32, from_be_bytes),
+            (Endianness::Big, 8) => read_as!(u64, from_be_bytes),
+            _ => unreachable!("unsupported size_t sizes are rejected by the parser"),
+        }
+    }
+
+    /// Decodes the float value at the beginning of the given slice
+    #[inline]
+    fn read_float(&self, bytes: &[u8]) -> f64 {
+        let bytes = bytes[..FLOAT_SIZE].try_into().unwrap();
+        match self.endianness {
+            Endianness::Little => f64::from_le_bytes(bytes),
+            Endianness::Big => f64::from_be_bytes(bytes),
+        }
+    }
+}
+
+/// Views into all node blocks of a mesh
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodesRef<'a> {
+    /// Total number of nodes across all node blocks
+    pub num_nodes: u64,
+    /// The smallest node tag assigned to a node
+    pub min_node_tag: u64,
+    /// The largest node tag assigned to a node
+    pub max_node_tag: u64,
+    /// Views into the blocks of nodes with shared properties
+    pub node_blocks: Vec<NodeBlockRef<'a>>,
+}
+
+impl<'a> NodesRef<'a> {
+    /// Decodes all node blocks into an owned [`Nodes`](../mshfile/struct.Nodes.html) section
+    pub fn to_nodes(&self) -> Nodes<u64, i32, f64> {
+        let sparse_tags = self.max_node_tag - self.min_node_tag >= self.num_nodes;
+        Nodes {
+            num_nodes: self.num_nodes,
+            min_node_tag: self.min_node_tag,
+            max_node_tag: self.max_node_tag,
+            node_blocks: self
+                .node_blocks
+                .iter()
+                .map(|block| block.to_node_block(sparse_tags))
+                .collect(),
+        }
+    }
+}
+
+/// A view into a block of nodes whose tags and coordinates are decoded on access
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NodeBlockRef<'a> {
+    /// The number of dimensions of nodes in this block
+    pub entity_dim: i32,
+    /// The tag of the geometric entity this block of nodes is associated to
+    pub entity_tag: i32,
+    /// Whether this node entity provides parametric coordinates for its nodes
+    ///
+    /// This is currently unimplemented.
+    pub parametric: bool,
+    pub(crate) num_nodes: usize,
+    pub(crate) tags: &'a [u8],
+    pub(crate) coordinates: &'a [u8],
+    pub(crate) layout: BinaryLayout,
+}
+
+impl<'a> NodeBlockRef<'a> {
+    /// Returns the number of nodes in this block
+    pub fn len(&self) -> usize {
+        self.num_nodes
+    }
+
+    /// Returns whether this block contains no nodes
+    pub fn is_empty(&self) -> bool {
+        self.num_nodes == 0
+    }
+
+    /// Returns the tag of the node with the given index in this block
+    pub fn node_tag(&self, index: usize) -> u64 {
+        let size = self.layout.size_t_size;
+        self.layout.read_size_t(&self.tags[index * size..])
+    }
+
+    /// Returns the coordinates of the node with the given index in this block
+    pub fn coordinates(&self, index: usize) -> [f64; 3] {
+        let offset = index * 3 * FLOAT_SIZE;
+        let layout = self.layout;
+        let coords = &self.coordinates[offset..offset + 3 * FLOAT_SIZE];
+        [
+            layout.read_float(coords),
+            layout.read_float(&coords[FLOAT_SIZE..]),
+            layout.read_float(&coords[2 * FLOAT_SIZE..]),
+        ]
+    }
+
+    /// Returns the node with the given index in this block
+    pub fn node(&self, index: usize) -> Node<f64> {
+        let [x, y, z] = self.coordinates(index);
+        Node { x, y, z }
+    }
+
+    /// Returns an iterator over the tags of all nodes in this block
+    pub fn node_tags(&self) -> impl Iterator<Item = u64> + 'a {
+        let block = *self;
+        (0..self.num_nodes).map(move |i| block.node_tag(i))
+    }
+
+    /// Returns an iterator over all nodes in this block
+    pub fn nodes(&self) -> impl Iterator<Item = Node<f64>> + 'a {
+        let block = *self;
+        (0..self.num_nodes).map(move |i| block.node(i))
+    }
+
+    /// Returns the raw bytes of all node tags of this block as stored in the file
+    pub fn raw_node_tags(&self) -> &'a [u8] {
+        self.tags
+    }
+
+    /// Returns the raw bytes of all node coordinates (x, y, z per node) as stored in the file
+    pub fn raw_coordinates(&self) -> &'a [u8] {
+        self.coordinates
+    }
+
+    pub(crate) fn to_node_block(self, sparse_tags: bool) -> NodeBlock<u64, i32, f64> {
+        let node_tags = if sparse_tags {
+            Some(
+                self.node_tags()
+                    .enumerate()
+                    .map(|(i, tag)| (tag, i))
+                    .collect::<HashMap<_, _>>(),
+            )
+        } else {
+            None
+        };
+
+        NodeBlock {
+            entity_dim: self.entity_dim,
+            entity_tag: self.entity_tag,
+            parametric: self.parametric,
+            node_tags,
+            nodes: self.nodes().collect(),
+            parametric_nodes: None,
+        }
+    }
+}
+
+/// Views into all element blocks of a mesh
+#[derive(Clone, Debug, PartialEq)]
+pub struct ElementsRef<'a> {
+    /// Total number of elements across all element blocks
+    pub num_elements: u64,
+    /// The smallest element tag assigned to an element
+    pub min_element_tag: u64,
+    /// The largest element tag assigned to an element
+    pub max_element_tag: u64,
+    /// Views into the blocks of elements with shared properties
+    pub element_blocks: Vec<ElementBlockRef<'a>>,
+}
+
+impl<'a> ElementsRef<'a> {
+    /// Decodes all element blocks into an owned [`Elements`](../mshfile/struct.Elements.html) section
+    pub fn to_elements(&self) -> Elements<u64, i32> {
+        let sparse_tags = self.max_element_tag - self.min_element_tag >= self.num_elements;
+        Elements {
+            num_elements: self.num_elements,
+            min_element_tag: self.min_element_tag,
+            max_element_tag: self.max_element_tag,
+            element_blocks: self
+                .element_blocks
+                .iter()
+                .map(|block| block.to_element_block(sparse_tags))
+                .collect(),
+        }
+    }
+}
+
+/// A view into a block of elements whose tags and node tags are decoded on access
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ElementBlockRef<'a> {
+    /// The number of dimensions of elements in this block
+    pub entity_dim: i32,
+    /// The tag of the geometric entity this block of elements is associated to
+    pub entity_tag: i32,
+    /// The type of all elements in this block
+    pub element_type: ElementType,
+    pub(crate) num_elements: usize,
+    pub(crate) nodes_per_element: usize,
+    pub(crate) data: &'a [u8],
+    pub(crate) layout: BinaryLayout,
+}
+
+impl<'a> ElementBlockRef<'a> {
+    /// Returns the number of elements in this block
+    pub fn len(&self) -> usize {
+        self.num_elements
+    }
+
+    /// Returns whether this block contains no elements
+    pub fn is_empty(&self) -> bool {
+        self.num_elements == 0
+    }
+
+    /// Returns the number of nodes of every element in this block
+    pub fn nodes_per_element(&self) -> usize {
+        self.nodes_per_element
+    }
+
+    /// Offset in bytes of the element with the given index
+    #[inline]
+    fn element_offset(&self, index: usize) -> usize {
+        index * (self.nodes_per_element + 1) * self.layout.size_t_size
+    }
+
+    /// Returns the tag of the element with the given index in this block
+    pub fn element_tag(&self, index: usize) -> u64 {
+        self.layout
+            .read_size_t(&self.data[self.element_offset(index)..])
+    }
+
+    /// Returns the tag of the `local_node`-th node of the element with the given index
+    pub fn element_node(&self, index: usize, local_node: usize) -> u64 {
+        assert!(local_node < self.nodes_per_element);
+        let offset = self.element_offset(index) + (local_node + 1) * self.layout.size_t_size;
+        self.layout.read_size_t(&self.data[offset..])
+    }
+
+    /// Returns an iterator over the node tags of the element with the given index
+    pub fn element_nodes(&self, index: usize) -> impl Iterator<Item = u64> + 'a {
+        let block = *self;
+        (0..self.nodes_per_element).map(move |j| block.element_node(index, j))
+    }
+
+    /// Returns an iterator over the tags of all elements in this block
+    pub fn element_tags(&self) -> impl Iterator<Item = u64> + 'a {
+        let block = *self;
+        (0..self.num_elements).map(move |i| block.element_tag(i))
+    }
+
+    /// Returns the raw bytes of all elements (element tag followed by its node tags) as stored in the file
+    pub fn raw_data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub(crate) fn to_element_block(self, sparse_tags: bool) -> ElementBlock<u64, i32> {
+        let elements: Vec<_> = (0..self.num_elements)
+            .map(|i| Element {
+                element_tag: self.element_tag(i),
+                nodes: self.element_nodes(i).collect(),
+            })
+            .collect();
+
+        let element_tags = if sparse_tags {
+            Some(
+                elements
+                    .iter()
+                    .enumerate()
+                    .map(|(i, ele)| (ele.element_tag, i))
+                    .collect::<HashMap<_, _>>(),
+            )
+        } else {
+            None
+        };
+
+        ElementBlock {
+            entity_dim: self.entity_dim,
+            entity_tag: self.entity_tag,
+            element_type: self.element_type,
+            element_tags,
+            elements,
+        }
+    }
+}
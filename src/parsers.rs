@@ -2,14 +2,28 @@ pub(crate) mod general_parsers;
 pub(crate) mod num_parser_traits;
 pub(crate) mod num_parsers;
 
+mod ascii_parsers;
 mod elements_section;
 mod entities_section;
 mod header_section;
 mod nodes_section;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod physical_names_section;
 
-pub(crate) use elements_section::parse_element_section;
-pub(crate) use entities_section::parse_entity_section;
+pub(crate) use elements_section::{
+    parse_element_entity_header, parse_element_section, parse_element_section_header,
+    parse_element_section_ref, ElementBlockHeader, ElementSectionHeader,
+};
+pub(crate) use entities_section::{
+    parse_curve, parse_entity_section, parse_entity_section_header, parse_point, parse_surface,
+    parse_volume, EntitySectionHeader,
+};
 pub(crate) use header_section::parse_header_section;
-pub(crate) use nodes_section::parse_node_section;
+pub(crate) use nodes_section::{
+    parse_node_entity_header, parse_node_section, parse_node_section_header,
+    parse_node_section_ref, NodeBlockHeader, NodeSectionHeader,
+};
+pub(crate) use physical_names_section::parse_physical_names_section;
 
 pub use general_parsers::*;
@@ -0,0 +1,305 @@
+//! Fast parsers for whitespace separated ASCII numbers
+//!
+//! These parsers are used instead of composed nom combinators in the hot loops of ASCII MSH files
+//! (node coordinates, element connectivity). They work directly on the input bytes and accept
+//! the same inputs as `ws(digit1)`, `ws(recognize_integer)` and `ws(double)`, respectively.
+
+use std::convert::TryFrom;
+use std::str;
+
+use nom::error::ErrorKind;
+use nom::IResult;
+
+use num::Integer;
+use num_traits::{Float, NumCast, Signed, Unsigned};
+
+use crate::error::{always_error, make_error, MshParserError, MshParserErrorKind, ValueType};
+
+/// Powers of ten that are exactly representable as `f64`
+static EXACT_POWERS_OF_TEN: [f64; 23] = [
+    1e0, 1e1, 1e2, 1e3, 1e4, 1e5, 1e6, 1e7, 1e8, 1e9, 1e10, 1e11, 1e12, 1e13, 1e14, 1e15, 1e16,
+    1e17, 1e18, 1e19, 1e20, 1e21, 1e22,
+];
+
+/// Largest mantissa that is exactly representable as `f64`
+const MAX_EXACT_MANTISSA: u64 = 1 << 53;
+
+/// Maximum number of decimal digits that are accumulated into the `u64` mantissa
+const MAX_MANTISSA_DIGITS: usize = 19;
+
+fn is_space(c: u8) -> bool {
+    c == b' ' || c == b'\t' || c == b'\r' || c == b'\n'
+}
+
+/// Returns the input without preceding whitespace
+fn skip_sp(input: &[u8]) -> &[u8] {
+    let len = input.iter().take_while(|&&c| is_space(c)).count();
+    &input[len..]
+}
+
+/// Returns the number of consecutive decimal digits at the start of the input
+fn count_digits(input: &[u8]) -> usize {
+    input.iter().take_while(|c| c.is_ascii_digit()).count()
+}
+
+/// Accumulates the decimal digits into an `u128`, returns `None` on overflow
+fn accumulate_digits(digits: &[u8]) -> Option<u128> {
+    digits.iter().try_fold(0u128, |value, &c| {
+        value.checked_mul(10)?.checked_add((c - b'0') as u128)
+    })
+}
+
+/// Parses an unsigned integer surrounded by optional whitespace
+pub(crate) fn ascii_uint<T: Unsigned + Integer + NumCast>(
+    input: &[u8],
+) -> IResult<&[u8], T, MshParserError<&[u8]>> {
+    let input = skip_sp(input);
+    let num_digits = count_digits(input);
+    if num_digits == 0 {
+        return Err(make_error(
+            input,
+            MshParserErrorKind::NomError(ErrorKind::Digit),
+        ));
+    }
+
+    let (digits, rest) = input.split_at(num_digits);
+    let rest = skip_sp(rest);
+    match accumulate_digits(digits).and_then(T::from) {
+        Some(value) => Ok((rest, value)),
+        None => always_error(MshParserErrorKind::ValueOutOfRange(ValueType::UnsignedInt))(rest),
+    }
+}
+
+/// Parses a signed integer with optional minus sign surrounded by optional whitespace
+pub(crate) fn ascii_int<T: Signed + Integer + NumCast>(
+    input: &[u8],
+) -> IResult<&[u8], T, MshParserError<&[u8]>> {
+    let input = skip_sp(input);
+    let negative = input.first() == Some(&b'-');
+    let sign_len = negative as usize;
+    let num_digits = count_digits(&input[sign_len..]);
+    if num_digits == 0 {
+        return Err(make_error(
+            &input[sign_len..],
+            MshParserErrorKind::NomError(ErrorKind::Digit),
+        ));
+    }
+
+    let (digits, rest) = input[sign_len..].split_at(num_digits);
+    let rest = skip_sp(rest);
+    let value = accumulate_digits(digits).and_then(|magnitude| {
+        if negative {
+            // The magnitude of the smallest value is larger than the largest value
+            if magnitude == i128::MAX as u128 + 1 {
+                Some(i128::MIN)
+            } else {
+                i128::try_from(magnitude).ok().map(|v| -v)
+            }
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    });
+
+    match value.and_then(T::from) {
+        Some(value) => Ok((rest, value)),
+        None => always_error(MshParserErrorKind::ValueOutOfRange(ValueType::Int))(rest),
+    }
+}
+
+/// Parses a floating point number surrounded by optional whitespace
+pub(crate) fn ascii_float<T: Float + NumCast>(
+    input: &[u8],
+) -> IResult<&[u8], T, MshParserError<&[u8]>> {
+    let input = skip_sp(input);
+    let (len, value) = match parse_float(input) {
+        Some(result) => result,
+        None => {
+            return Err(make_error(
+                input,
+                MshParserErrorKind::NomError(ErrorKind::Float),
+            ))
+        }
+    };
+
+    let rest = skip_sp(&input[len..]);
+    match T::from(value) {
+        Some(value) => Ok((rest, value)),
+        None => always_error(MshParserErrorKind::ValueOutOfRange(ValueType::Float))(rest),
+    }
+}
+
+/// Parses a float at the start of the input, returns the number of consumed bytes and its value
+///
+/// Accepts the same syntax as nom's `recognize_float`, i.e. `[+-]?(\d+(\.\d*)?|\.\d+)([eE][+-]?\d+)?`.
+/// Values with at most 19 significant digits and small exponents are computed directly using
+/// exact floating point operations, all other values are handed to the standard library.
+fn parse_float(input: &[u8]) -> Option<(usize, f64)> {
+    let mut pos = 0;
+
+    // Sign
+    let negative = match input.first() {
+        Some(b'-') => {
+            pos += 1;
+            true
+        }
+        Some(b'+') => {
+            pos += 1;
+            false
+        }
+        _ => false,
+    };
+
+    let mut mantissa: u64 = 0;
+    let mut mantissa_digits = 0;
+    let mut exponent: i64 = 0;
+    let mut truncated = false;
+
+    let mut push_digit = |c: u8, is_fraction: bool| {
+        if mantissa == 0 && c == b'0' {
+            // Leading zeros are not significant
+            if is_fraction {
+                exponent -= 1;
+            }
+        } else if mantissa_digits < MAX_MANTISSA_DIGITS {
+            mantissa = mantissa * 10 + (c - b'0') as u64;
+            mantissa_digits += 1;
+            if is_fraction {
+                exponent -= 1;
+            }
+        } else {
+            truncated = true;
+            if !is_fraction {
+                exponent += 1;
+            }
+        }
+    };
+
+    // Integer part
+    let num_int_digits = count_digits(&input[pos..]);
+    for &c in &input[pos..pos + num_int_digits] {
+        push_digit(c, false);
+    }
+    pos += num_int_digits;
+
+    // Fractional part
+    let mut num_frac_digits = 0;
+    if input.get(pos) == Some(&b'.') {
+        num_frac_digits = count_digits(&input[pos + 1..]);
+        if num_int_digits > 0 || num_frac_digits > 0 {
+            for &c in &input[pos + 1..pos + 1 + num_frac_digits] {
+                push_digit(c, true);
+            }
+            pos += 1 + num_frac_digits;
+        }
+    }
+
+    if num_int_digits == 0 && num_frac_digits == 0 {
+        return None;
+    }
+
+    // Exponent
+    if let Some(b'e') | Some(b'E') = input.get(pos) {
+        pos += 1;
+        let exp_negative = match input.get(pos) {
+            Some(b'-') => {
+                pos += 1;
+                true
+            }
+            Some(b'+') => {
+                pos += 1;
+                false
+            }
+            _ => false,
+        };
+
+        let num_exp_digits = count_digits(&input[pos..]);
+        if num_exp_digits == 0 {
+            return None;
+        }
+
+        let exp_value = input[pos..pos + num_exp_digits]
+            .iter()
+            .fold(0i64, |exp, &c| {
+                (exp * 10 + (c - b'0') as i64).min(i32::MAX as i64)
+            });
+        exponent += if exp_negative { -exp_value } else { exp_value };
+        pos += num_exp_digits;
+    }
+
+    // Fast path: both the mantissa and the power of ten are exact, so a single rounding occurs
+    if !truncated && mantissa <= MAX_EXACT_MANTISSA && exponent.abs() < 23 {
+        let value = mantissa as f64;
+        let value = if exponent < 0 {
+            value / EXACT_POWERS_OF_TEN[(-exponent) as usize]
+        } else {
+            value * EXACT_POWERS_OF_TEN[exponent as usize]
+        };
+        return Some((pos, if negative { -value } else { value }));
+    }
+
+    // Slow path: correctly rounded parsing by the standard library
+    let value = str::from_utf8(&input[..pos]).ok()?.parse::<f64>().ok()?;
+    Some((pos, value))
+}
+
+#[test]
+fn test_parse_float_matches_std() {
+    let inputs = [
+        "0",
+        "-0",
+        "1",
+        "+1",
+        "1.",
+        ".5",
+        "-.5",
+        "0.1",
+        "0.3",
+        "-1.25e-3",
+        "1E5",
+        "6.02214076e23",
+        "1.7976931348623157e308",
+        "2.2250738585072014e-308",
+        "4.9e-324",
+        "1e400",
+        "0.000000000000000000000000000123",
+        "123456789012345678901234567890",
+        "0.1000000000000000055511151231257827",
+        "9007199254740993",
+        "3.141592653589793238462643383279",
+        "-2.718281828459045",
+    ];
+
+    for input in inputs.iter() {
+        let expected: f64 = input.parse().unwrap();
+        let (len, value) = parse_float(input.as_bytes()).unwrap();
+        assert_eq!(len, input.len(), "input: {}", input);
+        assert_eq!(value.to_bits(), expected.to_bits(), "input: {}", input);
+    }
+}
+
+#[test]
+fn test_parse_float_partial_and_invalid() {
+    assert_eq!(parse_float(b"1.5abc"), Some((3, 1.5)));
+    assert_eq!(parse_float(b"2 3"), Some((1, 2.0)));
+    assert_eq!(parse_float(b"1.e2"), Some((4, 100.0)));
+    assert_eq!(parse_float(b"abc"), None);
+    assert_eq!(parse_float(b"."), None);
+    assert_eq!(parse_float(b"-"), None);
+    assert_eq!(parse_float(b"1e"), None);
+    assert_eq!(parse_float(b"1e+"), None);
+}
+
+#[test]
+fn test_ascii_int_parsers() {
+    let (rest, value) = ascii_uint::<u64>(b"  42 \n7").unwrap();
+    assert_eq!((rest, value), (&b"7"[..], 42));
+    let (rest, value) = ascii_int::<i32>(b"-17\t8").unwrap();
+    assert_eq!((rest, value), (&b"8"[..], -17));
+    assert_eq!(ascii_int::<i8>(b"-128").unwrap().1, -128);
+
+    assert!(ascii_uint::<u64>(b"-1").is_err());
+    assert!(ascii_uint::<u8>(b"256").is_err());
+    assert!(ascii_int::<i8>(b"128").is_err());
+    assert!(ascii_int::<i32>(b"- 1").is_err());
+    assert!(ascii_uint::<u64>(b"340282366920938463463374607431768211456").is_err());
+}
@@ -1,26 +1,32 @@
 use std::collections::HashMap;
 
+use nom::bytes::complete::take;
 use nom::IResult;
 use num::traits::FromPrimitive;
 
 use crate::error::{
-    always_error, context, make_error, MapMshError, MshParserError, MshParserErrorKind,
+    always_error, context, error, make_error, MapMshError, MshParserError, MshParserErrorKind,
 };
 use crate::mshfile::{Element, ElementBlock, ElementType, Elements, MshIntT, MshUsizeT};
+use crate::mshfile_ref::{BinaryLayout, ElementBlockRef, ElementsRef};
 use crate::parsers::num_parser_traits::{
-    int_parser, size_t_parser, usize_parser, ParsesInt, ParsesSizeT,
+    int_parser, size_t_parser, usize_parser, ParsesInParallel, ParsesInt, ParsesSizeT,
 };
+#[cfg(feature = "rayon")]
+use crate::parsers::num_parser_traits::SkipsValues;
+#[cfg(feature = "rayon")]
+use crate::parsers::parallel::count_indexed_parallel;
 use crate::parsers::{count_indexed, verify_or};
 
-struct ElementSectionHeader<U: MshUsizeT> {
-    num_entity_blocks: usize,
-    num_elements: U,
-    min_element_tag: U,
-    max_element_tag: U,
+pub(crate) struct ElementSectionHeader<U: MshUsizeT> {
+    pub(crate) num_entity_blocks: usize,
+    pub(crate) num_elements: U,
+    pub(crate) min_element_tag: U,
+    pub(crate) max_element_tag: U,
 }
 
 pub(crate) fn parse_element_section<'a, 'b: 'a>(
-    parsers: impl ParsesSizeT<u64> + ParsesInt<i32>,
+    parsers: impl ParsesSizeT<u64> + ParsesInt<i32> + ParsesInParallel,
 ) -> impl Fn(&'b [u8]) -> IResult<&'b [u8], Elements<u64, i32>, MshParserError<&'b [u8]>> {
     move |input| {
         // Parse the section header
@@ -41,10 +47,73 @@ pub(crate) fn parse_element_section<'a, 'b: 'a>(
             false
         };
 
+        let block_context = |index| {
+            move || {
+                format!(
+                    "element entity block ({} of {})",
+                    index + 1,
+                    num_entity_blocks
+                )
+            }
+        };
+
         // Parse the individual element entity blocks
+        #[cfg(not(feature = "rayon"))]
         let (input, element_entity_blocks) = count_indexed(
             |index, input| {
-                parse_element_entity(&parsers, sparse_tags, input).with_context_from(input, || {
+                parse_element_entity(&parsers, sparse_tags, input)
+                    .with_context_from(input, block_context(index))
+            },
+            num_entity_blocks,
+        )(input)?;
+
+        // Locate the individual element entity blocks and parse them in parallel
+        #[cfg(feature = "rayon")]
+        let (input, element_entity_blocks) = count_indexed_parallel(
+            |index, input| {
+                skip_element_entity(&parsers, input).with_context_from(input, block_context(index))
+            },
+            |index, input| {
+                parse_element_entity(&parsers, sparse_tags, input)
+                    .with_context_from(input, block_context(index))
+            },
+            num_entity_blocks,
+        )(input)?;
+
+        // Return the element section content
+        Ok((
+            input,
+            Elements {
+                num_elements,
+                min_element_tag,
+                max_element_tag,
+                element_blocks: element_entity_blocks,
+            },
+        ))
+    }
+}
+
+pub(crate) fn parse_element_section_ref<'a>(
+    parsers: impl ParsesSizeT<u64> + ParsesInt<i32>,
+    layout: BinaryLayout,
+) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], ElementsRef<'a>, MshParserError<&'a [u8]>> {
+    move |input| {
+        // Parse the section header
+        let (input, element_section_header) = context("element section header", |input| {
+            parse_element_section_header(&parsers, input)
+        })(input)?;
+
+        let ElementSectionHeader {
+            num_entity_blocks,
+            num_elements,
+            min_element_tag,
+            max_element_tag,
+        } = element_section_header;
+
+        // Only locate the individual element entity blocks without decoding their content
+        let (input, element_blocks) = count_indexed(
+            |index, input| {
+                parse_element_entity_ref(&parsers, layout, input).with_context_from(input, || {
                     format!(
                         "element entity block ({} of {})",
                         index + 1,
@@ -55,23 +124,22 @@ pub(crate) fn parse_element_section<'a, 'b: 'a>(
             num_entity_blocks,
         )(input)?;
 
-        // Return the element section content
         Ok((
             input,
-            Elements {
+            ElementsRef {
                 num_elements,
                 min_element_tag,
                 max_element_tag,
-                element_blocks: element_entity_blocks,
+                element_blocks,
             },
         ))
     }
 }
 
-fn parse_element_section_header<'a, U: MshUsizeT>(
+pub(crate) fn parse_element_section_header<U: MshUsizeT>(
     parser: impl ParsesSizeT<U>,
-    input: &'a [u8],
-) -> IResult<&'a [u8], ElementSectionHeader<U>, MshParserError<&'a [u8]>> {
+    input: &[u8],
+) -> IResult<&[u8], ElementSectionHeader<U>, MshParserError<&[u8]>> {
     let size_t_parser = size_t_parser(&parser);
     let usize_parser = usize_parser(&parser);
 
@@ -112,7 +180,7 @@ fn parse_element_section_header<'a, U: MshUsizeT>(
     ))
 }
 
-fn parse_element_entity<'a, U, I>(
+pub(crate) fn parse_element_entity<'a, U, I>(
     parser: impl ParsesSizeT<U> + ParsesInt<I>,
     sparse_tags: bool,
     input: &'a [u8],
@@ -122,15 +190,14 @@ where
     I: MshIntT,
 {
     let parser = &parser;
-    let int_parser = int_parser(parser);
-    let usize_parser = usize_parser(parser);
 
-    let (input, entity_dim) = context("entity dimension", &int_parser)(input)?;
-    let (input, entity_tag) = context("entity tag", &int_parser)(input)?;
-    let (input, element_type) =
-        context("element type", move |i| parse_element_type(parser, i))(input)?;
-    let (input_new, num_elements_in_block) =
-        context("number of elements in element block", usize_parser)(input)?;
+    let (input_new, block_header) = parse_element_entity_header(parser, input)?;
+    let ElementBlockHeader {
+        entity_dim,
+        entity_tag,
+        element_type,
+        num_elements_in_block,
+    } = block_header;
 
     // Try to get the number of nodes per element
     let num_nodes_per_element = element_type.nodes().map_err(|_| {
@@ -181,6 +248,126 @@ where
     ))
 }
 
+/// Skips an element entity block after parsing its header
+#[cfg(feature = "rayon")]
+pub(crate) fn skip_element_entity<U, I>(
+    parser: impl ParsesSizeT<U> + ParsesInt<I> + SkipsValues,
+    input: &[u8],
+) -> IResult<&[u8], (), MshParserError<&[u8]>>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+{
+    let (input_new, block_header) = parse_element_entity_header(&parser, input)?;
+
+    // Try to get the number of nodes per element
+    let num_nodes_per_element = block_header.element_type.nodes().map_err(|_| {
+        make_error(input, MshParserErrorKind::Unimplemented).with_context(
+            input,
+            "An element type encountered in the MSH file does not have a known number of nodes.",
+        )
+    })?;
+
+    // Every element consists of its tag followed by the tags of its nodes
+    let num_values = block_header
+        .num_elements_in_block
+        .checked_mul(num_nodes_per_element + 1)
+        .ok_or_else(|| make_error(input, MshParserErrorKind::TooManyEntities))?;
+
+    context(
+        "element definitions",
+        error(MshParserErrorKind::InvalidElementDefinition, move |i| {
+            parser.skip_size_t(num_values, i)
+        }),
+    )(input_new)
+}
+
+fn parse_element_entity_ref<'a>(
+    parser: impl ParsesSizeT<u64> + ParsesInt<i32>,
+    layout: BinaryLayout,
+    input: &'a [u8],
+) -> IResult<&'a [u8], ElementBlockRef<'a>, MshParserError<&'a [u8]>> {
+    let (input_new, block_header) = parse_element_entity_header(&parser, input)?;
+    let ElementBlockHeader {
+        entity_dim,
+        entity_tag,
+        element_type,
+        num_elements_in_block,
+    } = block_header;
+
+    // Try to get the number of nodes per element
+    let num_nodes_per_element = element_type.nodes().map_err(|_| {
+        make_error(input, MshParserErrorKind::Unimplemented).with_context(
+            input,
+            "An element type encountered in the MSH file does not have a known number of nodes.",
+        )
+    })?;
+
+    // Every element consists of its tag followed by the tags of its nodes
+    let block_size = num_elements_in_block
+        .checked_mul(num_nodes_per_element + 1)
+        .and_then(|values| values.checked_mul(layout.size_t_size))
+        .ok_or_else(|| make_error(input, MshParserErrorKind::TooManyEntities))?;
+
+    let (input, data) = context(
+        "element definitions",
+        error(
+            MshParserErrorKind::InvalidElementDefinition,
+            take(block_size),
+        ),
+    )(input_new)?;
+
+    Ok((
+        input,
+        ElementBlockRef {
+            entity_dim,
+            entity_tag,
+            element_type,
+            num_elements: num_elements_in_block,
+            nodes_per_element: num_nodes_per_element,
+            data,
+            layout,
+        },
+    ))
+}
+
+pub(crate) struct ElementBlockHeader<I: MshIntT> {
+    pub(crate) entity_dim: I,
+    pub(crate) entity_tag: I,
+    pub(crate) element_type: ElementType,
+    pub(crate) num_elements_in_block: usize,
+}
+
+pub(crate) fn parse_element_entity_header<U, I>(
+    parser: impl ParsesSizeT<U> + ParsesInt<I>,
+    input: &[u8],
+) -> IResult<&[u8], ElementBlockHeader<I>, MshParserError<&[u8]>>
+where
+    U: MshUsizeT,
+    I: MshIntT,
+{
+    let parser = &parser;
+    let int_parser = int_parser(parser);
+    let usize_parser = usize_parser(parser);
+
+    let (input, entity_dim) = context("entity dimension", &int_parser)(input)?;
+    let (input, entity_tag) = context("entity tag", &int_parser)(input)?;
+    let (input, element_type) =
+        context("element type", move |i| parse_element_type(parser, i))(input)?;
+    let (input, num_elements_in_block) =
+        context("number of elements in element block", usize_parser)(input)?;
+
+    Ok((
+        input,
+        ElementBlockHeader {
+            entity_dim,
+            entity_tag,
+            element_type,
+            num_elements_in_block,
+        },
+    ))
+}
+
 fn parse_element_type<'a, I>(
     parser: impl ParsesInt<I>,
     input: &'a [u8],
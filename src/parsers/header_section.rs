@@ -11,7 +11,7 @@ use crate::error::{
     always_error, context, make_error, MapMshError, MshParserError, MshParserErrorKind,
 };
 use crate::mshfile::{MshFloatT, MshHeader, MshIntT, MshUsizeT};
-use crate::parsers::num_parser_traits::{ParsesFloat, ParsesInt, ParsesSizeT};
+use crate::parsers::num_parser_traits::{ParsesFloat, ParsesInParallel, ParsesInt, ParsesSizeT};
 use crate::parsers::num_parsers;
 use crate::parsers::{br, sp, verify_or};
 
@@ -21,7 +21,7 @@ pub(crate) fn parse_header_section<'a>(
     &'a [u8],
     (
         MshHeader,
-        impl ParsesSizeT<u64> + ParsesInt<i32> + ParsesFloat<f64>,
+        impl ParsesSizeT<u64> + ParsesInt<i32> + ParsesFloat<f64> + ParsesInParallel,
     ),
     MshParserError<&'a [u8]>,
 > {
@@ -86,7 +86,7 @@ pub(crate) fn parse_header_section<'a>(
 
 pub(crate) fn num_parsers_from_header<'a, U: MshUsizeT, I: MshIntT, F: MshFloatT>(
     header: &'a MshHeader,
-) -> impl ParsesSizeT<U> + ParsesInt<I> + ParsesFloat<F> {
+) -> impl ParsesSizeT<U> + ParsesInt<I> + ParsesFloat<F> + ParsesInParallel {
     let size_t_parser = num_parsers::uint_parser::<U>(header.size_t_size, header.endianness);
     let int_parser = num_parsers::int_parser::<I>(header.int_size, header.endianness);
     let double_parser = num_parsers::float_parser::<F>(header.float_size, header.endianness);
@@ -95,5 +95,9 @@ pub(crate) fn num_parsers_from_header<'a, U: MshUsizeT, I: MshIntT, F: MshFloatT
         size_t_parser,
         int_parser,
         float_parser: double_parser,
+        #[cfg(feature = "rayon")]
+        binary_sizes: header
+            .endianness
+            .map(|_| (header.size_t_size, header.float_size)),
     }
 }
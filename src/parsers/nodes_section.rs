@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use nom::bytes::complete::take;
 use nom::multi::count;
 use nom::IResult;
 
@@ -8,20 +9,26 @@ use crate::error::{
     MshParserErrorKind,
 };
 use crate::mshfile::{MshFloatT, MshIntT, MshUsizeT, Node, NodeBlock, Nodes};
+use crate::mshfile_ref::{BinaryLayout, NodeBlockRef, NodesRef, FLOAT_SIZE};
 use crate::parsers::num_parser_traits::{
-    float_parser, int_parser, size_t_parser, usize_parser, ParsesFloat, ParsesInt, ParsesSizeT,
+    float_parser, int_parser, size_t_parser, usize_parser, ParsesFloat, ParsesInParallel,
+    ParsesInt, ParsesSizeT,
 };
+#[cfg(feature = "rayon")]
+use crate::parsers::num_parser_traits::SkipsValues;
+#[cfg(feature = "rayon")]
+use crate::parsers::parallel::count_indexed_parallel;
 use crate::parsers::{count_indexed, verify_or};
 
-struct NodeSectionHeader<U: MshUsizeT> {
-    num_entity_blocks: usize,
-    num_nodes: U,
-    min_node_tag: U,
-    max_node_tag: U,
+pub(crate) struct NodeSectionHeader<U: MshUsizeT> {
+    pub(crate) num_entity_blocks: usize,
+    pub(crate) num_nodes: U,
+    pub(crate) min_node_tag: U,
+    pub(crate) max_node_tag: U,
 }
 
 pub(crate) fn parse_node_section<'a, 'b: 'a>(
-    parsers: impl ParsesSizeT<u64> + ParsesInt<i32> + ParsesFloat<f64>,
+    parsers: impl ParsesSizeT<u64> + ParsesInt<i32> + ParsesFloat<f64> + ParsesInParallel,
 ) -> impl Fn(&'b [u8]) -> IResult<&'b [u8], Nodes<u64, i32, f64>, MshParserError<&'b [u8]>> {
     move |input| {
         // Parse the section header
@@ -42,12 +49,29 @@ pub(crate) fn parse_node_section<'a, 'b: 'a>(
             false
         };
 
+        let block_context = |index| {
+            move || format!("node entity block ({} of {})", index + 1, num_entity_blocks)
+        };
+
         // Parse the individual node entity blocks
+        #[cfg(not(feature = "rayon"))]
         let (input, node_entity_blocks) = count_indexed(
             |index, input| {
-                parse_node_entity(&parsers, sparse_tags, input).with_context_from(input, || {
-                    format!("node entity block ({} of {})", index + 1, num_entity_blocks)
-                })
+                parse_node_entity(&parsers, sparse_tags, input)
+                    .with_context_from(input, block_context(index))
+            },
+            num_entity_blocks,
+        )(input)?;
+
+        // Locate the individual node entity blocks and parse them in parallel
+        #[cfg(feature = "rayon")]
+        let (input, node_entity_blocks) = count_indexed_parallel(
+            |index, input| {
+                skip_node_entity(&parsers, input).with_context_from(input, block_context(index))
+            },
+            |index, input| {
+                parse_node_entity(&parsers, sparse_tags, input)
+                    .with_context_from(input, block_context(index))
             },
             num_entity_blocks,
         )(input)?;
@@ -64,10 +88,49 @@ pub(crate) fn parse_node_section<'a, 'b: 'a>(
     }
 }
 
-fn parse_node_section_header<'a, U: MshUsizeT>(
+pub(crate) fn parse_node_section_ref<'a>(
+    parsers: impl ParsesSizeT<u64> + ParsesInt<i32>,
+    layout: BinaryLayout,
+) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], NodesRef<'a>, MshParserError<&'a [u8]>> {
+    move |input| {
+        // Parse the section header
+        let (input, node_section_header) = context("node section header", |input| {
+            parse_node_section_header(&parsers, input)
+        })(input)?;
+
+        let NodeSectionHeader {
+            num_entity_blocks,
+            num_nodes,
+            min_node_tag,
+            max_node_tag,
+        } = node_section_header;
+
+        // Only locate the individual node entity blocks without decoding their content
+        let (input, node_blocks) = count_indexed(
+            |index, input| {
+                parse_node_entity_ref(&parsers, layout, input).with_context_from(input, || {
+                    format!("node entity block ({} of {})", index + 1, num_entity_blocks)
+                })
+            },
+            num_entity_blocks,
+        )(input)?;
+
+        Ok((
+            input,
+            NodesRef {
+                num_nodes,
+                min_node_tag,
+                max_node_tag,
+                node_blocks,
+            },
+        ))
+    }
+}
+
+pub(crate) fn parse_node_section_header<U: MshUsizeT>(
     parser: impl ParsesSizeT<U>,
-    input: &'a [u8],
-) -> IResult<&'a [u8], NodeSectionHeader<U>, MshParserError<&'a [u8]>> {
+    input: &[u8],
+) -> IResult<&[u8], NodeSectionHeader<U>, MshParserError<&[u8]>> {
     let size_t_parser = size_t_parser(&parser);
     let usize_parser = usize_parser(&parser);
 
@@ -107,15 +170,19 @@ fn parse_node_section_header<'a, U: MshUsizeT>(
     ))
 }
 
-fn parse_node_entity<'a, U: MshUsizeT, I: MshIntT, F: MshFloatT>(
-    parser: impl ParsesSizeT<U> + ParsesInt<I> + ParsesFloat<F>,
-    sparse_tags: bool,
-    input: &'a [u8],
-) -> IResult<&'a [u8], NodeBlock<U, I, F>, MshParserError<&'a [u8]>> {
-    let size_t_parser = size_t_parser(&parser);
+pub(crate) struct NodeBlockHeader<I: MshIntT> {
+    pub(crate) entity_dim: I,
+    pub(crate) entity_tag: I,
+    pub(crate) parametric: bool,
+    pub(crate) num_nodes_in_block: usize,
+}
+
+pub(crate) fn parse_node_entity_header<U: MshUsizeT, I: MshIntT>(
+    parser: impl ParsesSizeT<U> + ParsesInt<I>,
+    input: &[u8],
+) -> IResult<&[u8], NodeBlockHeader<I>, MshParserError<&[u8]>> {
     let usize_parser = usize_parser(&parser);
     let int_parser = int_parser(&parser);
-    let float_parser = float_parser(&parser);
 
     let (input, entity_dim) = context("entity dimension", &int_parser)(input)?;
     let (input, entity_tag) = context("entity tag", &int_parser)(input)?;
@@ -139,6 +206,61 @@ fn parse_node_entity<'a, U: MshUsizeT, I: MshIntT, F: MshFloatT>(
             .with_context(input, "Parsing of parametric nodes is not supported yet"));
     }
 
+    Ok((
+        input,
+        NodeBlockHeader {
+            entity_dim,
+            entity_tag,
+            parametric,
+            num_nodes_in_block,
+        },
+    ))
+}
+
+/// Skips a node entity block after parsing its header
+#[cfg(feature = "rayon")]
+pub(crate) fn skip_node_entity<U: MshUsizeT, I: MshIntT>(
+    parser: impl ParsesSizeT<U> + ParsesInt<I> + SkipsValues,
+    input: &[u8],
+) -> IResult<&[u8], (), MshParserError<&[u8]>> {
+    let (input, block_header) = parse_node_entity_header(&parser, input)?;
+    let num_nodes = block_header.num_nodes_in_block;
+
+    let num_coordinates = num_nodes
+        .checked_mul(3)
+        .ok_or_else(|| make_error(input, MshParserErrorKind::TooManyEntities))?;
+
+    let parser = &parser;
+    let (input, _) = context(
+        "node tags",
+        error(MshParserErrorKind::InvalidTag, move |i| {
+            parser.skip_size_t(num_nodes, i)
+        }),
+    )(input)?;
+    context(
+        "node coordinates",
+        error(MshParserErrorKind::InvalidNodeDefinition, move |i| {
+            parser.skip_float(num_coordinates, i)
+        }),
+    )(input)
+}
+
+pub(crate) fn parse_node_entity<'a, U: MshUsizeT, I: MshIntT, F: MshFloatT>(
+    parser: impl ParsesSizeT<U> + ParsesInt<I> + ParsesFloat<F>,
+    sparse_tags: bool,
+    input: &'a [u8],
+) -> IResult<&'a [u8], NodeBlock<U, I, F>, MshParserError<&'a [u8]>> {
+    let size_t_parser = size_t_parser(&parser);
+    let float_parser = float_parser(&parser);
+
+    let (input, block_header) = parse_node_entity_header(&parser, input)?;
+    let NodeBlockHeader {
+        entity_dim,
+        entity_tag,
+        parametric,
+        num_nodes_in_block,
+    } = block_header;
+
     // Closure that parses all node tags
     let parse_all_node_tags = |input| {
         context(
@@ -204,3 +326,52 @@ fn parse_node_entity<'a, U: MshUsizeT, I: MshIntT, F: MshFloatT>(
         },
     ))
 }
+
+fn parse_node_entity_ref<'a>(
+    parser: impl ParsesSizeT<u64> + ParsesInt<i32>,
+    layout: BinaryLayout,
+    input: &'a [u8],
+) -> IResult<&'a [u8], NodeBlockRef<'a>, MshParserError<&'a [u8]>> {
+    let (input, block_header) = parse_node_entity_header(&parser, input)?;
+    let NodeBlockHeader {
+        entity_dim,
+        entity_tag,
+        parametric,
+        num_nodes_in_block,
+    } = block_header;
+
+    // Number of bytes occupied by the given number of values per node
+    let block_size = |value_size: usize| {
+        num_nodes_in_block
+            .checked_mul(value_size)
+            .ok_or_else(|| make_error(input, MshParserErrorKind::TooManyEntities))
+    };
+
+    let (input, tags) = context(
+        "node tags",
+        error(
+            MshParserErrorKind::InvalidTag,
+            take(block_size(layout.size_t_size)?),
+        ),
+    )(input)?;
+    let (input, coordinates) = context(
+        "node coordinates",
+        error(
+            MshParserErrorKind::InvalidNodeDefinition,
+            take(block_size(3 * FLOAT_SIZE)?),
+        ),
+    )(input)?;
+
+    Ok((
+        input,
+        NodeBlockRef {
+            entity_dim,
+            entity_tag,
+            parametric,
+            num_nodes: num_nodes_in_block,
+            tags,
+            coordinates,
+            layout,
+        },
+    ))
+}
@@ -3,6 +3,8 @@ use nom::IResult;
 use crate::error::MshParserError;
 use crate::mshfile::{MshFloatT, MshIntT, MshUsizeT};
 use crate::parsers::num_parsers::{construct_usize_parser, NumParsers};
+#[cfg(feature = "rayon")]
+use crate::parsers::num_parsers::{skip_ascii_values, skip_binary_values};
 
 pub(crate) trait ParsesSizeT<U: MshUsizeT> {
     fn parse_size_t<'a>(&self, i: &'a [u8]) -> IResult<&'a [u8], U, MshParserError<&'a [u8]>>;
@@ -18,6 +20,33 @@ pub(crate) trait ParsesFloat<F: MshFloatT> {
     fn parse_float<'a>(&self, i: &'a [u8]) -> IResult<&'a [u8], F, MshParserError<&'a [u8]>>;
 }
 
+/// Allows to skip values without parsing them, used to locate blocks for parallel parsing
+#[cfg(feature = "rayon")]
+pub(crate) trait SkipsValues {
+    fn skip_size_t<'a>(
+        &self,
+        count: usize,
+        i: &'a [u8],
+    ) -> IResult<&'a [u8], (), MshParserError<&'a [u8]>>;
+    fn skip_float<'a>(
+        &self,
+        count: usize,
+        i: &'a [u8],
+    ) -> IResult<&'a [u8], (), MshParserError<&'a [u8]>>;
+}
+
+/// Additional requirements of the section parsers if blocks are parsed in parallel
+#[cfg(feature = "rayon")]
+pub(crate) trait ParsesInParallel: SkipsValues + Sync {}
+#[cfg(feature = "rayon")]
+impl<T: SkipsValues + Sync> ParsesInParallel for T {}
+
+/// Additional requirements of the section parsers if blocks are parsed in parallel (none without `rayon`)
+#[cfg(not(feature = "rayon"))]
+pub(crate) trait ParsesInParallel {}
+#[cfg(not(feature = "rayon"))]
+impl<T> ParsesInParallel for T {}
+
 impl<U: MshUsizeT, T> ParsesSizeT<U> for &T
 where
     T: ParsesSizeT<U>,
@@ -56,6 +85,30 @@ where
     }
 }
 
+#[cfg(feature = "rayon")]
+impl<T> SkipsValues for &T
+where
+    T: SkipsValues,
+{
+    #[inline(always)]
+    fn skip_size_t<'a>(
+        &self,
+        count: usize,
+        i: &'a [u8],
+    ) -> IResult<&'a [u8], (), MshParserError<&'a [u8]>> {
+        (*self).skip_size_t(count, i)
+    }
+
+    #[inline(always)]
+    fn skip_float<'a>(
+        &self,
+        count: usize,
+        i: &'a [u8],
+    ) -> IResult<&'a [u8], (), MshParserError<&'a [u8]>> {
+        (*self).skip_float(count, i)
+    }
+}
+
 impl<U: MshUsizeT, I: MshIntT, F: MshFloatT, SizeTParser, IntParser, FloatParser> ParsesSizeT<U>
     for NumParsers<U, I, F, SizeTParser, IntParser, FloatParser>
 where
@@ -99,6 +152,37 @@ where
     }
 }
 
+#[cfg(feature = "rayon")]
+impl<U: MshUsizeT, I: MshIntT, F: MshFloatT, SizeTParser, IntParser, FloatParser> SkipsValues
+    for NumParsers<U, I, F, SizeTParser, IntParser, FloatParser>
+where
+    for<'a> SizeTParser: Fn(&'a [u8]) -> IResult<&'a [u8], U, MshParserError<&'a [u8]>>,
+    for<'a> IntParser: Fn(&'a [u8]) -> IResult<&'a [u8], I, MshParserError<&'a [u8]>>,
+    for<'a> FloatParser: Fn(&'a [u8]) -> IResult<&'a [u8], F, MshParserError<&'a [u8]>>,
+{
+    fn skip_size_t<'a>(
+        &self,
+        count: usize,
+        input: &'a [u8],
+    ) -> IResult<&'a [u8], (), MshParserError<&'a [u8]>> {
+        match self.binary_sizes {
+            Some((size_t_size, _)) => skip_binary_values(count, size_t_size)(input),
+            None => skip_ascii_values(count)(input),
+        }
+    }
+
+    fn skip_float<'a>(
+        &self,
+        count: usize,
+        input: &'a [u8],
+    ) -> IResult<&'a [u8], (), MshParserError<&'a [u8]>> {
+        match self.binary_sizes {
+            Some((_, float_size)) => skip_binary_values(count, float_size)(input),
+            None => skip_ascii_values(count)(input),
+        }
+    }
+}
+
 #[inline(always)]
 pub(crate) fn size_t_parser<'a, U: MshUsizeT, P: ParsesSizeT<U> + 'a>(
     parser: P,
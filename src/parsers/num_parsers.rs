@@ -1,7 +1,5 @@
 use std::str;
 
-use nom::character::complete::digit1;
-use nom::combinator::map;
 #[allow(unused)]
 use nom::error::VerboseError;
 use nom::number::complete as numbers;
@@ -13,7 +11,7 @@ use num_traits::{Float, NumCast, Signed, Unsigned};
 
 use crate::error::{always_error, make_error, MshParserError, MshParserErrorKind, ValueType};
 use crate::mshfile::{MshFloatT, MshIntT, MshUsizeT};
-use crate::parsers::{recognize_integer, ws};
+use crate::parsers::ascii_parsers::{ascii_float, ascii_int, ascii_uint};
 
 // TODO: Replace the unimplemented! calls with errors
 
@@ -32,6 +30,49 @@ pub(crate) struct NumParsers<
     pub(crate) size_t_parser: SizeTParser,
     pub(crate) int_parser: IntParser,
     pub(crate) float_parser: DoubleParser,
+    /// Sizes of the size_t and float values if the values are binary encoded (None for ASCII files)
+    #[cfg(feature = "rayon")]
+    pub(crate) binary_sizes: Option<(usize, usize)>,
+}
+
+/// Returns a parser that skips the given number of ASCII values separated by whitespace
+#[cfg(feature = "rayon")]
+pub(crate) fn skip_ascii_values(
+    count: usize,
+) -> impl for<'a> Fn(&'a [u8]) -> IResult<&'a [u8], (), MshParserError<&'a [u8]>> {
+    move |input| {
+        let is_space = |c: &u8| b" \t\r\n".contains(c);
+        let mut pos = 0;
+        for _ in 0..count {
+            // Skip the whitespace in front of the value and then the value itself
+            pos += input[pos..].iter().take_while(|c| is_space(c)).count();
+            let value_len = input[pos..].iter().take_while(|c| !is_space(c)).count();
+            if value_len == 0 {
+                return Err(make_error(
+                    &input[pos..],
+                    MshParserErrorKind::NomError(nom::error::ErrorKind::Eof),
+                ));
+            }
+            pos += value_len;
+        }
+        pos += input[pos..].iter().take_while(|c| is_space(c)).count();
+        Ok((&input[pos..], ()))
+    }
+}
+
+/// Returns a parser that skips the given number of binary values of the given size
+#[cfg(feature = "rayon")]
+pub(crate) fn skip_binary_values(
+    count: usize,
+    value_size: usize,
+) -> impl for<'a> Fn(&'a [u8]) -> IResult<&'a [u8], (), MshParserError<&'a [u8]>> {
+    move |input| {
+        let num_bytes = count
+            .checked_mul(value_size)
+            .ok_or_else(|| make_error(input, MshParserErrorKind::TooManyEntities))?;
+        let (input, _) = nom::bytes::complete::take(num_bytes)(input)?;
+        Ok((input, ()))
+    }
 }
 
 pub fn construct_usize_parser<U, SizeTParser>(
@@ -96,21 +137,7 @@ pub fn uint_parser<T: Unsigned + Integer + NumCast + str::FromStr>(
                 );
             }
         },
-        None => {
-            // TODO: Replace this expect
-            (|i| match ws(map(digit1, |items| {
-                str::FromStr::from_str(str::from_utf8(items).expect("Cannot parse UTF8 to digits"))
-            }))(i)
-            {
-                Ok((i, v)) => match v {
-                    Ok(v) => Ok((i, v)),
-                    Err(_) => {
-                        always_error(MshParserErrorKind::ValueOutOfRange(ValueType::UnsignedInt))(i)
-                    }
-                },
-                Err(e) => Err(e),
-            }) as for<'a> fn(&'a [u8]) -> IResult<&'a [u8], T, MshParserError<&'a [u8]>>
-        }
+        None => ascii_uint::<T>,
     }
 }
 
@@ -160,19 +187,7 @@ pub fn int_parser<T: Signed + Integer + NumCast + str::FromStr>(
                 );
             }
         },
-        None => {
-            // TODO: Replace this expect
-            (|i| match ws(map(recognize_integer, |items| {
-                str::FromStr::from_str(str::from_utf8(items).expect("Cannot parse UTF8 to integer"))
-            }))(i)
-            {
-                Ok((i, v)) => match v {
-                    Ok(v) => Ok((i, v)),
-                    Err(_) => always_error(MshParserErrorKind::ValueOutOfRange(ValueType::Int))(i),
-                },
-                Err(e) => Err(e),
-            }) as for<'a> fn(&'a [u8]) -> IResult<&'a [u8], T, MshParserError<&'a [u8]>>
-        }
+        None => ascii_int::<T>,
     }
 }
 
@@ -216,18 +231,7 @@ pub fn float_parser<T: Float + NumCast>(
                 );
             }
         },
-        None => {
-            (|i| match ws(numbers::double)(i) {
-                Ok((i, v)) => {
-                    if let Some(v) = T::from(v) {
-                        Ok((i, v))
-                    } else {
-                        always_error(MshParserErrorKind::ValueOutOfRange(ValueType::Float))(i)
-                    }
-                }
-                Err(e) => Err(e),
-            }) as for<'a> fn(&'a [u8]) -> IResult<&'a [u8], T, MshParserError<&'a [u8]>>
-        }
+        None => ascii_float::<T>,
     }
 }
 
@@ -0,0 +1,149 @@
+use nom::error::{ErrorKind, ParseError};
+use nom::{IResult, InputLength};
+use rayon::prelude::*;
+
+/// Parses `counts` consecutive blocks in parallel
+///
+/// The blocks are located sequentially using `skipper` which has to consume exactly one block
+/// (typically by only parsing its header and skipping the block content). Afterwards, `parser`
+/// is applied to the start of every block in parallel. If multiple blocks contain errors, the
+/// error of the first block is returned. It is an error if `parser` does not stop exactly where
+/// `skipper` located the start of the next block (or the end of the last block).
+pub(crate) fn count_indexed_parallel<I, O, E, S, F>(
+    skipper: S,
+    parser: F,
+    counts: usize,
+) -> impl Fn(I) -> IResult<I, Vec<O>, E>
+where
+    I: Clone + Send + InputLength,
+    O: Send,
+    E: Send + ParseError<I>,
+    S: Fn(usize, I) -> IResult<I, (), E>,
+    F: Fn(usize, I) -> IResult<I, O, E> + Sync,
+{
+    move |input| {
+        // Locate the beginning of every block
+        let mut block_inputs = Vec::with_capacity(counts);
+        let mut input = input;
+        for index in 0..counts {
+            block_inputs.push(input.clone());
+            let (input_, _) = skipper(index, input)?;
+            input = input_;
+        }
+
+        // The inputs are suffixes of the same input, so positions can be compared by their lengths
+        let mut block_ends: Vec<_> = block_inputs.iter().skip(1).map(I::input_len).collect();
+        block_ends.push(input.input_len());
+
+        // Parse the block contents in parallel
+        let results: Vec<_> = block_inputs
+            .into_par_iter()
+            .enumerate()
+            .map(|(index, block_input)| parser(index, block_input))
+            .collect();
+
+        let mut blocks = Vec::with_capacity(counts);
+        for (result, block_end) in results.into_iter().zip(block_ends) {
+            let (rest, block) = result?;
+            if rest.input_len() != block_end {
+                let error = E::from_error_kind(rest.clone(), ErrorKind::Verify);
+                return Err(nom::Err::Error(E::add_context(
+                    rest,
+                    "block parser and skipper disagree on the end of the block",
+                    error,
+                )));
+            }
+            blocks.push(block);
+        }
+
+        Ok((input, blocks))
+    }
+}
+
+#[test]
+fn test_parallel_matches_sequential() {
+    use nom::number::Endianness;
+
+    use crate::mshfile::MshHeader;
+    use crate::parsers::count_indexed;
+    use crate::parsers::elements_section::{parse_element_entity, skip_element_entity};
+    use crate::parsers::header_section::num_parsers_from_header;
+    use crate::parsers::nodes_section::{parse_node_entity, skip_node_entity};
+
+    // Contents of a node and an element section with two entity blocks each (without headers)
+    let ascii_nodes = "0 1 0 1\n1\n0 0 0\n2 1 0 3\n2\n3\n4\n1 0 0\n1 1 0\n0 1 0\n";
+    let ascii_elements = "0 1 15 1\n1 1\n2 1 2 2\n10 1 2 3\n20 1 3 4\n$EndElements\n";
+
+    let binary_sections = |endianness: Endianness| {
+        let mut msh = Vec::new();
+        macro_rules! push {
+            ($($value:expr),*) => {
+                $(match endianness {
+                    Endianness::Little => msh.extend_from_slice(&$value.to_le_bytes()),
+                    Endianness::Big => msh.extend_from_slice(&$value.to_be_bytes()),
+                })*
+            };
+        }
+
+        push!(0i32, 1i32, 0i32, 1u64, 1u64, 0.0f64, 0.0f64, 0.0f64);
+        push!(2i32, 1i32, 0i32, 3u64, 2u64, 3u64, 4u64);
+        push!(1.0f64, 0.0f64, 0.0f64, 1.0f64, 1.0f64, 0.0f64, 0.0f64, 1.0f64, 0.0f64);
+        let nodes_end = msh.len();
+        push!(0i32, 1i32, 15i32, 1u64, 1u64, 1u64);
+        push!(2i32, 1i32, 2i32, 2u64, 10u64, 1u64, 2u64, 3u64, 20u64, 1u64, 3u64, 4u64);
+        msh.extend_from_slice(b"\n$EndElements\n");
+
+        let elements = msh.split_off(nodes_end);
+        (msh, elements)
+    };
+
+    for endianness in [None, Some(Endianness::Little), Some(Endianness::Big)] {
+        let header = MshHeader {
+            version: 4.1,
+            file_type: if endianness.is_some() { 1 } else { 0 },
+            size_t_size: 8,
+            int_size: 4,
+            float_size: 8,
+            endianness,
+        };
+        let parsers = num_parsers_from_header::<u64, i32, f64>(&header);
+        let (nodes, elements) = match endianness {
+            Some(endianness) => binary_sections(endianness),
+            None => (ascii_nodes.into(), ascii_elements.into()),
+        };
+
+        let parse = |_, input| parse_node_entity(&parsers, false, input);
+        let skip = |_, input| skip_node_entity(&parsers, input);
+        let sequential = count_indexed(parse, 2)(&nodes).unwrap();
+        let parallel = count_indexed_parallel(skip, parse, 2)(&nodes).unwrap();
+        assert_eq!(parallel, sequential);
+        assert_eq!(parallel.1[1].nodes.len(), 3);
+
+        let parse = |_, input| parse_element_entity(&parsers, false, input);
+        let skip = |_, input| skip_element_entity(&parsers, input);
+        let sequential = count_indexed(parse, 2)(&elements).unwrap();
+        let parallel = count_indexed_parallel(skip, parse, 2)(&elements).unwrap();
+        assert_eq!(parallel, sequential);
+        assert_eq!(parallel.1[1].elements.len(), 2);
+    }
+}
+
+#[test]
+fn test_block_end_mismatch() {
+    use nom::bytes::complete::take;
+
+    let input = &b"aabbc"[..];
+    let skip = |_, input| take(2usize)(input).map(|(rest, _)| (rest, ()));
+
+    // The skipper locates blocks of two bytes but the parser only consumes one byte per block
+    let parse = |_, input| take(1usize)(input);
+    let result: IResult<_, _, (&[u8], ErrorKind)> = count_indexed_parallel(skip, parse, 2)(input);
+    assert_eq!(
+        result,
+        Err(nom::Err::Error((&b"abbc"[..], ErrorKind::Verify)))
+    );
+
+    let parse = |_, input| take(2usize)(input);
+    let result: IResult<_, _, (&[u8], ErrorKind)> = count_indexed_parallel(skip, parse, 2)(input);
+    assert_eq!(result, Ok((&b"c"[..], vec![&b"aa"[..], &b"bb"[..]])));
+}
@@ -0,0 +1,51 @@
+use nom::bytes::complete::{tag, take_till};
+use nom::sequence::delimited;
+use nom::IResult;
+
+use crate::error::{context, MapMshError, MshParserError, MshParserErrorKind};
+use crate::mshfile::PhysicalName;
+use crate::parsers::ascii_parsers::{ascii_int, ascii_uint};
+use crate::parsers::count_indexed;
+
+/// Parses the content of a `$PhysicalNames` section
+///
+/// The section is always ASCII encoded, also in binary MSH files.
+pub(crate) fn parse_physical_names_section(
+    input: &[u8],
+) -> IResult<&[u8], Vec<PhysicalName<i32>>, MshParserError<&[u8]>> {
+    let (input, num_names) = context("number of physical names", ascii_uint::<usize>)(input)?;
+
+    count_indexed(
+        move |index, input| {
+            parse_physical_name(input).with_context_from(input, || {
+                format!("physical name ({} of {})", index + 1, num_names)
+            })
+        },
+        num_names,
+    )(input)
+}
+
+fn parse_physical_name(input: &[u8]) -> IResult<&[u8], PhysicalName<i32>, MshParserError<&[u8]>> {
+    let (input, dim) = context("physical group dimension", ascii_int::<i32>)(input)?;
+    let (input, tag_value) = context("physical group tag", ascii_int::<i32>)(input)?;
+
+    // The name is enclosed in double quotes and cannot contain double quotes itself
+    let (input, name) = delimited(tag("\""), take_till(|c| c == b'"' || c == b'\n'), tag("\""))(
+        input,
+    )
+    .map_err(|_: nom::Err<MshParserError<&[u8]>>| {
+        MshParserErrorKind::InvalidParameter
+            .into_error(input)
+            .with_context(input, "Expected a physical name enclosed in double quotes")
+            .into_nom_error()
+    })?;
+
+    Ok((
+        input,
+        PhysicalName {
+            dim,
+            tag: tag_value,
+            name: String::from_utf8_lossy(name).into_owned(),
+        },
+    ))
+}
@@ -0,0 +1,628 @@
+use std::io::{self, Read};
+
+use nom::bytes::complete::tag;
+use nom::character::complete::{alpha0, char};
+use nom::error::ErrorKind;
+use nom::sequence::{delimited, preceded};
+use nom::IResult;
+
+use crate::error::{context, MapMshError, MshParserError, MshParserErrorKind};
+use crate::mshfile::{Curve, ElementType, MshHeader, Node, Point, Surface, Volume};
+use crate::parsers::num_parser_traits::{
+    float_parser, size_t_parser, ParsesFloat, ParsesInt, ParsesSizeT,
+};
+use crate::parsers::{br, take_sp};
+use crate::parsers::{
+    parse_curve, parse_element_entity_header, parse_element_section_header,
+    parse_entity_section_header, parse_node_entity_header, parse_node_section_header, parse_point,
+    parse_surface, parse_volume, ElementBlockHeader, ElementSectionHeader, EntitySectionHeader,
+    NodeBlockHeader, NodeSectionHeader,
+};
+
+/// Default size of the read buffer used by [`parse_msh_stream`](fn.parse_msh_stream.html)
+pub const DEFAULT_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Minimum size of the read buffer, smaller sizes are increased to this value
+pub const MIN_BUFFER_SIZE: usize = 256;
+
+/// A single entity of the `Entities` section
+#[derive(Clone, Debug, PartialEq)]
+pub enum Entity {
+    /// A point entity
+    Point(Point<i32, f64>),
+    /// A curve entity
+    Curve(Curve<i32, f64>),
+    /// A surface entity
+    Surface(Surface<i32, f64>),
+    /// A volume entity
+    Volume(Volume<i32, f64>),
+}
+
+/// Header information of a node entity block that is passed to the visitor before its nodes
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeBlockInfo {
+    /// Dimension of the entity the nodes of this block belong to
+    pub entity_dim: i32,
+    /// Tag of the entity the nodes of this block belong to
+    pub entity_tag: i32,
+    /// Whether the nodes of this block have parametric coordinates
+    pub parametric: bool,
+    /// Number of nodes in this block
+    pub num_nodes: usize,
+}
+
+/// Header information of an element entity block that is passed to the visitor before its elements
+#[derive(Clone, Debug, PartialEq)]
+pub struct ElementBlockInfo {
+    /// Dimension of the entity the elements of this block belong to
+    pub entity_dim: i32,
+    /// Tag of the entity the elements of this block belong to
+    pub entity_tag: i32,
+    /// Type of all elements of this block
+    pub element_type: ElementType,
+    /// Number of elements in this block
+    pub num_elements: usize,
+}
+
+/// Callbacks that are invoked by [`parse_msh_stream`](fn.parse_msh_stream.html) for the contents of a MSH file
+///
+/// All methods have empty default implementations, so implementors only have to override the
+/// callbacks they are interested in. The callbacks are invoked in the order in which the
+/// corresponding data appears in the file.
+pub trait MshVisitor {
+    /// Called after the `$MeshFormat` section was parsed
+    fn on_header(&mut self, _header: &MshHeader) {}
+    /// Called for every point, curve, surface and volume entity of an `$Entities` section
+    fn on_entity(&mut self, _entity: Entity) {}
+    /// Called at the start of every node entity block, before the nodes of the block
+    fn on_node_block_begin(&mut self, _block: &NodeBlockInfo) {}
+    /// Called for every node with its tag and coordinates
+    fn on_node(&mut self, _tag: u64, _node: &Node<f64>) {}
+    /// Called at the start of every element entity block, before the elements of the block
+    fn on_element_block_begin(&mut self, _block: &ElementBlockInfo) {}
+    /// Called for every element with its tag and the tags of its nodes
+    fn on_element(&mut self, _tag: u64, _node_tags: &[u64]) {}
+    /// Called with the name (without `$`) of every section that is not parsed and skipped
+    fn on_unknown_section(&mut self, _name: &str) {}
+}
+
+/// Error type returned by [`parse_msh_stream`](fn.parse_msh_stream.html)
+#[derive(Debug, thiserror::Error)]
+pub enum MshStreamError {
+    /// Reading from the source failed
+    #[error("Error while reading the MSH file: {0}")]
+    Io(#[from] io::Error),
+    /// The MSH file could not be parsed, the error contains byte offsets into the file
+    #[error("{0}")]
+    Parser(MshParserError<u64>),
+}
+
+impl From<MshParserError<u64>> for MshStreamError {
+    fn from(error: MshParserError<u64>) -> Self {
+        MshStreamError::Parser(error)
+    }
+}
+
+/// Parses a MSH file from the given source and passes its contents to the visitor
+///
+/// Uses a read buffer of [`DEFAULT_BUFFER_SIZE`](constant.DEFAULT_BUFFER_SIZE.html) bytes,
+/// see [`parse_msh_stream_with_buffer_size`](fn.parse_msh_stream_with_buffer_size.html).
+pub fn parse_msh_stream<R: Read, V: MshVisitor + ?Sized>(
+    reader: R,
+    visitor: &mut V,
+) -> Result<(), MshStreamError> {
+    parse_msh_stream_with_buffer_size(reader, visitor, DEFAULT_BUFFER_SIZE)
+}
+
+/// Parses a MSH file from the given source using a read buffer of the given size and passes its contents to the visitor
+///
+/// In contrast to [`parse_msh_bytes`](../fn.parse_msh_bytes.html), the file does not have to be
+/// loaded into memory and the parsed values are not collected. This allows to e.g. compute
+/// statistics of or convert meshes that are larger than the available memory.
+///
+/// The reader is consumed in chunks of at most `buffer_size` bytes (but at least
+/// [`MIN_BUFFER_SIZE`](constant.MIN_BUFFER_SIZE.html) bytes). Every single item of the file
+/// (e.g. the `$MeshFormat` section, an entity, a node or an element definition) has to fit into
+/// the buffer. Note that the MSH format stores the tags of all nodes of a node entity block before
+/// their coordinates, therefore the tags of the current node block are kept in memory.
+///
+/// ```
+/// use mshio::streaming::{parse_msh_stream, MshVisitor};
+///
+/// #[derive(Default)]
+/// struct CountElements(usize);
+///
+/// impl MshVisitor for CountElements {
+///     fn on_element(&mut self, _tag: u64, _node_tags: &[u64]) {
+///         self.0 += 1;
+///     }
+/// }
+///
+/// let msh = "\
+/// $MeshFormat
+/// 4.1 0 8
+/// $EndMeshFormat
+/// $Elements
+/// 1 2 1 2
+/// 2 1 2 2
+/// 1 1 2 3
+/// 2 1 3 4
+/// $EndElements
+/// ";
+///
+/// let mut visitor = CountElements::default();
+/// parse_msh_stream(msh.as_bytes(), &mut visitor).unwrap();
+/// assert_eq!(visitor.0, 2);
+/// ```
+pub fn parse_msh_stream_with_buffer_size<R: Read, V: MshVisitor + ?Sized>(
+    reader: R,
+    visitor: &mut V,
+    buffer_size: usize,
+) -> Result<(), MshStreamError> {
+    let mut buffer = StreamBuffer::new(reader, buffer_size);
+
+    let (header, parsers) = buffer.parse(crate::parse_msh_header)?;
+    buffer.set_ascii(header.endianness.is_none());
+    visitor.on_header(&header);
+
+    // Loop over all sections of the mesh file
+    while !buffer.at_end()? {
+        if buffer.try_parse(section_tag("$Entities"))?.is_ok() {
+            parse_entities(&mut buffer, &parsers, visitor)?;
+        } else if buffer.try_parse(section_tag("$Nodes"))?.is_ok() {
+            parse_nodes(&mut buffer, &parsers, visitor)?;
+        } else if buffer.try_parse(section_tag("$Elements"))?.is_ok() {
+            parse_elements(&mut buffer, &parsers, visitor)?;
+        } else if let Ok(section_name) = buffer.try_parse(|i| {
+            preceded(take_sp, delimited(char('$'), alpha0, br))(i)
+                .map(|(i, name)| (i, String::from_utf8_lossy(name).into_owned()))
+        })? {
+            // Skip unknown sections
+            visitor.on_unknown_section(&section_name);
+            let section_end_tag = format!("$End{}", section_name);
+            if !buffer.skip_past(section_end_tag.as_bytes())? {
+                return Err(buffer
+                    .error_here(MshParserErrorKind::NomError(ErrorKind::TakeUntil))
+                    .with_context(
+                        buffer.offset(),
+                        format!("Missing '{}' tag of unknown section", section_end_tag),
+                    )
+                    .into());
+            }
+        } else {
+            return Err(buffer
+                .error_here(MshParserErrorKind::InvalidSectionHeader)
+                .into());
+        }
+    }
+
+    Ok(())
+}
+
+type ParserResult<'a, O> = IResult<&'a [u8], O, MshParserError<&'a [u8]>>;
+
+/// Returns a parser for the start tag of a section
+fn section_tag(start_tag: &'static str) -> impl Fn(&[u8]) -> ParserResult<()> {
+    move |i| delimited(take_sp, tag(start_tag), br)(i).map(|(i, _)| (i, ()))
+}
+
+/// Returns a parser for the end tag of a section
+fn section_end_tag(end_tag: &'static str) -> impl Fn(&[u8]) -> ParserResult<()> {
+    move |i| delimited(take_sp, tag(end_tag), take_sp)(i).map(|(i, _)| (i, ()))
+}
+
+fn parse_entities<R: Read, V: MshVisitor + ?Sized>(
+    buffer: &mut StreamBuffer<R>,
+    parsers: &(impl ParsesSizeT<u64> + ParsesInt<i32> + ParsesFloat<f64>),
+    visitor: &mut V,
+) -> Result<(), MshStreamError> {
+    let EntitySectionHeader {
+        num_points,
+        num_curves,
+        num_surfaces,
+        num_volumes,
+    } = buffer.parse(|i| {
+        context("entity section header", |i| {
+            parse_entity_section_header(parsers, i)
+        })(i)
+        .with_context(i, "entity section")
+    })?;
+
+    // Macro that parses `$num_entities` entities using `$entity_parser_fun` and passes them to the visitor
+    macro_rules! visit_entities_of_kind {
+        ($entity_type:ident, $variant:ident, $num_entities:ident, $entity_parser_fun:ident) => {
+            for index in 0..$num_entities {
+                let entity = buffer.parse(|i| {
+                    $entity_parser_fun(parsers, i)
+                        .with_context_from(i, || {
+                            format!(
+                                concat!(stringify!($entity_type), " entity ({} of {})"),
+                                index + 1,
+                                $num_entities
+                            )
+                        })
+                        .with_context(i, "entity section")
+                })?;
+                visitor.on_entity(Entity::$variant(entity));
+            }
+        };
+    }
+
+    visit_entities_of_kind!(point, Point, num_points, parse_point);
+    visit_entities_of_kind!(curve, Curve, num_curves, parse_curve);
+    visit_entities_of_kind!(surface, Surface, num_surfaces, parse_surface);
+    visit_entities_of_kind!(volume, Volume, num_volumes, parse_volume);
+
+    buffer.parse(section_end_tag("$EndEntities"))?;
+    Ok(())
+}
+
+fn parse_nodes<R: Read, V: MshVisitor + ?Sized>(
+    buffer: &mut StreamBuffer<R>,
+    parsers: &(impl ParsesSizeT<u64> + ParsesInt<i32> + ParsesFloat<f64>),
+    visitor: &mut V,
+) -> Result<(), MshStreamError> {
+    let size_t_parser = size_t_parser(parsers);
+    let float_parser = float_parser(parsers);
+
+    let NodeSectionHeader {
+        num_entity_blocks, ..
+    } = buffer.parse(|i| {
+        context("node section header", |i| {
+            parse_node_section_header(parsers, i)
+        })(i)
+        .with_context(i, "node section")
+    })?;
+
+    // The node tags of the current block, the coordinates follow after all tags
+    let mut node_tags = Vec::new();
+
+    for block_index in 0..num_entity_blocks {
+        let block_context = || {
+            format!(
+                "node entity block ({} of {})",
+                block_index + 1,
+                num_entity_blocks
+            )
+        };
+
+        let NodeBlockHeader {
+            entity_dim,
+            entity_tag,
+            parametric,
+            num_nodes_in_block,
+        } = buffer.parse(|i| {
+            parse_node_entity_header(parsers, i)
+                .with_context_from(i, block_context)
+                .with_context(i, "node section")
+        })?;
+
+        visitor.on_node_block_begin(&NodeBlockInfo {
+            entity_dim,
+            entity_tag,
+            parametric,
+            num_nodes: num_nodes_in_block,
+        });
+
+        node_tags.clear();
+        for _ in 0..num_nodes_in_block {
+            let node_tag = buffer.parse(|i| {
+                context(
+                    "node tags",
+                    crate::error::error(MshParserErrorKind::InvalidTag, &size_t_parser),
+                )(i)
+                .with_context_from(i, block_context)
+                .with_context(i, "node section")
+            })?;
+            node_tags.push(node_tag);
+        }
+
+        for &node_tag in &node_tags {
+            let node = buffer.parse(|i| {
+                let parse_node = |input| {
+                    let (input, x) = context("x coordinate", &float_parser)(input)?;
+                    let (input, y) = context("y coordinate", &float_parser)(input)?;
+                    let (input, z) = context("z coordinate", &float_parser)(input)?;
+                    Ok((input, Node { x, y, z }))
+                };
+
+                context(
+                    "node coordinates",
+                    crate::error::error(MshParserErrorKind::InvalidNodeDefinition, parse_node),
+                )(i)
+                .with_context_from(i, block_context)
+                .with_context(i, "node section")
+            })?;
+            visitor.on_node(node_tag, &node);
+        }
+    }
+
+    buffer.parse(section_end_tag("$EndNodes"))?;
+    Ok(())
+}
+
+fn parse_elements<R: Read, V: MshVisitor + ?Sized>(
+    buffer: &mut StreamBuffer<R>,
+    parsers: &(impl ParsesSizeT<u64> + ParsesInt<i32>),
+    visitor: &mut V,
+) -> Result<(), MshStreamError> {
+    let ElementSectionHeader {
+        num_entity_blocks, ..
+    } = buffer.parse(|i| {
+        context("element section header", |i| {
+            parse_element_section_header(parsers, i)
+        })(i)
+        .with_context(i, "element section")
+    })?;
+
+    // The node tags of the current element
+    let mut node_tags = Vec::new();
+
+    for block_index in 0..num_entity_blocks {
+        let block_context = || {
+            format!(
+                "element entity block ({} of {})",
+                block_index + 1,
+                num_entity_blocks
+            )
+        };
+
+        let block_start = buffer.offset();
+        let ElementBlockHeader {
+            entity_dim,
+            entity_tag,
+            element_type,
+            num_elements_in_block,
+        } = buffer.parse(|i| {
+            parse_element_entity_header(parsers, i)
+                .with_context_from(i, block_context)
+                .with_context(i, "element section")
+        })?;
+
+        // Try to get the number of nodes per element
+        let num_nodes_per_element = element_type.nodes().map_err(|_| {
+            MshParserError::from_error_kind(block_start, MshParserErrorKind::Unimplemented)
+                .with_context(
+                    block_start,
+                    "An element type encountered in the MSH file does not have a known number of nodes.",
+                )
+        })?;
+
+        visitor.on_element_block_begin(&ElementBlockInfo {
+            entity_dim,
+            entity_tag,
+            element_type,
+            num_elements: num_elements_in_block,
+        });
+
+        for index in 0..num_elements_in_block {
+            let element_tag = buffer.parse(|i| {
+                let mut parse_element = |input| {
+                    node_tags.clear();
+                    let (mut input, element_tag) = parsers.parse_size_t(input)?;
+                    for _ in 0..num_nodes_per_element {
+                        let (input_, node_tag) = parsers.parse_size_t(input)?;
+                        node_tags.push(node_tag);
+                        input = input_;
+                    }
+                    Ok((input, element_tag))
+                };
+
+                parse_element(i)
+                    .with_error(i, MshParserErrorKind::InvalidElementDefinition)
+                    .with_context_from(i, || {
+                        format!(
+                            "element definition ({} of {})",
+                            index + 1,
+                            num_elements_in_block
+                        )
+                    })
+                    .with_context_from(i, block_context)
+                    .with_context(i, "element section")
+            })?;
+
+            visitor.on_element(element_tag, &node_tags);
+        }
+    }
+
+    buffer.parse(section_end_tag("$EndElements"))?;
+    Ok(())
+}
+
+/// Read buffer of bounded size that applies complete nom parsers to the data of a `Read` source
+struct StreamBuffer<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    /// Start of the data in the buffer that was not consumed yet
+    start: usize,
+    /// End of the valid data in the buffer
+    end: usize,
+    /// End of the data that is passed to the parsers
+    window_end: usize,
+    /// Position of the data at `start` in the whole stream
+    offset: u64,
+    /// Whether the end of the source was reached
+    eof: bool,
+    /// Whether the data consists of whitespace separated ASCII values
+    ascii: bool,
+}
+
+impl<R: Read> StreamBuffer<R> {
+    fn new(reader: R, buffer_size: usize) -> Self {
+        Self {
+            reader,
+            buffer: vec![0; buffer_size.max(MIN_BUFFER_SIZE)],
+            start: 0,
+            end: 0,
+            window_end: 0,
+            offset: 0,
+            eof: false,
+            ascii: false,
+        }
+    }
+
+    /// Position of the first byte that was not consumed yet in the whole stream
+    fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Sets whether values of the stream are ASCII encoded
+    ///
+    /// In this case, parsers are only applied up to the last whitespace in the buffer to
+    /// ensure that they never see a value which is cut off at the end of the buffer.
+    fn set_ascii(&mut self, ascii: bool) {
+        self.ascii = ascii;
+        self.update_window();
+    }
+
+    fn update_window(&mut self) {
+        self.window_end = if self.ascii && !self.eof {
+            self.buffer[self.start..self.end]
+                .iter()
+                .rposition(|c| b" \t\r\n".contains(c))
+                .map(|pos| self.start + pos + 1)
+                .unwrap_or(self.start)
+        } else {
+            self.end
+        };
+    }
+
+    /// Returns whether more data can be read into the buffer
+    fn can_fill(&self) -> bool {
+        !self.eof && (self.start > 0 || self.end < self.buffer.len())
+    }
+
+    /// Moves the remaining data to the front of the buffer and fills it with new data from the source
+    fn fill(&mut self) -> io::Result<()> {
+        if self.start > 0 {
+            self.buffer.copy_within(self.start..self.end, 0);
+            self.end -= self.start;
+            self.window_end = self.window_end.saturating_sub(self.start);
+            self.start = 0;
+        }
+
+        while self.end < self.buffer.len() && !self.eof {
+            match self.reader.read(&mut self.buffer[self.end..]) {
+                Ok(0) => self.eof = true,
+                Ok(n) => self.end += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        self.update_window();
+        Ok(())
+    }
+
+    fn consume(&mut self, num_bytes: usize) {
+        self.start += num_bytes;
+        self.offset += num_bytes as u64;
+        // Data that is skipped without a parser may extend beyond the parser window
+        self.window_end = self.window_end.max(self.start);
+    }
+
+    /// Applies the parser to the buffered data and reads more data while the parser fails
+    ///
+    /// Returns an error with offsets into the stream if the parser fails although the buffer is
+    /// full or the end of the source was reached.
+    fn try_parse<O, P>(&mut self, mut parser: P) -> io::Result<Result<O, MshParserError<u64>>>
+    where
+        P: FnMut(&[u8]) -> ParserResult<O>,
+    {
+        loop {
+            let window = &self.buffer[self.start..self.window_end];
+            let result = match parser(window) {
+                Ok((rest, output)) => Ok((window.len() - rest.len(), output)),
+                Err(error) => Err(MshParserError::from(error).into_offsets(window, self.offset)),
+            };
+
+            match result {
+                Ok((num_bytes, output)) => {
+                    self.consume(num_bytes);
+                    return Ok(Ok(output));
+                }
+                Err(error) => {
+                    if self.can_fill() {
+                        self.fill()?;
+                    } else {
+                        return Ok(Err(error));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Applies the parser to the buffered data, see `try_parse`
+    fn parse<O, P>(&mut self, parser: P) -> Result<O, MshStreamError>
+    where
+        P: FnMut(&[u8]) -> ParserResult<O>,
+    {
+        Ok(self.try_parse(parser)??)
+    }
+
+    /// Returns whether the rest of the stream only consists of whitespace, which is consumed
+    fn at_end(&mut self) -> io::Result<bool> {
+        loop {
+            let num_whitespace = self.buffer[self.start..self.end]
+                .iter()
+                .take_while(|c| b" \t\r\n".contains(c))
+                .count();
+            let is_whitespace = self.start + num_whitespace == self.end;
+            self.consume(num_whitespace);
+
+            if !is_whitespace {
+                return Ok(false);
+            } else if !self.can_fill() {
+                return Ok(true);
+            }
+            self.fill()?;
+        }
+    }
+
+    /// Consumes all data up to and including the first occurrence of the pattern
+    ///
+    /// The pattern only matches if it is followed by whitespace or the end of the stream. Returns
+    /// false if the pattern does not occur in the rest of the stream.
+    fn skip_past(&mut self, pattern: &[u8]) -> io::Result<bool> {
+        loop {
+            let data = &self.buffer[self.start..self.end];
+            // Position of a match at the end of the data that might be followed by more data
+            let mut pending = None;
+            let matches = data
+                .windows(pattern.len())
+                .enumerate()
+                .filter(|(_, window)| *window == pattern)
+                .map(|(pos, _)| pos);
+            for pos in matches {
+                match data.get(pos + pattern.len()) {
+                    Some(c) if b" \t\r\n".contains(c) => {}
+                    Some(_) => continue,
+                    None if !self.eof => {
+                        pending = Some(pos);
+                        break;
+                    }
+                    None => {}
+                }
+                self.consume(pos + pattern.len());
+                return Ok(true);
+            }
+
+            // Keep the end of the data as it might be the beginning of the pattern
+            let num_kept = match pending {
+                Some(pos) => data.len() - pos,
+                None => data.len().min(pattern.len() - 1),
+            };
+            self.consume(data.len() - num_kept);
+
+            if !self.can_fill() {
+                return Ok(false);
+            }
+            self.fill()?;
+        }
+    }
+
+    /// Returns an error of the given kind at the current position
+    fn error_here(&self, kind: MshParserErrorKind) -> MshParserError<u64> {
+        MshParserError::from_error_kind(self.offset, kind)
+    }
+}
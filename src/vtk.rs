@@ -0,0 +1,455 @@
+use std::convert::TryFrom;
+use std::sync::OnceLock;
+
+use num_traits::FromPrimitive;
+
+use crate::lagrange::{self, Monomial, NUM_ELEMENT_TYPES};
+use crate::mshfile::{ElementFamily, ElementType};
+
+/// Cell types of the VTK file formats that MSH element types are mapped to
+///
+/// The discriminants of the variants are the cell type ids defined in VTK's `vtkCellType.h`, i.e.
+/// `VtkCellType::QuadraticTetra as u8` is the id that has to be written to VTK files.
+/// ```
+/// use mshio::vtk::VtkCellType;
+/// use num_traits::FromPrimitive;
+///
+/// assert_eq!(VtkCellType::LagrangeHexahedron as u8, 72);
+/// assert_eq!(VtkCellType::from_u8(24), Some(VtkCellType::QuadraticTetra));
+/// assert_eq!(VtkCellType::from_u8(2), None);
+/// assert_eq!(VtkCellType::from_i32(-24), None);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum VtkCellType {
+    Vertex = 1,
+    Line = 3,
+    Triangle = 5,
+    Quad = 9,
+    Tetra = 10,
+    Hexahedron = 12,
+    Wedge = 13,
+    Pyramid = 14,
+    QuadraticEdge = 21,
+    QuadraticTriangle = 22,
+    QuadraticQuad = 23,
+    QuadraticTetra = 24,
+    QuadraticHexahedron = 25,
+    QuadraticWedge = 26,
+    QuadraticPyramid = 27,
+    BiquadraticQuad = 28,
+    TriquadraticHexahedron = 29,
+    BiquadraticQuadraticWedge = 32,
+    LagrangeCurve = 68,
+    LagrangeTriangle = 69,
+    LagrangeQuadrilateral = 70,
+    LagrangeTetrahedron = 71,
+    LagrangeHexahedron = 72,
+    LagrangeWedge = 73,
+}
+
+impl FromPrimitive for VtkCellType {
+    fn from_i64(id: i64) -> Option<Self> {
+        u64::try_from(id).ok().and_then(Self::from_u64)
+    }
+
+    fn from_u64(id: u64) -> Option<Self> {
+        use VtkCellType::*;
+
+        Some(match id {
+            1 => Vertex,
+            3 => Line,
+            5 => Triangle,
+            9 => Quad,
+            10 => Tetra,
+            12 => Hexahedron,
+            13 => Wedge,
+            14 => Pyramid,
+            21 => QuadraticEdge,
+            22 => QuadraticTriangle,
+            23 => QuadraticQuad,
+            24 => QuadraticTetra,
+            25 => QuadraticHexahedron,
+            26 => QuadraticWedge,
+            27 => QuadraticPyramid,
+            28 => BiquadraticQuad,
+            29 => TriquadraticHexahedron,
+            32 => BiquadraticQuadraticWedge,
+            68 => LagrangeCurve,
+            69 => LagrangeTriangle,
+            70 => LagrangeQuadrilateral,
+            71 => LagrangeTetrahedron,
+            72 => LagrangeHexahedron,
+            73 => LagrangeWedge,
+            _ => return None,
+        })
+    }
+}
+
+impl VtkCellType {
+    /// Returns the MSH element type corresponding to a VTK cell with the given number of nodes
+    ///
+    /// The number of nodes determines the order of the arbitrary order Lagrange cells. Returns
+    /// `None` if no element type corresponds to the cell type and number of nodes. VTK vertices
+    /// are mapped to points (`Pnt`).
+    /// ```
+    /// use mshio::mshfile::ElementType;
+    /// use mshio::vtk::VtkCellType;
+    ///
+    /// assert_eq!(VtkCellType::QuadraticHexahedron.element_type(20), Some(ElementType::Hex20));
+    /// assert_eq!(VtkCellType::LagrangeTriangle.element_type(15), Some(ElementType::Tri15));
+    /// assert_eq!(VtkCellType::LagrangeTriangle.element_type(14), None);
+    /// ```
+    pub fn element_type(&self, num_nodes: usize) -> Option<ElementType> {
+        (1..=u8::MAX)
+            .filter_map(ElementType::from_u8)
+            .find(|t| cell_type(*t) == Some(*self) && t.nodes() == Ok(num_nodes))
+    }
+}
+
+/// Returns the VTK cell type corresponding to the given element type
+pub(crate) fn cell_type(element_type: ElementType) -> Option<VtkCellType> {
+    use VtkCellType::*;
+
+    let order = element_type.order();
+    let complete = element_type.is_complete();
+    if !complete && !element_type.is_serendipity() {
+        return None;
+    }
+
+    // All single node elements are written as vertices
+    if order == 0 {
+        return Some(Vertex);
+    }
+
+    Some(match (element_type.family(), order, complete) {
+        (ElementFamily::Line, 1, _) => Line,
+        (ElementFamily::Triangle, 1, _) => Triangle,
+        (ElementFamily::Quadrangle, 1, _) => Quad,
+        (ElementFamily::Tetrahedron, 1, _) => Tetra,
+        (ElementFamily::Hexahedron, 1, _) => Hexahedron,
+        (ElementFamily::Prism, 1, _) => Wedge,
+        (ElementFamily::Pyramid, 1, _) => Pyramid,
+        (ElementFamily::Line, 2, true) => QuadraticEdge,
+        (ElementFamily::Triangle, 2, true) => QuadraticTriangle,
+        (ElementFamily::Quadrangle, 2, true) => BiquadraticQuad,
+        (ElementFamily::Quadrangle, 2, false) => QuadraticQuad,
+        (ElementFamily::Tetrahedron, 2, true) => QuadraticTetra,
+        (ElementFamily::Hexahedron, 2, true) => TriquadraticHexahedron,
+        (ElementFamily::Hexahedron, 2, false) => QuadraticHexahedron,
+        (ElementFamily::Prism, 2, true) => BiquadraticQuadraticWedge,
+        (ElementFamily::Prism, 2, false) => QuadraticWedge,
+        (ElementFamily::Pyramid, 2, false) => QuadraticPyramid,
+        (ElementFamily::Line, _, true) => LagrangeCurve,
+        (ElementFamily::Triangle, _, true) => LagrangeTriangle,
+        (ElementFamily::Quadrangle, _, true) => LagrangeQuadrilateral,
+        (ElementFamily::Tetrahedron, _, true) => LagrangeTetrahedron,
+        (ElementFamily::Hexahedron, _, true) => LagrangeHexahedron,
+        (ElementFamily::Prism, _, true) => LagrangeWedge,
+        _ => return None,
+    })
+}
+
+/// Returns for each node of the VTK cell the index of the corresponding node in Gmsh ordering
+///
+/// The permutation is computed by matching the reference coordinates of the VTK nodes with the
+/// Gmsh reference nodes and cached on first use.
+pub(crate) fn node_ordering(element_type: ElementType) -> Option<&'static [usize]> {
+    static CACHE: [OnceLock<Option<Vec<usize>>>; NUM_ELEMENT_TYPES] =
+        [const { OnceLock::new() }; NUM_ELEMENT_TYPES];
+
+    CACHE[element_type as usize]
+        .get_or_init(|| generate_node_ordering(element_type))
+        .as_deref()
+}
+
+fn generate_node_ordering(element_type: ElementType) -> Option<Vec<usize>> {
+    let cell_type = cell_type(element_type)?;
+    let vtk_nodes = vtk_nodes(cell_type, element_type)?;
+    lagrange::node_permutation(element_type, &vtk_nodes)
+}
+
+/// Returns the reference coordinates of the nodes of the VTK cell in VTK ordering
+///
+/// The vertices of all VTK cells are ordered in the same way as in Gmsh, so the reference domains
+/// of Gmsh can be used.
+fn vtk_nodes(cell_type: VtkCellType, element_type: ElementType) -> Option<Vec<[f64; 3]>> {
+    use VtkCellType::*;
+
+    let fixed_nodes = fixed_nodes(cell_type);
+    if !fixed_nodes.is_empty() {
+        return lagrange::vertex_averages(element_type, fixed_nodes);
+    }
+
+    let order = element_type.order();
+    let n = order as i32;
+    let monomials = match cell_type {
+        LagrangeCurve => curve(n),
+        LagrangeTriangle => triangle(n),
+        LagrangeQuadrilateral => quadrilateral(n),
+        LagrangeTetrahedron => tetrahedron(n),
+        LagrangeHexahedron => hexahedron(n),
+        LagrangeWedge => wedge(n),
+        _ => unreachable!(),
+    };
+
+    let o = order as f64;
+    Some(
+        monomials
+            .iter()
+            .map(|&[i, j, k]| {
+                let [u, v, w] = [i as f64 / o, j as f64 / o, k as f64 / o];
+                match cell_type {
+                    LagrangeCurve => [2.0 * u - 1.0, 0.0, 0.0],
+                    LagrangeTriangle | LagrangeTetrahedron => [u, v, w],
+                    LagrangeQuadrilateral => [2.0 * u - 1.0, 2.0 * v - 1.0, 0.0],
+                    LagrangeHexahedron => [2.0 * u - 1.0, 2.0 * v - 1.0, 2.0 * w - 1.0],
+                    LagrangeWedge => [u, v, 2.0 * w - 1.0],
+                    _ => unreachable!(),
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Returns the nodes of the VTK cells with a fixed number of nodes
+///
+/// Each node is given by the vertices that it is centered at. Returns an empty slice for the
+/// arbitrary order Lagrange cells.
+#[rustfmt::skip]
+fn fixed_nodes(cell_type: VtkCellType) -> &'static [&'static [usize]] {
+    use VtkCellType::*;
+
+    match cell_type {
+        Vertex => &[&[0]],
+        Line => &[&[0], &[1]],
+        Triangle => &[&[0], &[1], &[2]],
+        Quad => &[&[0], &[1], &[2], &[3]],
+        Tetra => &[&[0], &[1], &[2], &[3]],
+        Hexahedron => &[&[0], &[1], &[2], &[3], &[4], &[5], &[6], &[7]],
+        Wedge => &[&[0], &[1], &[2], &[3], &[4], &[5]],
+        Pyramid => &[&[0], &[1], &[2], &[3], &[4]],
+        QuadraticEdge => &[&[0], &[1], &[0, 1]],
+        QuadraticTriangle => &[&[0], &[1], &[2], &[0, 1], &[1, 2], &[2, 0]],
+        QuadraticQuad => &[
+            &[0], &[1], &[2], &[3],
+            &[0, 1], &[1, 2], &[2, 3], &[3, 0],
+        ],
+        BiquadraticQuad => &[
+            &[0], &[1], &[2], &[3],
+            &[0, 1], &[1, 2], &[2, 3], &[3, 0],
+            &[0, 1, 2, 3],
+        ],
+        QuadraticTetra => &[
+            &[0], &[1], &[2], &[3],
+            &[0, 1], &[1, 2], &[2, 0], &[0, 3], &[1, 3], &[2, 3],
+        ],
+        QuadraticHexahedron => &[
+            &[0], &[1], &[2], &[3], &[4], &[5], &[6], &[7],
+            &[0, 1], &[1, 2], &[2, 3], &[3, 0],
+            &[4, 5], &[5, 6], &[6, 7], &[7, 4],
+            &[0, 4], &[1, 5], &[2, 6], &[3, 7],
+        ],
+        TriquadraticHexahedron => &[
+            &[0], &[1], &[2], &[3], &[4], &[5], &[6], &[7],
+            &[0, 1], &[1, 2], &[2, 3], &[3, 0],
+            &[4, 5], &[5, 6], &[6, 7], &[7, 4],
+            &[0, 4], &[1, 5], &[2, 6], &[3, 7],
+            &[0, 3, 7, 4], &[1, 2, 6, 5], &[0, 1, 5, 4], &[3, 2, 6, 7], &[0, 1, 2, 3], &[4, 5, 6, 7],
+            &[0, 1, 2, 3, 4, 5, 6, 7],
+        ],
+        QuadraticWedge => &[
+            &[0], &[1], &[2], &[3], &[4], &[5],
+            &[0, 1], &[1, 2], &[2, 0], &[3, 4], &[4, 5], &[5, 3],
+            &[0, 3], &[1, 4], &[2, 5],
+        ],
+        BiquadraticQuadraticWedge => &[
+            &[0], &[1], &[2], &[3], &[4], &[5],
+            &[0, 1], &[1, 2], &[2, 0], &[3, 4], &[4, 5], &[5, 3],
+            &[0, 3], &[1, 4], &[2, 5],
+            &[0, 1, 4, 3], &[1, 2, 5, 4], &[2, 0, 3, 5],
+        ],
+        QuadraticPyramid => &[
+            &[0], &[1], &[2], &[3], &[4],
+            &[0, 1], &[1, 2], &[2, 3], &[3, 0],
+            &[0, 4], &[1, 4], &[2, 4], &[3, 4],
+        ],
+        LagrangeCurve
+        | LagrangeTriangle
+        | LagrangeQuadrilateral
+        | LagrangeTetrahedron
+        | LagrangeHexahedron
+        | LagrangeWedge => &[],
+    }
+}
+
+/// Node lattice of the Lagrange curve (`vtkLagrangeCurve`)
+fn curve(n: i32) -> Vec<Monomial> {
+    let mut monomials = vec![[0, 0, 0], [n, 0, 0]];
+    monomials.extend((1..n).map(|i| [i, 0, 0]));
+    monomials
+}
+
+/// Node lattice of the Lagrange triangle (`vtkHigherOrderTriangle::BarycentricIndex`)
+///
+/// Vertices, edge nodes and recursively the interior nodes as a triangle of order `n - 3`.
+fn triangle(n: i32) -> Vec<Monomial> {
+    if n == 0 {
+        return vec![[0, 0, 0]];
+    }
+
+    let mut monomials = vec![[0, 0, 0], [n, 0, 0], [0, n, 0]];
+    monomials.extend((1..n).map(|i| [i, 0, 0]));
+    monomials.extend((1..n).map(|j| [n - j, j, 0]));
+    monomials.extend((1..n).map(|j| [0, n - j, 0]));
+    if n > 2 {
+        monomials.extend(triangle(n - 3).iter().map(|m| [m[0] + 1, m[1] + 1, 0]));
+    }
+    monomials
+}
+
+/// Node lattice of the Lagrange quadrilateral (`vtkHigherOrderQuadrilateral::PointIndexFromIJK`)
+fn quadrilateral(n: i32) -> Vec<Monomial> {
+    let mut monomials = vec![[0, 0, 0], [n, 0, 0], [n, n, 0], [0, n, 0]];
+    monomials.extend((1..n).map(|i| [i, 0, 0]));
+    monomials.extend((1..n).map(|j| [n, j, 0]));
+    monomials.extend((1..n).map(|i| [i, n, 0]));
+    monomials.extend((1..n).map(|j| [0, j, 0]));
+    for j in 1..n {
+        monomials.extend((1..n).map(|i| [i, j, 0]));
+    }
+    monomials
+}
+
+/// Node lattice of the Lagrange tetrahedron (`vtkHigherOrderTetra::BarycentricIndex`)
+///
+/// Vertices, edge nodes, face nodes ordered like triangles of order `n - 3` and recursively the
+/// interior nodes as a tetrahedron of order `n - 4`.
+fn tetrahedron(n: i32) -> Vec<Monomial> {
+    /// Vertex pairs of the edges, the edge nodes are ordered from the first to the second vertex
+    const EDGES: [[usize; 2]; 6] = [[0, 1], [1, 2], [2, 0], [0, 3], [1, 3], [2, 3]];
+    /// Vertices of the faces, the face nodes are ordered like a triangle with these vertices
+    const FACES: [[usize; 3]; 4] = [[0, 1, 3], [2, 3, 1], [0, 3, 2], [0, 2, 1]];
+
+    if n == 0 {
+        return vec![[0, 0, 0]];
+    }
+
+    let vertices = [[0, 0, 0], [n, 0, 0], [0, n, 0], [0, 0, n]];
+    let mut monomials = vertices.to_vec();
+    for &[a, b] in EDGES.iter() {
+        let (ma, mb) = (vertices[a], vertices[b]);
+        for i in 1..n {
+            monomials.push([0, 1, 2].map(|d| ma[d] + i * (mb[d] - ma[d]) / n));
+        }
+    }
+    if n > 2 {
+        let face_nodes = triangle(n - 3);
+        for &[a, b, c] in FACES.iter() {
+            let unit = |v: usize| vertices[v].map(|x| x / n);
+            let (ua, ub, uc) = (unit(a), unit(b), unit(c));
+            for m in &face_nodes {
+                let (wb, wc) = (m[0] + 1, m[1] + 1);
+                let wa = n - wb - wc;
+                monomials.push([0, 1, 2].map(|d| wa * ua[d] + wb * ub[d] + wc * uc[d]));
+            }
+        }
+    }
+    if n > 3 {
+        monomials.extend(
+            tetrahedron(n - 4)
+                .iter()
+                .map(|m| [m[0] + 1, m[1] + 1, m[2] + 1]),
+        );
+    }
+    monomials
+}
+
+/// Node lattice of the Lagrange hexahedron (`vtkHigherOrderHexahedron::PointIndexFromIJK`)
+fn hexahedron(n: i32) -> Vec<Monomial> {
+    let mut monomials = vec![
+        [0, 0, 0],
+        [n, 0, 0],
+        [n, n, 0],
+        [0, n, 0],
+        [0, 0, n],
+        [n, 0, n],
+        [n, n, n],
+        [0, n, n],
+    ];
+    // Edges of the bottom and top face
+    for k in [0, n] {
+        monomials.extend((1..n).map(|i| [i, 0, k]));
+        monomials.extend((1..n).map(|j| [n, j, k]));
+        monomials.extend((1..n).map(|i| [i, n, k]));
+        monomials.extend((1..n).map(|j| [0, j, k]));
+    }
+    // Vertical edges, the edge at vertex 3 comes before the edge at vertex 2
+    for [i, j] in [[0, 0], [n, 0], [0, n], [n, n]] {
+        monomials.extend((1..n).map(|k| [i, j, k]));
+    }
+    // Faces with normals in x, y and z direction
+    for i in [0, n] {
+        for k in 1..n {
+            monomials.extend((1..n).map(|j| [i, j, k]));
+        }
+    }
+    for j in [0, n] {
+        for k in 1..n {
+            monomials.extend((1..n).map(|i| [i, j, k]));
+        }
+    }
+    for k in [0, n] {
+        for j in 1..n {
+            monomials.extend((1..n).map(|i| [i, j, k]));
+        }
+    }
+    for k in 1..n {
+        for j in 1..n {
+            monomials.extend((1..n).map(|i| [i, j, k]));
+        }
+    }
+    monomials
+}
+
+/// Node lattice of the Lagrange wedge (`vtkHigherOrderWedge::PointIndexFromIJK`)
+///
+/// Unlike the Lagrange triangle, the nodes inside the triangles of the wedge are ordered row-wise.
+fn wedge(n: i32) -> Vec<Monomial> {
+    let mut monomials = vec![
+        [0, 0, 0],
+        [n, 0, 0],
+        [0, n, 0],
+        [0, 0, n],
+        [n, 0, n],
+        [0, n, n],
+    ];
+    // Edges of the bottom and top triangle
+    for k in [0, n] {
+        monomials.extend((1..n).map(|i| [i, 0, k]));
+        monomials.extend((1..n).map(|j| [n - j, j, k]));
+        monomials.extend((1..n).map(|j| [0, n - j, k]));
+    }
+    for [i, j] in [[0, 0], [n, 0], [0, n]] {
+        monomials.extend((1..n).map(|k| [i, j, k]));
+    }
+
+    let triangle_nodes: Vec<_> = (1..n)
+        .flat_map(|j| (1..n - j).map(move |i| [i, j]))
+        .collect();
+    for k in [0, n] {
+        monomials.extend(triangle_nodes.iter().map(|&[i, j]| [i, j, k]));
+    }
+    for k in 1..n {
+        monomials.extend((1..n).map(|i| [i, 0, k]));
+    }
+    for k in 1..n {
+        monomials.extend((1..n).map(|j| [n - j, j, k]));
+    }
+    for k in 1..n {
+        monomials.extend((1..n).map(|j| [0, n - j, k]));
+    }
+    for k in 1..n {
+        monomials.extend(triangle_nodes.iter().map(|&[i, j]| [i, j, k]));
+    }
+    monomials
+}
@@ -0,0 +1,185 @@
+use mshio::mshfile::*;
+
+#[macro_use]
+mod utils;
+
+use crate::utils::*;
+
+/// Returns the local face of the linear volume element with the given vertices
+fn find_face(elements: &Elements<u64, i32>, element: usize, vertices: &[u64]) -> ElementFace {
+    let (block, index) = elements.element_location(element).unwrap();
+    let block = &elements.element_blocks[block];
+    let nodes = &block.elements[index].nodes;
+    let mut vertices = vertices.to_vec();
+    vertices.sort_unstable();
+
+    let face = block
+        .element_type
+        .faces()
+        .unwrap()
+        .iter()
+        .position(|face| {
+            let mut face_vertices: Vec<u64> = face.iter().map(|&i| nodes[i]).collect();
+            face_vertices.sort_unstable();
+            face_vertices == vertices
+        })
+        .unwrap();
+    ElementFace { element, face }
+}
+
+/// A pyramid on top of a prism with a tetrahedron attached to one of its triangular faces and a
+/// triangle on the outside of another face
+fn mixed_volume_elements() -> Elements<u64, i32> {
+    build_elements(vec![
+        (ElementType::Pyr5, 1, vec![vec![1, 2, 3, 4, 5]]),
+        (ElementType::Pri6, 2, vec![vec![1, 2, 6, 4, 3, 7]]),
+        (ElementType::Tet4, 3, vec![vec![2, 3, 5, 8]]),
+        (ElementType::Tri3, 1, vec![vec![1, 2, 5]]),
+    ])
+}
+
+#[test]
+fn test_node_elements() {
+    let elements = mixed_volume_elements();
+    let incidence = elements.node_elements();
+
+    assert_eq!(incidence.node_tags, (1..=8).collect::<Vec<u64>>());
+    assert_eq!(incidence.elements.num_rows(), 8);
+    assert_eq!(incidence.elements_of(2), Some(&[0, 1, 2, 3][..]));
+    assert_eq!(incidence.elements_of(5), Some(&[0, 2, 3][..]));
+    assert_eq!(incidence.elements_of(8), Some(&[2][..]));
+    assert_eq!(incidence.elements_of(9), None);
+    assert_eq!(incidence.elements.offsets.last(), Some(&18));
+    assert_eq!(
+        incidence
+            .elements
+            .rows()
+            .map(|row| row.len())
+            .sum::<usize>(),
+        18
+    );
+
+    // Nodes referenced multiple times by an element are only listed once
+    let degenerate = build_elements(vec![(ElementType::Tri3, 1, vec![vec![1, 1, 2]])]);
+    assert_eq!(degenerate.node_elements().elements_of(1), Some(&[0][..]));
+
+    assert_eq!(elements.element_location(3), Some((3, 0)));
+    assert_eq!(elements.element_location(4), None);
+}
+
+#[test]
+fn test_mixed_volume_face_adjacency() {
+    let elements = mixed_volume_elements();
+    let adjacency = elements.face_adjacency();
+
+    // One row per element with an entry per face (triangles have their edges as faces)
+    let num_faces: Vec<usize> = adjacency.neighbors.rows().map(|row| row.len()).collect();
+    assert_eq!(num_faces, vec![5, 5, 4, 3]);
+
+    // The base of the pyramid is shared with the prism
+    let base = ElementFace {
+        element: 0,
+        face: 4,
+    };
+    let prism_top = find_face(&elements, 1, &[1, 2, 3, 4]);
+    assert_eq!(adjacency.neighbor(base), Some(prism_top));
+    assert_eq!(adjacency.neighbor(prism_top), Some(base));
+
+    // A triangular face of the pyramid is shared with the tetrahedron
+    let pyramid_side = find_face(&elements, 0, &[2, 3, 5]);
+    let tet_side = find_face(&elements, 2, &[2, 3, 5]);
+    assert_eq!(adjacency.neighbor(pyramid_side), Some(tet_side));
+    assert_eq!(adjacency.neighbor(tet_side), Some(pyramid_side));
+
+    // The triangle is not a neighbor of the pyramid although it covers one of its faces
+    assert_eq!(
+        adjacency.element_neighbors(0).collect::<Vec<_>>(),
+        vec![2, 1]
+    );
+    assert_eq!(adjacency.element_neighbors(3).count(), 0);
+
+    assert_eq!(adjacency.boundary_faces.len(), 3 + 4 + 3 + 3);
+    assert!(!adjacency.boundary_faces.contains(&base));
+    assert!(adjacency
+        .boundary_faces
+        .contains(&find_face(&elements, 0, &[1, 2, 5])));
+    assert!(adjacency.boundary_faces.windows(2).all(|w| w[0] < w[1]));
+    assert!(adjacency.non_manifold_faces.is_empty());
+}
+
+#[test]
+fn test_surface_and_line_face_adjacency() {
+    let elements = build_elements(vec![
+        (ElementType::Qua4, 1, vec![vec![1, 2, 3, 4]]),
+        // A quadratic triangle sharing the edge (2, 3) with the quadrangle
+        (ElementType::Tri6, 1, vec![vec![2, 5, 3, 10, 11, 12]]),
+        // Three triangles sharing the edge (1, 2) with the quadrangle
+        (
+            ElementType::Tri3,
+            2,
+            vec![vec![1, 2, 6], vec![2, 1, 7], vec![1, 8, 2]],
+        ),
+        (ElementType::Lin2, 1, vec![vec![20, 21], vec![21, 22]]),
+        // Elements with the wrong number of nodes do not have faces
+        (ElementType::Tri3, 3, vec![vec![1, 2]]),
+    ]);
+    let adjacency = elements.face_adjacency();
+
+    // Edge (2, 3) is the second edge of the quadrangle and the third edge of the triangle
+    assert_eq!(
+        adjacency.neighbor(ElementFace {
+            element: 0,
+            face: 1
+        }),
+        Some(ElementFace {
+            element: 1,
+            face: 2
+        })
+    );
+
+    // Edge (1, 2) is non-manifold and neither a boundary face nor shared by neighbors
+    assert_eq!(
+        adjacency.non_manifold_faces,
+        vec![vec![
+            ElementFace {
+                element: 0,
+                face: 0
+            },
+            ElementFace {
+                element: 2,
+                face: 0
+            },
+            ElementFace {
+                element: 3,
+                face: 0
+            },
+            ElementFace {
+                element: 4,
+                face: 2
+            },
+        ]]
+    );
+    assert_eq!(
+        adjacency.neighbor(ElementFace {
+            element: 0,
+            face: 0
+        }),
+        None
+    );
+
+    // Lines are neighbors across their end points
+    assert_eq!(adjacency.element_neighbors(5).collect::<Vec<_>>(), vec![6]);
+    assert_eq!(
+        adjacency.neighbor(ElementFace {
+            element: 6,
+            face: 0
+        }),
+        Some(ElementFace {
+            element: 5,
+            face: 1
+        })
+    );
+
+    assert_eq!(adjacency.neighbors.row(7).len(), 0);
+    assert_eq!(adjacency.boundary_faces.len(), 2 + 2 + 2 * 3 + 2);
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+
+use mshio::mshfile::*;
+
+#[macro_use]
+mod utils;
+
+use crate::utils::*;
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn centroid(points: &[[f64; 3]]) -> [f64; 3] {
+    let n = points.len() as f64;
+    [0, 1, 2].map(|d| points.iter().map(|p| p[d]).sum::<f64>() / n)
+}
+
+#[test]
+fn test_tetrahedra_boundary() {
+    let coords: HashMap<u64, [f64; 3]> = vec![
+        (1, [0.0, 0.0, 0.0]),
+        (2, [1.0, 0.0, 0.0]),
+        (3, [0.0, 1.0, 0.0]),
+        (4, [0.0, 0.0, 1.0]),
+        (5, [1.0, 1.0, 1.0]),
+    ]
+    .into_iter()
+    .collect();
+
+    // Two tetrahedra sharing the face (2, 3, 4) and a triangle with the opposite orientation
+    // covering the face (1, 2, 3)
+    let elements = build_elements(vec![
+        (
+            ElementType::Tet4,
+            1,
+            vec![vec![1, 2, 3, 4], vec![2, 3, 4, 5]],
+        ),
+        (ElementType::Tri3, 7, vec![vec![1, 3, 2]]),
+    ]);
+    let boundary = elements.extract_boundary(3, 10);
+
+    assert_eq!(boundary.element_blocks.len(), 1);
+    let block = &boundary.element_blocks[0];
+    assert_eq!(block.element_type, ElementType::Tri3);
+    assert_eq!((block.entity_dim, block.entity_tag), (2, 10));
+    assert_eq!(block.elements.len(), 6);
+    assert_eq!(boundary.faces.len(), 6);
+
+    let tets = &elements.element_blocks[0].elements;
+    for (element, face) in block.elements.iter().zip(&boundary.faces) {
+        assert_eq!(element.element_tag, face.boundary_element_tag);
+
+        // The normal of each boundary triangle points away from its tetrahedron
+        let tet = tets
+            .iter()
+            .find(|tet| tet.element_tag == face.element_tag)
+            .unwrap();
+        let tet_points: Vec<_> = tet.nodes.iter().map(|tag| coords[tag]).collect();
+        let points: Vec<_> = element.nodes.iter().map(|tag| coords[tag]).collect();
+        let normal = cross(sub(points[1], points[0]), sub(points[2], points[0]));
+        assert!(dot(normal, sub(centroid(&points), centroid(&tet_points))) > 0.0);
+
+        let mut vertices = element.nodes.clone();
+        vertices.sort_unstable();
+        assert_ne!(vertices, vec![2, 3, 4]);
+        if vertices == vec![1, 2, 3] {
+            assert_eq!(face.covered_by, Some((7, 3)));
+        } else {
+            assert_eq!(face.covered_by, None);
+        }
+    }
+
+    let tags: Vec<u64> = block.elements.iter().map(|e| e.element_tag).collect();
+    assert_eq!(tags, (4..=9).collect::<Vec<_>>());
+    assert_eq!(boundary.uncovered_faces().count(), 5);
+
+    let mut elements = elements;
+    boundary.append_to(&mut elements);
+    assert_eq!(elements.num_elements, 9);
+    assert_eq!((elements.min_element_tag, elements.max_element_tag), (1, 9));
+    assert_eq!(elements.element_blocks.len(), 3);
+}
+
+#[test]
+fn test_quadratic_tetrahedron_boundary() {
+    // Gmsh ordering of the edge nodes of a quadratic tetrahedron
+    let edge_nodes: HashMap<(u64, u64), u64> = vec![
+        ((1, 2), 5),
+        ((2, 3), 6),
+        ((1, 3), 7),
+        ((1, 4), 8),
+        ((3, 4), 9),
+        ((2, 4), 10),
+    ]
+    .into_iter()
+    .collect();
+    let elements = build_elements(vec![(ElementType::Tet10, 1, vec![(1..=10).collect()])]);
+    let boundary = elements.extract_boundary(3, 1);
+
+    assert_eq!(boundary.element_blocks.len(), 1);
+    let block = &boundary.element_blocks[0];
+    assert_eq!(block.element_type, ElementType::Tri6);
+    assert_eq!(block.elements.len(), 4);
+
+    // The edge nodes of each triangle are the midpoints of its edges
+    for element in &block.elements {
+        let nodes = &element.nodes;
+        for i in 0..3 {
+            let (a, b) = (nodes[i], nodes[(i + 1) % 3]);
+            assert_eq!(nodes[3 + i], edge_nodes[&(a.min(b), a.max(b))]);
+        }
+    }
+    let faces: Vec<usize> = boundary.faces.iter().map(|face| face.face).collect();
+    assert_eq!(faces, vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn test_mixed_volume_boundary() {
+    // A hexahedron with a pyramid on its top face
+    let elements = build_elements(vec![
+        (ElementType::Hex8, 1, vec![(1..=8).collect()]),
+        (ElementType::Pyr5, 1, vec![vec![5, 6, 7, 8, 9]]),
+    ]);
+    let boundary = elements.extract_boundary(3, 2);
+
+    let types: Vec<ElementType> = boundary
+        .element_blocks
+        .iter()
+        .map(|block| block.element_type)
+        .collect();
+    assert_eq!(types, vec![ElementType::Qua4, ElementType::Tri3]);
+    assert_eq!(boundary.element_blocks[0].elements.len(), 5);
+    assert_eq!(boundary.element_blocks[1].elements.len(), 4);
+    assert!(boundary.element_blocks[1]
+        .elements
+        .iter()
+        .all(|element| element.nodes.contains(&9)));
+
+    // Tags are consecutive in the order of the blocks
+    let tags: Vec<u64> = boundary
+        .faces
+        .iter()
+        .map(|f| f.boundary_element_tag)
+        .collect();
+    assert_eq!(tags, (3..=11).collect::<Vec<_>>());
+    assert!(boundary.faces[..5].iter().all(|f| f.element_tag == 1));
+    assert!(boundary.faces[5..].iter().all(|f| f.element_tag == 2));
+}
+
+#[test]
+fn test_surface_and_line_boundary() {
+    let coords: HashMap<u64, [f64; 3]> = vec![
+        (1, [0.0, 0.0, 0.0]),
+        (2, [1.0, 0.0, 0.0]),
+        (3, [2.0, 0.0, 0.0]),
+        (4, [2.0, 1.0, 0.0]),
+        (5, [1.0, 1.0, 0.0]),
+        (6, [0.0, 1.0, 0.0]),
+    ]
+    .into_iter()
+    .collect();
+
+    // Two counterclockwise quadrangles sharing the edge (2, 5) and two lines on the bottom
+    let elements = build_elements(vec![
+        (
+            ElementType::Qua4,
+            1,
+            vec![vec![1, 2, 5, 6], vec![2, 3, 4, 5]],
+        ),
+        (ElementType::Lin2, 3, vec![vec![1, 2], vec![2, 3]]),
+    ]);
+
+    let boundary = elements.extract_boundary(2, 5);
+    assert_eq!(boundary.element_blocks.len(), 1);
+    let block = &boundary.element_blocks[0];
+    assert_eq!(block.element_type, ElementType::Lin2);
+    assert_eq!((block.entity_dim, block.entity_tag), (1, 5));
+    assert_eq!(block.elements.len(), 6);
+
+    // The edges traverse the boundary counterclockwise, i.e. with the surface on their left
+    let quads = &elements.element_blocks[0].elements;
+    for (element, face) in block.elements.iter().zip(&boundary.faces) {
+        let quad = &quads[face.element_tag as usize - 1];
+        let quad_points: Vec<_> = quad.nodes.iter().map(|tag| coords[tag]).collect();
+        let points: Vec<_> = element.nodes.iter().map(|tag| coords[tag]).collect();
+        let direction = sub(points[1], points[0]);
+        let outward = [direction[1], -direction[0], 0.0];
+        assert!(dot(outward, sub(centroid(&points), centroid(&quad_points))) > 0.0);
+    }
+
+    let covered: Vec<Option<(i32, u64)>> = boundary.faces.iter().map(|f| f.covered_by).collect();
+    assert_eq!(
+        covered,
+        vec![Some((3, 3)), None, None, Some((3, 4)), None, None]
+    );
+
+    // The boundary of the lines are the two end points
+    let points = elements.extract_boundary(1, 1);
+    assert_eq!(points.element_blocks.len(), 1);
+    assert_eq!(points.element_blocks[0].element_type, ElementType::Pnt);
+    let nodes: Vec<Vec<u64>> = points.element_blocks[0]
+        .elements
+        .iter()
+        .map(|e| e.nodes.clone())
+        .collect();
+    assert_eq!(nodes, vec![vec![1], vec![3]]);
+
+    // There are no volume elements
+    assert!(elements.extract_boundary(3, 1).element_blocks.is_empty());
+}
@@ -0,0 +1,131 @@
+use mshio::mshfile::*;
+
+#[macro_use]
+mod utils;
+
+use crate::utils::*;
+
+/// A curve from (0,0,0) to (2,0,0) with its end points and a triangle attached to it
+///
+/// The declared bounding box of the curve is too short in x-direction and the box of the surface
+/// is enlarged slightly in all directions.
+static CURVE_AND_SURFACE_MSH: &str = "\
+$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$Entities
+2 1 1 0
+1 0 0 0 0
+2 2 0 0 0
+1 0 0 0 1.5 0 0 0 2 1 -2
+1 -0.001 -0.001 -0.001 2.001 1.001 0.001 0 1 1
+$EndEntities
+$Nodes
+4 4 1 4
+0 1 0 1
+1
+0 0 0
+0 2 0 1
+2
+2 0 0
+1 1 0 1
+3
+1 0 0
+2 1 0 1
+4
+1 1 0
+$EndNodes
+$Elements
+2 3 1 3
+1 1 1 2
+1 1 3
+2 3 2
+2 1 2 1
+3 1 2 4
+$EndElements
+";
+
+#[test]
+fn test_compute_entity_bounding_boxes() {
+    let msh = mshio::parse_msh_bytes(CURVE_AND_SURFACE_MSH.as_bytes()).unwrap();
+    let boxes = msh.compute_entity_bounding_boxes();
+
+    // Points have the bounding box of their node
+    assert_eq!(boxes[&(0, 2)].min, [2.0, 0.0, 0.0]);
+    assert_eq!(boxes[&(0, 2)].max, [2.0, 0.0, 0.0]);
+    // The curve only stores its interior node, the end points are referenced by its elements
+    assert_eq!(
+        boxes[&(1, 1)],
+        BoundingBox {
+            min: [0.0, 0.0, 0.0],
+            max: [2.0, 0.0, 0.0]
+        }
+    );
+    assert_eq!(
+        boxes[&(2, 1)],
+        BoundingBox {
+            min: [0.0, 0.0, 0.0],
+            max: [2.0, 1.0, 0.0]
+        }
+    );
+    assert_eq!(boxes.len(), 4);
+
+    let msh = mshio::parse_msh_bytes(ASCII_UNIT_SQUARE_MSH.as_bytes()).unwrap();
+    let boxes = msh.compute_entity_bounding_boxes();
+    assert_eq!(boxes[&(2, 1)].max, [1.0, 1.0, 0.0]);
+    assert_eq!(boxes[&(0, 1)].max, [0.0, 0.0, 0.0]);
+}
+
+#[test]
+fn test_verify_entity_bounding_boxes() {
+    let msh = mshio::parse_msh_bytes(CURVE_AND_SURFACE_MSH.as_bytes()).unwrap();
+
+    let mismatches = msh.verify_entity_bounding_boxes(0.0);
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!((mismatches[0].dim, mismatches[0].tag), (1, 1));
+    assert_eq!(mismatches[0].declared.max, [1.5, 0.0, 0.0]);
+    assert_eq!(mismatches[0].computed.max, [2.0, 0.0, 0.0]);
+
+    // The tolerance enlarges the declared boxes
+    assert_eq!(msh.verify_entity_bounding_boxes(0.4).len(), 1);
+    assert!(msh.verify_entity_bounding_boxes(0.5).is_empty());
+
+    let msh = mshio::parse_msh_bytes(ASCII_UNIT_SQUARE_MSH.as_bytes()).unwrap();
+    assert!(msh.verify_entity_bounding_boxes(0.0).is_empty());
+
+    // Files without entities have nothing to verify
+    let mut msh = msh;
+    msh.data.entities = None;
+    assert!(msh.verify_entity_bounding_boxes(0.0).is_empty());
+    assert_eq!(msh.update_entity_bounding_boxes(), 0);
+}
+
+#[test]
+fn test_update_entity_bounding_boxes() {
+    let mut msh = mshio::parse_msh_bytes(CURVE_AND_SURFACE_MSH.as_bytes()).unwrap();
+    let mut expected = msh.clone();
+
+    assert_eq!(msh.update_entity_bounding_boxes(), 2);
+    assert!(msh.verify_entity_bounding_boxes(0.0).is_empty());
+
+    // Only the bounding boxes are changed
+    let entities = expected.data.entities.as_mut().unwrap();
+    entities.curves[0].max_x = 2.0;
+    let surface = &mut entities.surfaces[0];
+    surface.min_x = 0.0;
+    surface.min_y = 0.0;
+    surface.min_z = 0.0;
+    surface.max_x = 2.0;
+    surface.max_y = 1.0;
+    surface.max_z = 0.0;
+    assert_eq!(msh, expected);
+
+    // Entities without nodes keep their bounding box
+    let mut msh = mshio::parse_msh_bytes(CURVE_AND_SURFACE_MSH.as_bytes()).unwrap();
+    msh.data.elements = None;
+    msh.data.nodes.as_mut().unwrap().node_blocks.pop();
+    assert_eq!(msh.update_entity_bounding_boxes(), 1);
+    let entities = msh.data.entities.as_ref().unwrap();
+    assert_eq!(entities.curves[0].max_x, 1.0);
+    assert_eq!(entities.surfaces[0].max_x, 2.001);
+}
@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+
+use mshio::mshfile::*;
+
+/// Two quadrangles of different surfaces with separate nodes on their common edge, a point at
+/// one end of the edge and a surface with a single node at the other end
+static SEPARATE_QUADS_MSH: &str = "\
+$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$Nodes
+4 10 1 20
+0 1 0 1
+1
+1 0 0
+2 1 0 4
+2
+3
+4
+5
+0 0 0
+1 0 0
+1 1 0
+0 1 0
+2 2 0 4
+10
+11
+12
+13
+1 0 0
+2 0 0
+2 1 0
+1 1 0
+2 3 0 1
+20
+1 1 0
+$EndNodes
+$Elements
+3 3 1 3
+0 1 15 1
+3 1
+2 1 3 1
+1 2 3 4 5
+2 2 3 1
+2 10 11 12 13
+$EndElements
+";
+
+/// Returns the tags of the nodes of all node blocks
+fn node_tags(nodes: &Nodes<u64, i32, f64>) -> Vec<Vec<u64>> {
+    let mut offset = 0;
+    nodes
+        .node_blocks
+        .iter()
+        .map(|block| {
+            let tags = match &block.node_tags {
+                Some(tags) => {
+                    let mut sorted: Vec<_> = tags.iter().map(|(&tag, &i)| (i, tag)).collect();
+                    sorted.sort_unstable();
+                    sorted.into_iter().map(|(_, tag)| tag).collect()
+                }
+                None => (0..block.nodes.len() as u64)
+                    .map(|i| nodes.min_node_tag + offset + i)
+                    .collect(),
+            };
+            offset += block.nodes.len() as u64;
+            tags
+        })
+        .collect()
+}
+
+/// Returns a MSH file with the given nodes on a single curve
+fn nodes_on_curve(coords: &[[f64; 3]]) -> MshFile<u64, i32, f64> {
+    let mut msh = String::from("$MeshFormat\n4.1 0 8\n$EndMeshFormat\n$Nodes\n");
+    msh += &format!("1 {0} 1 {0}\n1 1 0 {0}\n", coords.len());
+    for tag in 1..=coords.len() {
+        msh += &format!("{}\n", tag);
+    }
+    for [x, y, z] in coords {
+        msh += &format!("{} {} {}\n", x, y, z);
+    }
+    msh += "$EndNodes\n";
+    mshio::parse_msh_bytes(msh.as_bytes()).unwrap()
+}
+
+#[test]
+fn test_merge_nodes_across_blocks() {
+    let mut msh = mshio::parse_msh_bytes(SEPARATE_QUADS_MSH.as_bytes()).unwrap();
+    let mapping = msh.merge_duplicate_nodes(1e-8);
+
+    let expected: BTreeMap<u64, u64> = vec![(3, 1), (10, 1), (13, 4), (20, 4)]
+        .into_iter()
+        .collect();
+    assert_eq!(mapping, expected);
+
+    // The nodes of the point take precedence and the block of the third surface is dropped
+    let nodes = msh.data.nodes.as_ref().unwrap();
+    assert_eq!(nodes.num_nodes, 6);
+    assert_eq!((nodes.min_node_tag, nodes.max_node_tag), (1, 12));
+    assert_eq!(nodes.node_blocks.len(), 3);
+    assert_eq!(node_tags(nodes), vec![vec![1], vec![2, 4, 5], vec![11, 12]]);
+    assert_eq!(nodes.node_blocks[1].nodes[1].y, 1.0);
+
+    let elements = msh.data.elements.as_ref().unwrap();
+    let connectivity: Vec<Vec<u64>> = elements
+        .element_blocks
+        .iter()
+        .flat_map(|block| block.elements.iter())
+        .map(|element| element.nodes.clone())
+        .collect();
+    assert_eq!(
+        connectivity,
+        vec![vec![1], vec![2, 1, 4, 5], vec![1, 11, 12, 4]]
+    );
+    assert_eq!(elements.num_elements, 3);
+
+    // Merging again does not change anything
+    let merged = msh.clone();
+    assert!(msh.merge_duplicate_nodes(1e-8).is_empty());
+    assert_eq!(msh, merged);
+}
+
+#[test]
+fn test_merge_nodes_keeps_parametric_coordinates() {
+    let mut msh = mshio::parse_msh_bytes(SEPARATE_QUADS_MSH.as_bytes()).unwrap();
+    let block = &mut msh.data.nodes.as_mut().unwrap().node_blocks[2];
+    block.parametric = true;
+    let parametric_nodes = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]
+        .iter()
+        .map(|&[u, v]| Node { x: u, y: v, z: 0.0 })
+        .collect();
+    block.parametric_nodes = Some(parametric_nodes);
+    msh.merge_duplicate_nodes(1e-8);
+
+    // Only the parametric coordinates of the remaining nodes 11 and 12 are kept
+    let nodes = msh.data.nodes.as_ref().unwrap();
+    assert_eq!(node_tags(nodes)[2], vec![11, 12]);
+    let parametric: Vec<_> = nodes.node_blocks[2]
+        .parametric_nodes
+        .as_ref()
+        .unwrap()
+        .iter()
+        .map(|n| [n.x, n.y])
+        .collect();
+    assert_eq!(parametric, vec![[1.0, 0.0], [1.0, 1.0]]);
+    assert!(nodes.node_blocks[2].parametric);
+    assert!(!nodes.node_blocks[1].parametric);
+}
+
+#[test]
+fn test_merge_nodes_within_tolerance() {
+    let mut msh = nodes_on_curve(&[
+        [0.0, 0.0, 0.0],
+        // Close to the first node but in a different cell of the grid
+        [0.0099, -0.0001, 0.0],
+        // Within the tolerance of the second node only, which is merged itself
+        [0.0195, 0.0, 0.0],
+        [1.0, 1.0, 1.0],
+        [1.0, 1.0, 1.0 - 1e-3],
+        [1.0, 1.0, 1.02],
+    ]);
+    let mapping = msh.merge_duplicate_nodes(0.01);
+    let expected: BTreeMap<u64, u64> = vec![(2, 1), (5, 4)].into_iter().collect();
+    assert_eq!(mapping, expected);
+
+    let nodes = msh.data.nodes.as_ref().unwrap();
+    assert_eq!(nodes.num_nodes, 4);
+    assert_eq!(node_tags(nodes), vec![vec![1, 3, 4, 6]]);
+
+    // Without tolerance only exactly coincident nodes are merged
+    let mut msh = nodes_on_curve(&[[0.5, 0.5, 0.0], [0.5, 0.5, 1e-12], [0.5, 0.5, 0.0]]);
+    let mapping = msh.merge_duplicate_nodes(0.0);
+    assert_eq!(mapping.into_iter().collect::<Vec<_>>(), vec![(3, 1)]);
+
+    // Consecutive tags remain stored without tag maps
+    let nodes = msh.data.nodes.as_ref().unwrap();
+    assert_eq!((nodes.num_nodes, nodes.max_node_tag), (2, 2));
+    assert!(nodes.node_blocks[0].node_tags.is_none());
+}
+
+#[test]
+fn test_merge_nodes_without_nodes() {
+    let mut msh = mshio::parse_msh_bytes(b"$MeshFormat\n4.1 0 8\n$EndMeshFormat\n").unwrap();
+    assert!(msh.merge_duplicate_nodes(1.0).is_empty());
+    assert!(msh.data.nodes.is_none());
+}
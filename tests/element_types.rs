@@ -0,0 +1,673 @@
+use mshio::mshfile::ElementFamily;
+use mshio::mshfile::ElementType::{self, *};
+use num_traits::FromPrimitive;
+
+/// MSH type number, variant, Gmsh name, dimension, order, number of nodes, complete, serendipity
+type ElementTypeEntry = (
+    u8,
+    ElementType,
+    &'static str,
+    usize,
+    usize,
+    Option<usize>,
+    bool,
+    bool,
+);
+
+/// Element type properties according to Gmsh's `GmshDefines.h` and `ElementType.cpp`
+#[rustfmt::skip]
+static ELEMENT_TYPES: &[ElementTypeEntry] = &[
+    (1, Lin2, "Line 2", 1, 1, Some(2), true, false),
+    (2, Tri3, "Triangle 3", 2, 1, Some(3), true, false),
+    (3, Qua4, "Quadrilateral 4", 2, 1, Some(4), true, false),
+    (4, Tet4, "Tetrahedron 4", 3, 1, Some(4), true, false),
+    (5, Hex8, "Hexahedron 8", 3, 1, Some(8), true, false),
+    (6, Pri6, "Prism 6", 3, 1, Some(6), true, false),
+    (7, Pyr5, "Pyramid 5", 3, 1, Some(5), true, false),
+    (8, Lin3, "Line 3", 1, 2, Some(3), true, false),
+    (9, Tri6, "Triangle 6", 2, 2, Some(6), true, false),
+    (10, Qua9, "Quadrilateral 9", 2, 2, Some(9), true, false),
+    (11, Tet10, "Tetrahedron 10", 3, 2, Some(10), true, false),
+    (12, Hex27, "Hexahedron 27", 3, 2, Some(27), true, false),
+    (13, Pri18, "Prism 18", 3, 2, Some(18), true, false),
+    (14, Pyr14, "Pyramid 14", 3, 2, Some(14), true, false),
+    (15, Pnt, "Point", 0, 0, Some(1), true, false),
+    (16, Qua8, "Quadrilateral 8", 2, 2, Some(8), false, true),
+    (17, Hex20, "Hexahedron 20", 3, 2, Some(20), false, true),
+    (18, Pri15, "Prism 15", 3, 2, Some(15), false, true),
+    (19, Pyr13, "Pyramid 13", 3, 2, Some(13), false, true),
+    (20, Tri9, "Triangle 9", 2, 3, Some(9), false, true),
+    (21, Tri10, "Triangle 10", 2, 3, Some(10), true, false),
+    (22, Tri12, "Triangle 12", 2, 4, Some(12), false, true),
+    (23, Tri15, "Triangle 15", 2, 4, Some(15), true, false),
+    (24, Tri15i, "Triangle 15I", 2, 5, Some(15), false, true),
+    (25, Tri21, "Triangle 21", 2, 5, Some(21), true, false),
+    (26, Lin4, "Line 4", 1, 3, Some(4), true, false),
+    (27, Lin5, "Line 5", 1, 4, Some(5), true, false),
+    (28, Lin6, "Line 6", 1, 5, Some(6), true, false),
+    (29, Tet20, "Tetrahedron 20", 3, 3, Some(20), true, false),
+    (30, Tet35, "Tetrahedron 35", 3, 4, Some(35), true, false),
+    (31, Tet56, "Tetrahedron 56", 3, 5, Some(56), true, false),
+    (32, Tet22, "Tetrahedron 22", 3, 4, Some(22), false, true),
+    (33, Tet28, "Tetrahedron 28", 3, 5, Some(28), false, true),
+    (34, Polyg, "Polygon", 2, 1, None, false, false),
+    (35, Polyh, "Polyhedron", 3, 1, None, false, false),
+    (36, Qua16, "Quadrilateral 16", 2, 3, Some(16), true, false),
+    (37, Qua25, "Quadrilateral 25", 2, 4, Some(25), true, false),
+    (38, Qua36, "Quadrilateral 36", 2, 5, Some(36), true, false),
+    (39, Qua12, "Quadrilateral 12", 2, 3, Some(12), false, true),
+    (40, Qua16i, "Quadrilateral 16I", 2, 4, Some(16), false, true),
+    (41, Qua20, "Quadrilateral 20", 2, 5, Some(20), false, true),
+    (42, Tri28, "Triangle 28", 2, 6, Some(28), true, false),
+    (43, Tri36, "Triangle 36", 2, 7, Some(36), true, false),
+    (44, Tri45, "Triangle 45", 2, 8, Some(45), true, false),
+    (45, Tri55, "Triangle 55", 2, 9, Some(55), true, false),
+    (46, Tri66, "Triangle 66", 2, 10, Some(66), true, false),
+    (47, Qua49, "Quadrilateral 49", 2, 6, Some(49), true, false),
+    (48, Qua64, "Quadrilateral 64", 2, 7, Some(64), true, false),
+    (49, Qua81, "Quadrilateral 81", 2, 8, Some(81), true, false),
+    (50, Qua100, "Quadrilateral 100", 2, 9, Some(100), true, false),
+    (51, Qua121, "Quadrilateral 121", 2, 10, Some(121), true, false),
+    (52, Tri18, "Triangle 18", 2, 6, Some(18), false, true),
+    (53, Tri21i, "Triangle 21I", 2, 7, Some(21), false, true),
+    (54, Tri24, "Triangle 24", 2, 8, Some(24), false, true),
+    (55, Tri27, "Triangle 27", 2, 9, Some(27), false, true),
+    (56, Tri30, "Triangle 30", 2, 10, Some(30), false, true),
+    (57, Qua24, "Quadrilateral 24", 2, 6, Some(24), false, true),
+    (58, Qua28, "Quadrilateral 28", 2, 7, Some(28), false, true),
+    (59, Qua32, "Quadrilateral 32", 2, 8, Some(32), false, true),
+    (60, Qua36i, "Quadrilateral 36I", 2, 9, Some(36), false, true),
+    (61, Qua40, "Quadrilateral 40", 2, 10, Some(40), false, true),
+    (62, Lin7, "Line 7", 1, 6, Some(7), true, false),
+    (63, Lin8, "Line 8", 1, 7, Some(8), true, false),
+    (64, Lin9, "Line 9", 1, 8, Some(9), true, false),
+    (65, Lin10, "Line 10", 1, 9, Some(10), true, false),
+    (66, Lin11, "Line 11", 1, 10, Some(11), true, false),
+    (67, LinB, "Line Border", 1, 1, Some(3), false, false),
+    (68, TriB, "Triangle Border", 2, 1, None, false, false),
+    (69, PolygB, "Polygon Border", 2, 1, None, false, false),
+    (70, LinC, "Line Child", 1, 1, Some(3), false, false),
+    (71, Tet84, "Tetrahedron 84", 3, 6, Some(84), true, false),
+    (72, Tet120, "Tetrahedron 120", 3, 7, Some(120), true, false),
+    (73, Tet165, "Tetrahedron 165", 3, 8, Some(165), true, false),
+    (74, Tet220, "Tetrahedron 220", 3, 9, Some(220), true, false),
+    (75, Tet286, "Tetrahedron 286", 3, 10, Some(286), true, false),
+    (79, Tet34, "Tetrahedron 34", 3, 6, Some(34), false, true),
+    (80, Tet40, "Tetrahedron 40", 3, 7, Some(40), false, true),
+    (81, Tet46, "Tetrahedron 46", 3, 8, Some(46), false, true),
+    (82, Tet52, "Tetrahedron 52", 3, 9, Some(52), false, true),
+    (83, Tet58, "Tetrahedron 58", 3, 10, Some(58), false, true),
+    (84, Lin1, "Line 1", 1, 0, Some(1), true, false),
+    (85, Tri1, "Triangle 1", 2, 0, Some(1), true, false),
+    (86, Qua1, "Quadrilateral 1", 2, 0, Some(1), true, false),
+    (87, Tet1, "Tetrahedron 1", 3, 0, Some(1), true, false),
+    (88, Hex1, "Hexahedron 1", 3, 0, Some(1), true, false),
+    (89, Pri1, "Prism 1", 3, 0, Some(1), true, false),
+    (90, Pri40, "Prism 40", 3, 3, Some(40), true, false),
+    (91, Pri75, "Prism 75", 3, 4, Some(75), true, false),
+    (92, Hex64, "Hexahedron 64", 3, 3, Some(64), true, false),
+    (93, Hex125, "Hexahedron 125", 3, 4, Some(125), true, false),
+    (94, Hex216, "Hexahedron 216", 3, 5, Some(216), true, false),
+    (95, Hex343, "Hexahedron 343", 3, 6, Some(343), true, false),
+    (96, Hex512, "Hexahedron 512", 3, 7, Some(512), true, false),
+    (97, Hex729, "Hexahedron 729", 3, 8, Some(729), true, false),
+    (98, Hex1000, "Hexahedron 1000", 3, 9, Some(1000), true, false),
+    (99, Hex32, "Hexahedron 32", 3, 3, Some(32), false, true),
+    (100, Hex44, "Hexahedron 44", 3, 4, Some(44), false, true),
+    (101, Hex56, "Hexahedron 56", 3, 5, Some(56), false, true),
+    (102, Hex68, "Hexahedron 68", 3, 6, Some(68), false, true),
+    (103, Hex80, "Hexahedron 80", 3, 7, Some(80), false, true),
+    (104, Hex92, "Hexahedron 92", 3, 8, Some(92), false, true),
+    (105, Hex104, "Hexahedron 104", 3, 9, Some(104), false, true),
+    (106, Pri126, "Prism 126", 3, 5, Some(126), true, false),
+    (107, Pri196, "Prism 196", 3, 6, Some(196), true, false),
+    (108, Pri288, "Prism 288", 3, 7, Some(288), true, false),
+    (109, Pri405, "Prism 405", 3, 8, Some(405), true, false),
+    (110, Pri550, "Prism 550", 3, 9, Some(550), true, false),
+    (111, Pri24, "Prism 24", 3, 3, Some(24), false, true),
+    (112, Pri33, "Prism 33", 3, 4, Some(33), false, true),
+    (113, Pri42, "Prism 42", 3, 5, Some(42), false, true),
+    (114, Pri51, "Prism 51", 3, 6, Some(51), false, true),
+    (115, Pri60, "Prism 60", 3, 7, Some(60), false, true),
+    (116, Pri69, "Prism 69", 3, 8, Some(69), false, true),
+    (117, Pri78, "Prism 78", 3, 9, Some(78), false, true),
+    (118, Pyr30, "Pyramid 30", 3, 3, Some(30), true, false),
+    (119, Pyr55, "Pyramid 55", 3, 4, Some(55), true, false),
+    (120, Pyr91, "Pyramid 91", 3, 5, Some(91), true, false),
+    (121, Pyr140, "Pyramid 140", 3, 6, Some(140), true, false),
+    (122, Pyr204, "Pyramid 204", 3, 7, Some(204), true, false),
+    (123, Pyr285, "Pyramid 285", 3, 8, Some(285), true, false),
+    (124, Pyr385, "Pyramid 385", 3, 9, Some(385), true, false),
+    (125, Pyr21, "Pyramid 21", 3, 3, Some(21), false, true),
+    (126, Pyr29, "Pyramid 29", 3, 4, Some(29), false, true),
+    (127, Pyr37, "Pyramid 37", 3, 5, Some(37), false, true),
+    (128, Pyr45, "Pyramid 45", 3, 6, Some(45), false, true),
+    (129, Pyr53, "Pyramid 53", 3, 7, Some(53), false, true),
+    (130, Pyr61, "Pyramid 61", 3, 8, Some(61), false, true),
+    (131, Pyr69, "Pyramid 69", 3, 9, Some(69), false, true),
+    (132, Pyr1, "Pyramid 1", 3, 0, Some(1), true, false),
+    (133, PntSub, "Point Xfem", 0, 0, Some(1), false, false),
+    (134, LinSub, "Line Xfem", 1, 1, Some(2), false, false),
+    (135, TriSub, "Triangle Xfem", 2, 1, Some(3), false, false),
+    (136, TetSub, "Tetrahedron Xfem", 3, 1, Some(4), false, false),
+    (137, Tet16, "Tetrahedron 16", 3, 3, Some(16), false, true),
+    (138, TriMini, "Triangle Mini", 2, 1, Some(4), false, false),
+    (139, TetMini, "Tetrahedron Mini", 3, 1, Some(5), false, false),
+    (140, Trih4, "Trihedron 4", 3, 1, Some(4), false, false),
+];
+
+#[test]
+fn test_element_type_table() {
+    for &(msh_type, element_type, name, dim, order, num_nodes, complete, serendipity) in
+        ELEMENT_TYPES
+    {
+        assert_eq!(ElementType::from_u8(msh_type), Some(element_type));
+        assert_eq!(element_type.name(), name, "name of {:?}", element_type);
+        assert_eq!(
+            element_type.dimension(),
+            dim,
+            "dimension of {:?}",
+            element_type
+        );
+        assert_eq!(element_type.order(), order, "order of {:?}", element_type);
+        assert_eq!(
+            element_type.nodes().ok(),
+            num_nodes,
+            "nodes of {:?}",
+            element_type
+        );
+        assert_eq!(element_type.is_complete(), complete, "{:?}", element_type);
+        assert_eq!(
+            element_type.is_serendipity(),
+            serendipity,
+            "{:?}",
+            element_type
+        );
+    }
+
+    // All type numbers not listed in the table are unused
+    for msh_type in 0..=255u8 {
+        if !ELEMENT_TYPES.iter().any(|entry| entry.0 == msh_type) {
+            assert_eq!(ElementType::from_u8(msh_type), None);
+        }
+    }
+}
+
+#[test]
+fn test_element_type_node_counts() {
+    // Number of nodes of complete and serendipity Lagrange elements of the given order
+    let complete_nodes = |family, p: usize| match family {
+        ElementFamily::Line => p + 1,
+        ElementFamily::Triangle => (p + 1) * (p + 2) / 2,
+        ElementFamily::Quadrangle => (p + 1) * (p + 1),
+        ElementFamily::Tetrahedron => (p + 1) * (p + 2) * (p + 3) / 6,
+        ElementFamily::Hexahedron => (p + 1) * (p + 1) * (p + 1),
+        ElementFamily::Prism => (p + 1) * (p + 1) * (p + 2) / 2,
+        ElementFamily::Pyramid => (p + 1) * (p + 2) * (2 * p + 3) / 6,
+        _ => unreachable!(),
+    };
+    let serendipity_nodes = |family, p: usize| match family {
+        ElementFamily::Triangle => 3 + 3 * (p - 1),
+        ElementFamily::Quadrangle => 4 + 4 * (p - 1),
+        ElementFamily::Tetrahedron => 4 + 6 * (p - 1),
+        ElementFamily::Hexahedron => 8 + 12 * (p - 1),
+        ElementFamily::Prism => 6 + 9 * (p - 1),
+        ElementFamily::Pyramid => 5 + 8 * (p - 1),
+        _ => unreachable!(),
+    };
+
+    for &(_, element_type, ..) in ELEMENT_TYPES {
+        let family = element_type.family();
+        let order = element_type.order();
+        if element_type.is_complete() && order > 0 && family != ElementFamily::Point {
+            assert_eq!(
+                element_type.nodes(),
+                Ok(complete_nodes(family, order)),
+                "{:?}",
+                element_type
+            );
+        } else if element_type.is_serendipity() {
+            assert_eq!(
+                element_type.nodes(),
+                Ok(serendipity_nodes(family, order)),
+                "{:?}",
+                element_type
+            );
+        }
+    }
+}
+
+/// Asserts that the reference nodes of the element type match the expected coordinates
+fn assert_reference_nodes(element_type: ElementType, expected: &[[f64; 3]]) {
+    let nodes = element_type.reference_nodes().unwrap();
+    assert_eq!(nodes.len(), expected.len(), "{:?}", element_type);
+    for (i, (node, expected)) in nodes.iter().zip(expected).enumerate() {
+        assert!(
+            node.iter()
+                .zip(expected)
+                .all(|(a, b)| (a - b).abs() < 1e-14),
+            "{:?} node {}: {:?} != {:?}",
+            element_type,
+            i,
+            node,
+            expected
+        );
+    }
+}
+
+/// Returns whether the point lies in the (closed) reference domain of the family
+fn in_reference_domain(family: ElementFamily, [x, y, z]: [f64; 3]) -> bool {
+    let eps = 1e-14;
+    let in_interval = |v: f64| v >= -1.0 - eps && v <= 1.0 + eps;
+    let in_simplex = |coords: &[f64]| {
+        coords.iter().all(|&c| c >= -eps) && coords.iter().sum::<f64>() <= 1.0 + eps
+    };
+    match family {
+        ElementFamily::Point => [x, y, z] == [0.0, 0.0, 0.0],
+        ElementFamily::Line => in_interval(x) && y == 0.0 && z == 0.0,
+        ElementFamily::Triangle => in_simplex(&[x, y]) && z == 0.0,
+        ElementFamily::Quadrangle => in_interval(x) && in_interval(y) && z == 0.0,
+        ElementFamily::Tetrahedron => in_simplex(&[x, y, z]),
+        ElementFamily::Hexahedron => in_interval(x) && in_interval(y) && in_interval(z),
+        ElementFamily::Prism => in_simplex(&[x, y]) && in_interval(z),
+        ElementFamily::Pyramid => {
+            z >= -eps && z <= 1.0 + eps && x.abs() <= 1.0 - z + eps && y.abs() <= 1.0 - z + eps
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_reference_nodes_gmsh_ordering() {
+    // Node orderings as documented in the Gmsh reference manual ("Node ordering")
+    assert_reference_nodes(Pnt, &[[0.0, 0.0, 0.0]]);
+    assert_reference_nodes(Lin3, &[[-1.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 0.0]]);
+
+    #[rustfmt::skip]
+    let tri6 = [
+        [0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0],
+        [0.5, 0.0, 0.0], [0.5, 0.5, 0.0], [0.0, 0.5, 0.0],
+    ];
+    assert_reference_nodes(Tri3, &tri6[..3]);
+    assert_reference_nodes(Tri6, &tri6);
+
+    #[rustfmt::skip]
+    let qua9 = [
+        [-1.0, -1.0, 0.0], [1.0, -1.0, 0.0], [1.0, 1.0, 0.0], [-1.0, 1.0, 0.0],
+        [0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [-1.0, 0.0, 0.0],
+        [0.0, 0.0, 0.0],
+    ];
+    assert_reference_nodes(Qua4, &qua9[..4]);
+    assert_reference_nodes(Qua8, &qua9[..8]);
+    assert_reference_nodes(Qua9, &qua9);
+
+    #[rustfmt::skip]
+    let tet10 = [
+        [0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0],
+        [0.5, 0.0, 0.0], [0.5, 0.5, 0.0], [0.0, 0.5, 0.0],
+        [0.0, 0.0, 0.5], [0.0, 0.5, 0.5], [0.5, 0.0, 0.5],
+    ];
+    assert_reference_nodes(Tet4, &tet10[..4]);
+    assert_reference_nodes(Tet10, &tet10);
+
+    #[rustfmt::skip]
+    let hex27 = [
+        [-1.0, -1.0, -1.0], [1.0, -1.0, -1.0], [1.0, 1.0, -1.0], [-1.0, 1.0, -1.0],
+        [-1.0, -1.0, 1.0], [1.0, -1.0, 1.0], [1.0, 1.0, 1.0], [-1.0, 1.0, 1.0],
+        [0.0, -1.0, -1.0], [-1.0, 0.0, -1.0], [-1.0, -1.0, 0.0], [1.0, 0.0, -1.0],
+        [1.0, -1.0, 0.0], [0.0, 1.0, -1.0], [1.0, 1.0, 0.0], [-1.0, 1.0, 0.0],
+        [0.0, -1.0, 1.0], [-1.0, 0.0, 1.0], [1.0, 0.0, 1.0], [0.0, 1.0, 1.0],
+        [0.0, 0.0, -1.0], [0.0, -1.0, 0.0], [-1.0, 0.0, 0.0], [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0], [0.0, 0.0, 1.0],
+        [0.0, 0.0, 0.0],
+    ];
+    assert_reference_nodes(Hex8, &hex27[..8]);
+    assert_reference_nodes(Hex20, &hex27[..20]);
+    assert_reference_nodes(Hex27, &hex27);
+
+    #[rustfmt::skip]
+    let pri18 = [
+        [0.0, 0.0, -1.0], [1.0, 0.0, -1.0], [0.0, 1.0, -1.0],
+        [0.0, 0.0, 1.0], [1.0, 0.0, 1.0], [0.0, 1.0, 1.0],
+        [0.5, 0.0, -1.0], [0.0, 0.5, -1.0], [0.0, 0.0, 0.0],
+        [0.5, 0.5, -1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0],
+        [0.5, 0.0, 1.0], [0.0, 0.5, 1.0], [0.5, 0.5, 1.0],
+        [0.5, 0.0, 0.0], [0.0, 0.5, 0.0], [0.5, 0.5, 0.0],
+    ];
+    assert_reference_nodes(Pri6, &pri18[..6]);
+    assert_reference_nodes(Pri15, &pri18[..15]);
+    assert_reference_nodes(Pri18, &pri18);
+
+    #[rustfmt::skip]
+    let pyr14 = [
+        [-1.0, -1.0, 0.0], [1.0, -1.0, 0.0], [1.0, 1.0, 0.0], [-1.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0],
+        [0.0, -1.0, 0.0], [-1.0, 0.0, 0.0], [-0.5, -0.5, 0.5], [1.0, 0.0, 0.0],
+        [0.5, -0.5, 0.5], [0.0, 1.0, 0.0], [0.5, 0.5, 0.5], [-0.5, 0.5, 0.5],
+        [0.0, 0.0, 0.0],
+    ];
+    assert_reference_nodes(Pyr5, &pyr14[..5]);
+    assert_reference_nodes(Pyr13, &pyr14[..13]);
+    assert_reference_nodes(Pyr14, &pyr14);
+}
+
+#[test]
+fn test_reference_nodes_third_order() {
+    let t = 1.0 / 3.0;
+    let s = 2.0 / 3.0;
+
+    assert_reference_nodes(
+        Lin4,
+        &[
+            [-1.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [-t, 0.0, 0.0],
+            [t, 0.0, 0.0],
+        ],
+    );
+
+    // Edge nodes are ordered from the first to the second edge vertex
+    #[rustfmt::skip]
+    let tri10 = [
+        [0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0],
+        [t, 0.0, 0.0], [s, 0.0, 0.0], [s, t, 0.0], [t, s, 0.0], [0.0, s, 0.0], [0.0, t, 0.0],
+        [t, t, 0.0],
+    ];
+    assert_reference_nodes(Tri9, &tri10[..9]);
+    assert_reference_nodes(Tri10, &tri10);
+
+    // The interior nodes form a quadrangle of order 1
+    #[rustfmt::skip]
+    let qua16 = [
+        [-1.0, -1.0, 0.0], [1.0, -1.0, 0.0], [1.0, 1.0, 0.0], [-1.0, 1.0, 0.0],
+        [-t, -1.0, 0.0], [t, -1.0, 0.0], [1.0, -t, 0.0], [1.0, t, 0.0],
+        [t, 1.0, 0.0], [-t, 1.0, 0.0], [-1.0, t, 0.0], [-1.0, -t, 0.0],
+        [-t, -t, 0.0], [t, -t, 0.0], [t, t, 0.0], [-t, t, 0.0],
+    ];
+    assert_reference_nodes(Qua12, &qua16[..12]);
+    assert_reference_nodes(Qua16, &qua16);
+
+    // Edges 3-0, 3-2 and 3-1 start at the top vertex, one node per face follows the edge nodes
+    #[rustfmt::skip]
+    let tet20 = [
+        [0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0],
+        [t, 0.0, 0.0], [s, 0.0, 0.0], [s, t, 0.0], [t, s, 0.0], [0.0, s, 0.0], [0.0, t, 0.0],
+        [0.0, 0.0, s], [0.0, 0.0, t], [0.0, t, s], [0.0, s, t], [t, 0.0, s], [s, 0.0, t],
+        [t, t, 0.0], [t, 0.0, t], [0.0, t, t], [t, t, t],
+    ];
+    assert_reference_nodes(Tet16, &tet20[..16]);
+    assert_reference_nodes(Tet20, &tet20);
+}
+
+#[test]
+fn test_reference_nodes_all_types() {
+    for &(_, element_type, ..) in ELEMENT_TYPES {
+        let is_lagrange = element_type.is_complete() || element_type.is_serendipity();
+        let nodes = match element_type.reference_nodes() {
+            Some(nodes) => nodes,
+            None => {
+                assert!(!is_lagrange, "{:?}", element_type);
+                continue;
+            }
+        };
+        assert!(is_lagrange, "{:?}", element_type);
+        assert_eq!(Ok(nodes.len()), element_type.nodes(), "{:?}", element_type);
+
+        let family = element_type.family();
+        for node in nodes {
+            assert!(
+                in_reference_domain(family, *node),
+                "{:?}: {:?}",
+                element_type,
+                node
+            );
+        }
+
+        // All nodes are distinct
+        for (i, a) in nodes.iter().enumerate() {
+            for b in &nodes[..i] {
+                let distance: f64 = a.iter().zip(b).map(|(a, b)| (a - b).abs()).sum();
+                assert!(distance > 1e-10, "{:?}: duplicate {:?}", element_type, a);
+            }
+        }
+
+        // The vertices come first and serendipity elements are a prefix of the complete element
+        if element_type.order() > 0 {
+            let linear = ELEMENT_TYPES
+                .iter()
+                .map(|entry| entry.1)
+                .find(|t| t.family() == family && t.order() == 1 && t.is_complete())
+                .unwrap();
+            let vertices = linear.reference_nodes().unwrap();
+            assert_eq!(&nodes[..vertices.len()], vertices, "{:?}", element_type);
+        }
+        if element_type.is_serendipity() {
+            let complete = ELEMENT_TYPES
+                .iter()
+                .map(|entry| entry.1)
+                .find(|t| {
+                    t.family() == family && t.order() == element_type.order() && t.is_complete()
+                })
+                .unwrap();
+            let complete_nodes = complete.reference_nodes().unwrap();
+            assert_eq!(nodes, &complete_nodes[..nodes.len()], "{:?}", element_type);
+        }
+    }
+}
+
+#[test]
+fn test_topology_gmsh_ordering() {
+    assert_eq!(Lin3.edges().unwrap(), &[vec![0, 1, 2]]);
+    assert!(Lin3.faces().unwrap().is_empty());
+    assert!(Pnt.edges().unwrap().is_empty());
+
+    assert_eq!(
+        Qua9.edges().unwrap(),
+        &[vec![0, 1, 4], vec![1, 2, 5], vec![2, 3, 6], vec![3, 0, 7]]
+    );
+    assert_eq!(Qua9.faces().unwrap(), &[(0..9).collect::<Vec<_>>()]);
+    assert_eq!(Qua9.face_types().unwrap(), &[Qua9]);
+
+    assert_eq!(
+        Tet10.edges().unwrap(),
+        &[
+            vec![0, 1, 4],
+            vec![1, 2, 5],
+            vec![2, 0, 6],
+            vec![3, 0, 7],
+            vec![3, 2, 8],
+            vec![3, 1, 9]
+        ]
+    );
+    assert_eq!(
+        Tet10.faces().unwrap(),
+        &[
+            vec![0, 2, 1, 6, 5, 4],
+            vec![0, 1, 3, 4, 9, 7],
+            vec![0, 3, 2, 7, 8, 6],
+            vec![3, 1, 2, 9, 5, 8]
+        ]
+    );
+
+    assert_eq!(
+        Hex8.faces().unwrap(),
+        &[
+            vec![0, 3, 2, 1],
+            vec![0, 1, 5, 4],
+            vec![0, 4, 7, 3],
+            vec![1, 2, 6, 5],
+            vec![2, 3, 7, 6],
+            vec![4, 5, 6, 7]
+        ]
+    );
+    assert_eq!(Hex20.faces().unwrap()[0], vec![0, 3, 2, 1, 9, 13, 11, 8]);
+    assert_eq!(
+        Hex27.faces().unwrap()[0],
+        vec![0, 3, 2, 1, 9, 13, 11, 8, 20]
+    );
+    assert_eq!(Hex27.face_types().unwrap(), &[Qua9; 6]);
+
+    assert_eq!(
+        Pri6.faces().unwrap(),
+        &[
+            vec![0, 2, 1],
+            vec![3, 4, 5],
+            vec![0, 1, 4, 3],
+            vec![0, 3, 5, 2],
+            vec![1, 2, 5, 4]
+        ]
+    );
+    assert_eq!(Pri18.face_types().unwrap(), &[Tri6, Tri6, Qua9, Qua9, Qua9]);
+    assert_eq!(
+        Pri18.faces().unwrap()[2],
+        vec![0, 1, 4, 3, 6, 10, 12, 8, 15]
+    );
+
+    assert_eq!(
+        Pyr5.faces().unwrap(),
+        &[
+            vec![0, 1, 4],
+            vec![3, 0, 4],
+            vec![1, 2, 4],
+            vec![2, 3, 4],
+            vec![0, 3, 2, 1]
+        ]
+    );
+    assert_eq!(Pyr13.face_types().unwrap(), &[Tri6, Tri6, Tri6, Tri6, Qua8]);
+    assert_eq!(Tet16.face_types().unwrap(), &[Tri9; 4]);
+    assert_eq!(Tet20.face_types().unwrap(), &[Tri10; 4]);
+}
+
+#[test]
+fn test_topology_all_types() {
+    let sub = |a: [f64; 3], b: [f64; 3]| [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+    let dot = |a: [f64; 3], b: [f64; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+    let cross = |a: [f64; 3], b: [f64; 3]| {
+        [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ]
+    };
+
+    for &(_, element_type, ..) in ELEMENT_TYPES {
+        let nodes = match element_type.reference_nodes() {
+            Some(nodes) => nodes,
+            None => {
+                assert!(element_type.edges().is_none());
+                assert!(element_type.faces().is_none());
+                assert!(element_type.face_types().is_none());
+                continue;
+            }
+        };
+
+        let order = element_type.order();
+        let edges = element_type.edges().unwrap();
+        let faces = element_type.faces().unwrap();
+        let face_types = element_type.face_types().unwrap();
+        assert_eq!(faces.len(), face_types.len());
+        if order == 0 || element_type.family() == ElementFamily::Point {
+            assert!(edges.is_empty() && faces.is_empty());
+            continue;
+        }
+
+        let num_edges = match element_type.family() {
+            ElementFamily::Line => 1,
+            ElementFamily::Triangle => 3,
+            ElementFamily::Quadrangle => 4,
+            ElementFamily::Tetrahedron => 6,
+            ElementFamily::Hexahedron => 12,
+            ElementFamily::Prism => 9,
+            ElementFamily::Pyramid => 8,
+            _ => unreachable!(),
+        };
+        assert_eq!(edges.len(), num_edges, "{:?}", element_type);
+
+        // Edge nodes are equidistant on the straight edge between its vertices
+        for edge in edges {
+            assert_eq!(edge.len(), order + 1, "{:?}", element_type);
+            let [a, b] = [nodes[edge[0]], nodes[edge[1]]];
+            for (k, &node) in edge[2..].iter().enumerate() {
+                let t = (k + 1) as f64 / order as f64;
+                let expected = [0, 1, 2].map(|d| a[d] + t * (b[d] - a[d]));
+                assert!(
+                    dot(sub(nodes[node], expected), sub(nodes[node], expected)) < 1e-20,
+                    "{:?}: {:?}",
+                    element_type,
+                    edge
+                );
+            }
+        }
+
+        let is_edge = |a: usize, b: usize| {
+            edges
+                .iter()
+                .any(|e| (e[0], e[1]) == (a, b) || (e[0], e[1]) == (b, a))
+        };
+
+        let centroid = {
+            let n = nodes.len() as f64;
+            [0, 1, 2].map(|d| nodes.iter().map(|node| node[d]).sum::<f64>() / n)
+        };
+        for (face, &face_type) in faces.iter().zip(face_types) {
+            assert_eq!(Ok(face.len()), face_type.nodes(), "{:?}", element_type);
+            assert_eq!(face_type.order(), order, "{:?}", element_type);
+
+            // The edges of the face are edges of the element
+            let face_edges = face_type.edges().unwrap();
+            for face_edge in face_edges {
+                assert!(is_edge(face[face_edge[0]], face[face_edge[1]]));
+                let element_edge: Vec<_> = face_edge.iter().map(|&i| face[i]).collect();
+                assert!(
+                    edges.iter().any(|e| {
+                        *e == element_edge || {
+                            // Reversed edge: swapped vertices and reversed interior nodes
+                            let mut reversed = vec![e[1], e[0]];
+                            reversed.extend(e[2..].iter().rev());
+                            reversed == element_edge
+                        }
+                    }),
+                    "{:?}: {:?}",
+                    element_type,
+                    element_edge
+                );
+            }
+
+            // All face nodes lie in the plane of the face, the normal points outwards
+            if element_type.dimension() == 3 {
+                let v0 = nodes[face[0]];
+                let v1 = nodes[face[1]];
+                let v2 = nodes[face[face_type.edges().unwrap().len() - 1]];
+                let normal = cross(sub(v1, v0), sub(v2, v0));
+                assert!(dot(normal, sub(v0, centroid)) > 0.0, "{:?}", element_type);
+                for &node in face {
+                    assert!(dot(normal, sub(nodes[node], v0)).abs() < 1e-12);
+                }
+            }
+        }
+
+        // All nodes on the boundary of volume elements are part of a face
+        if element_type.dimension() == 3 {
+            let face_nodes: std::collections::HashSet<_> = faces.iter().flatten().collect();
+            let num_interior = match element_type.family() {
+                ElementFamily::Tetrahedron if element_type.is_complete() && order > 3 => {
+                    (order - 1) * (order - 2) * (order - 3) / 6
+                }
+                ElementFamily::Hexahedron if element_type.is_complete() => (order - 1).pow(3),
+                ElementFamily::Prism if element_type.is_complete() && order > 2 => {
+                    (order - 1) * (order - 2) / 2 * (order - 1)
+                }
+                ElementFamily::Pyramid if element_type.is_complete() && order > 2 => {
+                    (order - 2) * (order - 1) * (2 * order - 3) / 6
+                }
+                _ => 0,
+            };
+            assert_eq!(
+                face_nodes.len() + num_interior,
+                nodes.len(),
+                "{:?}",
+                element_type
+            );
+        }
+    }
+}
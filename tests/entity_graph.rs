@@ -0,0 +1,152 @@
+use mshio::mshfile::*;
+
+/// Geometry of two adjacent squares with a volume bounded by them and an isolated surface
+///
+/// Surface 1 is bounded by the curves 1 to 4, surface 2 by the curves 5, 6, 7 and the reversed
+/// curve 2. Volume 1 is bounded by surface 1 and the reversed surface 2. Surface 3 has no boundary.
+static TWO_SQUARES_MSH: &str = "\
+$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$Entities
+6 7 3 1
+1 0 0 0 0
+2 1 0 0 0
+3 1 1 0 0
+4 0 1 0 0
+5 2 0 0 0
+6 2 1 0 0
+1 0 0 0 1 0 0 0 2 1 -2
+2 1 0 0 1 1 0 0 2 2 -3
+3 0 1 0 1 1 0 0 2 3 -4
+4 0 0 0 0 1 0 0 2 4 -1
+5 1 0 0 2 0 0 0 2 2 -5
+6 2 0 0 2 1 0 0 2 5 -6
+7 1 1 0 2 1 0 0 2 6 -3
+1 0 0 0 1 1 0 0 4 1 2 3 4
+2 1 0 0 2 1 0 0 4 5 6 7 -2
+3 5 5 5 6 6 6 0 0
+1 0 0 0 2 1 0 0 2 1 -2
+$EndEntities
+";
+
+fn graph() -> EntityGraph<i32> {
+    mshio::parse_msh_bytes(TWO_SQUARES_MSH.as_bytes())
+        .unwrap()
+        .entity_graph()
+}
+
+fn tags(oriented: &[OrientedTag<i32>]) -> Vec<(i32, bool)> {
+    oriented.iter().map(|o| (o.tag, o.reversed)).collect()
+}
+
+#[test]
+fn test_entity_graph_boundary() {
+    let graph = graph();
+
+    assert!(graph.contains(2, 3));
+    assert!(!graph.contains(2, 4));
+    assert!(!graph.contains(4, 1));
+    assert_eq!(graph.entity_tags(1), (1..=7).collect::<Vec<_>>());
+
+    assert_eq!(
+        tags(graph.boundary(2, 2).unwrap()),
+        vec![(5, false), (6, false), (7, false), (2, true)]
+    );
+    assert_eq!(
+        tags(graph.boundary(3, 1).unwrap()),
+        vec![(1, false), (2, true)]
+    );
+    assert_eq!(
+        tags(graph.boundary(1, 4).unwrap()),
+        vec![(4, false), (1, true)]
+    );
+    assert!(graph.boundary(0, 1).unwrap().is_empty());
+    assert!(graph.boundary(2, 3).unwrap().is_empty());
+    assert!(graph.boundary(2, 4).is_none());
+    assert!(graph.boundary(-1, 1).is_none());
+}
+
+#[test]
+fn test_entity_graph_upward_adjacency() {
+    let graph = graph();
+
+    // The shared curve bounds both surfaces with opposite orientations
+    assert_eq!(
+        tags(graph.upward_adjacency(1, 2).unwrap()),
+        vec![(1, false), (2, true)]
+    );
+    assert_eq!(tags(graph.upward_adjacency(2, 2).unwrap()), vec![(1, true)]);
+    assert_eq!(
+        tags(graph.upward_adjacency(0, 3).unwrap()),
+        vec![(2, true), (3, false), (7, true)]
+    );
+    assert!(graph.upward_adjacency(2, 3).unwrap().is_empty());
+    assert!(graph.upward_adjacency(3, 1).unwrap().is_empty());
+    assert!(graph.upward_adjacency(0, 7).is_none());
+}
+
+#[test]
+fn test_entity_graph_closure() {
+    let graph = graph();
+
+    assert_eq!(
+        graph.closure(2, 1).unwrap(),
+        vec![
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (0, 4),
+            (1, 1),
+            (1, 2),
+            (1, 3),
+            (1, 4),
+            (2, 1)
+        ]
+    );
+
+    let closure = graph.closure(3, 1).unwrap();
+    assert_eq!(closure.len(), 6 + 7 + 2 + 1);
+    assert!(!closure.contains(&(2, 3)));
+
+    assert_eq!(graph.closure(2, 3).unwrap(), vec![(2, 3)]);
+    assert_eq!(graph.closure(0, 5).unwrap(), vec![(0, 5)]);
+    assert!(graph.closure(1, 8).is_none());
+}
+
+#[test]
+fn test_entity_graph_connected_components() {
+    let graph = graph();
+
+    assert_eq!(
+        graph.connected_components(0),
+        vec![(1..=6).collect::<Vec<_>>()]
+    );
+    assert_eq!(
+        graph.connected_components(1),
+        vec![(1..=7).collect::<Vec<_>>()]
+    );
+    assert_eq!(graph.connected_components(2), vec![vec![1, 2], vec![3]]);
+    assert_eq!(graph.connected_components(3), vec![vec![1]]);
+    assert!(graph.connected_components(4).is_empty());
+
+    // Without the shared curve, the squares are separate components
+    let msh = TWO_SQUARES_MSH.replace("4 5 6 7 -2", "4 5 6 7 -8");
+    let graph = mshio::parse_msh_bytes(msh.as_bytes())
+        .unwrap()
+        .entity_graph();
+    assert_eq!(
+        graph.connected_components(2),
+        vec![vec![1], vec![2], vec![3]]
+    );
+    assert_eq!(
+        tags(graph.upward_adjacency(1, 2).unwrap()),
+        vec![(1, false)]
+    );
+
+    // Files without entities result in an empty graph
+    let msh = mshio::parse_msh_bytes(b"$MeshFormat\n4.1 0 8\n$EndMeshFormat\n").unwrap();
+    let graph = msh.entity_graph();
+    assert!(graph.entity_tags(0).is_empty());
+    assert!(graph.connected_components(2).is_empty());
+}
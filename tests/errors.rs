@@ -129,3 +129,41 @@ $Entities
 $EndEntities\
 "
 );
+
+simple_error_test!(
+    test_invalid_node_tag_second_block,
+    MshParserErrorKind::InvalidTag,
+    "\
+$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$Nodes
+2 3 1 3
+0 1 0 1
+1
+0. 0. 0.
+2 1 0 2
+2
+x
+1. 0. 0.
+1. 1. 0.
+$EndNodes\
+"
+);
+
+simple_error_test!(
+    test_wrong_element_amount_second_block,
+    MshParserErrorKind::InvalidElementDefinition,
+    "\
+$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$Elements
+2 3 1 3
+1 1 1 1
+1 1 2
+2 1 2 2
+2 1 2 3
+$EndElements\
+"
+);
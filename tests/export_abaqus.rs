@@ -0,0 +1,188 @@
+use mshio::export::abaqus::{
+    self, abaqus_element_type, abaqus_node_ordering, AbaqusOptions, AbaqusPlanarElements,
+};
+use mshio::export::ExportError;
+use mshio::mshfile::ElementType;
+use num_traits::FromPrimitive;
+
+#[macro_use]
+mod utils;
+
+use crate::utils::*;
+
+/// A `Hex20` on volume 1 and a `Tet10` on volume 2 (both physical group 7) and a `Tri3` on surface 3
+/// (physical group 8)
+fn quadratic_volumes_msh() -> String {
+    let mut msh = String::from(
+        "\
+$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$Entities
+0 0 1 2
+3 0 0 0 1 1 1 1 8 0
+1 0 0 0 1 1 1 1 7 0
+2 0 0 0 1 1 1 1 7 0
+$EndEntities
+$Nodes
+1 30 1 30
+3 1 0 30
+",
+    );
+    for tag in 1..=30 {
+        msh.push_str(&format!("{}\n", tag));
+    }
+    for tag in 1..=30 {
+        msh.push_str(&format!("{} 0 0\n", tag));
+    }
+    msh.push_str("$EndNodes\n$Elements\n3 3 1 3\n3 1 17 1\n1");
+    for tag in 1..=20 {
+        msh.push_str(&format!(" {}", tag));
+    }
+    msh.push_str("\n3 2 11 1\n2");
+    for tag in 21..=30 {
+        msh.push_str(&format!(" {}", tag));
+    }
+    msh.push_str("\n2 3 2 1\n3 1 2 3\n$EndElements\n");
+    msh
+}
+
+fn write_inp(msh: &str, options: &AbaqusOptions) -> String {
+    let msh = mshio::parse_msh_bytes(msh.as_bytes()).unwrap();
+    let mut output = Vec::new();
+    abaqus::write_with_options(&msh, options, &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn test_abaqus_unit_square() {
+    let expected = "\
+*HEADING
+Mesh exported by mshio
+*NODE
+1, 0.0, 0.0, 0.0
+2, 1.0, 0.0, 0.0
+3, 1.0, 1.0, 0.0
+4, 0.0, 1.0, 0.0
+*ELEMENT, TYPE=S3
+10, 1, 2, 3
+20, 1, 3, 4
+*ELSET, ELSET=PHYSICAL_SURFACE_5
+10, 20
+*ELSET, ELSET=SURFACE_1
+10, 20
+*NSET, NSET=PHYSICAL_SURFACE_5
+1, 2, 3, 4
+*NSET, NSET=SURFACE_1
+1, 2, 3, 4
+";
+    let msh = mshio::parse_msh_bytes(ASCII_UNIT_SQUARE_MSH.as_bytes()).unwrap();
+    let mut output = Vec::new();
+    abaqus::write(&msh, &mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), expected);
+
+    let options = AbaqusOptions {
+        planar_elements: AbaqusPlanarElements::PlaneStrain,
+        ..Default::default()
+    };
+    let output = write_inp(ASCII_UNIT_SQUARE_MSH, &options);
+    assert!(output.contains("*ELEMENT, TYPE=CPE3\n10, 1, 2, 3\n"));
+}
+
+#[test]
+fn test_abaqus_quadratic_elements() {
+    let output = write_inp(&quadratic_volumes_msh(), &AbaqusOptions::default());
+
+    // Elements with more than 15 nodes are continued on the next line
+    assert!(output.contains(
+        "\
+*ELEMENT, TYPE=C3D20
+1, 1, 2, 3, 4, 5, 6, 7, 8, 9, 12, 14, 10, 17, 19, 20,
+18, 11, 13, 15, 16
+*ELEMENT, TYPE=C3D10
+2, 21, 22, 23, 24, 25, 26, 27, 28, 30, 29
+*ELSET, ELSET=PHYSICAL_VOLUME_7
+1, 2
+*ELSET, ELSET=VOLUME_1
+1
+*ELSET, ELSET=VOLUME_2
+2
+*NSET, NSET=PHYSICAL_SURFACE_8
+1, 2, 3
+*NSET, NSET=PHYSICAL_VOLUME_7
+1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16
+17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30
+*NSET, NSET=SURFACE_3
+1, 2, 3
+*NSET, NSET=VOLUME_1
+"
+    ));
+    // The boundary triangle is only written on request
+    assert!(!output.contains("S3"));
+    assert!(!output.contains("ELSET=SURFACE_3"));
+
+    let options = AbaqusOptions {
+        all_dimensions: true,
+        ..Default::default()
+    };
+    let output = write_inp(&quadratic_volumes_msh(), &options);
+    assert!(output.contains("*ELEMENT, TYPE=S3\n3, 1, 2, 3\n"));
+    assert!(output.contains("*ELSET, ELSET=SURFACE_3\n3\n"));
+    assert!(output.contains("*ELSET, ELSET=PHYSICAL_SURFACE_8\n3\n"));
+}
+
+#[test]
+fn test_abaqus_node_ordering() {
+    assert_eq!(
+        abaqus_node_ordering(ElementType::Hex27).unwrap(),
+        &[
+            0, 1, 2, 3, 4, 5, 6, 7, 8, 11, 13, 9, 16, 18, 19, 17, 10, 12, 14, 15, 20, 25, 21, 23,
+            24, 22, 26
+        ]
+    );
+    assert_eq!(
+        abaqus_node_ordering(ElementType::Pri15).unwrap(),
+        &[0, 1, 2, 3, 4, 5, 6, 9, 7, 12, 14, 13, 8, 10, 11]
+    );
+    assert_eq!(
+        abaqus_node_ordering(ElementType::Qua9).unwrap(),
+        &[0, 1, 2, 3, 4, 5, 6, 7, 8]
+    );
+
+    // Every supported element type has a valid node ordering
+    for element_type in (1..=u8::MAX).filter_map(ElementType::from_u8) {
+        let supported = abaqus_element_type(element_type, AbaqusPlanarElements::Shell).is_some();
+        let ordering = abaqus_node_ordering(element_type);
+        assert_eq!(supported, ordering.is_some(), "{:?}", element_type);
+        if let Some(ordering) = ordering {
+            let mut sorted = ordering.to_vec();
+            sorted.sort_unstable();
+            let n = element_type.nodes().unwrap();
+            assert_eq!(sorted, (0..n).collect::<Vec<_>>(), "{:?}", element_type);
+        }
+    }
+}
+
+#[test]
+fn test_abaqus_unsupported_elements() {
+    let mut msh = mshio::parse_msh_bytes(ASCII_UNIT_SQUARE_MSH.as_bytes()).unwrap();
+    msh.data.elements.as_mut().unwrap().element_blocks[0].element_type = ElementType::Qua9;
+    for node in 4..=9 {
+        msh.data.elements.as_mut().unwrap().element_blocks[0].elements[0]
+            .nodes
+            .push(node % 4 + 1);
+    }
+    msh.data.elements.as_mut().unwrap().element_blocks[0]
+        .elements
+        .truncate(1);
+
+    let options = AbaqusOptions {
+        planar_elements: AbaqusPlanarElements::PlaneStress,
+        ..Default::default()
+    };
+    assert!(matches!(
+        abaqus::write_with_options(&msh, &options, &mut Vec::new()),
+        Err(ExportError::UnsupportedElementType(ElementType::Qua9))
+    ));
+    assert!(abaqus::write(&msh, &mut Vec::new()).is_ok());
+}
@@ -0,0 +1,228 @@
+use mshio::export::medit::{self, MeditOptions, MeditReadError, MeditReferences};
+use mshio::export::ExportError;
+use mshio::mshfile::ElementType;
+
+#[macro_use]
+mod utils;
+
+use crate::utils::*;
+
+fn unit_square() -> mshio::MshFile<u64, i32, f64> {
+    mshio::parse_msh_bytes(ASCII_UNIT_SQUARE_MSH.as_bytes()).unwrap()
+}
+
+fn physical_tags() -> MeditOptions {
+    MeditOptions {
+        references: MeditReferences::PhysicalTags,
+    }
+}
+
+fn write_medit(msh: &mshio::MshFile<u64, i32, f64>, options: &MeditOptions) -> String {
+    let mut output = Vec::new();
+    medit::write_with_options(msh, options, &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+/// Returns the entity dimension, entity tag, element type and element nodes of all blocks
+fn element_blocks(
+    msh: &mshio::MshFile<u64, i32, f64>,
+) -> Vec<(i32, i32, ElementType, Vec<Vec<u64>>)> {
+    msh.data
+        .elements
+        .as_ref()
+        .unwrap()
+        .element_blocks
+        .iter()
+        .map(|block| {
+            let nodes = block.elements.iter().map(|e| e.nodes.clone()).collect();
+            (
+                block.entity_dim,
+                block.entity_tag,
+                block.element_type,
+                nodes,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_medit_write() {
+    let expected = "\
+MeshVersionFormatted 2
+Dimension 3
+Vertices
+4
+0.0 0.0 0.0 1
+1.0 0.0 0.0 1
+1.0 1.0 0.0 1
+0.0 1.0 0.0 1
+Triangles
+2
+1 2 3 1
+1 3 4 1
+End
+";
+    assert_eq!(
+        write_medit(&unit_square(), &MeditOptions::default()),
+        expected
+    );
+
+    let mut output = Vec::new();
+    medit::write(&unit_square(), &mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), expected);
+
+    // The first node is not on an entity with physical groups
+    let expected = "\
+MeshVersionFormatted 2
+Dimension 3
+Vertices
+4
+0.0 0.0 0.0 0
+1.0 0.0 0.0 5
+1.0 1.0 0.0 5
+0.0 1.0 0.0 5
+Triangles
+2
+1 2 3 5
+1 3 4 5
+End
+";
+    assert_eq!(write_medit(&unit_square(), &physical_tags()), expected);
+}
+
+#[test]
+fn test_medit_round_trip() {
+    let output = write_medit(&unit_square(), &physical_tags());
+    let msh = medit::read_with_options(output.as_bytes(), &physical_tags()).unwrap();
+
+    assert_eq!(msh.total_node_count(), 4);
+    assert_eq!(msh.total_element_count(), 2);
+    assert_eq!(
+        element_blocks(&msh),
+        vec![(2, 5, ElementType::Tri3, vec![vec![1, 2, 3], vec![1, 3, 4]])]
+    );
+
+    let entities = msh.data.entities.as_ref().unwrap();
+    assert_eq!(entities.surfaces.len(), 1);
+    let surface = &entities.surfaces[0];
+    assert_eq!(surface.tag, 5);
+    assert_eq!(surface.physical_tags, vec![5]);
+    assert_eq!(
+        [surface.min_x, surface.min_y, surface.max_x, surface.max_y],
+        [0.0, 0.0, 1.0, 1.0]
+    );
+
+    // Writing the imported mesh again results in the same file (except for the vertex references)
+    let output_again = write_medit(&msh, &physical_tags());
+    assert_eq!(
+        output_again,
+        output.replace("0.0 0.0 0.0 0\n", "0.0 0.0 0.0 5\n")
+    );
+}
+
+#[test]
+fn test_medit_read() {
+    let mesh = "\
+# Comments are ignored
+MeshVersionFormatted 1
+dimension 2
+Vertices 5
+0 0 1   1 0 1
+1 1 1
+0 1 1
+2 0 0 # a comment
+Corners
+2 1 2
+Edges
+3
+1 2 0
+2 3 4
+3 4 0
+Triangles
+1
+2 5 3 4
+Quadrilaterals
+1
+1 2 3 4 3
+End
+";
+    let msh = medit::read(mesh.as_bytes()).unwrap();
+    assert_eq!(msh.total_node_count(), 5);
+    let nodes = &msh.data.nodes.as_ref().unwrap().node_blocks[0].nodes;
+    assert_eq!([nodes[4].x, nodes[4].y, nodes[4].z], [2.0, 0.0, 0.0]);
+
+    // Elements with reference zero are assigned to a new entity
+    assert_eq!(
+        element_blocks(&msh),
+        vec![
+            (1, 5, ElementType::Lin2, vec![vec![1, 2], vec![3, 4]]),
+            (1, 4, ElementType::Lin2, vec![vec![2, 3]]),
+            (2, 4, ElementType::Tri3, vec![vec![2, 5, 3]]),
+            (2, 3, ElementType::Qua4, vec![vec![1, 2, 3, 4]]),
+        ]
+    );
+    let elements = msh.data.elements.as_ref().unwrap();
+    assert_eq!(elements.max_element_tag, 5);
+    assert_eq!(elements.element_blocks[3].elements[0].element_tag, 5);
+
+    let entities = msh.data.entities.as_ref().unwrap();
+    let curve_tags: Vec<_> = entities.curves.iter().map(|c| c.tag).collect();
+    assert_eq!(curve_tags, vec![5, 4]);
+    let surface_tags: Vec<_> = entities.surfaces.iter().map(|s| s.tag).collect();
+    assert_eq!(surface_tags, vec![4, 3]);
+    assert!(entities.curves[0].physical_tags.is_empty());
+
+    // The nodes are stored on the first surface
+    let block = &msh.data.nodes.as_ref().unwrap().node_blocks[0];
+    assert_eq!((block.entity_dim, block.entity_tag), (2, 4));
+
+    let msh = medit::read_with_options(mesh.as_bytes(), &physical_tags()).unwrap();
+    let entities = msh.data.entities.as_ref().unwrap();
+    assert!(entities.curves[0].physical_tags.is_empty());
+    assert_eq!(entities.curves[1].physical_tags, vec![4]);
+}
+
+#[test]
+fn test_medit_errors() {
+    let read_error = |mesh: &str| match medit::read(mesh.as_bytes()) {
+        Err(MeditReadError::InvalidFile { line, message }) => (line, message),
+        other => panic!("Unexpected result {:?}", other),
+    };
+
+    let (line, message) =
+        read_error("Dimension 3\nVertices\n1\n0 0 0 0\nTriangles\n1\n1 1 2 0\nEnd\n");
+    assert_eq!(line, 7);
+    assert_eq!(message, "Invalid vertex index 2");
+
+    let (line, message) = read_error("Dimension 3\nPrisms\n0\nEnd\n");
+    assert_eq!(line, 2);
+    assert_eq!(message, "Unsupported keyword 'Prisms'");
+
+    let (line, message) = read_error("Dimension 3\nVertices\n1\n0 0 0\n");
+    assert_eq!(line, 4);
+    assert_eq!(message, "Unexpected end of file, expected vertex reference");
+
+    let (line, _) = read_error("Dimension 4\n");
+    assert_eq!(line, 1);
+
+    let mut msh = unit_square();
+    msh.data.elements.as_mut().unwrap().element_blocks[0].element_type = ElementType::Tri6;
+    assert!(matches!(
+        medit::write(&msh, &mut Vec::new()),
+        Err(ExportError::UnsupportedElementType(ElementType::Tri6))
+    ));
+
+    // Entities of several physical groups cannot be represented by a single reference
+    let mut msh = unit_square();
+    let entities = msh.data.entities.as_mut().unwrap();
+    entities.surfaces[0].physical_tags = vec![5, 7];
+    assert!(medit::write(&msh, &mut Vec::new()).is_ok());
+    assert!(matches!(
+        medit::write_with_options(&msh, &physical_tags(), &mut Vec::new()),
+        Err(ExportError::MultiplePhysicalTags {
+            entity_dim: 2,
+            entity_tag: 1,
+            num_physical_tags: 2,
+        })
+    ));
+}
@@ -0,0 +1,209 @@
+use std::convert::TryInto;
+
+use mshio::export::obj::{self, ObjOptions};
+use mshio::export::stl::{self, StlOptions};
+use mshio::export::{Encoding, Selection};
+
+/// Two surfaces with a quadratic triangle (physical group 5) and a quadrangle (physical group 7),
+/// a line element and a node that is not used by any surface element
+static TWO_SURFACES_MSH: &str = "\
+$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$Entities
+0 0 2 0
+1 0 0 0 1 1 0 1 5 0
+2 1 0 0 2 1 0 1 7 0
+$EndEntities
+$Nodes
+1 10 1 10
+2 1 0 10
+1
+2
+3
+4
+5
+6
+7
+8
+9
+10
+0 0 0
+1 0 0
+1 1 0
+5 5 5
+0.5 0 0
+1 0.5 0
+0.5 0.5 0
+2 0 0
+2 1 0
+0 1 0
+$EndNodes
+$Elements
+3 3 1 3
+2 1 9 1
+1 1 2 3 5 6 7
+2 2 3 1
+2 2 8 9 3
+1 1 1 1
+3 1 2
+$EndElements
+";
+
+fn two_surfaces() -> mshio::MshFile<u64, i32, f64> {
+    mshio::parse_msh_bytes(TWO_SURFACES_MSH.as_bytes()).unwrap()
+}
+
+fn write_obj(selection: Selection) -> String {
+    let mut output = Vec::new();
+    obj::write_with_options(&two_surfaces(), &ObjOptions { selection }, &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+fn write_stl(encoding: Encoding, selection: Selection) -> Vec<u8> {
+    let options = StlOptions {
+        encoding,
+        selection,
+    };
+    let mut output = Vec::new();
+    stl::write_with_options(&two_surfaces(), &options, &mut output).unwrap();
+    output
+}
+
+#[test]
+fn test_obj_all_surfaces() {
+    let expected = "\
+# Surface mesh exported by mshio
+v 0.0 0.0 0.0
+v 1.0 0.0 0.0
+v 1.0 1.0 0.0
+v 2.0 0.0 0.0
+v 2.0 1.0 0.0
+g surface_1
+f 1 2 3
+g surface_2
+f 2 4 5 3
+";
+    assert_eq!(write_obj(Selection::All), expected);
+
+    let mut output = Vec::new();
+    obj::write(&two_surfaces(), &mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), expected);
+}
+
+#[test]
+fn test_obj_selection() {
+    let expected = "\
+# Surface mesh exported by mshio
+v 1.0 0.0 0.0
+v 1.0 1.0 0.0
+v 2.0 0.0 0.0
+v 2.0 1.0 0.0
+g surface_2
+f 1 3 4 2
+";
+    assert_eq!(write_obj(Selection::PhysicalGroups(vec![7])), expected);
+    assert_eq!(write_obj(Selection::Entities(vec![2])), expected);
+
+    let empty = "# Surface mesh exported by mshio\n";
+    assert_eq!(write_obj(Selection::PhysicalGroups(vec![1])), empty);
+    assert_eq!(write_obj(Selection::Entities(vec![3])), empty);
+}
+
+#[test]
+fn test_stl_ascii() {
+    let output =
+        String::from_utf8(write_stl(Encoding::Ascii, Selection::Entities(vec![1]))).unwrap();
+    let expected = "\
+solid mshio
+  facet normal 0e0 0e0 1e0
+    outer loop
+      vertex 0e0 0e0 0e0
+      vertex 1e0 0e0 0e0
+      vertex 1e0 1e0 0e0
+    endloop
+  endfacet
+endsolid mshio
+";
+    assert_eq!(output, expected);
+
+    // The quadrangle is split into two triangles
+    let output = String::from_utf8(write_stl(Encoding::Ascii, Selection::All)).unwrap();
+    assert_eq!(output.matches("facet normal 0e0 0e0 1e0").count(), 3);
+    assert!(output.contains(
+        "      vertex 1e0 0e0 0e0\n      vertex 2e0 1e0 0e0\n      vertex 1e0 1e0 0e0\n"
+    ));
+
+    let mut default_output = Vec::new();
+    stl::write(&two_surfaces(), &mut default_output).unwrap();
+    assert_eq!(String::from_utf8(default_output).unwrap(), output);
+}
+
+#[test]
+fn test_stl_binary() {
+    let output = write_stl(Encoding::Binary, Selection::PhysicalGroups(vec![5, 7]));
+    assert_eq!(output.len(), 84 + 3 * 50);
+    assert!(output.starts_with(b"Binary STL exported by mshio"));
+    assert_eq!(u32::from_le_bytes(output[80..84].try_into().unwrap()), 3);
+
+    let triangles: Vec<Vec<f32>> = output[84..]
+        .chunks(50)
+        .map(|triangle| {
+            assert_eq!(&triangle[48..], &[0, 0]);
+            triangle[0..48]
+                .chunks(4)
+                .map(|x| f32::from_le_bytes(x.try_into().unwrap()))
+                .collect()
+        })
+        .collect();
+
+    let expected = [
+        [0., 0., 1., 0., 0., 0., 1., 0., 0., 1., 1., 0.],
+        [0., 0., 1., 1., 0., 0., 2., 0., 0., 2., 1., 0.],
+        [0., 0., 1., 1., 0., 0., 2., 1., 0., 1., 1., 0.],
+    ];
+    for (triangle, expected) in triangles.iter().zip(&expected) {
+        assert_eq!(triangle.as_slice(), expected);
+    }
+}
+
+#[test]
+fn test_stl_normals() {
+    // A single triangle in the plane x + y + z = 1 with its nodes in reverse order
+    let msh = "\
+$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$Nodes
+1 3 1 3
+2 1 0 3
+1
+2
+3
+0 0 1
+0 1 0
+1 0 0
+$EndNodes
+$Elements
+1 1 1 1
+2 1 2 1
+1 1 2 3
+$EndElements
+";
+    let msh = mshio::parse_msh_bytes(msh.as_bytes()).unwrap();
+    let options = StlOptions {
+        encoding: Encoding::Binary,
+        ..Default::default()
+    };
+    let mut output = Vec::new();
+    stl::write_with_options(&msh, &options, &mut output).unwrap();
+
+    let normal: Vec<f32> = output[84..96]
+        .chunks(4)
+        .map(|x| f32::from_le_bytes(x.try_into().unwrap()))
+        .collect();
+    let expected = -1.0 / 3f32.sqrt();
+    for n in normal {
+        assert!((n - expected).abs() < 1e-6);
+    }
+}
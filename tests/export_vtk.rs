@@ -0,0 +1,409 @@
+use std::collections::HashMap;
+use std::convert::TryInto;
+
+use mshio::export::vtk::{self, DataField, VtkOptions};
+use mshio::export::{vtu, Encoding, ExportError};
+use mshio::mshfile::ElementType;
+
+#[macro_use]
+mod utils;
+
+use crate::utils::*;
+
+fn unit_square() -> mshio::MshFile<u64, i32, f64> {
+    mshio::parse_msh_bytes(ASCII_UNIT_SQUARE_MSH.as_bytes()).unwrap()
+}
+
+fn binary_options() -> VtkOptions {
+    VtkOptions {
+        encoding: Encoding::Binary,
+        ..Default::default()
+    }
+}
+
+/// Point and cell data fields on some of the nodes and elements of the unit square
+fn data_fields() -> VtkOptions {
+    VtkOptions {
+        point_data: vec![DataField {
+            name: "displacement".to_string(),
+            num_components: 3,
+            values: vec![(1, vec![0.5, 0.0, 0.0]), (3, vec![0.0, -1.5, 1e-20])]
+                .into_iter()
+                .collect(),
+        }],
+        cell_data: vec![DataField {
+            name: "von mises".to_string(),
+            num_components: 1,
+            values: vec![(20, vec![1.25])].into_iter().collect(),
+        }],
+        ..Default::default()
+    }
+}
+
+/// Returns the child elements of the XML node
+fn child_elements<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+) -> Vec<roxmltree::Node<'a, 'input>> {
+    node.children().filter(|n| n.is_element()).collect()
+}
+
+/// Returns the values of all data arrays of a VTU file by their name
+///
+/// Checks that the file is a valid unstructured grid and that the arrays have the expected size.
+/// The arrays of binary files are decoded from the appended data section.
+fn read_vtu(vtu: &[u8]) -> HashMap<String, Vec<f64>> {
+    // The appended raw data is not valid XML, split it off before parsing
+    let appended_tag = b"<AppendedData encoding=\"raw\">\n_";
+    let (xml, appended) = match vtu
+        .windows(appended_tag.len())
+        .position(|w| w == appended_tag)
+    {
+        Some(i) => {
+            let end_tag = b"\n  </AppendedData>\n</VTKFile>\n";
+            assert!(vtu.ends_with(end_tag));
+            let mut xml = vtu[..i].to_vec();
+            xml.extend_from_slice(b"</VTKFile>");
+            let appended = &vtu[i + appended_tag.len()..vtu.len() - end_tag.len()];
+            (String::from_utf8(xml).unwrap(), Some(appended))
+        }
+        None => (String::from_utf8(vtu.to_vec()).unwrap(), None),
+    };
+
+    let document = roxmltree::Document::parse(&xml).unwrap();
+    let root = document.root_element();
+    assert_eq!(root.tag_name().name(), "VTKFile");
+    assert_eq!(root.attribute("type"), Some("UnstructuredGrid"));
+    assert_eq!(root.attribute("byte_order"), Some("LittleEndian"));
+    assert_eq!(root.attribute("header_type"), Some("UInt64"));
+
+    let grid = child_elements(root);
+    assert_eq!(grid.len(), 1);
+    assert_eq!(grid[0].tag_name().name(), "UnstructuredGrid");
+    let pieces = child_elements(grid[0]);
+    assert_eq!(pieces.len(), 1);
+    let piece = pieces[0];
+    assert_eq!(piece.tag_name().name(), "Piece");
+    let num_points: usize = piece.attribute("NumberOfPoints").unwrap().parse().unwrap();
+    let num_cells: usize = piece.attribute("NumberOfCells").unwrap().parse().unwrap();
+
+    let sections = child_elements(piece);
+    let section_names: Vec<_> = sections.iter().map(|n| n.tag_name().name()).collect();
+    assert_eq!(section_names, ["PointData", "CellData", "Points", "Cells"]);
+
+    let mut arrays = HashMap::new();
+    for section in sections {
+        for array in child_elements(section) {
+            assert_eq!(array.tag_name().name(), "DataArray");
+            let name = array.attribute("Name").unwrap().to_string();
+            let num_components: usize = array
+                .attribute("NumberOfComponents")
+                .unwrap()
+                .parse()
+                .unwrap();
+            let type_name = array.attribute("type").unwrap();
+
+            let values: Vec<f64> = match array.attribute("format").unwrap() {
+                "ascii" => array
+                    .text()
+                    .unwrap()
+                    .split_whitespace()
+                    .map(|v| v.parse().unwrap())
+                    .collect(),
+                "appended" => {
+                    let appended = appended.expect("Appended data section is missing");
+                    let offset: usize = array.attribute("offset").unwrap().parse().unwrap();
+                    let header = &appended[offset..offset + 8];
+                    let num_bytes = u64::from_le_bytes(header.try_into().unwrap()) as usize;
+                    let data = &appended[offset + 8..offset + 8 + num_bytes];
+                    match type_name {
+                        "Float64" => data
+                            .chunks(8)
+                            .map(|b| f64::from_le_bytes(b.try_into().unwrap()))
+                            .collect(),
+                        "UInt64" => data
+                            .chunks(8)
+                            .map(|b| u64::from_le_bytes(b.try_into().unwrap()) as f64)
+                            .collect(),
+                        "Int64" => data
+                            .chunks(8)
+                            .map(|b| i64::from_le_bytes(b.try_into().unwrap()) as f64)
+                            .collect(),
+                        "Int32" => data
+                            .chunks(4)
+                            .map(|b| i32::from_le_bytes(b.try_into().unwrap()) as f64)
+                            .collect(),
+                        "UInt8" => data.iter().map(|&b| b as f64).collect(),
+                        _ => panic!("Unexpected data type {}", type_name),
+                    }
+                }
+                format => panic!("Unexpected format {}", format),
+            };
+
+            let expected_len = match (section.tag_name().name(), name.as_str()) {
+                ("PointData", _) | ("Points", _) => num_points * num_components,
+                ("CellData", _) => num_cells * num_components,
+                ("Cells", "offsets") | ("Cells", "types") => num_cells,
+                ("Cells", "connectivity") => values.len(),
+                _ => panic!("Unexpected data array {}", name),
+            };
+            assert_eq!(values.len(), expected_len, "{}", name);
+            arrays.insert(name, values);
+        }
+    }
+
+    arrays
+}
+
+#[test]
+fn test_vtu_ascii() {
+    let mut output = Vec::new();
+    vtu::write(&unit_square(), &mut output).unwrap();
+    let arrays = read_vtu(&output);
+
+    assert_eq!(
+        arrays["Points"],
+        [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0]
+    );
+    assert_eq!(arrays["connectivity"], [0.0, 1.0, 2.0, 0.0, 2.0, 3.0]);
+    assert_eq!(arrays["offsets"], [3.0, 6.0]);
+    assert_eq!(arrays["types"], [5.0, 5.0]);
+    assert_eq!(arrays["node_tag"], [1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(arrays["element_tag"], [10.0, 20.0]);
+    assert_eq!(arrays["entity_dim"], [2.0, 2.0]);
+    assert_eq!(arrays["entity_tag"], [1.0, 1.0]);
+    assert_eq!(arrays["physical_tag"], [5.0, 5.0]);
+}
+
+#[test]
+fn test_vtu_binary_matches_ascii() {
+    for options in [VtkOptions::default(), data_fields()] {
+        let mut ascii = Vec::new();
+        vtu::write_with_options(&unit_square(), &options, &mut ascii).unwrap();
+
+        let binary_options = VtkOptions {
+            encoding: Encoding::Binary,
+            ..options
+        };
+        let mut binary = Vec::new();
+        vtu::write_with_options(&unit_square(), &binary_options, &mut binary).unwrap();
+
+        let ascii = read_vtu(&ascii);
+        let binary = read_vtu(&binary);
+        assert_eq!(ascii.len(), binary.len());
+        for (name, values) in ascii {
+            let binary_values = &binary[&name];
+            assert_eq!(values.len(), binary_values.len());
+            for (a, b) in values.iter().zip(binary_values) {
+                assert!(a == b || (a.is_nan() && b.is_nan()), "{}", name);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_vtu_data_fields() {
+    let mut output = Vec::new();
+    vtu::write_with_options(&unit_square(), &data_fields(), &mut output).unwrap();
+
+    let output_str = String::from_utf8(output.clone()).unwrap();
+    assert!(output_str.contains("Name=\"von mises\" NumberOfComponents=\"1\""));
+
+    let arrays = read_vtu(&output);
+    let displacement = &arrays["displacement"];
+    assert_eq!(&displacement[0..3], &[0.5, 0.0, 0.0]);
+    assert!(displacement[3..6].iter().all(|v| v.is_nan()));
+    assert_eq!(&displacement[6..9], &[0.0, -1.5, 1e-20]);
+    assert!(displacement[9..12].iter().all(|v| v.is_nan()));
+
+    let von_mises = &arrays["von mises"];
+    assert!(von_mises[0].is_nan());
+    assert_eq!(von_mises[1], 1.25);
+}
+
+#[test]
+fn test_vtk_legacy_ascii() {
+    let mut output = Vec::new();
+    vtk::write(&unit_square(), &mut output).unwrap();
+
+    let expected = "\
+# vtk DataFile Version 2.0
+Unstructured grid exported by mshio
+ASCII
+DATASET UNSTRUCTURED_GRID
+POINTS 4 double
+0.0 0.0 0.0
+1.0 0.0 0.0
+1.0 1.0 0.0
+0.0 1.0 0.0
+CELLS 2 8
+3 0 1 2
+3 0 2 3
+CELL_TYPES 2
+5
+5
+CELL_DATA 2
+FIELD FieldData 4
+element_tag 1 2 vtktypeuint64
+10
+20
+entity_dim 1 2 int
+2
+2
+entity_tag 1 2 int
+1
+1
+physical_tag 1 2 int
+5
+5
+POINT_DATA 4
+FIELD FieldData 1
+node_tag 1 4 vtktypeuint64
+1
+2
+3
+4
+";
+    assert_eq!(String::from_utf8(output).unwrap(), expected);
+}
+
+#[test]
+fn test_physical_tags_of_several_groups() {
+    let mut msh = unit_square();
+    let entities = msh.data.entities.as_mut().unwrap();
+    entities.surfaces[0].physical_tags = vec![5, 7];
+
+    let mut output = Vec::new();
+    vtu::write(&msh, &mut output).unwrap();
+    assert_eq!(read_vtu(&output)["physical_tag"], [5.0, 7.0, 5.0, 7.0]);
+
+    let mut output = Vec::new();
+    vtk::write(&msh, &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("physical_tag 2 2 int\n5 7\n5 7\n"));
+}
+
+#[test]
+fn test_vtk_legacy_binary() {
+    let mut output = Vec::new();
+    vtk::write_with_options(&unit_square(), &binary_options(), &mut output).unwrap();
+
+    let header = "# vtk DataFile Version 2.0\nUnstructured grid exported by mshio\nBINARY\nDATASET UNSTRUCTURED_GRID\nPOINTS 4 double\n";
+    assert!(output.starts_with(header.as_bytes()));
+
+    // Points are stored as big endian doubles
+    let points: Vec<f64> = output[header.len()..header.len() + 12 * 8]
+        .chunks(8)
+        .map(|b| f64::from_be_bytes(b.try_into().unwrap()))
+        .collect();
+    assert_eq!(
+        points,
+        [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0]
+    );
+
+    // Cells are stored as big endian 32 bit integers
+    let cells_header = b"\nCELLS 2 8\n";
+    let begin = header.len() + 12 * 8;
+    assert_eq!(&output[begin..begin + cells_header.len()], cells_header);
+    let cells: Vec<i32> = output[begin + cells_header.len()..begin + cells_header.len() + 8 * 4]
+        .chunks(4)
+        .map(|b| i32::from_be_bytes(b.try_into().unwrap()))
+        .collect();
+    assert_eq!(cells, [3, 0, 1, 2, 3, 0, 2, 3]);
+}
+
+#[test]
+fn test_vtk_legacy_data_fields() {
+    let mut output = Vec::new();
+    vtk::write_with_options(&unit_square(), &data_fields(), &mut output).unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("FIELD FieldData 5\n"));
+    assert!(output.contains("von_mises 1 2 double\nnan\n1.25\n"));
+    assert!(output.contains(
+        "displacement 3 4 double\n0.5 0.0 0.0\nnan nan nan\n0.0 -1.5 1e-20\nnan nan nan\n"
+    ));
+}
+
+#[test]
+fn test_export_errors() {
+    let mut msh = unit_square();
+    msh.data.elements.as_mut().unwrap().element_blocks[0].element_type = ElementType::Tri9;
+    assert!(matches!(
+        vtu::write(&msh, Vec::new()),
+        Err(ExportError::UnsupportedElementType(ElementType::Tri9))
+    ));
+
+    let mut msh = unit_square();
+    msh.data.elements.as_mut().unwrap().element_blocks[0].elements[1].nodes[2] = 99;
+    assert!(matches!(
+        vtk::write(&msh, Vec::new()),
+        Err(ExportError::UnknownNode {
+            element_tag: 20,
+            node_tag: 99
+        })
+    ));
+
+    let mut options = data_fields();
+    options.cell_data[0].values.insert(10, vec![1.0, 2.0]);
+    assert!(matches!(
+        vtu::write_with_options(&unit_square(), &options, Vec::new()),
+        Err(ExportError::InvalidDataField {
+            tag: 10,
+            len: 2,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_vtu_sparse_tags_and_node_ordering() {
+    let msh = "\
+$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$Nodes
+1 10 10 100
+3 1 0 10
+10
+20
+30
+40
+50
+60
+70
+80
+90
+100
+0 0 0
+1 0 0
+0 1 0
+0 0 1
+0.5 0 0
+0.5 0.5 0
+0 0.5 0
+0 0 0.5
+0 0.5 0.5
+0.5 0 0.5
+$EndNodes
+$Elements
+1 1 7 7
+3 1 11 1
+7 10 20 30 40 50 60 70 80 90 100
+$EndElements
+";
+    let msh = mshio::parse_msh_bytes(msh.as_bytes()).unwrap();
+
+    let mut output = Vec::new();
+    vtu::write(&msh, &mut output).unwrap();
+    let arrays = read_vtu(&output);
+
+    let node_tags: Vec<_> = (1..=10).map(|i| (10 * i) as f64).collect();
+    assert_eq!(arrays["node_tag"], node_tags);
+    assert_eq!(
+        arrays["connectivity"],
+        [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 9.0, 8.0]
+    );
+    assert_eq!(arrays["types"], [24.0]);
+    assert_eq!(arrays["element_tag"], [7.0]);
+    assert_eq!(arrays["physical_tag"], [0.0]);
+}
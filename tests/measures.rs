@@ -0,0 +1,402 @@
+use mshio::geometry::measures::*;
+use mshio::mshfile::*;
+
+#[macro_use]
+mod utils;
+
+use crate::utils::*;
+
+fn assert_close(value: f64, expected: f64, tolerance: f64) {
+    assert!(
+        (value - expected).abs() < tolerance,
+        "{} is not close to {}",
+        value,
+        expected
+    );
+}
+
+fn assert_close_vec(value: [f64; 3], expected: [f64; 3], tolerance: f64) {
+    for k in 0..3 {
+        assert_close(value[k], expected[k], tolerance);
+    }
+}
+
+/// Maps the reference nodes of the element type with the given linear geometry mapping
+fn mapped_element(
+    element_type: ElementType,
+    linear_type: ElementType,
+    vertices: &[[f64; 3]],
+) -> Vec<[f64; 3]> {
+    element_type
+        .reference_nodes()
+        .unwrap()
+        .iter()
+        .map(|&xi| {
+            let weights = linear_type.shape_functions(xi).unwrap();
+            let mut x = [0.0; 3];
+            for (w, v) in weights.iter().zip(vertices) {
+                for k in 0..3 {
+                    x[k] += w * v[k];
+                }
+            }
+            x
+        })
+        .collect()
+}
+
+#[test]
+fn test_linear_elements() {
+    let point = [[1.0, 2.0, 3.0]];
+    assert_eq!(measure(ElementType::Pnt, &point), Some(0.0));
+    assert_eq!(centroid(ElementType::Pnt, &point), Some([1.0, 2.0, 3.0]));
+
+    let line = [[1.0, 1.0, 1.0], [4.0, 5.0, 1.0]];
+    assert_eq!(measure(ElementType::Lin2, &line), Some(5.0));
+    assert_eq!(centroid(ElementType::Lin2, &line), Some([2.5, 3.0, 1.0]));
+    assert_eq!(normal(ElementType::Lin2, &line), None);
+
+    let quadrangle = [
+        [0.0, 0.0, 0.0],
+        [2.0, 0.0, 0.0],
+        [3.0, 1.0, 0.0],
+        [0.0, 1.0, 0.0],
+    ];
+    assert_close(measure(ElementType::Qua4, &quadrangle).unwrap(), 2.5, 1e-14);
+    assert_close_vec(
+        centroid(ElementType::Qua4, &quadrangle).unwrap(),
+        [19.0 / 15.0, 8.0 / 15.0, 0.0],
+        1e-14,
+    );
+
+    // A parallelepiped spanned by (1, 0, 0), (1, 2, 0) and (0, 1, 3)
+    let hexahedron = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [2.0, 2.0, 0.0],
+        [1.0, 2.0, 0.0],
+        [0.0, 1.0, 3.0],
+        [1.0, 1.0, 3.0],
+        [2.0, 3.0, 3.0],
+        [1.0, 3.0, 3.0],
+    ];
+    assert_close(measure(ElementType::Hex8, &hexahedron).unwrap(), 6.0, 1e-13);
+    assert_close_vec(
+        centroid(ElementType::Hex8, &hexahedron).unwrap(),
+        [1.0, 1.5, 1.5],
+        1e-13,
+    );
+
+    let prism = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 2.0],
+        [1.0, 0.0, 2.0],
+        [0.0, 1.0, 2.0],
+    ];
+    assert_close(measure(ElementType::Pri6, &prism).unwrap(), 1.0, 1e-14);
+    assert_close_vec(
+        centroid(ElementType::Pri6, &prism).unwrap(),
+        [1.0 / 3.0, 1.0 / 3.0, 1.0],
+        1e-14,
+    );
+
+    let pyramid = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [1.0, 1.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.5, 0.5, 1.0],
+    ];
+    assert_close(
+        measure(ElementType::Pyr5, &pyramid).unwrap(),
+        1.0 / 3.0,
+        1e-14,
+    );
+    assert_close_vec(
+        centroid(ElementType::Pyr5, &pyramid).unwrap(),
+        [0.5, 0.5, 0.25],
+        1e-14,
+    );
+
+    // Inverted elements have a positive measure
+    let tetrahedron = [
+        [0.0, 0.0, 0.0],
+        [0.0, 3.0, 0.0],
+        [3.0, 0.0, 0.0],
+        [0.0, 0.0, 3.0],
+    ];
+    assert_eq!(measure(ElementType::Tet4, &tetrahedron), Some(4.5));
+    assert_eq!(
+        centroid(ElementType::Tet4, &tetrahedron),
+        Some([0.75, 0.75, 0.75])
+    );
+
+    // Degenerate elements are centered at the average of their nodes
+    let degenerate = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [2.0, 0.0, 0.0]];
+    assert_eq!(measure(ElementType::Tri3, &degenerate), Some(0.0));
+    assert_eq!(
+        centroid(ElementType::Tri3, &degenerate),
+        Some([1.0, 0.0, 0.0])
+    );
+    assert_eq!(normal(ElementType::Tri3, &degenerate), None);
+}
+
+#[test]
+fn test_unsupported_elements() {
+    let nodes = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+    assert_eq!(measure(ElementType::Tri1, &nodes[..1]), None);
+    assert_eq!(measure(ElementType::Polyg, &nodes), None);
+    assert_eq!(centroid(ElementType::Qua4, &nodes), None);
+    assert_eq!(normal(ElementType::Tri6, &nodes), None);
+}
+
+#[test]
+fn test_normals() {
+    let triangle = [[0.0, 0.0, 1.0], [0.0, 2.0, 1.0], [0.0, 0.0, 3.0]];
+    assert_eq!(normal(ElementType::Tri3, &triangle), Some([1.0, 0.0, 0.0]));
+
+    // Reversing the node ordering flips the normal
+    let reversed = [triangle[0], triangle[2], triangle[1]];
+    assert_eq!(normal(ElementType::Tri3, &reversed), Some([-1.0, 0.0, 0.0]));
+
+    let quadrangle = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 1.0],
+        [1.0, 1.0, 1.0],
+        [0.0, 1.0, 0.0],
+    ];
+    let s = 0.5f64.sqrt();
+    assert_close_vec(
+        normal(ElementType::Qua4, &quadrangle).unwrap(),
+        [-s, 0.0, s],
+        1e-14,
+    );
+
+    // Volume elements have no normal
+    let tetrahedron = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0],
+    ];
+    assert_eq!(normal(ElementType::Tet4, &tetrahedron), None);
+}
+
+#[test]
+fn test_higher_order_elements() {
+    // Straight-sided elements have the measure and centroid of their vertices
+    let triangle = [[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 1.0, 1.0]];
+    let tri10 = mapped_element(ElementType::Tri10, ElementType::Tri3, &triangle);
+    assert_close(
+        measure(ElementType::Tri10, &tri10).unwrap(),
+        measure(ElementType::Tri3, &triangle).unwrap(),
+        1e-14,
+    );
+    assert_close_vec(
+        centroid(ElementType::Tri10, &tri10).unwrap(),
+        centroid(ElementType::Tri3, &triangle).unwrap(),
+        1e-14,
+    );
+    assert_close_vec(
+        normal(ElementType::Tri10, &tri10).unwrap(),
+        normal(ElementType::Tri3, &triangle).unwrap(),
+        1e-14,
+    );
+
+    let tetrahedron = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.0, 2.0, 0.0],
+        [0.0, 0.0, 3.0],
+    ];
+    let tet10 = mapped_element(ElementType::Tet10, ElementType::Tet4, &tetrahedron);
+    assert_close(measure(ElementType::Tet10, &tet10).unwrap(), 1.0, 1e-14);
+    assert_close_vec(
+        centroid(ElementType::Tet10, &tet10).unwrap(),
+        [0.25, 0.5, 0.75],
+        1e-14,
+    );
+
+    let pyramid = [
+        [0.0, 0.0, 0.0],
+        [2.0, 0.0, 0.0],
+        [2.0, 2.0, 0.0],
+        [0.0, 2.0, 0.0],
+        [1.0, 1.0, 3.0],
+    ];
+    let pyr14 = mapped_element(ElementType::Pyr14, ElementType::Pyr5, &pyramid);
+    assert_close(measure(ElementType::Pyr14, &pyr14).unwrap(), 4.0, 1e-13);
+
+    // A quarter of the unit circle and an eighth of the unit sphere approximated by quadratic
+    // elements with the nodes on the circle and sphere
+    let s = 0.5f64.sqrt();
+    let arc = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [s, s, 0.0]];
+    let length = measure(ElementType::Lin3, &arc).unwrap();
+    assert_close(length, std::f64::consts::FRAC_PI_2, 1e-2);
+    let center = centroid(ElementType::Lin3, &arc).unwrap();
+    assert_close(center[0], center[1], 1e-14);
+    assert!(center[0] > 0.5);
+
+    let sphere = [
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0],
+        [s, s, 0.0],
+        [0.0, s, s],
+        [s, 0.0, s],
+    ];
+    let area = measure(ElementType::Tri6, &sphere).unwrap();
+    // The quadratic element covers more of the sphere than the flat triangle of its vertices
+    assert!(3f64.sqrt() / 2.0 < area && area < std::f64::consts::FRAC_PI_2);
+    let n = 1.0 / 3f64.sqrt();
+    assert_close_vec(
+        normal(ElementType::Tri6, &sphere).unwrap(),
+        [n, n, n],
+        1e-14,
+    );
+}
+
+#[test]
+fn test_mesh_measures() {
+    let mut msh = mshio::parse_msh_bytes(ASCII_UNIT_SQUARE_MSH.as_bytes()).unwrap();
+    msh.data
+        .elements
+        .as_mut()
+        .unwrap()
+        .element_blocks
+        .push(ElementBlock {
+            entity_dim: 1,
+            entity_tag: 3,
+            element_type: ElementType::Lin2,
+            element_tags: None,
+            elements: vec![
+                Element {
+                    element_tag: 30,
+                    nodes: vec![1, 2],
+                },
+                Element {
+                    element_tag: 31,
+                    nodes: vec![2, 3],
+                },
+            ],
+        });
+
+    let measures = element_measures(&msh).unwrap();
+    assert_eq!(measures.len(), 4);
+    assert_eq!(
+        measures[0],
+        ElementMeasure {
+            element_tag: 10,
+            element_type: ElementType::Tri3,
+            entity_dim: 2,
+            entity_tag: 1,
+            measure: 0.5,
+            centroid: [2.0 / 3.0, 1.0 / 3.0, 0.0],
+            normal: Some([0.0, 0.0, 1.0]),
+        }
+    );
+    assert_eq!(measures[3].measure, 1.0);
+    assert_eq!(measures[3].normal, None);
+
+    let entity_totals = entity_measures(&msh).unwrap();
+    assert_eq!(
+        entity_totals.into_iter().collect::<Vec<_>>(),
+        vec![((1, 3), 2.0), ((2, 1), 1.0)]
+    );
+
+    // Only the surface belongs to a physical group
+    let group_totals = physical_group_measures(&msh).unwrap();
+    assert_eq!(
+        group_totals.into_iter().collect::<Vec<_>>(),
+        vec![((2, 5), 1.0)]
+    );
+}
+
+#[test]
+fn test_outward_normals() {
+    // Two tetrahedra sharing the face (2, 3, 4) and a hexahedron with surface elements on their
+    // faces, of which the triangle 10 and the quadrangle 20 are oriented into their volume element
+    let msh_bytes = "\
+$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$Nodes
+2 14 1 18
+3 1 0 6
+1
+2
+3
+4
+5
+6
+0 0 0
+1 0 0
+0 1 0
+0 0 1
+1 1 1
+2 0 0
+3 2 0 8
+11
+12
+13
+14
+15
+16
+17
+18
+3 0 0
+4 0 0
+4 1 0
+3 1 0
+3 0 1
+4 0 1
+4 1 1
+3 1 1
+$EndNodes
+$Elements
+4 10 1 21
+3 1 4 2
+1 1 2 3 4
+2 2 3 4 5
+3 2 5 1
+3 11 12 13 14 15 16 17 18
+2 1 2 4
+10 1 2 3
+11 1 2 4
+12 2 3 4
+13 1 6 2
+2 2 3 2
+20 11 12 13 14
+21 15 16 17 18
+$EndElements
+";
+    let msh = mshio::parse_msh_bytes(msh_bytes.as_bytes()).unwrap();
+    let normals = outward_normals(&msh).unwrap();
+
+    // The interior triangle 12 and the triangle 13 outside of the volumes have no outward normal
+    assert_eq!(
+        normals.keys().copied().collect::<Vec<_>>(),
+        vec![10, 11, 20, 21]
+    );
+    assert_close_vec(normals[&10], [0.0, 0.0, -1.0], 1e-14);
+    assert_close_vec(normals[&11], [0.0, -1.0, 0.0], 1e-14);
+    assert_close_vec(normals[&20], [0.0, 0.0, -1.0], 1e-14);
+    assert_close_vec(normals[&21], [0.0, 0.0, 1.0], 1e-14);
+
+    // The normals of the individual elements follow their node ordering
+    let measures = element_measures(&msh).unwrap();
+    let node_order_normal = |tag: u64| {
+        measures
+            .iter()
+            .find(|m| m.element_tag == tag)
+            .and_then(|m| m.normal)
+            .unwrap()
+    };
+    assert_close_vec(node_order_normal(10), [0.0, 0.0, 1.0], 1e-14);
+    assert_close_vec(node_order_normal(20), [0.0, 0.0, 1.0], 1e-14);
+
+    // Surface meshes do not have outward normals
+    let msh = mshio::parse_msh_bytes(ASCII_UNIT_SQUARE_MSH.as_bytes()).unwrap();
+    assert!(outward_normals(&msh).unwrap().is_empty());
+}
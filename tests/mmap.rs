@@ -0,0 +1,90 @@
+#![cfg(feature = "mmap")]
+
+use std::fs;
+use std::path::PathBuf;
+
+use mshio::error::MshParserErrorKind;
+use nom::number::Endianness;
+
+#[macro_use]
+mod utils;
+
+use crate::utils::*;
+
+/// Writes the given data to a file in the temporary directory and returns its path
+fn write_temp_file(filename: &str, data: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("mshio_{}_{}", std::process::id(), filename));
+    fs::write(&path, data).unwrap();
+    path
+}
+
+#[test]
+fn test_mmap_unit_square_bin() {
+    let msh_bin = binary_unit_square_msh(Endianness::Little);
+    let path = write_temp_file("unit_square_bin.msh", &msh_bin);
+
+    let mapped = mshio::open_mmap(&path).unwrap();
+    assert_eq!(mapped.as_bytes(), &msh_bin[..]);
+
+    let msh = mapped.parse().unwrap();
+    assert_eq!(msh.total_node_count(), 4);
+    assert_eq!(msh.total_element_count(), 2);
+    assert_eq!(msh.to_msh_file(), mshio::parse_msh_bytes(&msh_bin).unwrap());
+
+    drop(msh);
+    drop(mapped);
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_mmap_error_offset() {
+    let mut msh_bin = binary_unit_square_msh(Endianness::Little);
+    // Replace the element type of the element block (after the section header of four size_t values
+    // and the entity dimension and tag) by an invalid value
+    let element_type_offset =
+        find_subslice(&msh_bin, b"$Elements\n").unwrap() + "$Elements\n".len() + 4 * 8 + 2 * 4;
+    msh_bin[element_type_offset..element_type_offset + 4].copy_from_slice(&0i32.to_le_bytes());
+    let path = write_temp_file("invalid_element_bin.msh", &msh_bin);
+
+    let mapped = mshio::open_mmap(&path).unwrap();
+    let error = mapped.parse().unwrap_err();
+    intended_error_output!(test_mmap_error_offset, print_error_report(&error));
+    assert_eq!(
+        error.first_msh_error(),
+        Some(MshParserErrorKind::UnknownElement)
+    );
+    assert_eq!(
+        error.offset_in(mapped.as_bytes()),
+        Some(element_type_offset)
+    );
+
+    drop(error);
+    drop(mapped);
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_parse_mmap() {
+    let msh_bin = binary_unit_square_msh(Endianness::Big);
+    let path = write_temp_file("parse_unit_square_bin.msh", &msh_bin);
+
+    let (num_nodes, num_bytes) = mshio::parse_mmap(&path, |mapped, msh| {
+        (msh.unwrap().total_node_count(), mapped.as_bytes().len())
+    })
+    .unwrap();
+    assert_eq!((num_nodes, num_bytes), (4, msh_bin.len()));
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_mmap_missing_file() {
+    assert!(mshio::open_mmap("tests/data/does_not_exist.msh").is_err());
+    assert!(mshio::parse_mmap("tests/data/does_not_exist.msh", |_, _| ()).is_err());
+}
+
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
@@ -0,0 +1,78 @@
+use mshio::error::MshParserErrorKind;
+use mshio::mshfile::ElementType;
+use nom::number::Endianness;
+
+#[macro_use]
+mod utils;
+
+use crate::utils::*;
+
+#[test]
+fn test_unit_square_bin_ref() {
+    for &endianness in &[Endianness::Little, Endianness::Big] {
+        let msh_bin = binary_unit_square_msh(endianness);
+        assert!(msh_parses(&msh_bin));
+
+        let msh = mshio::parse_msh_bytes_ref(&msh_bin).unwrap();
+        assert_eq!(msh.header.endianness, Some(endianness));
+        assert_eq!(msh.total_node_count(), 4);
+        assert_eq!(msh.total_element_count(), 2);
+
+        let nodes = msh.data.nodes.as_ref().unwrap();
+        assert_eq!(nodes.node_blocks.len(), 2);
+
+        let block = &nodes.node_blocks[1];
+        assert_eq!(block.entity_dim, 2);
+        assert_eq!(block.len(), 3);
+        assert_eq!(block.node_tags().collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(block.coordinates(1), [1.0, 1.0, 0.0]);
+        assert_eq!(block.node(2).y, 1.0);
+
+        let elements = msh.data.elements.as_ref().unwrap();
+        let block = &elements.element_blocks[0];
+        assert_eq!(block.element_type, ElementType::Tri3);
+        assert_eq!(block.len(), 2);
+        assert_eq!(block.nodes_per_element(), 3);
+        assert_eq!(block.element_tags().collect::<Vec<_>>(), vec![10, 20]);
+        assert_eq!(block.element_node(1, 2), 4);
+        assert_eq!(block.element_nodes(0).collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+}
+
+#[test]
+fn test_compare_ref_owned() {
+    let msh_ascii = mshio::parse_msh_bytes(ASCII_UNIT_SQUARE_MSH.as_bytes()).unwrap();
+
+    for &endianness in &[Endianness::Little, Endianness::Big] {
+        let msh_bin = binary_unit_square_msh(endianness);
+        let msh_owned = mshio::parse_msh_bytes(&msh_bin).unwrap();
+        let msh_ref = mshio::parse_msh_bytes_ref(&msh_bin).unwrap();
+
+        assert_eq!(msh_ref.to_msh_file(), msh_owned);
+        assert_eq!(msh_ref.to_msh_file().data, msh_ascii.data);
+    }
+}
+
+#[test]
+fn test_ascii_ref_unsupported() {
+    let error = mshio::parse_msh_bytes_ref(ASCII_UNIT_SQUARE_MSH.as_bytes()).unwrap_err();
+    assert_eq!(
+        error.first_msh_error(),
+        Some(MshParserErrorKind::Unimplemented)
+    );
+}
+
+#[test]
+fn test_truncated_element_block_ref() {
+    let mut msh_bin = binary_unit_square_msh(Endianness::Little);
+    // Cut off the section end tag and the last node tag of the last element
+    let truncated_len = msh_bin.len() - "\n$EndElements\n".len() - 8;
+    msh_bin.truncate(truncated_len);
+
+    let error = mshio::parse_msh_bytes_ref(&msh_bin).unwrap_err();
+    intended_error_output!(test_truncated_element_block_ref, print_error_report(&error));
+    assert_eq!(
+        error.first_msh_error(),
+        Some(MshParserErrorKind::InvalidElementDefinition)
+    );
+}
@@ -0,0 +1,432 @@
+use mshio::error::MshParserErrorKind;
+use mshio::mshfile::*;
+use nom::number::Endianness;
+
+#[macro_use]
+mod utils;
+
+use crate::utils::*;
+
+/// Two triangulated surfaces sharing the interface curve 3
+///
+/// Surface 1 belongs to the physical group "Left", surface 2 to the groups "Right" and
+/// "Right side with spaces" and curve 3 to "Interface".
+static TWO_SURFACES_MSH: &str = "\
+$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$PhysicalNames
+5
+1 7 \"Interface\"
+2 5 \"Left\"
+2 6 \"Right\"
+2 8 \"Right side with spaces\"
+3 9 \"Unused\"
+$EndPhysicalNames
+$Entities
+0 1 2 0
+3 1 0 0 1 1 0 1 7 0
+1 0 0 0 1 1 0 1 5 0
+2 1 0 0 2 1 0 2 6 8 0
+$EndEntities
+$Nodes
+2 6 1 6
+2 1 0 4
+1
+2
+3
+4
+0 0 0
+1 0 0
+1 1 0
+0 1 0
+2 2 0 2
+5
+6
+2 0 0
+2 1 0
+$EndNodes
+$Elements
+3 5 10 30
+2 1 2 2
+10 1 2 3
+11 1 3 4
+2 2 2 2
+20 2 5 6
+21 2 6 3
+1 3 1 1
+30 2 3
+$EndElements
+";
+
+/// Two unit squares sharing the curve 2 with the bounding curves and points of both surfaces
+///
+/// Surface 1 belongs to the physical group 5 and surface 2 to the physical group 6, all nodes
+/// are stored in the blocks of the points.
+static TWO_BOUNDED_SURFACES_MSH: &str = "\
+$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$Entities
+6 7 2 0
+1 0 0 0 0
+2 1 0 0 0
+3 1 1 0 0
+4 0 1 0 0
+5 2 0 0 0
+6 2 1 0 0
+1 0 0 0 1 0 0 0 2 1 -2
+2 1 0 0 1 1 0 0 2 2 -3
+3 0 1 0 1 1 0 0 2 3 -4
+4 0 0 0 0 1 0 0 2 4 -1
+5 1 0 0 2 0 0 0 2 2 -5
+6 2 0 0 2 1 0 0 2 5 -6
+7 1 1 0 2 1 0 0 2 6 -3
+1 0 0 0 1 1 0 1 5 4 1 2 3 4
+2 1 0 0 2 1 0 1 6 4 5 6 7 -2
+$EndEntities
+$Nodes
+6 6 1 6
+0 1 0 1
+1
+0 0 0
+0 2 0 1
+2
+1 0 0
+0 3 0 1
+3
+1 1 0
+0 4 0 1
+4
+0 1 0
+0 5 0 1
+5
+2 0 0
+0 6 0 1
+6
+2 1 0
+$EndNodes
+$Elements
+2 4 1 4
+2 1 2 2
+1 1 2 3
+2 1 3 4
+2 2 2 2
+3 2 5 6
+4 2 6 3
+$EndElements
+";
+
+fn parse(msh: &str) -> MshFile<u64, i32, f64> {
+    mshio::parse_msh_bytes(msh.as_bytes()).unwrap()
+}
+
+#[test]
+fn test_parse_physical_names() {
+    let msh = parse(TWO_SURFACES_MSH);
+    let names = msh.data.physical_names.as_ref().unwrap();
+    assert_eq!(names.len(), 5);
+    assert_eq!(
+        names[0],
+        PhysicalName {
+            dim: 1,
+            tag: 7,
+            name: String::from("Interface"),
+        }
+    );
+    assert_eq!(names[3].name, "Right side with spaces");
+    assert_eq!((names[4].dim, names[4].tag), (3, 9));
+
+    // Files without the section have no physical names
+    let msh = parse(ASCII_UNIT_SQUARE_MSH);
+    assert_eq!(msh.data.physical_names, None);
+}
+
+#[test]
+fn test_parse_physical_names_binary() {
+    let physical_names = b"$PhysicalNames\n1\n2 5 \"Square\"\n$EndPhysicalNames\n";
+    for endianness in [Endianness::Little, Endianness::Big] {
+        // The section is ASCII encoded, also in binary files
+        let mut msh_bytes = binary_unit_square_msh(endianness);
+        let position = msh_bytes.windows(8).position(|w| w == b"$Entitie").unwrap();
+        msh_bytes.splice(position..position, physical_names.iter().copied());
+
+        let msh = mshio::parse_msh_bytes(msh_bytes.as_slice()).unwrap();
+        let names = msh.data.physical_names.as_ref().unwrap();
+        assert_eq!(names.len(), 1);
+        assert_eq!(names[0].name, "Square");
+        assert_eq!(
+            msh.physical_group_by_name("Square")
+                .unwrap()
+                .element_count(),
+            2
+        );
+
+        let msh_ref = mshio::parse_msh_bytes_ref(msh_bytes.as_slice()).unwrap();
+        assert_eq!(&msh_ref.data.physical_names, &msh.data.physical_names);
+        assert_eq!(msh_ref.to_msh_file(), msh);
+    }
+}
+
+#[test]
+fn test_physical_group_lookup() {
+    let msh = parse(TWO_SURFACES_MSH);
+
+    let left = msh.physical_group(2, 5).unwrap();
+    assert_eq!(left.name, Some("Left"));
+    assert_eq!(left.entity_tags, vec![1]);
+    assert_eq!(left.element_blocks.len(), 1);
+    assert_eq!(left.element_blocks[0].entity_tag, 1);
+    assert_eq!(left.element_count(), 2);
+
+    let interface = msh.physical_group_by_name("Interface").unwrap();
+    assert_eq!((interface.dim, interface.tag), (1, 7));
+    assert_eq!(interface.element_blocks[0].element_type, ElementType::Lin2);
+
+    // Entities can belong to several physical groups
+    let right = msh.physical_group_by_name("Right").unwrap();
+    let right_with_spaces = msh
+        .physical_group_by_name("Right side with spaces")
+        .unwrap();
+    assert_eq!(right.entity_tags, right_with_spaces.entity_tags);
+    assert_eq!(right.element_blocks, right_with_spaces.element_blocks);
+
+    // Named groups without entities are still returned
+    let unused = msh.physical_group_by_name("Unused").unwrap();
+    assert!(unused.entity_tags.is_empty());
+    assert!(unused.element_blocks.is_empty());
+    assert_eq!(unused.extract_submesh().total_node_count(), 0);
+
+    // Physical tags are only unique per dimension
+    assert!(msh.physical_group(1, 5).is_none());
+    assert!(msh.physical_group(2, 7).is_none());
+    assert!(msh.physical_group_by_name("left").is_none());
+
+    // Groups can be found without the physical names section
+    let msh = parse(ASCII_UNIT_SQUARE_MSH);
+    let group = msh.physical_group(2, 5).unwrap();
+    assert_eq!(group.name, None);
+    assert_eq!(group.element_count(), 2);
+    assert!(msh.physical_group_by_name("Square").is_none());
+}
+
+#[test]
+fn test_extract_submesh_consecutive_nodes() {
+    let msh = parse(TWO_SURFACES_MSH);
+    let left = msh
+        .physical_group_by_name("Left")
+        .unwrap()
+        .extract_submesh();
+
+    assert_eq!(left.header, msh.header);
+    assert_eq!(
+        left.data.physical_names.as_ref().unwrap(),
+        &vec![PhysicalName {
+            dim: 2,
+            tag: 5,
+            name: String::from("Left"),
+        }]
+    );
+
+    let entities = left.data.entities.as_ref().unwrap();
+    assert!(entities.curves.is_empty());
+    assert_eq!(
+        entities.surfaces,
+        vec![msh.data.entities.as_ref().unwrap().surfaces[0].clone()]
+    );
+
+    let nodes = left.data.nodes.as_ref().unwrap();
+    assert_eq!(
+        (nodes.num_nodes, nodes.min_node_tag, nodes.max_node_tag),
+        (4, 1, 4)
+    );
+    assert_eq!(nodes.node_blocks.len(), 1);
+    assert_eq!(nodes.node_blocks[0].node_tags, None);
+    assert_eq!(
+        nodes.node_blocks[0].nodes,
+        msh.data.nodes.as_ref().unwrap().node_blocks[0].nodes
+    );
+
+    let elements = left.data.elements.as_ref().unwrap();
+    assert_eq!(
+        (
+            elements.num_elements,
+            elements.min_element_tag,
+            elements.max_element_tag
+        ),
+        (2, 10, 11)
+    );
+    assert_eq!(
+        elements.element_blocks,
+        vec![msh.data.elements.as_ref().unwrap().element_blocks[0].clone()]
+    );
+
+    // The interface only references nodes of surface 1, which are moved to a block of the curve
+    let interface = msh.physical_group(1, 7).unwrap().extract_submesh();
+    let nodes = interface.data.nodes.as_ref().unwrap();
+    assert_eq!(
+        (nodes.num_nodes, nodes.min_node_tag, nodes.max_node_tag),
+        (2, 2, 3)
+    );
+    assert_eq!(nodes.node_blocks.len(), 1);
+    assert_eq!(
+        (
+            nodes.node_blocks[0].entity_dim,
+            nodes.node_blocks[0].entity_tag
+        ),
+        (1, 3)
+    );
+    assert_eq!(nodes.node_blocks[0].node_tags, None);
+    assert_eq!(nodes.node_blocks[0].nodes[0].x, 1.0);
+    assert_eq!(nodes.node_blocks[0].nodes[1].y, 1.0);
+}
+
+#[test]
+fn test_extract_submesh_sparse_nodes() {
+    let msh = parse(TWO_SURFACES_MSH);
+    let right = msh
+        .physical_group_by_name("Right")
+        .unwrap()
+        .extract_submesh();
+
+    let nodes = right.data.nodes.as_ref().unwrap();
+    assert_eq!(
+        (nodes.num_nodes, nodes.min_node_tag, nodes.max_node_tag),
+        (4, 2, 6)
+    );
+
+    // The nodes 2 and 3 of surface 1, which is not part of the submesh, are moved to the block
+    // of surface 2 and the remaining node tags are not consecutive
+    assert_eq!(nodes.node_blocks.len(), 1);
+    assert_eq!(nodes.node_blocks[0].entity_tag, 2);
+    let tags = nodes.node_blocks[0].node_tags.as_ref().unwrap();
+    assert_eq!(tags.len(), 4);
+    assert_eq!((tags[&5], tags[&6], tags[&2], tags[&3]), (0, 1, 2, 3));
+    assert_eq!(nodes.node_blocks[0].nodes[2].x, 1.0);
+    assert_eq!(right.data.entities.as_ref().unwrap().surfaces.len(), 1);
+
+    // All element nodes can be resolved in the submesh
+    let node_tags: Vec<u64> = nodes
+        .node_blocks
+        .iter()
+        .flat_map(|b| b.node_tags.as_ref().unwrap().keys().copied())
+        .collect();
+    for block in &right.data.elements.as_ref().unwrap().element_blocks {
+        for element in &block.elements {
+            assert!(element.nodes.iter().all(|tag| node_tags.contains(tag)));
+        }
+    }
+
+    // The submesh can be exported like any parsed file
+    let mut output = Vec::new();
+    mshio::export::vtk::write(&right, &mut output).unwrap();
+    assert!(String::from_utf8(output)
+        .unwrap()
+        .contains("POINTS 4 double"));
+}
+
+#[test]
+fn test_extract_submesh_parametric_nodes() {
+    let mut msh = parse(TWO_SURFACES_MSH);
+    for block in &mut msh.data.nodes.as_mut().unwrap().node_blocks {
+        let parametric_nodes = (0..block.nodes.len())
+            .map(|i| Node {
+                x: i as f64,
+                y: 0.0,
+                z: 0.0,
+            })
+            .collect();
+        block.parametric = true;
+        block.parametric_nodes = Some(parametric_nodes);
+    }
+
+    // The parametric coordinates of the kept blocks are kept
+    let left = msh.physical_group(2, 5).unwrap().extract_submesh();
+    let nodes = left.data.nodes.as_ref().unwrap();
+    assert_eq!(nodes.node_blocks.len(), 1);
+    assert!(nodes.node_blocks[0].parametric);
+    let parametric_nodes = nodes.node_blocks[0].parametric_nodes.as_ref().unwrap();
+    assert_eq!(parametric_nodes.len(), 4);
+
+    // The block of surface 2 receives the nodes of surface 1 and loses its parametric coordinates
+    let right = msh.physical_group(2, 6).unwrap().extract_submesh();
+    let nodes = right.data.nodes.as_ref().unwrap();
+    assert_eq!(nodes.node_blocks.len(), 1);
+    assert_eq!(nodes.node_blocks[0].nodes.len(), 4);
+    assert!(!nodes.node_blocks[0].parametric);
+    assert_eq!(nodes.node_blocks[0].parametric_nodes, None);
+}
+
+#[test]
+fn test_extract_submesh_entity_closure() {
+    let msh = parse(TWO_BOUNDED_SURFACES_MSH);
+    let right = msh.physical_group(2, 6).unwrap().extract_submesh();
+
+    // The surface is kept with its bounding curves and points
+    let entities = right.data.entities.as_ref().unwrap();
+    let point_tags: Vec<i32> = entities.points.iter().map(|p| p.tag).collect();
+    let curve_tags: Vec<i32> = entities.curves.iter().map(|c| c.tag).collect();
+    assert_eq!(point_tags, vec![2, 3, 5, 6]);
+    assert_eq!(curve_tags, vec![2, 5, 6, 7]);
+    assert_eq!(
+        entities.surfaces,
+        vec![msh.data.entities.unwrap().surfaces[1].clone()]
+    );
+
+    // All bounding entities are part of the submesh
+    let graph = right.entity_graph();
+    let closure = graph.closure(2, 2).unwrap();
+    assert_eq!(closure.len(), 9);
+    assert!(closure.iter().all(|&(dim, tag)| graph.contains(dim, tag)));
+
+    // The nodes stay in the blocks of their points
+    let nodes = right.data.nodes.as_ref().unwrap();
+    let blocks: Vec<(i32, i32)> = nodes
+        .node_blocks
+        .iter()
+        .map(|b| (b.entity_dim, b.entity_tag))
+        .collect();
+    assert_eq!(blocks, vec![(0, 2), (0, 3), (0, 5), (0, 6)]);
+    assert_eq!(
+        (nodes.num_nodes, nodes.min_node_tag, nodes.max_node_tag),
+        (4, 2, 6)
+    );
+    assert_eq!(nodes.node_blocks[2].node_tags.as_ref().unwrap()[&5], 0);
+
+    // The nodes of the left surface have consecutive tags
+    let left = parse(TWO_BOUNDED_SURFACES_MSH)
+        .physical_group(2, 5)
+        .unwrap()
+        .extract_submesh();
+    let nodes = left.data.nodes.as_ref().unwrap();
+    assert_eq!(nodes.node_blocks.len(), 4);
+    assert!(nodes.node_blocks.iter().all(|b| b.node_tags.is_none()));
+    assert_eq!(left.data.entities.as_ref().unwrap().curves.len(), 4);
+}
+
+#[test]
+fn test_physical_names_errors() {
+    let missing_quotes = TWO_SURFACES_MSH.replace("2 5 \"Left\"", "2 5 Left");
+    let error = mshio::parse_msh_bytes(missing_quotes.as_bytes()).unwrap_err();
+    intended_error_output!(test_physical_names_errors, print_error_report(&error));
+    assert_eq!(
+        error.first_msh_error(),
+        Some(MshParserErrorKind::InvalidParameter)
+    );
+
+    let unterminated = TWO_SURFACES_MSH.replace("2 5 \"Left\"", "2 5 \"Left");
+    assert!(mshio::parse_msh_bytes(unterminated.as_bytes()).is_err());
+
+    let too_few = TWO_SURFACES_MSH.replace("$PhysicalNames\n5\n", "$PhysicalNames\n6\n");
+    assert!(mshio::parse_msh_bytes(too_few.as_bytes()).is_err());
+
+    let duplicated = TWO_SURFACES_MSH.replace(
+        "$Entities\n",
+        "$PhysicalNames\n0\n$EndPhysicalNames\n$Entities\n",
+    );
+    let error = mshio::parse_msh_bytes(duplicated.as_bytes()).unwrap_err();
+    assert_eq!(
+        error.first_msh_error(),
+        Some(MshParserErrorKind::Unimplemented)
+    );
+}
@@ -0,0 +1,443 @@
+use mshio::geometry::quality::*;
+use mshio::geometry::GeometryError;
+use mshio::mshfile::*;
+
+#[macro_use]
+mod utils;
+
+use crate::utils::*;
+
+fn assert_close(value: Option<f64>, expected: f64) {
+    let value = value.unwrap();
+    assert!(
+        (value - expected).abs() < 1e-12,
+        "{} is not close to {}",
+        value,
+        expected
+    );
+}
+
+/// Returns the nodes of the straight-sided element with the given vertices
+fn straight_element(element_type: ElementType, vertices: &[[f64; 3]]) -> Vec<[f64; 3]> {
+    element_type
+        .reference_nodes()
+        .unwrap()
+        .iter()
+        .map(|&xi| {
+            let weights = match element_type.family() {
+                ElementFamily::Triangle => ElementType::Tri3.shape_functions(xi),
+                ElementFamily::Tetrahedron => ElementType::Tet4.shape_functions(xi),
+                ElementFamily::Hexahedron => ElementType::Hex8.shape_functions(xi),
+                _ => unimplemented!(),
+            }
+            .unwrap();
+            let mut x = [0.0; 3];
+            for (w, v) in weights.iter().zip(vertices) {
+                for k in 0..3 {
+                    x[k] += w * v[k];
+                }
+            }
+            x
+        })
+        .collect()
+}
+
+#[test]
+fn test_ideal_elements() {
+    let h = 3f64.sqrt() / 2.0;
+    let ideal_elements = vec![
+        (
+            ElementType::Tri3,
+            vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.5, h, 0.0]],
+        ),
+        (
+            ElementType::Qua4,
+            vec![
+                [0.0, 0.0, 1.0],
+                [0.0, 1.0, 1.0],
+                [0.0, 1.0, 2.0],
+                [0.0, 0.0, 2.0],
+            ],
+        ),
+        (
+            ElementType::Tet4,
+            vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.5, h, 0.0],
+                [0.5, h / 3.0, (2.0f64 / 3.0).sqrt()],
+            ],
+        ),
+        (
+            ElementType::Hex8,
+            vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [1.0, 1.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [0.0, 0.0, 1.0],
+                [1.0, 0.0, 1.0],
+                [1.0, 1.0, 1.0],
+                [0.0, 1.0, 1.0],
+            ],
+        ),
+        (
+            ElementType::Pri6,
+            vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.5, h, 0.0],
+                [0.0, 0.0, 1.0],
+                [1.0, 0.0, 1.0],
+                [0.5, h, 1.0],
+            ],
+        ),
+        (
+            ElementType::Pyr5,
+            vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [1.0, 1.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [0.5, 0.5, 0.5f64.sqrt()],
+            ],
+        ),
+    ];
+
+    for (element_type, nodes) in ideal_elements {
+        assert_close(scaled_jacobian(element_type, &nodes), 1.0);
+        assert_close(aspect_ratio(element_type, &nodes), 1.0);
+        assert_close(skewness(element_type, &nodes), 0.0);
+        match element_type.family() {
+            ElementFamily::Triangle | ElementFamily::Tetrahedron => {
+                assert_close(radius_ratio(element_type, &nodes), 1.0)
+            }
+            _ => assert_eq!(radius_ratio(element_type, &nodes), None),
+        }
+    }
+}
+
+#[test]
+fn test_distorted_elements() {
+    // Parallelogram with angles of about 63 and 117 degrees
+    let parallelogram = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [1.5, 1.0, 0.0],
+        [0.5, 1.0, 0.0],
+    ];
+    let angle = 2f64.atan();
+    assert_close(
+        scaled_jacobian(ElementType::Qua4, &parallelogram),
+        angle.sin(),
+    );
+    assert_close(
+        aspect_ratio(ElementType::Qua4, &parallelogram),
+        1.25f64.sqrt(),
+    );
+    assert_close(
+        skewness(ElementType::Qua4, &parallelogram),
+        1.0 - angle / std::f64::consts::FRAC_PI_2,
+    );
+
+    // Right isosceles triangle
+    let triangle = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+    let inradius = 1.0 / (2.0 + 2f64.sqrt());
+    assert_close(
+        aspect_ratio(ElementType::Tri3, &triangle),
+        2f64.sqrt() / (2.0 * 3f64.sqrt() * inradius),
+    );
+    assert_close(
+        radius_ratio(ElementType::Tri3, &triangle),
+        2f64.sqrt() / 2.0 / (2.0 * inradius),
+    );
+
+    // Non-convex quadrangles are partially inverted
+    let dart = [
+        [0.0, 0.0, 0.0],
+        [2.0, 0.0, 0.0],
+        [0.5, 0.5, 0.0],
+        [0.0, 2.0, 0.0],
+    ];
+    assert!(scaled_jacobian(ElementType::Qua4, &dart).unwrap() < 0.0);
+
+    // Swapping two vertices of a tetrahedron inverts it without changing its shape
+    let tetrahedron = [
+        [0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0],
+    ];
+    assert_close(scaled_jacobian(ElementType::Tet4, &tetrahedron), -1.0);
+    assert!(aspect_ratio(ElementType::Tet4, &tetrahedron).unwrap() > 1.0);
+    assert!(radius_ratio(ElementType::Tet4, &tetrahedron).unwrap() > 1.0);
+}
+
+#[test]
+fn test_degenerate_and_unsupported_elements() {
+    let collinear = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [2.0, 0.0, 0.0]];
+    assert_eq!(scaled_jacobian(ElementType::Tri3, &collinear), Some(0.0));
+    assert_eq!(
+        aspect_ratio(ElementType::Tri3, &collinear),
+        Some(f64::INFINITY)
+    );
+    assert_eq!(
+        radius_ratio(ElementType::Tri3, &collinear),
+        Some(f64::INFINITY)
+    );
+    assert_eq!(skewness(ElementType::Tri3, &collinear), Some(1.0));
+
+    let collapsed_edge = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+    ];
+    assert_eq!(
+        scaled_jacobian(ElementType::Qua4, &collapsed_edge),
+        Some(0.0)
+    );
+    assert_eq!(
+        aspect_ratio(ElementType::Qua4, &collapsed_edge),
+        Some(f64::INFINITY)
+    );
+    assert_eq!(skewness(ElementType::Qua4, &collapsed_edge), Some(1.0));
+
+    // Lines, polygons and elements with the wrong number of nodes are not supported
+    let line = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]];
+    assert_eq!(scaled_jacobian(ElementType::Lin2, &line), None);
+    assert_eq!(skewness(ElementType::Polyg, &collinear), None);
+    assert_eq!(aspect_ratio(ElementType::Qua4, &collinear), None);
+}
+
+#[test]
+fn test_higher_order_elements() {
+    let vertices = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.5, 3f64.sqrt() / 2.0, 0.0],
+    ];
+    let linear = scaled_jacobian(ElementType::Tri3, &vertices).unwrap();
+
+    // Straight-sided elements have the quality of their vertices
+    let mut tri6 = straight_element(ElementType::Tri6, &vertices);
+    assert_close(scaled_jacobian(ElementType::Tri6, &tri6), linear);
+    assert_close(
+        aspect_ratio(ElementType::Tri6, &tri6),
+        aspect_ratio(ElementType::Tri3, &vertices).unwrap(),
+    );
+
+    // Curving an edge reduces the scaled Jacobian but not the other metrics
+    tri6[3] = [0.5, 0.1, 0.0];
+    let curved = scaled_jacobian(ElementType::Tri6, &tri6).unwrap();
+    assert!(0.0 < curved && curved < linear);
+    assert_close(
+        skewness(ElementType::Tri6, &tri6),
+        skewness(ElementType::Tri3, &vertices).unwrap(),
+    );
+
+    // Moving an edge node close to a vertex folds the element at the vertex
+    let tet_vertices = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0],
+    ];
+    let mut tet10 = straight_element(ElementType::Tet10, &tet_vertices);
+    assert_close(
+        scaled_jacobian(ElementType::Tet10, &tet10),
+        scaled_jacobian(ElementType::Tet4, &tet_vertices).unwrap(),
+    );
+    let edge = &ElementType::Tet10.edges().unwrap()[0];
+    assert_eq!(edge[..2], [0, 1]);
+    tet10[edge[2]] = [0.1, 0.0, 0.0];
+    assert!(scaled_jacobian(ElementType::Tet10, &tet10).unwrap() < 0.0);
+
+    let cube = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [1.0, 1.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0],
+        [1.0, 0.0, 1.0],
+        [1.0, 1.0, 1.0],
+        [0.0, 1.0, 1.0],
+    ];
+    let hex27 = straight_element(ElementType::Hex27, &cube);
+    assert_close(scaled_jacobian(ElementType::Hex27, &hex27), 1.0);
+}
+
+#[test]
+fn test_element_qualities_and_summary() {
+    let mut msh = mshio::parse_msh_bytes(ASCII_UNIT_SQUARE_MSH.as_bytes()).unwrap();
+    {
+        // Moving node 4 onto the diagonal of the square flattens element 20
+        let nodes = msh.data.nodes.as_mut().unwrap();
+        nodes.node_blocks[1].nodes[2] = Node {
+            x: 0.5,
+            y: 0.5,
+            z: 0.0,
+        };
+    }
+    msh.data
+        .elements
+        .as_mut()
+        .unwrap()
+        .element_blocks
+        .push(ElementBlock {
+            entity_dim: 1,
+            entity_tag: 1,
+            element_type: ElementType::Lin2,
+            element_tags: None,
+            elements: vec![Element {
+                element_tag: 30,
+                nodes: vec![1, 2],
+            }],
+        });
+
+    // The line element is skipped
+    let qualities = element_qualities(&msh).unwrap();
+    assert_eq!(qualities.len(), 2);
+    assert_eq!(qualities[0].element_tag, 10);
+    assert_eq!((qualities[0].entity_dim, qualities[0].entity_tag), (2, 1));
+    assert_eq!(qualities[0].element_type, ElementType::Tri3);
+    assert_close(qualities[0].scaled_jacobian, 2f64.sqrt() / 3f64.sqrt());
+    assert_close(qualities[0].skewness, 0.25);
+    assert_eq!(qualities[1].scaled_jacobian, Some(0.0));
+    assert_eq!(qualities[1].aspect_ratio, Some(f64::INFINITY));
+
+    let summary = summarize(&qualities, QualityMetric::ScaledJacobian, 2, 5).unwrap();
+    assert_eq!(summary.metric, QualityMetric::ScaledJacobian);
+    assert_eq!(summary.count, 2);
+    assert_eq!(summary.min, 0.0);
+    assert_close(Some(summary.mean), summary.max / 2.0);
+    assert_eq!(summary.histogram.counts, vec![1, 1]);
+    assert_eq!(summary.histogram.bin_edges.len(), 3);
+    assert_eq!(summary.histogram.bin_edges[0], 0.0);
+    assert_eq!(summary.histogram.bin_edges[2], summary.max);
+    assert_eq!(summary.worst, vec![(20, 0.0), (10, summary.max)]);
+
+    // Infinite values do not widen the histogram and are counted in the last bin
+    let summary = summarize(&qualities, QualityMetric::AspectRatio, 3, 1).unwrap();
+    assert_eq!(summary.max, f64::INFINITY);
+    assert_eq!(summary.histogram.counts, vec![0, 0, 2]);
+    assert!(summary.histogram.bin_edges.iter().all(|x| x.is_finite()));
+    assert_eq!(summary.worst, vec![(20, f64::INFINITY)]);
+
+    assert!(summarize(&[], QualityMetric::Skewness, 10, 10).is_none());
+}
+
+#[test]
+fn test_flipped_surface_elements() {
+    let up = [0.0, 0.0, 1.0];
+    let triangle = [[0.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, 0.0, 0.0]];
+    let square = [
+        [0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [1.0, 1.0, 0.0],
+        [1.0, 0.0, 0.0],
+    ];
+
+    // Without a reference, clockwise elements cannot be told apart from counterclockwise ones
+    assert_close(
+        scaled_jacobian(ElementType::Tri3, &triangle),
+        2f64.sqrt() / 3f64.sqrt(),
+    );
+    assert_close(scaled_jacobian(ElementType::Qua4, &square), 1.0);
+
+    // The right angle of the flipped triangle is its worst corner
+    assert_close(
+        oriented_scaled_jacobian(ElementType::Tri3, &triangle, up),
+        -1.0,
+    );
+    assert_close(
+        oriented_scaled_jacobian(ElementType::Qua4, &square, up),
+        -1.0,
+    );
+    assert_close(
+        oriented_scaled_jacobian(ElementType::Qua4, &square, [0.0, 0.0, -2.0]),
+        1.0,
+    );
+
+    // Volume elements ignore the reference
+    let tet = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0],
+    ];
+    assert_eq!(
+        oriented_scaled_jacobian(ElementType::Tet4, &tet, [0.0, 0.0, -1.0]),
+        scaled_jacobian(ElementType::Tet4, &tet)
+    );
+}
+
+#[test]
+fn test_element_qualities_flipped_surface_elements() {
+    // A surface of two quadrangles and three triangles on a grid of 3 by 3 nodes, the second
+    // quadrangle and the second triangle are ordered clockwise
+    let msh_bytes = "\
+$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$Nodes
+1 9 1 9
+2 1 0 9
+1
+2
+3
+4
+5
+6
+7
+8
+9
+0 0 0
+1 0 0
+2 0 0
+0 1 0
+1 1 0
+2 1 0
+0 2 0
+1 2 0
+2 2 0
+$EndNodes
+$Elements
+2 5 10 14
+2 1 3 2
+10 1 2 5 4
+11 2 5 6 3
+2 1 2 3
+12 4 5 8
+13 5 8 9
+14 4 8 7
+$EndElements
+";
+    let msh = mshio::parse_msh_bytes(msh_bytes.as_bytes()).unwrap();
+    let qualities = element_qualities(&msh).unwrap();
+    let tags: Vec<u64> = qualities.iter().map(|q| q.element_tag).collect();
+    assert_eq!(tags, vec![10, 11, 12, 13, 14]);
+
+    let right_triangle = 2f64.sqrt() / 3f64.sqrt();
+    let expected = [1.0, -1.0, right_triangle, -1.0, right_triangle];
+    for (quality, &expected) in qualities.iter().zip(&expected) {
+        assert_close(quality.scaled_jacobian, expected);
+    }
+
+    let summary = summarize(&qualities, QualityMetric::ScaledJacobian, 1, 2).unwrap();
+    assert_eq!(summary.worst, vec![(11, -1.0), (13, -1.0)]);
+}
+
+#[test]
+fn test_element_qualities_unknown_node() {
+    let mut msh = mshio::parse_msh_bytes(ASCII_UNIT_SQUARE_MSH.as_bytes()).unwrap();
+    msh.data.elements.as_mut().unwrap().element_blocks[0].elements[1].nodes[2] = 9;
+
+    match element_qualities(&msh) {
+        Err(GeometryError::UnknownNode {
+            element_tag: 20,
+            node_tag: 9,
+        }) => {}
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
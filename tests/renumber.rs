@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+
+use mshio::mshfile::*;
+
+/// Two quadrangles of a surface with sparse tags and nodes on a point and a curve
+static SPARSE_SQUARE_MSH: &str = "\
+$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$Nodes
+3 6 3 90
+0 1 0 1
+50
+0 0 0
+1 1 0 1
+90
+1 0 0
+2 1 0 4
+3
+7
+8
+60
+1 1 0
+0 1 0
+2 0 0
+2 1 0
+$EndNodes
+$Elements
+3 4 4 40
+0 1 15 1
+40 50
+1 1 1 1
+30 50 90
+2 1 3 2
+4 50 90 3 7
+11 90 8 60 3
+$EndElements
+";
+
+/// Returns the coordinates of the nodes of a MSH file by their tags
+fn coordinates(msh: &MshFile<u64, i32, f64>) -> HashMap<u64, [f64; 3]> {
+    let nodes = msh.data.nodes.as_ref().unwrap();
+    let mut offset = 0;
+    let mut coords = HashMap::new();
+    for block in &nodes.node_blocks {
+        for (i, node) in block.nodes.iter().enumerate() {
+            let tag = match &block.node_tags {
+                Some(tags) => *tags.iter().find(|(_, &index)| index == i).unwrap().0,
+                None => nodes.min_node_tag + offset + i as u64,
+            };
+            coords.insert(tag, [node.x, node.y, node.z]);
+        }
+        offset += block.nodes.len() as u64;
+    }
+    coords
+}
+
+/// Returns the element tags and the coordinates of their nodes in the order of the blocks
+fn element_points(msh: &MshFile<u64, i32, f64>) -> Vec<(u64, Vec<[f64; 3]>)> {
+    let coords = coordinates(msh);
+    msh.data
+        .elements
+        .as_ref()
+        .unwrap()
+        .element_blocks
+        .iter()
+        .flat_map(|block| block.elements.iter())
+        .map(|e| {
+            (
+                e.element_tag,
+                e.nodes.iter().map(|tag| coords[tag]).collect(),
+            )
+        })
+        .collect()
+}
+
+/// Returns the element tags of each element block
+fn element_tags(msh: &MshFile<u64, i32, f64>) -> Vec<Vec<u64>> {
+    msh.data
+        .elements
+        .as_ref()
+        .unwrap()
+        .element_blocks
+        .iter()
+        .map(|block| block.elements.iter().map(|e| e.element_tag).collect())
+        .collect()
+}
+
+/// Asserts that the tags of the file are dense, that the blocks are sorted by tag, that the tag
+/// maps are consistent and that the geometry of all elements is unchanged
+fn assert_renumbered(
+    original: &MshFile<u64, i32, f64>,
+    msh: &MshFile<u64, i32, f64>,
+    renumbering: &Renumbering<u64>,
+) {
+    let nodes = msh.data.nodes.as_ref().unwrap();
+    let elements = msh.data.elements.as_ref().unwrap();
+    assert_eq!(
+        nodes.num_nodes,
+        original.data.nodes.as_ref().unwrap().num_nodes
+    );
+    assert_eq!(
+        (nodes.min_node_tag, nodes.max_node_tag),
+        (1, nodes.num_nodes)
+    );
+    assert_eq!(
+        (elements.min_element_tag, elements.max_element_tag),
+        (1, elements.num_elements)
+    );
+
+    let coords = coordinates(msh);
+    let mut node_tags: Vec<u64> = coords.keys().copied().collect();
+    node_tags.sort_unstable();
+    assert_eq!(node_tags, (1..=nodes.num_nodes).collect::<Vec<_>>());
+
+    let block_tags = element_tags(msh);
+    let mut tags: Vec<u64> = block_tags.iter().flatten().copied().collect();
+    tags.sort_unstable();
+    assert_eq!(tags, (1..=elements.num_elements).collect::<Vec<_>>());
+    for (block, tags) in elements.element_blocks.iter().zip(&block_tags) {
+        assert!(tags.windows(2).all(|w| w[0] < w[1]));
+        if let Some(map) = &block.element_tags {
+            assert!(tags.iter().enumerate().all(|(i, tag)| map[tag] == i));
+        }
+    }
+
+    let mut original_points = element_points(original);
+    for (tag, _) in &mut original_points {
+        *tag = renumbering.element_tags[tag];
+    }
+    original_points.sort_by_key(|(tag, _)| *tag);
+    let mut points = element_points(msh);
+    points.sort_by_key(|(tag, _)| *tag);
+    assert_eq!(points, original_points);
+}
+
+/// Asserts that no node or element block requires a tag map
+fn assert_without_tag_maps(msh: &MshFile<u64, i32, f64>) {
+    let nodes = msh.data.nodes.as_ref().unwrap();
+    assert!(nodes.node_blocks.iter().all(|b| b.node_tags.is_none()));
+    let elements = msh.data.elements.as_ref().unwrap();
+    assert!(elements
+        .element_blocks
+        .iter()
+        .all(|b| b.element_tags.is_none()));
+}
+
+/// Returns a grid of 8 by 8 quadrangles whose nodes are stored in a scrambled order
+///
+/// If `separate_boundary` is set, the nodes on the boundary of the grid are stored in a block of
+/// a curve before the interior nodes, like Gmsh does, and the elements are split into two blocks.
+fn scrambled_grid_msh(separate_boundary: bool) -> String {
+    let n = 8;
+    let num_nodes = (n + 1) * (n + 1);
+    // Node `i` of the grid (numbered row by row) is stored at position `i * 34 % 81`, which is a
+    // permutation as 34 and 81 are coprime
+    let step = 34;
+    let tag = |i: usize| (i * step % num_nodes + 1) as u64;
+
+    let mut positions = vec![0; num_nodes];
+    for i in 0..num_nodes {
+        positions[tag(i) as usize - 1] = i;
+    }
+    let on_boundary = |i: &usize| {
+        let (x, y) = (i % (n + 1), i / (n + 1));
+        separate_boundary && (x == 0 || y == 0 || x == n || y == n)
+    };
+    let (boundary, interior): (Vec<usize>, Vec<usize>) =
+        positions.into_iter().partition(on_boundary);
+    let node_blocks: Vec<(usize, Vec<usize>)> = vec![(1, boundary), (2, interior)]
+        .into_iter()
+        .filter(|(_, nodes)| !nodes.is_empty())
+        .collect();
+
+    let mut msh = String::from("$MeshFormat\n4.1 0 8\n$EndMeshFormat\n$Nodes\n");
+    msh += &format!("{} {1} 1 {1}\n", node_blocks.len(), num_nodes);
+    for (dim, nodes) in &node_blocks {
+        msh += &format!("{} 1 0 {}\n", dim, nodes.len());
+        for &i in nodes {
+            msh += &format!("{}\n", tag(i));
+        }
+        for &i in nodes {
+            msh += &format!("{} {} 0\n", i % (n + 1), i / (n + 1));
+        }
+    }
+    msh += "$EndNodes\n$Elements\n";
+
+    let element_blocks: Vec<Vec<usize>> = if separate_boundary {
+        let (even, odd) = (0..n * n).partition(|e| e % 2 == 0);
+        vec![even, odd]
+    } else {
+        vec![(0..n * n).collect()]
+    };
+    msh += &format!("{} {1} 1 {1}\n", element_blocks.len(), n * n);
+    for block in &element_blocks {
+        msh += &format!("2 1 3 {}\n", block.len());
+        for &e in block {
+            let i = e / n * (n + 1) + e % n;
+            let corners = [i, i + 1, i + n + 2, i + n + 1];
+            msh += &format!("{}", e + 1);
+            for &corner in &corners {
+                msh += &format!(" {}", tag(corner));
+            }
+            msh += "\n";
+        }
+    }
+    msh += "$EndElements\n";
+    msh
+}
+
+/// Returns the largest difference of two node tags of an element
+fn bandwidth(msh: &MshFile<u64, i32, f64>) -> u64 {
+    msh.data
+        .elements
+        .as_ref()
+        .unwrap()
+        .element_blocks
+        .iter()
+        .flat_map(|block| block.elements.iter())
+        .map(|e| e.nodes.iter().max().unwrap() - e.nodes.iter().min().unwrap())
+        .max()
+        .unwrap()
+}
+
+#[test]
+fn test_compact_renumbering() {
+    let original = mshio::parse_msh_bytes(SPARSE_SQUARE_MSH.as_bytes()).unwrap();
+    let mut msh = original.clone();
+    let renumbering = msh.renumber(TagOrdering::Compact);
+
+    let node_tags: Vec<(u64, u64)> = renumbering.node_tags.clone().into_iter().collect();
+    assert_eq!(
+        node_tags,
+        vec![(3, 3), (7, 4), (8, 5), (50, 1), (60, 6), (90, 2)]
+    );
+    let new_element_tags: Vec<(u64, u64)> = renumbering.element_tags.clone().into_iter().collect();
+    assert_eq!(new_element_tags, vec![(4, 3), (11, 4), (30, 2), (40, 1)]);
+    assert_renumbered(&original, &msh, &renumbering);
+    assert_without_tag_maps(&msh);
+
+    // The order of the nodes and elements is kept
+    let nodes = msh.data.nodes.as_ref().unwrap();
+    assert_eq!(
+        nodes.node_blocks[2].nodes,
+        original.data.nodes.unwrap().node_blocks[2].nodes
+    );
+    let elements = msh.data.elements.as_ref().unwrap();
+    assert_eq!(
+        elements.element_blocks[2].elements[1].nodes,
+        vec![2, 5, 6, 3]
+    );
+
+    // Renumbering a compact file does not change anything
+    let compact = msh.clone();
+    let renumbering = msh.renumber(TagOrdering::Compact);
+    assert!(renumbering.node_tags.iter().all(|(old, new)| old == new));
+    assert_eq!(msh, compact);
+}
+
+#[test]
+fn test_reverse_cuthill_mckee_renumbering() {
+    let original = mshio::parse_msh_bytes(scrambled_grid_msh(false).as_bytes()).unwrap();
+    assert!(bandwidth(&original) > 40);
+
+    let mut msh = original.clone();
+    let renumbering = msh.renumber(TagOrdering::ReverseCuthillMcKee);
+    assert_renumbered(&original, &msh, &renumbering);
+    assert_without_tag_maps(&msh);
+    assert!(bandwidth(&msh) <= 2 * 9);
+
+    // The elements are sorted by their earliest node
+    let first_nodes: Vec<u64> = msh.data.elements.as_ref().unwrap().element_blocks[0]
+        .elements
+        .iter()
+        .map(|e| *e.nodes.iter().min().unwrap())
+        .collect();
+    assert!(first_nodes.windows(2).all(|w| w[0] <= w[1]));
+}
+
+#[test]
+fn test_reverse_cuthill_mckee_renumbering_across_blocks() {
+    let original = mshio::parse_msh_bytes(scrambled_grid_msh(true).as_bytes()).unwrap();
+    let mut msh = original.clone();
+    let renumbering = msh.renumber(TagOrdering::ReverseCuthillMcKee);
+    assert_renumbered(&original, &msh, &renumbering);
+
+    // The boundary nodes are numbered together with their interior neighbors, which would not
+    // be possible when numbering the blocks one after the other
+    assert!(bandwidth(&msh) <= 2 * 9);
+    let mut block_order = original.clone();
+    block_order.renumber(TagOrdering::Compact);
+    assert!(bandwidth(&block_order) > 2 * 9);
+
+    // The new tags are not consecutive in the order of the blocks
+    let nodes = msh.data.nodes.as_ref().unwrap();
+    assert_eq!(nodes.node_blocks.len(), 2);
+    assert!(nodes.node_blocks.iter().all(|b| b.node_tags.is_some()));
+    let elements = msh.data.elements.as_ref().unwrap();
+    assert!(elements
+        .element_blocks
+        .iter()
+        .all(|b| b.element_tags.is_some()));
+
+    // The elements of both blocks are sorted by their earliest node
+    let mut first_nodes: Vec<(u64, u64)> = elements
+        .element_blocks
+        .iter()
+        .flat_map(|block| block.elements.iter())
+        .map(|e| (e.element_tag, *e.nodes.iter().min().unwrap()))
+        .collect();
+    first_nodes.sort_unstable();
+    assert!(first_nodes.windows(2).all(|w| w[0].1 <= w[1].1));
+
+    // Renumbering the file again in block order removes the tag maps
+    let renumbering = msh.renumber(TagOrdering::Compact);
+    assert_without_tag_maps(&msh);
+    assert_eq!(renumbering.node_tags.len(), 81);
+}
+
+#[test]
+fn test_hilbert_renumbering() {
+    let path_length = |points: &[[f64; 3]]| -> f64 {
+        points
+            .windows(2)
+            .map(|w| ((w[0][0] - w[1][0]).powi(2) + (w[0][1] - w[1][1]).powi(2)).sqrt())
+            .sum()
+    };
+    let nodes_by_tag = |msh: &MshFile<u64, i32, f64>| -> Vec<[f64; 3]> {
+        let coords = coordinates(msh);
+        (1..=coords.len() as u64).map(|tag| coords[&tag]).collect()
+    };
+
+    for &separate_boundary in &[false, true] {
+        let original =
+            mshio::parse_msh_bytes(scrambled_grid_msh(separate_boundary).as_bytes()).unwrap();
+        let mut msh = original.clone();
+        let renumbering = msh.renumber(TagOrdering::Hilbert);
+        assert_renumbered(&original, &msh, &renumbering);
+
+        // Consecutive nodes are close to each other, independently of their blocks
+        assert!(path_length(&nodes_by_tag(&original)) > 3.0 * 80.0);
+        assert!(path_length(&nodes_by_tag(&msh)) < 1.5 * 80.0);
+
+        // The element centroids follow the curve as well
+        let mut elements = element_points(&msh);
+        elements.sort_by_key(|(tag, _)| *tag);
+        let centroids: Vec<[f64; 3]> = elements
+            .into_iter()
+            .map(|(_, points)| {
+                [0, 1, 2].map(|d| points.iter().map(|p| p[d]).sum::<f64>() / points.len() as f64)
+            })
+            .collect();
+        assert!(path_length(&centroids) < 1.5 * 63.0);
+    }
+}
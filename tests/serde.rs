@@ -0,0 +1,179 @@
+#![cfg(feature = "serde")]
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use mshio::mshfile::*;
+use nom::number::Endianness;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+#[macro_use]
+mod utils;
+
+use crate::utils::*;
+
+/// A single triangle with sparse node tags
+static SPARSE_TRIANGLE_MSH: &str = "\
+$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$Nodes
+1 3 2 30
+2 1 0 3
+2
+10
+30
+0 0 0
+1 0 0
+0 1 0
+$EndNodes
+$Elements
+1 1 1 1
+2 1 2 1
+7 2 10 30
+$EndElements
+";
+
+fn assert_round_trips<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: &T) {
+    let json = serde_json::to_string(value).unwrap();
+    assert_eq!(&serde_json::from_str::<T>(&json).unwrap(), value);
+
+    let bytes = bincode::serialize(value).unwrap();
+    assert_eq!(&bincode::deserialize::<T>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn test_serde_ascii_msh() {
+    let msh = mshio::parse_msh_bytes(ASCII_UNIT_SQUARE_MSH.as_bytes()).unwrap();
+    assert_eq!(msh.header.endianness, None);
+    assert_round_trips(&msh);
+
+    let msh = mshio::parse_msh_bytes(SPARSE_TRIANGLE_MSH.as_bytes()).unwrap();
+    assert!(msh.data.nodes.as_ref().unwrap().node_blocks[0]
+        .node_tags
+        .is_some());
+    assert_round_trips(&msh);
+}
+
+#[test]
+fn test_serde_binary_msh() {
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let msh_bytes = binary_unit_square_msh(endianness);
+        let msh = mshio::parse_msh_bytes(msh_bytes.as_slice()).unwrap();
+        assert_eq!(msh.header.endianness, Some(endianness));
+        assert_round_trips(&msh);
+    }
+}
+
+#[test]
+fn test_serde_json_format() {
+    let msh = mshio::parse_msh_bytes(ASCII_UNIT_SQUARE_MSH.as_bytes()).unwrap();
+    let json: serde_json::Value = serde_json::to_value(&msh).unwrap();
+
+    assert_eq!(json["header"]["version"], 4.1);
+    assert_eq!(json["header"]["endianness"], serde_json::Value::Null);
+    let block = &json["data"]["elements"]["element_blocks"][0];
+    assert_eq!(block["element_type"], "Tri3");
+    assert_eq!(block["elements"][1]["element_tag"], 20);
+
+    let msh_bytes = binary_unit_square_msh(Endianness::Big);
+    let msh = mshio::parse_msh_bytes(msh_bytes.as_slice()).unwrap();
+    let json: serde_json::Value = serde_json::to_value(&msh).unwrap();
+    assert_eq!(json["header"]["endianness"], "Big");
+}
+
+#[test]
+fn test_serde_generic_types() {
+    let node_tags: HashMap<u32, usize> = vec![(4, 0), (2, 1)].into_iter().collect();
+    let msh: MshFile<u32, i64, f32> = MshFile {
+        header: MshHeader {
+            version: 4.1,
+            file_type: 1,
+            size_t_size: 4,
+            int_size: 8,
+            float_size: 4,
+            endianness: Some(Endianness::Little),
+        },
+        data: MshData {
+            physical_names: Some(vec![PhysicalName {
+                dim: 0,
+                tag: -3,
+                name: String::from("Corner"),
+            }]),
+            entities: Some(Entities {
+                points: vec![Point {
+                    tag: 1,
+                    x: 0.5,
+                    y: 0.0,
+                    z: 0.0,
+                    physical_tags: vec![-3],
+                }],
+                curves: vec![Curve {
+                    tag: 2,
+                    min_x: 0.0,
+                    min_y: 0.0,
+                    min_z: 0.0,
+                    max_x: 1.0,
+                    max_y: 0.0,
+                    max_z: 0.0,
+                    physical_tags: Vec::new(),
+                    point_tags: vec![1, -1],
+                }],
+                surfaces: Vec::new(),
+                volumes: Vec::new(),
+            }),
+            nodes: Some(Nodes {
+                num_nodes: 2,
+                min_node_tag: 2,
+                max_node_tag: 4,
+                node_blocks: vec![NodeBlock {
+                    entity_dim: 1,
+                    entity_tag: 2,
+                    parametric: false,
+                    node_tags: Some(node_tags),
+                    nodes: vec![
+                        Node {
+                            x: 0.0,
+                            y: 0.0,
+                            z: 0.0,
+                        },
+                        Node {
+                            x: 1.0,
+                            y: f32::MAX,
+                            z: -0.25,
+                        },
+                    ],
+                    parametric_nodes: None,
+                }],
+            }),
+            elements: Some(Elements {
+                num_elements: 1,
+                min_element_tag: 5,
+                max_element_tag: 5,
+                element_blocks: vec![ElementBlock {
+                    entity_dim: 1,
+                    entity_tag: 2,
+                    element_type: ElementType::Lin2,
+                    element_tags: None,
+                    elements: vec![Element {
+                        element_tag: 5,
+                        nodes: vec![4, 2],
+                    }],
+                }],
+            }),
+        },
+    };
+    assert_round_trips(&msh);
+}
+
+#[test]
+fn test_serde_stats() {
+    let msh = mshio::parse_msh_bytes(SPARSE_TRIANGLE_MSH.as_bytes()).unwrap();
+    let stats = msh.stats();
+    assert_round_trips(&stats);
+
+    let json: serde_json::Value = serde_json::to_value(&stats).unwrap();
+    assert_eq!(json["node_tags"]["sparse"], true);
+    assert_eq!(json["element_types"][0][0], "Tri3");
+}
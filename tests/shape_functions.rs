@@ -0,0 +1,205 @@
+use mshio::mshfile::{ElementFamily, ElementType};
+use num_traits::FromPrimitive;
+
+/// Returns all element types that provide reference nodes and shape functions
+fn lagrange_element_types() -> Vec<ElementType> {
+    (0..=255u8)
+        .filter_map(ElementType::from_u8)
+        .filter(|t| t.reference_nodes().is_some())
+        .collect()
+}
+
+/// Returns the number of vertices of the linear element of the given family
+fn num_vertices(family: ElementFamily) -> usize {
+    match family {
+        ElementFamily::Point => 1,
+        ElementFamily::Line => 2,
+        ElementFamily::Triangle => 3,
+        ElementFamily::Quadrangle | ElementFamily::Tetrahedron => 4,
+        ElementFamily::Pyramid => 5,
+        ElementFamily::Prism => 6,
+        ElementFamily::Hexahedron => 8,
+        _ => unreachable!(),
+    }
+}
+
+/// Returns points in the interior of the reference element as convex combinations of its vertices
+fn sample_points(element_type: ElementType) -> Vec<[f64; 3]> {
+    let nodes = element_type.reference_nodes().unwrap();
+    if element_type.order() == 0 {
+        return vec![nodes[0]];
+    }
+
+    let vertices = &nodes[..num_vertices(element_type.family())];
+    let weight_sets: [&[f64]; 3] = [
+        &[1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0],
+        &[0.1, 0.7, 0.3, 1.3, 0.2, 0.9, 0.4, 0.6],
+        &[2.0, 0.05, 0.5, 0.1, 0.3, 0.2, 1.1, 0.8],
+    ];
+
+    weight_sets
+        .iter()
+        .map(|weights| {
+            let weights = &weights[..vertices.len()];
+            let total: f64 = weights.iter().sum();
+            let mut point = [0.0; 3];
+            for (vertex, w) in vertices.iter().zip(weights) {
+                for d in 0..3 {
+                    point[d] += vertex[d] * w / total;
+                }
+            }
+            point
+        })
+        .collect()
+}
+
+#[test]
+fn test_shape_functions_kronecker_delta() {
+    for element_type in lagrange_element_types() {
+        let nodes = element_type.reference_nodes().unwrap();
+        for (i, node) in nodes.iter().enumerate() {
+            let values = element_type.shape_functions(*node).unwrap();
+            assert_eq!(values.len(), nodes.len(), "{:?}", element_type);
+            for (j, value) in values.iter().enumerate() {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!(
+                    (value - expected).abs() < 1e-8,
+                    "{:?}: shape function {} at node {} is {}",
+                    element_type,
+                    j,
+                    i,
+                    value
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_shape_functions_partition_of_unity() {
+    for element_type in lagrange_element_types() {
+        for point in sample_points(element_type) {
+            let values = element_type.shape_functions(point).unwrap();
+            let gradients = element_type.shape_gradients(point).unwrap();
+            assert_eq!(values.len(), gradients.len());
+
+            let sum: f64 = values.iter().sum();
+            assert!((sum - 1.0).abs() < 1e-8, "{:?}: {}", element_type, sum);
+            for d in 0..3 {
+                let sum: f64 = gradients.iter().map(|g| g[d]).sum();
+                assert!(sum.abs() < 1e-6, "{:?}: {}", element_type, sum);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_shape_functions_reproduce_coordinates() {
+    // Interpolating the reference node coordinates yields the identity map
+    for element_type in lagrange_element_types() {
+        if element_type.order() == 0 {
+            continue;
+        }
+
+        let nodes = element_type.reference_nodes().unwrap();
+        let dim = element_type.dimension();
+        for point in sample_points(element_type) {
+            let values = element_type.shape_functions(point).unwrap();
+            let gradients = element_type.shape_gradients(point).unwrap();
+            for d in 0..dim {
+                let x: f64 = values.iter().zip(nodes).map(|(n, node)| n * node[d]).sum();
+                assert!((x - point[d]).abs() < 1e-8, "{:?}", element_type);
+
+                for e in 0..3 {
+                    let jacobian: f64 = gradients
+                        .iter()
+                        .zip(nodes)
+                        .map(|(g, node)| g[e] * node[d])
+                        .sum();
+                    let expected = if d == e { 1.0 } else { 0.0 };
+                    assert!(
+                        (jacobian - expected).abs() < 1e-6,
+                        "{:?}: d x_{} / d xi_{} = {}",
+                        element_type,
+                        d,
+                        e,
+                        jacobian
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_shape_gradients_finite_differences() {
+    let h = 1e-6;
+    for element_type in lagrange_element_types() {
+        let dim = element_type.dimension();
+        for point in sample_points(element_type) {
+            let gradients = element_type.shape_gradients(point).unwrap();
+            for d in 0..3 {
+                let mut forward = point;
+                let mut backward = point;
+                forward[d] += h;
+                backward[d] -= h;
+                let forward = element_type.shape_functions(forward).unwrap();
+                let backward = element_type.shape_functions(backward).unwrap();
+
+                for (i, gradient) in gradients.iter().enumerate() {
+                    let expected = if d < dim {
+                        (forward[i] - backward[i]) / (2.0 * h)
+                    } else {
+                        0.0
+                    };
+                    assert!(
+                        (gradient[d] - expected).abs() < 1e-4 * (1.0 + expected.abs()),
+                        "{:?}: d N_{} / d xi_{} = {} != {}",
+                        element_type,
+                        i,
+                        d,
+                        gradient[d],
+                        expected
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_shape_functions_known_values() {
+    // Linear functions of the reference elements
+    let values = ElementType::Lin2.shape_functions([0.5, 0.0, 0.0]).unwrap();
+    assert_eq!(values, vec![0.25, 0.75]);
+    let values = ElementType::Tet4.shape_functions([0.1, 0.2, 0.3]).unwrap();
+    let expected = [0.4, 0.1, 0.2, 0.3];
+    assert!(values
+        .iter()
+        .zip(&expected)
+        .all(|(a, b)| (a - b).abs() < 1e-14));
+
+    // Quadratic triangle: N_3 = 4 x (1 - x - y)
+    let values = ElementType::Tri6
+        .shape_functions([0.25, 0.25, 0.0])
+        .unwrap();
+    assert!((values[3] - 0.5).abs() < 1e-14);
+    assert!((values[0] - 0.0).abs() < 1e-14);
+
+    // Standard 8 node serendipity quadrangle: N_0 = (1 - x)(1 - y)(-x - y - 1) / 4
+    let [x, y] = [0.3, -0.6];
+    let values = ElementType::Qua8.shape_functions([x, y, 0.0]).unwrap();
+    let expected = 0.25 * (1.0 - x) * (1.0 - y) * (-x - y - 1.0);
+    assert!((values[0] - expected).abs() < 1e-12);
+
+    // Linear pyramid: N_0 = (1 - x - z)(1 - y - z) / (4 (1 - z))
+    let [x, y, z] = [0.1, -0.2, 0.3];
+    let values = ElementType::Pyr5.shape_functions([x, y, z]).unwrap();
+    let expected = (1.0 - x - z) * (1.0 - y - z) / (4.0 * (1.0 - z));
+    assert!((values[0] - expected).abs() < 1e-12);
+    assert!((values[4] - z).abs() < 1e-12);
+
+    // Element types without reference element
+    assert!(ElementType::Polyg.shape_functions([0.0; 3]).is_none());
+    assert!(ElementType::TriMini.shape_gradients([0.0; 3]).is_none());
+}
@@ -0,0 +1,172 @@
+use mshio::mshfile::*;
+
+#[macro_use]
+mod utils;
+
+use crate::utils::*;
+
+#[test]
+fn test_stats_unit_square() {
+    let msh = mshio::parse_msh_bytes(ASCII_UNIT_SQUARE_MSH.as_bytes()).unwrap();
+    let stats = msh.stats();
+
+    assert_eq!((stats.num_nodes, stats.num_elements), (4, 2));
+    assert_eq!(
+        stats.node_tags,
+        Some(TagRange {
+            min: 1,
+            max: 4,
+            sparse: false
+        })
+    );
+    assert_eq!(
+        stats.element_tags,
+        Some(TagRange {
+            min: 10,
+            max: 20,
+            sparse: true
+        })
+    );
+    assert_eq!(
+        stats.bounding_box,
+        Some(BoundingBox {
+            min: [0.0, 0.0, 0.0],
+            max: [1.0, 1.0, 0.0]
+        })
+    );
+    assert_eq!(stats.element_types, vec![(ElementType::Tri3, 2)]);
+
+    let size = stats.element_size.unwrap();
+    assert_eq!(
+        (size.min, size.max, size.mean),
+        (2f64.sqrt(), 2f64.sqrt(), 2f64.sqrt())
+    );
+    assert_eq!((stats.duplicate_nodes, stats.orphan_nodes), (0, 0));
+
+    // The point block is listed although the entity section does not contain the point
+    assert_eq!(
+        stats.entities,
+        vec![
+            EntityStats {
+                dim: 0,
+                tag: 1,
+                num_nodes: 1,
+                num_elements: 0
+            },
+            EntityStats {
+                dim: 2,
+                tag: 1,
+                num_nodes: 4,
+                num_elements: 2
+            },
+        ]
+    );
+    assert_eq!(
+        stats.physical_groups,
+        vec![PhysicalGroupStats {
+            dim: 2,
+            tag: 5,
+            name: None,
+            num_entities: 1,
+            num_nodes: 4,
+            num_elements: 2
+        }]
+    );
+
+    let expected = "\
+Nodes: 4 (tags 1 to 4, consecutive)
+Elements: 2 (tags 10 to 20, sparse)
+Bounding box: [0.0, 0.0, 0.0] to [1.0, 1.0, 0.0]
+Element size: min 1.4142135623730951, max 1.4142135623730951, mean 1.4142135623730951
+Duplicate nodes: 0
+Orphan nodes: 0
+Element types:
+  Tri3: 2
+Entities:
+  point 1: 1 nodes, 0 elements
+  surface 1: 4 nodes, 2 elements
+Physical groups:
+  surface 5: 1 entities, 4 nodes, 2 elements
+";
+    assert_eq!(stats.to_string(), expected);
+}
+
+#[test]
+fn test_stats_duplicate_and_orphan_nodes() {
+    let mut msh = mshio::parse_msh_bytes(ASCII_UNIT_SQUARE_MSH.as_bytes()).unwrap();
+    {
+        let nodes = msh.data.nodes.as_mut().unwrap();
+        let block = &mut nodes.node_blocks[1];
+        // A copy of node 3 and a node at the origin with a negative zero coordinate
+        block.nodes.push(block.nodes[1].clone());
+        block.nodes.push(Node {
+            x: -0.0,
+            y: 0.0,
+            z: 0.0,
+        });
+        block.nodes.push(Node {
+            x: 5.0,
+            y: 0.0,
+            z: 0.0,
+        });
+        nodes.num_nodes = 7;
+        nodes.max_node_tag = 7;
+    }
+    msh.data
+        .elements
+        .as_mut()
+        .unwrap()
+        .element_blocks
+        .push(ElementBlock {
+            entity_dim: 1,
+            entity_tag: 2,
+            element_type: ElementType::Lin3,
+            element_tags: None,
+            elements: vec![Element {
+                element_tag: 30,
+                nodes: vec![2, 3, 7],
+            }],
+        });
+
+    let stats = msh.stats();
+    assert_eq!(stats.num_nodes, 7);
+    assert_eq!(stats.duplicate_nodes, 2);
+    assert_eq!(stats.orphan_nodes, 2);
+    assert_eq!(stats.bounding_box.unwrap().max, [5.0, 1.0, 0.0]);
+    assert_eq!(
+        stats.element_types,
+        vec![(ElementType::Tri3, 2), (ElementType::Lin3, 1)]
+    );
+
+    // The size of higher order elements only depends on their vertices
+    let size = stats.element_size.unwrap();
+    assert_eq!((size.min, size.max), (1.0, 2f64.sqrt()));
+    assert!((size.mean - (1.0 + 2.0 * 2f64.sqrt()) / 3.0).abs() < 1e-14);
+
+    // Entities without entry in the entity section are counted from the element blocks
+    assert_eq!(
+        stats.entities.iter().find(|e| (e.dim, e.tag) == (1, 2)),
+        Some(&EntityStats {
+            dim: 1,
+            tag: 2,
+            num_nodes: 3,
+            num_elements: 1
+        })
+    );
+}
+
+#[test]
+fn test_stats_empty_mesh() {
+    let msh = mshio::parse_msh_bytes(b"$MeshFormat\n4.1 0 8\n$EndMeshFormat\n").unwrap();
+    let stats = msh.stats();
+
+    assert_eq!((stats.num_nodes, stats.num_elements), (0, 0));
+    assert_eq!(stats.node_tags, None);
+    assert_eq!(stats.bounding_box, None);
+    assert_eq!(stats.element_size, None);
+    assert!(stats.entities.is_empty());
+    assert_eq!(
+        stats.to_string(),
+        "Nodes: 0\nElements: 0\nDuplicate nodes: 0\nOrphan nodes: 0\n"
+    );
+}
@@ -0,0 +1,256 @@
+use std::io::{self, Read};
+
+use mshio::error::MshParserErrorKind;
+use mshio::mshfile::{ElementType, MshHeader, Node};
+use mshio::streaming::{
+    parse_msh_stream, parse_msh_stream_with_buffer_size, ElementBlockInfo, Entity, MshStreamError,
+    MshVisitor, NodeBlockInfo, MIN_BUFFER_SIZE,
+};
+use nom::number::Endianness;
+
+#[macro_use]
+mod utils;
+
+use crate::utils::*;
+
+/// Visitor that records all callbacks
+#[derive(Default)]
+struct RecordingVisitor {
+    header: Option<MshHeader>,
+    entities: Vec<Entity>,
+    node_blocks: Vec<NodeBlockInfo>,
+    nodes: Vec<(u64, [f64; 3])>,
+    element_blocks: Vec<ElementBlockInfo>,
+    elements: Vec<(u64, Vec<u64>)>,
+    unknown_sections: Vec<String>,
+}
+
+impl MshVisitor for RecordingVisitor {
+    fn on_header(&mut self, header: &MshHeader) {
+        self.header = Some(header.clone());
+    }
+
+    fn on_entity(&mut self, entity: Entity) {
+        self.entities.push(entity);
+    }
+
+    fn on_node_block_begin(&mut self, block: &NodeBlockInfo) {
+        self.node_blocks.push(block.clone());
+    }
+
+    fn on_node(&mut self, tag: u64, node: &Node<f64>) {
+        self.nodes.push((tag, [node.x, node.y, node.z]));
+    }
+
+    fn on_element_block_begin(&mut self, block: &ElementBlockInfo) {
+        self.element_blocks.push(block.clone());
+    }
+
+    fn on_element(&mut self, tag: u64, node_tags: &[u64]) {
+        self.elements.push((tag, node_tags.to_vec()));
+    }
+
+    fn on_unknown_section(&mut self, name: &str) {
+        self.unknown_sections.push(name.to_string());
+    }
+}
+
+/// Reader that returns the data in chunks of at most the given size
+struct ChunkedReader<'a> {
+    data: &'a [u8],
+    chunk_size: usize,
+}
+
+impl<'a> Read for ChunkedReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = buf.len().min(self.chunk_size).min(self.data.len());
+        buf[..n].copy_from_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        Ok(n)
+    }
+}
+
+/// Streams the given data using the minimal buffer size and a reader returning small chunks
+fn stream_with_small_buffer(msh: &[u8]) -> Result<RecordingVisitor, MshStreamError> {
+    let mut visitor = RecordingVisitor::default();
+    let reader = ChunkedReader {
+        data: msh,
+        chunk_size: 7,
+    };
+    parse_msh_stream_with_buffer_size(reader, &mut visitor, MIN_BUFFER_SIZE)?;
+    Ok(visitor)
+}
+
+/// Checks that the recorded data corresponds to the data of the parsed MSH file
+fn assert_matches_parsed(visitor: &RecordingVisitor, msh: &[u8]) {
+    let parsed = mshio::parse_msh_bytes(msh).unwrap();
+    assert_eq!(visitor.header.as_ref(), Some(&parsed.header));
+
+    let nodes = parsed.data.nodes.unwrap();
+    assert_eq!(visitor.node_blocks.len(), nodes.node_blocks.len());
+    let parsed_coordinates: Vec<_> = nodes
+        .node_blocks
+        .iter()
+        .flat_map(|block| block.nodes.iter().map(|n| [n.x, n.y, n.z]))
+        .collect();
+    let visited_coordinates: Vec<_> = visitor.nodes.iter().map(|(_, c)| *c).collect();
+    assert_eq!(visited_coordinates, parsed_coordinates);
+
+    let elements = parsed.data.elements.unwrap();
+    assert_eq!(visitor.element_blocks.len(), elements.element_blocks.len());
+    let parsed_elements: Vec<_> = elements
+        .element_blocks
+        .iter()
+        .flat_map(|block| {
+            block
+                .elements
+                .iter()
+                .map(|e| (e.element_tag, e.nodes.clone()))
+        })
+        .collect();
+    assert_eq!(visitor.elements, parsed_elements);
+}
+
+/// Generates an ASCII MSH file with a single node block of `n` nodes and a line element block
+fn ascii_line_msh(n: usize) -> String {
+    let mut msh = String::from("$MeshFormat\n4.1 0 8\n$EndMeshFormat\n");
+    msh += &format!("$Nodes\n1 {} 1 {}\n1 1 0 {}\n", n, n, n);
+    for i in 1..=n {
+        msh += &format!("{}\n", i);
+    }
+    for i in 0..n {
+        msh += &format!("{} 0.123456789 -1.5e-3\n", i as f64 / 3.0);
+    }
+    msh += &format!(
+        "$EndNodes\n$Elements\n1 {} 1 {}\n1 1 1 {}\n",
+        n - 1,
+        n - 1,
+        n - 1
+    );
+    for i in 1..n {
+        msh += &format!("{} {} {}\n", i, i, i + 1);
+    }
+    msh += "$EndElements\n";
+    msh
+}
+
+#[test]
+fn test_stream_unit_square_ascii() {
+    let msh = ASCII_UNIT_SQUARE_MSH.as_bytes();
+    let mut visitor = RecordingVisitor::default();
+    parse_msh_stream(msh, &mut visitor).unwrap();
+
+    assert_eq!(visitor.entities.len(), 1);
+    match &visitor.entities[0] {
+        Entity::Surface(surface) => {
+            assert_eq!(surface.tag, 1);
+            assert_eq!(surface.physical_tags, vec![5]);
+        }
+        entity => panic!("Expected a surface entity, got {:?}", entity),
+    }
+
+    assert_eq!(visitor.node_blocks[1].entity_dim, 2);
+    assert_eq!(visitor.node_blocks[1].num_nodes, 3);
+    assert_eq!(
+        visitor
+            .nodes
+            .iter()
+            .map(|(tag, _)| *tag)
+            .collect::<Vec<_>>(),
+        vec![1, 2, 3, 4]
+    );
+    assert_eq!(visitor.nodes[2].1, [1.0, 1.0, 0.0]);
+
+    assert_eq!(visitor.element_blocks[0].element_type, ElementType::Tri3);
+    assert_eq!(
+        visitor.elements,
+        vec![(10, vec![1, 2, 3]), (20, vec![1, 3, 4])]
+    );
+    assert_matches_parsed(&visitor, msh);
+}
+
+#[test]
+fn test_stream_unit_square_bin() {
+    for &endianness in &[Endianness::Little, Endianness::Big] {
+        let msh = binary_unit_square_msh(endianness);
+        let visitor = stream_with_small_buffer(&msh).unwrap();
+        assert_eq!(visitor.entities.len(), 1);
+        assert_matches_parsed(&visitor, &msh);
+    }
+}
+
+#[test]
+fn test_stream_small_buffer_ascii() {
+    let msh = ascii_line_msh(500);
+    let visitor = stream_with_small_buffer(msh.as_bytes()).unwrap();
+    assert_eq!(visitor.nodes.len(), 500);
+    assert_eq!(visitor.elements.len(), 499);
+    assert_matches_parsed(&visitor, msh.as_bytes());
+}
+
+#[test]
+fn test_stream_unknown_sections() {
+    let msh = ASCII_UNIT_SQUARE_MSH.replace(
+        "$Nodes\n",
+        "$Comments\nThis section is $Ignored, even if it is long\n$EndComments\n$Nodes\n",
+    );
+    let visitor = stream_with_small_buffer(msh.as_bytes()).unwrap();
+    assert_eq!(visitor.unknown_sections, vec!["Comments".to_string()]);
+    assert_matches_parsed(&visitor, msh.as_bytes());
+}
+
+#[test]
+fn test_stream_unknown_section_end_tag() {
+    // The end tag is followed by data without whitespace that exceeds the buffer
+    let msh = format!(
+        "$MeshFormat\n4.1 0 8\n$EndMeshFormat\n$Foo\n$EndFoo{}\n",
+        "x".repeat(600)
+    );
+    let mut visitor = RecordingVisitor::default();
+    let result = parse_msh_stream_with_buffer_size(msh.as_bytes(), &mut visitor, 256);
+    assert!(matches!(result, Err(MshStreamError::Parser(_))));
+    assert_eq!(visitor.unknown_sections, vec!["Foo".to_string()]);
+
+    // Tags that only start with the end tag do not end the section
+    let msh = ASCII_UNIT_SQUARE_MSH.replace("$Nodes\n", "$Foo\n$EndFooBar\n$EndFoo\n$Bar\n$EndBar");
+    let msh = msh.replace("$EndBar", "$EndBar\n$Nodes\n");
+    let visitor = stream_with_small_buffer(msh.as_bytes()).unwrap();
+    assert_eq!(
+        visitor.unknown_sections,
+        vec!["Foo".to_string(), "Bar".to_string()]
+    );
+    assert_eq!((visitor.nodes.len(), visitor.elements.len()), (4, 2));
+
+    // The end tag may end the stream
+    let msh = "$MeshFormat\n4.1 0 8\n$EndMeshFormat\n$Foo\n$EndFoo";
+    let visitor = stream_with_small_buffer(msh.as_bytes()).unwrap();
+    assert_eq!(visitor.unknown_sections, vec!["Foo".to_string()]);
+}
+
+#[test]
+fn test_stream_invalid_node() {
+    let msh = ascii_line_msh(100).replacen("0.123456789", "0.12x", 1);
+    let error = match stream_with_small_buffer(msh.as_bytes()) {
+        Err(MshStreamError::Parser(error)) => error,
+        Err(error) => panic!("Expected a parser error, got {:?}", error),
+        Ok(_) => panic!("Expected a parser error"),
+    };
+
+    intended_error_output!(test_stream_invalid_node, eprintln!("{}", error));
+    let expected_offset = msh.find("0.12x").unwrap() + "0.12".len();
+    let (offset, _) = error.begin_msh_errors().next().unwrap();
+    assert_eq!(*offset, expected_offset as u64);
+    assert!(error
+        .filter_msh_errors()
+        .any(|(_, kind)| *kind == MshParserErrorKind::InvalidNodeDefinition));
+}
+
+#[test]
+fn test_stream_truncated_file() {
+    let msh = binary_unit_square_msh(Endianness::Little);
+    let truncated = &msh[..msh.len() - 30];
+    assert!(matches!(
+        stream_with_small_buffer(truncated),
+        Err(MshStreamError::Parser(_))
+    ));
+}
@@ -1,8 +1,12 @@
+#![allow(dead_code)]
+
 use std::fs::OpenOptions;
 use std::io::{BufReader, Read};
 use std::path::Path;
 
+use mshio::mshfile::{Element, ElementBlock, ElementType, Elements};
 use mshio::MshParserError;
+use nom::number::Endianness;
 
 /// Relative path to the directory containing the test mesh data
 static TEST_DATA_DIR: &'static str = "tests/data";
@@ -61,3 +65,139 @@ macro_rules! intended_error_output {
         println!("")
     };
 }
+
+/// ASCII MSH file of a unit square consisting of two triangles
+///
+/// Contains the same data as the file generated by [`binary_unit_square_msh`](fn.binary_unit_square_msh.html).
+pub static ASCII_UNIT_SQUARE_MSH: &str = "\
+$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$Entities
+0 0 1 0
+1 0 0 0 1 1 0 1 5 0
+$EndEntities
+$Nodes
+2 4 1 4
+0 1 0 1
+1
+0 0 0
+2 1 0 3
+2
+3
+4
+1 0 0
+1 1 0
+0 1 0
+$EndNodes
+$Elements
+1 2 10 20
+2 1 2 2
+10 1 2 3
+20 1 3 4
+$EndElements
+";
+
+/// Builds a binary MSH file of a unit square consisting of two triangles with the given byte order
+pub fn binary_unit_square_msh(endianness: Endianness) -> Vec<u8> {
+    let mut msh = Vec::new();
+
+    macro_rules! push {
+        ($value:expr) => {
+            match endianness {
+                Endianness::Little => msh.extend_from_slice(&$value.to_le_bytes()),
+                Endianness::Big => msh.extend_from_slice(&$value.to_be_bytes()),
+            }
+        };
+    }
+
+    msh.extend_from_slice(b"$MeshFormat\n4.1 1 8\n");
+    push!(1i32);
+    msh.extend_from_slice(b"\n$EndMeshFormat\n");
+
+    msh.extend_from_slice(b"$Entities\n");
+    for &num_entities in &[0u64, 0, 1, 0] {
+        push!(num_entities);
+    }
+    push!(1i32);
+    for &bound in &[0.0f64, 0.0, 0.0, 1.0, 1.0, 0.0] {
+        push!(bound);
+    }
+    push!(1u64);
+    push!(5i32);
+    push!(0u64);
+    msh.extend_from_slice(b"\n$EndEntities\n");
+
+    msh.extend_from_slice(b"$Nodes\n");
+    for &value in &[2u64, 4, 1, 4] {
+        push!(value);
+    }
+    for &(entity_dim, node_tags, coords) in &[
+        (0i32, &[1u64][..], &[[0.0f64, 0.0, 0.0]][..]),
+        (
+            2i32,
+            &[2u64, 3, 4][..],
+            &[[1.0f64, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]][..],
+        ),
+    ] {
+        push!(entity_dim);
+        push!(1i32);
+        push!(0i32);
+        push!(node_tags.len() as u64);
+        for &tag in node_tags {
+            push!(tag);
+        }
+        for node in coords {
+            for &x in node {
+                push!(x);
+            }
+        }
+    }
+    msh.extend_from_slice(b"\n$EndNodes\n");
+
+    msh.extend_from_slice(b"$Elements\n");
+    for &value in &[1u64, 2, 10, 20] {
+        push!(value);
+    }
+    push!(2i32);
+    push!(1i32);
+    push!(2i32);
+    push!(2u64);
+    for &value in &[10u64, 1, 2, 3, 20, 1, 3, 4] {
+        push!(value);
+    }
+    msh.extend_from_slice(b"\n$EndElements\n");
+
+    msh
+}
+
+/// Builds element blocks with consecutive element tags starting at one from the element type,
+/// entity tag and node tags of the elements of each block
+pub fn build_elements(blocks: Vec<(ElementType, i32, Vec<Vec<u64>>)>) -> Elements<u64, i32> {
+    let mut tag = 0;
+    let element_blocks: Vec<ElementBlock<u64, i32>> = blocks
+        .into_iter()
+        .map(|(element_type, entity_tag, elements)| ElementBlock {
+            entity_dim: element_type.dimension() as i32,
+            entity_tag,
+            element_type,
+            element_tags: None,
+            elements: elements
+                .into_iter()
+                .map(|nodes| {
+                    tag += 1;
+                    Element {
+                        element_tag: tag,
+                        nodes,
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+    Elements {
+        num_elements: tag,
+        min_element_tag: 1,
+        max_element_tag: tag,
+        element_blocks,
+    }
+}
@@ -0,0 +1,218 @@
+use mshio::mshfile::ElementFamily;
+use mshio::mshfile::ElementType::{self, *};
+use mshio::vtk::VtkCellType;
+use num_traits::FromPrimitive;
+
+fn all_element_types() -> impl Iterator<Item = ElementType> {
+    (1..=u8::MAX).filter_map(ElementType::from_u8)
+}
+
+fn identity(n: usize) -> Vec<usize> {
+    (0..n).collect()
+}
+
+#[test]
+fn test_vtk_cell_types() {
+    assert_eq!(Pnt.vtk_cell_type(), Some(VtkCellType::Vertex));
+    assert_eq!(Tri1.vtk_cell_type(), Some(VtkCellType::Vertex));
+    assert_eq!(Lin2.vtk_cell_type(), Some(VtkCellType::Line));
+    assert_eq!(Pyr5.vtk_cell_type(), Some(VtkCellType::Pyramid));
+    assert_eq!(Lin3.vtk_cell_type(), Some(VtkCellType::QuadraticEdge));
+    assert_eq!(Qua8.vtk_cell_type(), Some(VtkCellType::QuadraticQuad));
+    assert_eq!(Qua9.vtk_cell_type(), Some(VtkCellType::BiquadraticQuad));
+    assert_eq!(
+        Hex20.vtk_cell_type(),
+        Some(VtkCellType::QuadraticHexahedron)
+    );
+    assert_eq!(
+        Hex27.vtk_cell_type(),
+        Some(VtkCellType::TriquadraticHexahedron)
+    );
+    assert_eq!(Pri15.vtk_cell_type(), Some(VtkCellType::QuadraticWedge));
+    assert_eq!(
+        Pri18.vtk_cell_type(),
+        Some(VtkCellType::BiquadraticQuadraticWedge)
+    );
+    assert_eq!(Pyr13.vtk_cell_type(), Some(VtkCellType::QuadraticPyramid));
+    assert_eq!(Lin11.vtk_cell_type(), Some(VtkCellType::LagrangeCurve));
+    assert_eq!(Tri66.vtk_cell_type(), Some(VtkCellType::LagrangeTriangle));
+    assert_eq!(
+        Qua16.vtk_cell_type(),
+        Some(VtkCellType::LagrangeQuadrilateral)
+    );
+    assert_eq!(
+        Tet286.vtk_cell_type(),
+        Some(VtkCellType::LagrangeTetrahedron)
+    );
+    assert_eq!(
+        Hex1000.vtk_cell_type(),
+        Some(VtkCellType::LagrangeHexahedron)
+    );
+    assert_eq!(Pri40.vtk_cell_type(), Some(VtkCellType::LagrangeWedge));
+    assert_eq!(Pri75.vtk_cell_type(), Some(VtkCellType::LagrangeWedge));
+    assert_eq!(Pri126.vtk_cell_type(), Some(VtkCellType::LagrangeWedge));
+    assert_eq!(Pri550.vtk_cell_type(), Some(VtkCellType::LagrangeWedge));
+
+    for element_type in [
+        Pyr14, Pyr30, Tri9, Tet16, Hex32, Polyg, Polyh, LinB, TriMini,
+    ] {
+        assert_eq!(element_type.vtk_cell_type(), None, "{:?}", element_type);
+        assert_eq!(element_type.vtk_node_ordering(), None, "{:?}", element_type);
+    }
+}
+
+#[test]
+fn test_vtk_node_ordering() {
+    // Permutations of the fixed VTK cells as used by Gmsh's VTK export
+    assert_eq!(
+        Tet10.vtk_node_ordering().unwrap(),
+        &[0, 1, 2, 3, 4, 5, 6, 7, 9, 8]
+    );
+    assert_eq!(
+        Hex20.vtk_node_ordering().unwrap(),
+        &[0, 1, 2, 3, 4, 5, 6, 7, 8, 11, 13, 9, 16, 18, 19, 17, 10, 12, 14, 15]
+    );
+    assert_eq!(
+        Hex27.vtk_node_ordering().unwrap(),
+        &[
+            0, 1, 2, 3, 4, 5, 6, 7, 8, 11, 13, 9, 16, 18, 19, 17, 10, 12, 14, 15, 22, 23, 21, 24,
+            20, 25, 26
+        ]
+    );
+    assert_eq!(
+        Pri15.vtk_node_ordering().unwrap(),
+        &[0, 1, 2, 3, 4, 5, 6, 9, 7, 12, 14, 13, 8, 10, 11]
+    );
+    assert_eq!(
+        Pri18.vtk_node_ordering().unwrap(),
+        &[0, 1, 2, 3, 4, 5, 6, 9, 7, 12, 14, 13, 8, 10, 11, 15, 17, 16]
+    );
+    assert_eq!(
+        Pyr13.vtk_node_ordering().unwrap(),
+        &[0, 1, 2, 3, 4, 5, 8, 10, 6, 7, 9, 11, 12]
+    );
+
+    // Lines, complete triangles and quadrangles up to order 2 are ordered in the same way
+    for element_type in [
+        Lin2, Lin3, Lin4, Lin11, Tri3, Tri6, Tri10, Tri15, Tri66, Qua4, Qua8, Qua9,
+    ] {
+        let n = element_type.nodes().unwrap();
+        assert_eq!(
+            element_type.vtk_node_ordering().unwrap(),
+            identity(n).as_slice(),
+            "{:?}",
+            element_type
+        );
+    }
+
+    // The edges of the Gmsh tetrahedron point towards vertex 0, 1 and 2
+    assert_eq!(
+        Tet20.vtk_node_ordering().unwrap(),
+        &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 11, 10, 15, 14, 13, 12, 17, 19, 18, 16]
+    );
+
+    // Lagrange quadrangles: the third and fourth edge are reversed, interior nodes are row-wise
+    assert_eq!(
+        Qua16.vtk_node_ordering().unwrap(),
+        &[0, 1, 2, 3, 4, 5, 6, 7, 9, 8, 11, 10, 12, 13, 15, 14]
+    );
+
+    // Lagrange hexahedra: edges ordered by their direction, the edges at vertex 2 and 3 swapped
+    let hex64 = Hex64.vtk_node_ordering().unwrap();
+    assert_eq!(&hex64[0..8], identity(8).as_slice());
+    assert_eq!(
+        &hex64[8..32],
+        &[
+            8, 9, 14, 15, 19, 18, 10, 11, 24, 25, 28, 29, 31, 30, 26, 27, 12, 13, 16, 17, 22, 23,
+            20, 21
+        ]
+    );
+}
+
+#[test]
+fn test_vtk_node_ordering_high_order_wedge() {
+    // Reference coordinates of the VTK nodes, scaled to the integer lattice of order 5
+    let reference_nodes = Pri126.reference_nodes().unwrap();
+    let vtk_nodes: Vec<_> = Pri126
+        .vtk_node_ordering()
+        .unwrap()
+        .iter()
+        .map(|&i| {
+            let [u, v, w] = reference_nodes[i];
+            [u * 5.0, v * 5.0, (w + 1.0) * 2.5].map(|x| x.round() as i32)
+        })
+        .collect();
+
+    // Vertices, followed by the edges of the bottom triangle
+    assert_eq!(&vtk_nodes[0..3], &[[0, 0, 0], [5, 0, 0], [0, 5, 0]]);
+    assert_eq!(
+        &vtk_nodes[6..10],
+        &[[1, 0, 0], [2, 0, 0], [3, 0, 0], [4, 0, 0]]
+    );
+
+    // The nodes inside the triangles are ordered row-wise, both on the faces and in the interior
+    let triangle = [[1, 1], [2, 1], [3, 1], [1, 2], [2, 2], [1, 3]];
+    let num_edge_nodes = 6 + 9 * 4;
+    for (begin, k) in [(num_edge_nodes, 0), (num_edge_nodes + 6, 5)] {
+        let expected: Vec<_> = triangle.iter().map(|&[i, j]| [i, j, k]).collect();
+        assert_eq!(&vtk_nodes[begin..begin + 6], expected.as_slice());
+    }
+    let num_face_nodes = 2 * 6 + 3 * 16;
+    for k in 1..5 {
+        let begin = num_edge_nodes + num_face_nodes + 6 * (k as usize - 1);
+        let expected: Vec<_> = triangle.iter().map(|&[i, j]| [i, j, k]).collect();
+        assert_eq!(&vtk_nodes[begin..begin + 6], expected.as_slice());
+    }
+}
+
+#[test]
+fn test_vtk_node_ordering_all_types() {
+    let mut num_mapped = 0;
+    for element_type in all_element_types() {
+        let (cell_type, ordering) = match element_type.vtk_cell_type() {
+            Some(cell_type) => (cell_type, element_type.vtk_node_ordering().unwrap()),
+            None => {
+                assert!(element_type.vtk_node_ordering().is_none());
+                continue;
+            }
+        };
+        num_mapped += 1;
+
+        // The ordering is a permutation
+        let n = element_type.nodes().unwrap();
+        let mut sorted = ordering.to_vec();
+        sorted.sort_unstable();
+        assert_eq!(sorted, identity(n), "{:?}", element_type);
+
+        // The vertices are not reordered
+        let num_vertices = match (element_type.order(), element_type.family()) {
+            (0, _) | (_, ElementFamily::Point) => 1,
+            (_, ElementFamily::Line) => 2,
+            (_, ElementFamily::Triangle) => 3,
+            (_, ElementFamily::Quadrangle) | (_, ElementFamily::Tetrahedron) => 4,
+            (_, ElementFamily::Pyramid) => 5,
+            (_, ElementFamily::Prism) => 6,
+            (_, ElementFamily::Hexahedron) => 8,
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            &ordering[0..num_vertices],
+            identity(num_vertices).as_slice(),
+            "{:?}",
+            element_type
+        );
+
+        // Conversion back from the VTK cell type
+        let expected = if cell_type == VtkCellType::Vertex {
+            Pnt
+        } else {
+            element_type
+        };
+        assert_eq!(cell_type.element_type(n), Some(expected));
+        assert_eq!(VtkCellType::from_u8(cell_type as u8), Some(cell_type));
+    }
+
+    // All complete elements except pyramids of order 2 and higher, as well as the quadratic
+    // serendipity quadrangles, hexahedra, prisms and pyramids
+    assert_eq!(num_mapped, 71);
+}